@@ -56,7 +56,7 @@ fn bench_query_traditional(pairs: usize, c: &mut Criterion) {
 fn bench_spawn_methods(cnt: usize, c: &mut Criterion) {
     c.bench_function(&format!("spawn_tuple_{}times", cnt), |b| {
         b.iter_batched(
-            || World::new(),
+            World::new,
             |mut world| {
                 for _ in 0..cnt {
                     world.spawn((A(10), B(20)));
@@ -67,9 +67,34 @@ fn bench_spawn_methods(cnt: usize, c: &mut Criterion) {
         )
     });
 
+    c.bench_function(&format!("spawn_in_handle_{}times", cnt), |b| {
+        b.iter_batched(
+            World::new,
+            |mut world| {
+                let handle = world.archetype_handle::<(A, B)>();
+                for _ in 0..cnt {
+                    world.spawn_in(handle, (A(10), B(20)));
+                }
+                std::hint::black_box(world)
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    c.bench_function(&format!("spawn_batch_{}times", cnt), |b| {
+        b.iter_batched(
+            World::new,
+            |mut world| {
+                world.spawn_batch((0..cnt).map(|_| (A(10), B(20))));
+                std::hint::black_box(world)
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
     c.bench_function(&format!("spawn_empty_then_insert_{}times", cnt), |b| {
         b.iter_batched(
-            || World::new(),
+            World::new,
             |mut world| {
                 for _ in 0..cnt {
                     let e = world.spawn_empty();
@@ -145,7 +170,7 @@ fn bench_has_component(iterations: usize, c: &mut Criterion) {
 fn bench_despawn(iterations: usize, c: &mut Criterion) {
     c.bench_function(&format!("despawn_{}times", iterations), |b| {
         b.iter_batched(
-            || World::new(),
+            World::new,
             |mut world| {
                 for _ in 0..iterations {
                     let e = world.spawn((A(1), B(2)));
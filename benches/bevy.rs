@@ -57,7 +57,7 @@ fn bench_query_traditional(pairs: usize, c: &mut Criterion) {
 fn bench_spawn_methods(cnt: usize, c: &mut Criterion) {
     c.bench_function(&format!("spawn_tuple_{}times", cnt), |b| {
         b.iter_batched(
-            || World::new(),
+            World::new,
             |mut world| {
                 for _ in 0..cnt {
                     world.spawn((A(10), B(20)));
@@ -70,7 +70,7 @@ fn bench_spawn_methods(cnt: usize, c: &mut Criterion) {
 
     c.bench_function(&format!("spawn_empty_then_insert_{}times", cnt), |b| {
         b.iter_batched(
-            || World::new(),
+            World::new,
             |mut world| {
                 for _ in 0..cnt {
                     let mut e = world.spawn_empty();
@@ -150,7 +150,7 @@ fn bench_has_component(iterations: usize, c: &mut Criterion) {
 fn bench_despawn(iterations: usize, c: &mut Criterion) {
     c.bench_function(&format!("despawn_{}times", iterations), |b| {
         b.iter_batched(
-            || World::new(),
+            World::new,
             |mut world| {
                 for _ in 0..iterations {
                     let e = world.spawn((A(1), B(2))).id();
@@ -0,0 +1,150 @@
+//! Derive macros of `becs`, use them through `becs::prelude`.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{
+    Data, DeriveInput, Error, Fields, GenericParam, Index, Lifetime, Type, parse_macro_input,
+    visit_mut::{self, VisitMut},
+};
+
+/// Implements `SystemParam` for a struct whose fields are system parameters, e.g.
+///
+/// ```ignore
+/// #[derive(SystemParam)]
+/// struct Movement<'w, 's> {
+///     bodies: Query<'w, 's, (&'static mut Position, &'static Velocity)>,
+///     time: Res<'w, Time>,
+/// }
+/// ```
+///
+/// Here `Time` is the world clock, which worlds store as a resource.
+/// The struct may only have the lifetimes `'w` of the world and `'s` of the state, its fields are fetched in order.
+#[proc_macro_derive(SystemParam)]
+pub fn derive_system_param(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    system_param(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Replaces the lifetimes of the struct in the types of its fields.
+struct ReplaceLifetimes {
+    world: Lifetime,
+    state: Lifetime,
+}
+
+impl VisitMut for ReplaceLifetimes {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        match lifetime.ident.to_string().as_str() {
+            "w" => *lifetime = self.world.clone(),
+            "s" => *lifetime = self.state.clone(),
+            _ => {}
+        }
+        visit_mut::visit_lifetime_mut(self, lifetime);
+    }
+}
+
+fn replace_lifetimes(ty: &Type, world: &str, state: &str) -> Type {
+    let mut ty = ty.clone();
+    ReplaceLifetimes {
+        world: Lifetime::new(world, Span::call_site()),
+        state: Lifetime::new(state, Span::call_site()),
+    }
+    .visit_type_mut(&mut ty);
+    ty
+}
+
+fn system_param(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "SystemParam can only be derived for structs",
+        ));
+    };
+    for param in &input.generics.params {
+        if let GenericParam::Lifetime(param) = param
+            && !matches!(param.lifetime.ident.to_string().as_str(), "w" | "s")
+        {
+            return Err(Error::new_spanned(
+                &param.lifetime,
+                "SystemParam structs may only have the lifetimes 'w and 's",
+            ));
+        }
+    }
+
+    let fields: Vec<_> = data.fields.iter().collect();
+    let params: Vec<Type> = fields
+        .iter()
+        .map(|field| replace_lifetimes(&field.ty, "'static", "'static"))
+        .collect();
+    let states: Vec<_> = (0..fields.len())
+        .map(|index| format_ident!("state{index}"))
+        .collect();
+    let indices: Vec<_> = (0..fields.len()).map(Index::from).collect();
+    let fetched = params.iter().zip(&states).map(|(param, state)| {
+        quote! { <#param as ::becs::prelude::SystemParam>::fetch(#state, world) }
+    });
+    let construct = match &data.fields {
+        Fields::Named(_) => {
+            let names = fields.iter().map(|field| &field.ident);
+            quote! { { #(#names: #fetched),* } }
+        }
+        Fields::Unnamed(_) => quote! { ( #(#fetched),* ) },
+        Fields::Unit => quote! {},
+    };
+
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    let item_generics = input.generics.params.iter().map(|param| match param {
+        GenericParam::Lifetime(param) if param.lifetime.ident == "w" => quote! { '__w },
+        GenericParam::Lifetime(_) => quote! { '__s },
+        GenericParam::Type(param) => {
+            let ident = &param.ident;
+            quote! { #ident }
+        }
+        GenericParam::Const(param) => {
+            let ident = &param.ident;
+            quote! { #ident }
+        }
+    });
+    let mut where_clause = where_clause
+        .cloned()
+        .unwrap_or_else(|| syn::parse_quote!(where));
+    // Spelling out the items keeps the bounds from hiding them, e.g. for fields depending on type parameters
+    for (param, field) in params.iter().zip(&fields) {
+        let item = replace_lifetimes(&field.ty, "'__w", "'__s");
+        where_clause.predicates.push(syn::parse_quote!(
+            for<'__w, '__s> #param: ::becs::prelude::SystemParam<Item<'__w, '__s> = #item>
+        ));
+    }
+
+    Ok(quote! {
+        // SAFETY: Every field is a system parameter which adds everything it accesses
+        // The state of a struct without fields is `()`, which clippy flags in the body of `init`
+        #[allow(clippy::unused_unit)]
+        unsafe impl #impl_generics ::becs::prelude::SystemParam for #ident #type_generics #where_clause {
+            type State = (#(<#params as ::becs::prelude::SystemParam>::State,)*);
+            type Item<'__w, '__s> = #ident<#(#item_generics),*>;
+
+            fn init(
+                world: &mut ::becs::prelude::World,
+                access: &mut ::becs::prelude::SystemAccess,
+            ) -> Self::State {
+                (#(<#params as ::becs::prelude::SystemParam>::init(world, access),)*)
+            }
+
+            fn fetch<'__w, '__s>(
+                state: &'__s mut Self::State,
+                world: &'__w ::becs::prelude::World,
+            ) -> Self::Item<'__w, '__s> {
+                let (#(#states,)*) = state;
+                #ident #construct
+            }
+
+            fn apply(state: &mut Self::State, world: &mut ::becs::prelude::World) {
+                #(<#params as ::becs::prelude::SystemParam>::apply(&mut state.#indices, world);)*
+            }
+        }
+    })
+}
@@ -0,0 +1,107 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use crate::world::{Entity, World};
+
+/// Slot picked by an [`EntityAllocator`] for a new entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Slot {
+    /// Reuses the free slot at the given position of the free list.
+    Free(usize),
+    /// Creates a new slot with the given index, which must not be lower than the number of slots.
+    /// Skipped slots become free.
+    New(usize),
+}
+
+/// Strategy picking the slots of spawned entities, set with [`World::set_entity_allocator`].
+/// Entities reserved by [`World::spawn_concurrent`] or [`World::reserve_population`] always get new slots after the last one.
+///
+/// Closures taking the free list and the number of slots are allocators too, e.g. to take ids handed out by a server.
+pub trait EntityAllocator {
+    /// Picks the slot of the next entity. `free` lists the free slots in the order they were freed, `len` is the number of slots.
+    fn allocate(&mut self, free: &[usize], len: usize) -> Slot;
+}
+
+impl<F: FnMut(&[usize], usize) -> Slot> EntityAllocator for F {
+    fn allocate(&mut self, free: &[usize], len: usize) -> Slot {
+        self(free, len)
+    }
+}
+
+/// Default allocator, which reuses the most recently freed slot and only creates new slots when none is free.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RecyclingAllocator;
+
+impl EntityAllocator for RecyclingAllocator {
+    #[inline]
+    fn allocate(&mut self, free: &[usize], len: usize) -> Slot {
+        if free.is_empty() {
+            Slot::New(len)
+        } else {
+            Slot::Free(free.len() - 1)
+        }
+    }
+}
+
+/// Allocator which never reuses a slot, so entity ids only depend on the number of spawns, e.g. for peers of a deterministic simulation.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SequentialAllocator;
+
+impl EntityAllocator for SequentialAllocator {
+    #[inline]
+    fn allocate(&mut self, _free: &[usize], len: usize) -> Slot {
+        Slot::New(len)
+    }
+}
+
+/// Allocator taking blocks of slots from a counter shared between several worlds, e.g. one per thread,
+/// so entities spawned in different worlds never get the same index and can be merged later.
+/// Slots are never reused.
+#[derive(Debug, Clone)]
+pub struct BlockAllocator {
+    counter: Arc<AtomicUsize>,
+    block: usize,
+    next: usize,
+    end: usize,
+}
+
+impl BlockAllocator {
+    /// Creates an allocator which takes `block` slots from the counter at once. Panics when `block` is zero.
+    #[must_use]
+    pub fn new(counter: Arc<AtomicUsize>, block: usize) -> Self {
+        assert!(block > 0, "Block size must be greater than zero");
+        Self {
+            counter,
+            block,
+            next: 0,
+            end: 0,
+        }
+    }
+}
+
+impl EntityAllocator for BlockAllocator {
+    fn allocate(&mut self, _free: &[usize], len: usize) -> Slot {
+        // Blocks the world grew past are skipped, e.g. after concurrent spawns
+        while self.next == self.end || self.next < len {
+            self.next = self.counter.fetch_add(self.block, Ordering::Relaxed);
+            self.end = self.next + self.block;
+        }
+        self.next += 1;
+        Slot::New(self.next - 1)
+    }
+}
+
+impl World {
+    /// Replaces the strategy picking the slots of spawned entities, e.g. with a [`SequentialAllocator`] for deterministic ids.
+    /// Slots which are free already stay free.
+    pub fn set_entity_allocator(&mut self, allocator: impl EntityAllocator + 'static) {
+        self.allocator = Box::new(allocator);
+    }
+
+    /// Allocates an entity without components through the entity allocator.
+    pub(crate) fn alloc_entity(&mut self) -> Entity {
+        self.entities.create_with(self.allocator.as_mut())
+    }
+}
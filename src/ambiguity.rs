@@ -0,0 +1,101 @@
+use std::fmt;
+
+use crate::{schedule::Schedule, world::World};
+
+/// Two systems with conflicting access and no ordering constraint between them, found by [`Schedule::ambiguities`].
+/// They run in the order they were added, so reordering the calls to [`Schedule::add_system`] changes what they observe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ambiguity {
+    first: &'static str,
+    second: &'static str,
+    components: Vec<&'static str>,
+    resources: Vec<&'static str>,
+}
+
+impl Ambiguity {
+    /// Name of the system added first, which runs first.
+    #[inline]
+    #[must_use]
+    pub fn first(&self) -> &'static str {
+        self.first
+    }
+
+    /// Name of the system added second.
+    #[inline]
+    #[must_use]
+    pub fn second(&self) -> &'static str {
+        self.second
+    }
+
+    /// Type names of the components written by one system and accessed by the other.
+    #[inline]
+    #[must_use]
+    pub fn components(&self) -> &[&'static str] {
+        &self.components
+    }
+
+    /// Type names of the resources written by one system and accessed by the other.
+    #[inline]
+    #[must_use]
+    pub fn resources(&self) -> &[&'static str] {
+        &self.resources
+    }
+}
+
+impl fmt::Display for Ambiguity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} and {} conflict on", self.first, self.second)?;
+        let conflicts = self.components.iter().chain(&self.resources);
+        for (index, name) in conflicts.enumerate() {
+            let separator = if index == 0 { " " } else { ", " };
+            write!(f, "{separator}{name}")?;
+        }
+        write!(f, " without an ordering constraint")
+    }
+}
+
+impl Schedule {
+    /// Pairs of systems whose access conflicts but which aren't ordered by constraints, with the names of the conflicting types.
+    /// [`ApplyDeferred`](crate::schedule::ApplyDeferred) sync points conflict with every system, they aren't reported.
+    /// Valid after [`Schedule::initialize`].
+    #[must_use]
+    pub fn ambiguities(&self, world: &World) -> Vec<Ambiguity> {
+        self.ambiguities
+            .iter()
+            .map(|(first, second)| {
+                let first = self.systems[*first].system.as_ref();
+                let second = self.systems[*second].system.as_ref();
+                let (ours, theirs) = (first.access(), second.access());
+
+                let components = ours
+                    .components()
+                    .conflicts(theirs.components())
+                    .into_iter()
+                    .map(|id| {
+                        world
+                            .components()
+                            .get(id)
+                            .map_or("<unregistered>", |info| info.name())
+                    })
+                    .collect();
+                let resources = ours
+                    .resources()
+                    .conflicts(theirs.resources())
+                    .into_iter()
+                    .map(|id| {
+                        ours.resource_name(id)
+                            .or_else(|| theirs.resource_name(id))
+                            .unwrap_or("<unknown>")
+                    })
+                    .collect();
+
+                Ambiguity {
+                    first: first.name(),
+                    second: second.name(),
+                    components,
+                    resources,
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,182 @@
+use std::{any::type_name, mem};
+
+use crate::{
+    error::{OnError, SystemError},
+    schedule::{IntoSystemConfigs, Schedule},
+    world::{Component, World},
+};
+
+/// A reusable feature registering its components, resources and systems into an [`App`].
+pub trait Plugin: 'static {
+    fn build(&self, app: &mut App);
+
+    /// Name of the plugin, used to find plugins which were already added.
+    fn name(&self) -> &'static str {
+        type_name::<Self>()
+    }
+}
+
+/// Plugins added together by [`App::add_plugins`], implemented for plugins and for tuples of up to 8 of them.
+pub trait Plugins<Marker> {
+    fn add_to(self, app: &mut App);
+}
+
+/// Marks the [`Plugins`] impl of plugins.
+#[doc(hidden)]
+pub struct IsPlugin;
+
+impl<P: Plugin> Plugins<IsPlugin> for P {
+    fn add_to(self, app: &mut App) {
+        let name = self.name();
+        assert!(
+            !app.plugins.contains(&name),
+            "Plugin {name} was already added"
+        );
+        app.plugins.push(name);
+        self.build(app);
+    }
+}
+
+macro_rules! impl_plugins_tuple {
+    ($($name:ident $marker:ident),*) => {
+        impl<$($name: Plugins<$marker>, $marker),*> Plugins<($($marker,)*)> for ($($name,)*) {
+            fn add_to(self, app: &mut App) {
+                #[allow(non_snake_case)]
+                let ($($name,)*) = self;
+                $($name.add_to(app);)*
+            }
+        }
+    };
+}
+
+impl_plugins_tuple!(A MA);
+impl_plugins_tuple!(A MA, B MB);
+impl_plugins_tuple!(A MA, B MB, C MC);
+impl_plugins_tuple!(A MA, B MB, C MC, D MD);
+impl_plugins_tuple!(A MA, B MB, C MC, D MD, E ME);
+impl_plugins_tuple!(A MA, B MB, C MC, D MD, E ME, F MF);
+impl_plugins_tuple!(A MA, B MB, C MC, D MD, E ME, F MF, G MG);
+impl_plugins_tuple!(A MA, B MB, C MC, D MD, E ME, F MF, G MG, H MH);
+
+type Runner = Box<dyn FnOnce(App)>;
+
+/// A world with the schedule updating it, assembled from [`Plugin`]s, e.g.
+/// `App::new().add_plugins((PhysicsPlugin, RenderPlugin)).add_systems(gameplay).run()`.
+pub struct App {
+    world: World,
+    schedule: Schedule,
+    runner: Runner,
+    /// Names of the added plugins.
+    plugins: Vec<&'static str>,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self {
+            world: World::new(),
+            schedule: Schedule::new(),
+            runner: Box::new(run_once),
+            plugins: Vec::new(),
+        }
+    }
+}
+
+/// The default runner, updating the app once.
+fn run_once(mut app: App) {
+    app.update();
+}
+
+impl App {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds the plugins in order, e.g. `app.add_plugins((InputPlugin, PhysicsPlugin))`. Panics when a plugin was already added.
+    pub fn add_plugins<M>(&mut self, plugins: impl Plugins<M>) -> &mut Self {
+        plugins.add_to(self);
+        self
+    }
+
+    /// Returns `true` when a plugin of the type was added, e.g. to add a plugin other plugins depend on only once.
+    #[must_use]
+    pub fn is_plugin_added<P: Plugin>(&self) -> bool {
+        self.plugins.contains(&type_name::<P>())
+    }
+
+    /// Adds systems to the schedule of the app like [`Schedule::add_systems`].
+    pub fn add_systems<M>(&mut self, systems: impl IntoSystemConfigs<M>) -> &mut Self {
+        self.schedule.add_systems(systems);
+        self
+    }
+
+    /// Sets the function called with the errors of fallible systems like [`Schedule::set_error_handler`].
+    pub fn set_error_handler(
+        &mut self,
+        handler: impl FnMut(SystemError) -> OnError + Send + 'static,
+    ) -> &mut Self {
+        self.schedule.set_error_handler(handler);
+        self
+    }
+
+    /// Inserts a resource into the world like [`World::insert_resource`].
+    pub fn insert_resource<R: 'static>(&mut self, value: R) -> &mut Self {
+        self.world.insert_resource(value);
+        self
+    }
+
+    /// Inserts the default value of a resource unless the world already has one.
+    pub fn init_resource<R: Default + 'static>(&mut self) -> &mut Self {
+        if !self.world.contains_resource::<R>() {
+            self.world.insert_resource(R::default());
+        }
+        self
+    }
+
+    /// Registers a component like [`World::register_component`].
+    pub fn register_component<T: Component>(&mut self) -> &mut Self {
+        self.world.register_component::<T>();
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    #[inline]
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    #[inline]
+    pub fn schedule_mut(&mut self) -> &mut Schedule {
+        &mut self.schedule
+    }
+
+    /// Runs the schedule once against the world.
+    pub fn update(&mut self) {
+        self.schedule.run(&mut self.world);
+    }
+
+    /// Sets the function driving the app in [`App::run`], e.g. a loop calling [`App::update`] every frame until the game quits.
+    /// The default runner updates the app once.
+    pub fn set_runner(&mut self, runner: impl FnOnce(App) + 'static) -> &mut Self {
+        self.runner = Box::new(runner);
+        self
+    }
+
+    /// Moves the app into its runner, leaving an empty app behind.
+    pub fn run(&mut self) {
+        let mut app = mem::take(self);
+        let runner = mem::replace(&mut app.runner, Box::new(run_once));
+        runner(app);
+    }
+}
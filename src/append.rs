@@ -0,0 +1,80 @@
+use crate::{
+    mask::ComponentMask,
+    world::{EntityMap, Location, World},
+};
+
+impl World {
+    /// Moves every entity of the other world into this one, e.g. to load a sub-scene built offline, and returns where each entity went.
+    /// Whole columns are moved at once, so no component is cloned or dropped, and every moved component is marked as changed.
+    /// Components registered in the other world are registered here together with their clone, comparison and sharing functions.
+    ///
+    /// [`Parent`](crate::hierarchy::Parent) and [`Children`](crate::hierarchy::Children) are remapped, other components holding entities
+    /// can be fixed with the returned [`EntityMap`]. Quotas are not checked, and timers, transient values and recordings of the other world are dropped.
+    pub fn append(&mut self, mut other: World) -> EntityMap {
+        for info in other.components() {
+            self.register_info(info.clone());
+            let entry = self.component_info_mut(&info.id).unwrap();
+            entry.clone = entry.clone.or(info.clone);
+            entry.eq = entry.eq.or(info.eq);
+            entry.hash = entry.hash.or(info.hash);
+            entry.shareable |= info.shareable;
+        }
+
+        let mut map = EntityMap::default();
+        let mut appended = Vec::with_capacity(other.entities.len());
+
+        // Entities without components are not stored in any archetype
+        for entity in other.entities.alive() {
+            if other.is_empty(entity) {
+                let mapped = self.alloc_entity();
+                map.insert(entity, mapped);
+                appended.push(mapped);
+            }
+        }
+
+        let tick = self.change_tick();
+        for source in other.archetypes_mut() {
+            if source.count() == 0 {
+                continue;
+            }
+
+            let bitmask = source
+                .columns()
+                .map(|(id, _)| self.bit_of_id(id).unwrap())
+                .fold(ComponentMask::EMPTY, |mask, bit| mask | bit);
+            let index = self.archetype_index(&bitmask);
+
+            let first_row = self.archetypes()[index].count();
+            let entities: Vec<_> = source
+                .entities()
+                .iter()
+                .map(|entity| {
+                    let mapped = self.alloc_entity();
+                    map.insert(*entity, mapped);
+                    mapped
+                })
+                .collect();
+            self.archetypes_mut()[index].append(source, &entities, tick);
+
+            for (offset, entity) in entities.iter().enumerate() {
+                self.entities.metas[entity.index].location = Location {
+                    archetype: index,
+                    row: first_row + offset,
+                };
+            }
+            appended.extend(entities);
+        }
+
+        for entity in appended {
+            self.remap_hierarchy(entity, &map);
+            let bitmask = self
+                .archetype_of(entity)
+                .map_or(ComponentMask::EMPTY, |archetype| {
+                    archetype.bitmask().clone()
+                });
+            self.index_spawned_name(entity, &bitmask);
+            self.log_spawn(entity);
+        }
+        map
+    }
+}
@@ -1,68 +1,208 @@
-use std::{any::TypeId, collections::HashMap};
+use std::{collections::HashMap, marker::PhantomData};
 
 use crate::{
-    blob_data::{BlobData, TypeInfo},
-    world::{Component, Entity},
+    blob_data::{BlobData, ComponentTicks, TypeInfo},
+    mask::ComponentMask,
+    world::{Component, ComponentId, Entity, WorldId},
 };
 
+/// Identifies an archetype of a world. Archetypes are only renumbered by [`World::gc_archetypes`](crate::world::World::gc_archetypes), until then the id stays the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ArchetypeId(pub(crate) usize);
+
+impl ArchetypeId {
+    #[inline]
+    #[must_use]
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// Pre-resolved archetype of the bundle `B`, returned by [`World::archetype_handle`](crate::world::World::archetype_handle).
+pub struct ArchetypeHandle<B> {
+    pub(crate) id: ArchetypeId,
+    /// World and archetype layout the id was resolved in, so handles of other worlds or from before a renumbering are rejected.
+    pub(crate) world: WorldId,
+    pub(crate) epoch: u64,
+    pub(crate) _marker: PhantomData<fn() -> B>,
+}
+
+impl<B> ArchetypeHandle<B> {
+    #[inline]
+    #[must_use]
+    pub fn id(&self) -> ArchetypeId {
+        self.id
+    }
+}
+
+impl<B> Clone for ArchetypeHandle<B> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<B> Copy for ArchetypeHandle<B> {}
+
+impl<B> std::fmt::Debug for ArchetypeHandle<B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ArchetypeHandle")
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Describes a newly created archetype, see [`World::on_archetype_created`](crate::world::World::on_archetype_created).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchetypeInfo {
+    pub id: ArchetypeId,
+    pub bitmask: ComponentMask,
+    /// Ids and names of the components stored in the archetype, in bit order.
+    pub components: Vec<(ComponentId, &'static str)>,
+}
+
+pub(crate) type ArchetypeCallback = Box<dyn FnMut(&ArchetypeInfo)>;
+
 pub struct Archetype {
-    columns: HashMap<TypeId, BlobData>,
+    columns: HashMap<ComponentId, BlobData>,
+    /// Ids of the columns in the order they were created, so iterating them doesn't depend on the hasher.
+    order: Vec<ComponentId>,
     rows: Vec<Entity>,
     count: usize,
-    bitmask: u64,
+    bitmask: ComponentMask,
+    /// Population at which compact storage is promoted to amortized growth, `0` once promoted.
+    pub(crate) compact_until: usize,
 }
 
 impl Archetype {
-    pub fn new(bitmask: u64) -> Self {
+    pub fn new(bitmask: ComponentMask) -> Self {
         Self {
             columns: HashMap::new(),
+            order: Vec::new(),
             rows: Vec::new(),
             count: 0,
             bitmask,
+            compact_until: 0,
         }
     }
 
-    pub fn with(&mut self, id: TypeId, info: TypeInfo) {
+    /// Stores the rows compactly, growing by a single row at a time, until the archetype holds `population` entities.
+    pub(crate) fn set_compact_until(&mut self, population: usize) {
+        self.compact_until = population;
+        self.update_growth();
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn is_compact(&self) -> bool {
+        self.count < self.compact_until
+    }
+
+    fn update_growth(&mut self) {
+        let compact = self.is_compact();
+        for column in self.columns.values_mut() {
+            column.set_exact_growth(compact);
+        }
+    }
+
+    pub fn with(&mut self, id: ComponentId, info: TypeInfo) {
         if self.columns.contains_key(&id) {
             return;
         }
-        self.columns.insert(id, BlobData::new(info));
+        let mut column = BlobData::new(info);
+        column.set_exact_growth(self.is_compact());
+        self.columns.insert(id, column);
+        self.order.push(id);
     }
 
-    pub fn insert<T: Component>(&mut self, mut component: T) {
+    /// Makes room for `additional` more rows in the row list and every column created so far.
+    pub fn reserve(&mut self, additional: usize) {
+        self.rows.reserve(additional);
+        for column in self.columns.values_mut() {
+            column.reserve(additional);
+        }
+    }
+
+    pub fn insert<T: Component>(&mut self, mut component: T, tick: u64) {
         let bytes = &mut component as *mut T as *mut u8;
-        self.insert_bytes(TypeId::of::<T>(), bytes);
+        unsafe {
+            self.insert_bytes(ComponentId::of::<T>(), bytes, ComponentTicks::new(tick)); // SAFETY: The bytes come from a value of the type the id belongs to
+        }
         std::mem::forget(component);
     }
 
-    pub fn insert_bytes(&mut self, id: TypeId, bytes: *mut u8) {
+    /// # Safety
+    /// Caller must ensure that `bytes` points to a valid value of the type identified by `id`.
+    /// The value is moved into the column, so it must not be used or dropped afterwards.
+    pub unsafe fn insert_bytes(&mut self, id: ComponentId, bytes: *mut u8, ticks: ComponentTicks) {
         if let Some(column) = self.columns.get_mut(&id) {
             unsafe {
-                column.push_bytes(bytes); // SAFETY: We got a TypeId -> BlobData map so the type is correct
+                column.push_bytes(bytes, ticks); // SAFETY: We got a ComponentId -> BlobData map so the type is correct
             }
         }
     }
 
     pub fn insert_row(&mut self, entity: Entity) {
+        if self.is_compact() {
+            self.rows.reserve_exact(1);
+        }
         self.count += 1;
 
         self.rows.push(entity);
+
+        // Once the archetype is populated enough, the columns switch to regular growth for good
+        if self.compact_until != 0 && !self.is_compact() {
+            self.compact_until = 0;
+            self.update_growth();
+        }
     }
 
     pub fn get<T: Component>(&self, row: usize) -> Option<&T> {
-        let typeid = TypeId::of::<T>();
+        let typeid = ComponentId::of::<T>();
 
         self.get_bytes(typeid, row)
             .map(|bytes| unsafe { &*bytes.cast() }) // SAFETY: We are getting bytes from the column containing T data, so it must be valid
     }
 
-    pub fn get_mut<T: Component>(&mut self, row: usize) -> Option<&mut T> {
-        let typeid = TypeId::of::<T>();
+    /// Returns a mutable reference to the component in the given row and marks it as changed at the given tick.
+    pub fn get_mut<T: Component>(&mut self, row: usize, tick: u64) -> Option<&mut T> {
+        let typeid = ComponentId::of::<T>();
+
+        if let Some(column) = self.columns.get(&typeid)
+            && row < self.count
+        {
+            column.set_tick(row, tick);
+        }
 
         self.get_bytes(typeid, row)
             .map(|bytes| unsafe { &mut *bytes.cast() }) // SAFETY: We are getting bytes from the column containing T data, so it must be valid
     }
 
+    /// Moves every row of the other archetype, which must have the same components, to the end of this one.
+    /// The rows are stored as the given entities and their components are marked as changed at the tick.
+    pub(crate) fn append(&mut self, other: &mut Archetype, entities: &[Entity], tick: u64) {
+        debug_assert_eq!(other.count, entities.len());
+
+        for (id, column) in &mut other.columns {
+            self.with(*id, *column.type_info());
+            self.columns.get_mut(id).unwrap().append(column, tick);
+        }
+        for entity in entities {
+            self.insert_row(*entity);
+        }
+
+        other.rows.clear();
+        other.count = 0;
+    }
+
+    /// Drops every row while keeping the columns and their allocations.
+    pub(crate) fn clear(&mut self) {
+        for column in self.columns.values_mut() {
+            column.clear();
+        }
+        self.rows.clear();
+        self.count = 0;
+    }
+
     pub fn swap_remove(&mut self, index: usize) -> Option<Entity> {
         if index >= self.count {
             return None;
@@ -76,46 +216,45 @@ impl Archetype {
         }
 
         self.count -= 1;
+        self.rows.swap_remove(index);
 
         // If the removed row was the last one, no entity has moved
         if index == self.count {
-            self.rows.pop();
             return None;
         }
-        self.rows.swap(index, self.count - 1);
-        Some(self.rows.pop().unwrap())
+        Some(self.rows[index])
     }
 
     #[must_use]
     pub fn move_to(
         &mut self,
         index: usize,
-        mut f: impl FnMut(*mut u8, TypeId, &TypeInfo),
+        mut f: impl FnMut(*mut u8, ComponentId, &TypeInfo, ComponentTicks),
     ) -> Option<Entity> {
         if index >= self.count {
             return None;
         }
 
         for (id, column) in &mut self.columns {
+            let ticks = column.ticks(index);
             unsafe {
                 let bytes = column.swap_remove(index); // SAFETY: We are checking the bounds above
-                f(bytes, *id, column.type_info());
+                f(bytes, *id, column.type_info(), ticks);
             }
         }
 
         self.count -= 1;
+        self.rows.swap_remove(index);
 
         // If the removed row was the last one, no entity has moved
         if index == self.count {
-            self.rows.pop();
             return None;
         }
-        self.rows.swap(index, self.count - 1);
-        Some(self.rows.pop().unwrap())
+        Some(self.rows[index])
     }
 
     #[must_use]
-    pub(crate) fn get_bytes(&self, typeid: TypeId, row: usize) -> Option<*mut u8> {
+    pub(crate) fn get_bytes(&self, typeid: ComponentId, row: usize) -> Option<*mut u8> {
         let column = self.columns.get(&typeid)?;
 
         if self.count > row {
@@ -133,22 +272,28 @@ impl Archetype {
         &self.rows
     }
 
+    /// Returns the columns in the order they were created.
+    #[inline]
+    pub(crate) fn columns(&self) -> impl Iterator<Item = (&ComponentId, &BlobData)> {
+        self.order.iter().map(|id| (id, &self.columns[id]))
+    }
+
     #[inline]
     #[must_use]
-    pub(crate) fn column(&self, id: &TypeId) -> Option<&BlobData> {
+    pub(crate) fn column(&self, id: &ComponentId) -> Option<&BlobData> {
         self.columns.get(id)
     }
 
     #[inline]
     #[must_use]
-    pub(crate) fn column_mut(&mut self, id: &TypeId) -> Option<&mut BlobData> {
+    pub(crate) fn column_mut(&mut self, id: &ComponentId) -> Option<&mut BlobData> {
         self.columns.get_mut(id)
     }
 
     #[inline]
     #[must_use]
-    pub(crate) fn bitmask(&self) -> u64 {
-        self.bitmask
+    pub(crate) fn bitmask(&self) -> &ComponentMask {
+        &self.bitmask
     }
 
     #[inline]
@@ -157,3 +302,95 @@ impl Archetype {
         self.count
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Archetype;
+    use crate::{
+        blob_data::TypeInfo,
+        mask::ComponentMask,
+        world::{Component, ComponentId, Entity, World},
+    };
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Name(String);
+
+    impl Component for Name {}
+
+    /// Returns an archetype of names with a row for every given name, and the entities owning the rows.
+    fn named(names: &[&str]) -> (Archetype, Vec<Entity>) {
+        let mut world = World::new();
+        let mut archetype = Archetype::new(ComponentMask::bit(0));
+        archetype.with(ComponentId::of::<Name>(), TypeInfo::of::<Name>());
+
+        let entities = names
+            .iter()
+            .map(|name| {
+                let entity = world.spawn(Name(name.to_string()));
+                archetype.insert(Name(name.to_string()), 0);
+                archetype.insert_row(entity);
+                entity
+            })
+            .collect();
+        (archetype, entities)
+    }
+
+    /// Names of the rows, checking that the row of every entity holds its own name.
+    fn rows(archetype: &Archetype, owners: &[(Entity, &str)]) -> Vec<String> {
+        (0..archetype.count())
+            .map(|row| {
+                let name = archetype.get::<Name>(row).unwrap().0.clone();
+                let entity = archetype.entities()[row];
+                let owner = owners.iter().find(|(owner, _)| *owner == entity).unwrap();
+                assert_eq!(owner.1, name, "row {row} holds the name of another entity");
+                name
+            })
+            .collect()
+    }
+
+    #[test]
+    fn swap_remove_moves_the_last_row_with_its_entity() {
+        let (mut archetype, entities) = named(&["a", "b", "c", "d"]);
+        let owners: Vec<_> = entities.iter().copied().zip(["a", "b", "c", "d"]).collect();
+
+        assert_eq!(archetype.swap_remove(1), Some(entities[3]));
+        assert_eq!(archetype.entities().len(), 3);
+        assert_eq!(rows(&archetype, &owners), ["a", "d", "c"]);
+
+        // Removing the last row moves nothing
+        assert_eq!(archetype.swap_remove(2), None);
+        assert_eq!(rows(&archetype, &owners), ["a", "d"]);
+        assert_eq!(archetype.swap_remove(2), None);
+    }
+
+    #[test]
+    fn move_to_hands_out_the_row_and_keeps_the_rest_in_sync() {
+        let (mut archetype, entities) = named(&["a", "b", "c"]);
+        let owners: Vec<_> = entities.iter().copied().zip(["a", "b", "c"]).collect();
+
+        let mut moved = Vec::new();
+        let swapped = archetype.move_to(0, |bytes, id, _, _| {
+            assert_eq!(id, ComponentId::of::<Name>());
+            // SAFETY: The bytes are the removed name, which is moved out of the column
+            moved.push(unsafe { bytes.cast::<Name>().read() });
+        });
+        assert_eq!(swapped, Some(entities[2]));
+        assert_eq!(moved, [Name("a".to_string())]);
+        assert_eq!(rows(&archetype, &owners), ["c", "b"]);
+    }
+
+    #[test]
+    fn despawning_keeps_the_components_of_the_other_entities() {
+        let mut world = World::new();
+        let entities: Vec<_> = (0..4)
+            .map(|index| world.spawn(Name(index.to_string())))
+            .collect();
+
+        world.despawn_entity(entities[0]);
+        world.despawn_entity(entities[2]);
+        assert_eq!(world.get_component::<Name>(entities[1]).unwrap().0, "1");
+        let moved = world.spawn(Name("new".to_string()));
+        assert_eq!(world.get_component::<Name>(moved).unwrap().0, "new");
+        assert_eq!(world.get_component::<Name>(entities[3]).unwrap().0, "3");
+    }
+}
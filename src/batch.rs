@@ -0,0 +1,206 @@
+use std::sync::{Arc, RwLockReadGuard};
+
+use crate::{
+    archetype::Archetype,
+    query::{Filter, QueryData, QueryItem},
+    world::World,
+};
+
+/// Rows of one archetype handed out by [`QueryBatches`], which can be sent to another thread when the items can.
+/// Iterating it fetches the items of its rows, the columns stay borrowed until it is dropped.
+pub struct QueryBatch<'a, Q: QueryItem> {
+    state: Q::State,
+    len: usize,
+    _borrows: Arc<dyn Shared + 'a>,
+}
+
+// SAFETY: The batch only points to its own rows, which no other batch fetches, so moving it is fine when its items may be moved.
+// Dropping the last batch on another thread only releases the atomic borrow flags of the columns
+unsafe impl<'a, Q: QueryItem> Send for QueryBatch<'a, Q> where Q::Item<'a>: Send {}
+
+impl<'a, Q: QueryItem> Iterator for QueryBatch<'a, Q> {
+    type Item = Q::Item<'a>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // SAFETY: The state points to one of the remaining rows of the batch
+        Some(unsafe { Q::fetch(&mut self.state) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<Q: QueryItem> ExactSizeIterator for QueryBatch<'_, Q> {}
+
+/// Splits the matching rows into batches of at most the given size, returned by [`QueryData::batches`].
+/// Batches never span archetypes, and the columns stay borrowed until this iterator and every batch it handed out are dropped.
+pub struct QueryBatches<'a, Q: QueryItem, F: Filter> {
+    borrows: Arc<Borrows<'a, Q, F>>,
+    archetypes: &'a [Archetype],
+    matching: RwLockReadGuard<'a, Vec<usize>>,
+    state: Option<Q::State>,
+    tick: Option<u64>,
+    size: usize,
+    cursor: usize,
+    row: usize,
+}
+
+impl<'a, Q: QueryItem, F: Filter> Iterator for QueryBatches<'a, Q, F> {
+    type Item = QueryBatch<'a, Q>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let archetype = &self.archetypes[*self.matching.get(self.cursor)?];
+            let count = archetype.count();
+            if self.row >= count {
+                self.cursor += 1;
+                self.row = 0;
+                self.state = None;
+                continue;
+            }
+
+            // SAFETY: The archetype matches the query and its columns are borrowed until the batches are dropped
+            let base = self
+                .state
+                .get_or_insert_with(|| unsafe { Q::state(archetype, self.tick) });
+            let mut state = base.clone();
+            unsafe { Q::skip(&mut state, self.row) };
+
+            let len = self.size.min(count - self.row);
+            self.row += len;
+            return Some(QueryBatch {
+                state,
+                len,
+                _borrows: self.borrows.clone(),
+            });
+        }
+    }
+}
+
+/// Column borrows of [`QueryBatches`], shared with the batches and released when the last of them is dropped.
+pub(crate) struct Borrows<'a, Q: QueryItem, F: Filter> {
+    pub(crate) data: &'a QueryData<Q, F>,
+    pub(crate) archetypes: &'a [Archetype],
+}
+
+impl<Q: QueryItem, F: Filter> Drop for Borrows<'_, Q, F> {
+    fn drop(&mut self) {
+        self.data.release(self.archetypes);
+    }
+}
+
+/// Column borrows held by a batch, without the filter in its type.
+pub(crate) trait Shared {}
+
+impl<Q: QueryItem, F: Filter> Shared for Borrows<'_, Q, F> {}
+
+impl<'a, Q: QueryItem, F: Filter> QueryData<Q, F> {
+    /// Splits the matching rows into independent batches of at most `size` rows, e.g. to distribute them over the threads of a job system.
+    /// Panics when `size` is zero or with row filters like [`Changed`](crate::query::Changed).
+    pub fn batches(&'a mut self, world: &'a World, size: usize) -> QueryBatches<'a, Q, F> {
+        assert!(size > 0, "Batch size must be greater than zero");
+        assert!(
+            !F::filters_rows(),
+            "Cannot split a query with row filters into batches"
+        );
+        self.update_cache(world);
+        self.borrow(world.archetypes());
+        let (tick, _) = self.advance(world);
+
+        QueryBatches {
+            borrows: Arc::new(Borrows {
+                data: self,
+                archetypes: world.archetypes(),
+            }),
+            archetypes: world.archetypes(),
+            matching: self.matching(),
+            state: None,
+            tick,
+            size,
+            cursor: 0,
+            row: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::world::{Component, World};
+
+    #[derive(Debug, PartialEq)]
+    struct Value(u32);
+
+    impl Component for Value {}
+
+    #[test]
+    fn batches_cover_every_row_once() {
+        let mut world = World::new();
+        world.register_thread_safe::<Value>();
+        for value in 0..10 {
+            world.spawn(Value(value));
+        }
+        let mut query = world.query::<&mut Value>();
+
+        let batches: Vec<_> = query.batches(&world, 4).collect();
+        assert_eq!(
+            batches
+                .iter()
+                .map(ExactSizeIterator::len)
+                .collect::<Vec<_>>(),
+            [4, 4, 2]
+        );
+        thread::scope(|scope| {
+            for batch in batches {
+                scope.spawn(move || batch.for_each(|value| value.0 += 100));
+            }
+        });
+
+        let mut reader = world.query::<&Value>();
+        let mut values: Vec<_> = reader.iter(&world).map(|value| value.0).collect();
+        values.sort_unstable();
+        assert_eq!(values, (100..110).collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[should_panic(expected = "Conflicting Queries Detected")]
+    fn batches_keep_the_columns_borrowed_after_the_iterator() {
+        let mut world = World::new();
+        world.spawn(Value(1));
+        let mut query = world.query::<&mut Value>();
+        let mut reader = world.query::<&Value>();
+
+        let batch = query.batches(&world, 8).next().unwrap();
+        reader.iter(&world).count();
+        drop(batch);
+    }
+
+    #[test]
+    fn dropping_the_last_batch_releases_the_columns() {
+        let mut world = World::new();
+        world.spawn(Value(1));
+        let mut query = world.query::<&mut Value>();
+        let mut reader = world.query::<&Value>();
+
+        let batch = query.batches(&world, 8).next().unwrap();
+        thread::scope(|scope| {
+            scope.spawn(move || drop(batch));
+        });
+        assert_eq!(reader.iter(&world).count(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Batch size must be greater than zero")]
+    fn batches_must_not_be_empty() {
+        let mut world = World::new();
+        world.spawn(Value(1));
+        world.query::<&Value>().batches(&world, 0);
+    }
+}
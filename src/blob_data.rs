@@ -1,4 +1,4 @@
-use std::{alloc::Layout, ptr::NonNull};
+use std::{alloc::Layout, cell::Cell, ops::Deref, ptr::NonNull};
 
 use crate::borrow::AtomicBorrow;
 
@@ -8,6 +8,29 @@ pub struct BlobData {
     len: usize,
     capacity: usize,
     borrow: AtomicBorrow,
+    ticks: Vec<Cell<u64>>,
+    /// Ticks at which the values were added to their entities, they survive archetypal moves.
+    added: Vec<u64>,
+    exact: bool,
+}
+
+/// Ticks at which a component was added to its entity and last changed, see [`World::component_ticks`](crate::world::World::component_ticks).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComponentTicks {
+    pub added: u64,
+    pub changed: u64,
+}
+
+impl ComponentTicks {
+    /// Ticks of a value added at the given tick.
+    #[inline]
+    #[must_use]
+    pub fn new(tick: u64) -> Self {
+        Self {
+            added: tick,
+            changed: tick,
+        }
+    }
 }
 
 impl BlobData {
@@ -18,6 +41,9 @@ impl BlobData {
             len: 0,
             capacity: 0,
             borrow: AtomicBorrow::new(),
+            ticks: Vec::new(),
+            added: Vec::new(),
+            exact: false,
         }
     }
 
@@ -32,15 +58,14 @@ impl BlobData {
 
         unsafe {
             let new_buffer = if let Some(ptr) = self.ptr {
-                let buff = std::alloc::realloc(
+                std::alloc::realloc(
                     ptr.as_ptr(),
                     Layout::from_size_align_unchecked(
                         self.info.size * self.capacity,
                         self.info.align,
                     ),
                     self.info.size * new_capacity,
-                );
-                buff
+                )
             } else {
                 std::alloc::alloc(Layout::from_size_align_unchecked(
                     self.info.size * new_capacity,
@@ -53,11 +78,26 @@ impl BlobData {
         }
     }
 
-    pub fn push<T>(&mut self, mut value: T) {
+    /// Makes room for at least `additional` more values, so pushing them doesn't reallocate.
+    pub fn reserve(&mut self, additional: usize) {
+        self.ticks.reserve(additional);
+        self.added.reserve(additional);
+        let needed = self.len + additional;
+        if needed > self.capacity {
+            self.allocate(needed);
+        }
+    }
+
+    pub fn push<T>(&mut self, value: T, tick: u64) {
+        self.push_with_ticks(value, ComponentTicks::new(tick));
+    }
+
+    pub(crate) fn push_with_ticks<T>(&mut self, value: T, ticks: ComponentTicks) {
         debug_assert!(self.info.validate::<T>());
 
+        let mut value = std::mem::ManuallyDrop::new(value);
         unsafe {
-            self.push_bytes((&mut value as *mut T).cast());
+            self.push_bytes((&mut *value as *mut T).cast(), ticks);
         }
     }
 
@@ -76,7 +116,7 @@ impl BlobData {
     }
 
     #[must_use]
-    pub fn get_mut<T>(&self, index: usize) -> Option<&mut T> {
+    pub fn get_mut<T>(&mut self, index: usize) -> Option<&mut T> {
         debug_assert!(self.info.validate::<T>());
 
         if index >= self.len {
@@ -89,10 +129,64 @@ impl BlobData {
         }
     }
 
+    /// Moves every value of the other column, which must store the same type, to the end of this one and marks them as changed at the tick.
+    pub(crate) fn append(&mut self, other: &mut BlobData, tick: u64) {
+        debug_assert!(self.info.size == other.info.size && self.info.align == other.info.align);
+
+        let additional = other.len;
+        self.reserve(additional);
+        if self.info.size != 0 && additional != 0 {
+            unsafe {
+                // SAFETY: Room for the values was reserved above, and the other column forgets them below so they are moved
+                std::ptr::copy_nonoverlapping(
+                    other.ptr.unwrap().as_ptr(),
+                    self.spare_ptr(),
+                    additional * self.info.size,
+                );
+            }
+        }
+        unsafe {
+            self.assume_pushed(additional, tick);
+        }
+
+        other.len = 0;
+        other.ticks.clear();
+        other.added.clear();
+    }
+
+    /// Drops every value while keeping the allocation.
+    pub(crate) fn clear(&mut self) {
+        if self.info.size != 0 {
+            for i in 0..self.len {
+                unsafe {
+                    (self.info.drop)(self.ptr.unwrap().as_ptr().add(i * self.info.size));
+                }
+            }
+        }
+        self.len = 0;
+        self.ticks.clear();
+        self.added.clear();
+    }
+
+    /// Grows the buffer by a single row instead of doubling it, so columns of rarely populated archetypes don't hold unused capacity.
+    #[inline]
+    pub(crate) fn set_exact_growth(&mut self, exact: bool) {
+        self.exact = exact;
+    }
+
     /// Caller must ensure that the bytes have the same layout as the type that this blob data was created for
-    pub(crate) unsafe fn push_bytes(&mut self, bytes: *mut u8) {
+    pub(crate) unsafe fn push_bytes(&mut self, bytes: *mut u8, ticks: ComponentTicks) {
+        if self.exact {
+            self.ticks.reserve_exact(1);
+            self.added.reserve_exact(1);
+        }
+        self.ticks.push(Cell::new(ticks.changed));
+        self.added.push(ticks.added);
+
         if self.len == self.capacity {
-            self.allocate(if self.capacity == 0 {
+            self.allocate(if self.exact {
+                self.capacity + 1
+            } else if self.capacity == 0 {
                 8
             } else {
                 self.capacity * 2
@@ -148,6 +242,8 @@ impl BlobData {
 
             std::ptr::swap_nonoverlapping(a_ptr, b_ptr, self.info.size);
         }
+        self.ticks.swap(a, b);
+        self.added.swap(a, b);
     }
 
     /// Caller must ensure that the length is not zero
@@ -162,6 +258,8 @@ impl BlobData {
                 .as_ptr()
                 .add((self.len - 1) * self.info.size);
             self.len -= 1;
+            self.ticks.pop();
+            self.added.pop();
             last_ptr
         }
     }
@@ -175,6 +273,7 @@ impl BlobData {
         unsafe { self.ptr.unwrap().as_ptr().add(index * self.info.size) }
     }
 
+    /// # Safety
     /// Caller must ensure that the allocation exists and the generic type has exactly the same layout as the stored one
     #[inline]
     #[must_use]
@@ -187,6 +286,7 @@ impl BlobData {
         self.ptr.unwrap().as_ptr().cast::<T>()
     }
 
+    /// # Safety
     /// Caller must ensure that the allocation exists and the generic type has exactly the same layout as the stored one
     #[inline]
     #[must_use]
@@ -199,6 +299,80 @@ impl BlobData {
         self.ptr.unwrap().as_ptr().cast::<T>()
     }
 
+    /// Pointer to the first slot past the stored values, where reserved values are written before [`BlobData::assume_pushed`].
+    ///
+    /// # Safety
+    /// Caller must ensure that the column has allocated capacity.
+    #[inline]
+    #[must_use]
+    pub(crate) unsafe fn spare_ptr(&self) -> *mut u8 {
+        unsafe { self.ptr.unwrap().as_ptr().add(self.len * self.info.size) }
+    }
+
+    /// Marks `additional` values written past the stored ones as pushed at the given tick.
+    ///
+    /// # Safety
+    /// Caller must ensure that the slots were reserved and initialized with valid values of the stored type.
+    pub(crate) unsafe fn assume_pushed(&mut self, additional: usize, tick: u64) {
+        debug_assert!(self.len + additional <= self.capacity);
+        self.ticks
+            .extend(std::iter::repeat_with(|| Cell::new(tick)).take(additional));
+        self.added.extend(std::iter::repeat_n(tick, additional));
+        self.len += additional;
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Number of stored values, of their change ticks and of their added ticks, which must be the same.
+    #[cfg(feature = "consistency")]
+    #[inline]
+    #[must_use]
+    pub(crate) fn lens(&self) -> (usize, usize, usize) {
+        (self.len, self.ticks.len(), self.added.len())
+    }
+
+    /// Returns the tick at which the value in the given row was last changed.
+    #[inline]
+    #[must_use]
+    pub(crate) fn tick(&self, index: usize) -> u64 {
+        self.ticks[index].get()
+    }
+
+    #[inline]
+    pub(crate) fn set_tick(&self, index: usize, tick: u64) {
+        self.ticks[index].set(tick);
+    }
+
+    #[inline]
+    pub(crate) fn set_added(&mut self, index: usize, tick: u64) {
+        self.added[index] = tick;
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn ticks(&self, index: usize) -> ComponentTicks {
+        ComponentTicks {
+            added: self.added[index],
+            changed: self.ticks[index].get(),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn ticks_ptr(&self) -> *const Cell<u64> {
+        self.ticks.as_ptr()
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn added_ptr(&self) -> *const u64 {
+        self.added.as_ptr()
+    }
+
     #[inline]
     #[must_use]
     pub(crate) fn borrow(&self) -> bool {
@@ -228,6 +402,33 @@ impl BlobData {
     }
 }
 
+/// Shared borrow of a column which is released when dropped, so a panic while reading the column doesn't leave it borrowed.
+pub(crate) struct ColumnGuard<'a>(&'a BlobData);
+
+impl<'a> ColumnGuard<'a> {
+    /// Borrows the column, panics with `Cannot {action} a column which is mutably borrowed` when it can't be.
+    pub(crate) fn new(column: &'a BlobData, action: &str) -> Self {
+        if !column.borrow() {
+            panic!("Cannot {action} a column which is mutably borrowed");
+        }
+        Self(column)
+    }
+}
+
+impl Deref for ColumnGuard<'_> {
+    type Target = BlobData;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl Drop for ColumnGuard<'_> {
+    fn drop(&mut self) {
+        self.0.release();
+    }
+}
+
 impl Drop for BlobData {
     fn drop(&mut self) {
         if self.info.size == 0 {
@@ -283,12 +484,26 @@ impl TypeInfo {
         drop::<T>
     }
 
+    /// # Safety
+    /// Caller must ensure that `ptr` points to a valid value of the type this info was created for.
     pub unsafe fn call_drop(&self, ptr: *mut u8) {
         unsafe {
             (self.drop)(ptr);
         }
     }
 
+    #[inline]
+    #[must_use]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn align(&self) -> usize {
+        self.align
+    }
+
     #[inline]
     #[must_use]
     pub fn validate<T>(&self) -> bool {
@@ -0,0 +1,117 @@
+use crate::{
+    mask::ComponentMask,
+    world::{Component, ComponentId, World},
+};
+
+/// Registers a component type and returns its bit, like [`World::register_component`].
+type RegisterFn = fn(&mut World) -> ComponentMask;
+
+/// Configures a [`World`] before it is created, returned by [`World::builder`].
+pub struct WorldBuilder {
+    entity_capacity: usize,
+    archetype_capacity: usize,
+    compact_threshold: Option<usize>,
+    change_detection: bool,
+    deterministic: bool,
+    column_capacities: Vec<(RegisterFn, ComponentId, usize)>,
+}
+
+impl Default for WorldBuilder {
+    fn default() -> Self {
+        Self {
+            entity_capacity: 0,
+            archetype_capacity: 0,
+            compact_threshold: None,
+            change_detection: true,
+            deterministic: false,
+            column_capacities: Vec::new(),
+        }
+    }
+}
+
+impl WorldBuilder {
+    /// Reserves room for the given number of entities, so spawning them doesn't regrow the entity slots.
+    #[must_use]
+    pub fn entity_capacity(mut self, capacity: usize) -> Self {
+        self.entity_capacity = capacity;
+        self
+    }
+
+    /// Reserves room for the given number of archetypes and their lookup.
+    #[must_use]
+    pub fn archetype_capacity(mut self, capacity: usize) -> Self {
+        self.archetype_capacity = capacity;
+        self
+    }
+
+    /// Sets the threshold of compact archetype storage, see [`World::set_compact_threshold`].
+    #[must_use]
+    pub fn compact_threshold(mut self, threshold: Option<usize>) -> Self {
+        self.compact_threshold = threshold;
+        self
+    }
+
+    /// Registers `T` and makes every archetype with `T` start with room for `rows` values in its column, e.g. for a component spawned in bulk.
+    #[must_use]
+    pub fn column_capacity<T: Component>(mut self, rows: usize) -> Self {
+        self.column_capacities
+            .push((World::register_component::<T>, ComponentId::of::<T>(), rows));
+        self
+    }
+
+    /// Turns off the change ticks written by mutable query items, which saves a write for every fetched row. Enabled by default.
+    /// Queries with [`Added`](crate::query::Added) or [`Changed`](crate::query::Changed) panic without it, and features comparing change ticks,
+    /// like [`World::extract_into`], miss the changes made through queries.
+    #[must_use]
+    pub fn change_detection(mut self, enabled: bool) -> Self {
+        self.change_detection = enabled;
+        self
+    }
+
+    /// Makes queries visit archetypes ordered by their component bitmask instead of their creation order, e.g. for replays and lockstep
+    /// simulations, so the iteration order only depends on the registration order of the components and the order of the rows.
+    /// Columns are always visited in the order they were created. Disabled by default, since creating an archetype has to sort it into the match lists.
+    #[must_use]
+    pub fn deterministic_order(mut self, enabled: bool) -> Self {
+        self.deterministic = enabled;
+        self
+    }
+
+    #[must_use]
+    pub fn build(self) -> World {
+        let mut world = World::new();
+        world.entities.metas.reserve(self.entity_capacity);
+        world.reserve_archetypes(self.archetype_capacity);
+        world.set_compact_threshold(self.compact_threshold);
+        world.change_detection = self.change_detection;
+        world.deterministic = self.deterministic;
+
+        for (register, id, rows) in self.column_capacities {
+            register(&mut world);
+            world.component_info_mut(&id).unwrap().column_capacity = rows;
+        }
+        world
+    }
+}
+
+impl World {
+    /// Returns a builder configuring the world before it is created, e.g. `World::builder().entity_capacity(10_000).build()`.
+    #[must_use]
+    pub fn builder() -> WorldBuilder {
+        WorldBuilder::default()
+    }
+
+    /// Returns `false` when mutable query items don't write change ticks, see [`WorldBuilder::change_detection`].
+    #[inline]
+    #[must_use]
+    pub fn change_detection(&self) -> bool {
+        self.change_detection
+    }
+
+    /// Returns `true` when queries visit archetypes by bitmask, see [`WorldBuilder::deterministic_order`].
+    #[inline]
+    #[must_use]
+    pub fn deterministic_order(&self) -> bool {
+        self.deterministic
+    }
+}
@@ -1,15 +1,48 @@
-use std::any::TypeId;
-
 use crate::{
     archetype::Archetype,
     blob_data::TypeInfo,
-    world::{Component, Entity, World},
+    mask::ComponentMask,
+    world::{Component, ComponentId, Entity, World},
 };
 
 pub trait Bundle {
     fn register(world: &mut World);
-    fn bitmask(world: &World) -> u64;
-    fn put(self, entity: Entity, archetype: &mut Archetype);
+    fn bitmask(world: &World) -> ComponentMask;
+    /// Creates the columns of every component in the bundle.
+    fn init_columns(archetype: &mut Archetype);
+    fn put(self, entity: Entity, archetype: &mut Archetype, tick: u64);
+    /// Ids of the components, in the order [`Bundle::write`] expects their columns.
+    fn component_ids() -> Vec<ComponentId>;
+    /// Moves the components into reserved slots of their columns, `columns` holds the first free slot of each column in the order of [`Bundle::component_ids`].
+    ///
+    /// # Safety
+    /// Caller must ensure that every column has room for the value at `offset` past the given slot and that the slot is not initialized.
+    unsafe fn write(self, columns: &[*mut u8], offset: usize);
+    /// Moves the components out of the given values, which are in the order of [`Bundle::component_ids`].
+    ///
+    /// # Safety
+    /// Caller must ensure that every pointer points to a valid value of its component, which must not be used or dropped afterwards.
+    unsafe fn read(values: &[*const u8]) -> Self;
+}
+
+/// # Safety
+/// Caller must ensure that the column stores `T` and has a reserved, uninitialized slot at `offset`.
+#[inline(always)]
+unsafe fn write_component<T>(column: *mut u8, offset: usize, value: T) {
+    // Columns of zero sized types have no allocation and are never dropped, the same as when they are pushed
+    if std::mem::size_of::<T>() == 0 {
+        std::mem::forget(value);
+        return;
+    }
+    unsafe { column.cast::<T>().add(offset).write(value) }
+}
+
+/// # Safety
+/// Caller must ensure that the pointer points to a valid value of `T`.
+#[inline(always)]
+unsafe fn read_component<T>(value: *const u8) -> T {
+    // Values of zero sized types come from columns without an allocation, which may be unaligned
+    unsafe { value.cast::<T>().read_unaligned() }
 }
 
 impl<T0: Component> Bundle for T0 {
@@ -17,16 +50,32 @@ impl<T0: Component> Bundle for T0 {
         world.register_component::<T0>();
     }
 
-    fn bitmask(world: &World) -> u64 {
+    fn bitmask(world: &World) -> ComponentMask {
         world.bit_of::<T0>().unwrap()
     }
 
-    fn put(self, entity: Entity, archetype: &mut Archetype) {
-        archetype.with(TypeId::of::<T0>(), TypeInfo::of::<T0>());
+    fn init_columns(archetype: &mut Archetype) {
+        archetype.with(ComponentId::of::<T0>(), TypeInfo::of::<T0>());
+    }
+
+    fn put(self, entity: Entity, archetype: &mut Archetype, tick: u64) {
+        Self::init_columns(archetype);
 
-        archetype.insert(self);
+        archetype.insert(self, tick);
         archetype.insert_row(entity);
     }
+
+    fn component_ids() -> Vec<ComponentId> {
+        vec![ComponentId::of::<T0>()]
+    }
+
+    unsafe fn write(self, columns: &[*mut u8], offset: usize) {
+        unsafe { write_component(columns[0], offset, self) }
+    }
+
+    unsafe fn read(values: &[*const u8]) -> Self {
+        unsafe { read_component(values[0]) }
+    }
 }
 
 macro_rules! impl_bundle_for_tuple {
@@ -38,23 +87,43 @@ macro_rules! impl_bundle_for_tuple {
                 )*
             }
 
-            fn bitmask(world: &World) -> u64 {
+            fn bitmask(world: &World) -> ComponentMask {
                 $(
                     world.bit_of::<$T>().unwrap() |
-                )* 0
+                )* ComponentMask::EMPTY
             }
 
-            fn put(self, entity: Entity, archetype: &mut Archetype) {
+            fn init_columns(archetype: &mut Archetype) {
                 $(
-                    archetype.with(TypeId::of::<$T>(), TypeInfo::of::<$T>());
+                    archetype.with(ComponentId::of::<$T>(), TypeInfo::of::<$T>());
                 )*
+            }
+
+            fn put(self, entity: Entity, archetype: &mut Archetype, tick: u64) {
+                Self::init_columns(archetype);
 
                 $(
-                    archetype.insert(self.$N);
+                    archetype.insert(self.$N, tick);
                 )*
 
                 archetype.insert_row(entity);
             }
+
+            fn component_ids() -> Vec<ComponentId> {
+                vec![$(ComponentId::of::<$T>()),*]
+            }
+
+            unsafe fn write(self, columns: &[*mut u8], offset: usize) {
+                unsafe {
+                    $(
+                        write_component(columns[$N], offset, self.$N);
+                    )*
+                }
+            }
+
+            unsafe fn read(values: &[*const u8]) -> Self {
+                unsafe { ($(read_component::<$T>(values[$N])),*) }
+            }
         }
     };
 }
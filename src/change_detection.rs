@@ -0,0 +1,238 @@
+use std::{
+    cell::Cell,
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{
+    archetype::Archetype,
+    mask::ComponentMask,
+    query::{Access, Filter, QueryItem},
+    world::{Component, ComponentId, World},
+};
+
+/// Mutable access to a component which only marks it as changed when it is written, e.g. `QueryData<Mut<Health>>`.
+/// Unlike `&mut T`, iterating without writing doesn't make every row match [`Changed`](crate::query::Changed).
+pub struct Mut<'a, T> {
+    value: &'a mut T,
+    changed: &'a Cell<u64>,
+    /// `None` when change detection is disabled.
+    tick: Option<u64>,
+}
+
+impl<'a, T> Mut<'a, T> {
+    /// Marks the component as changed without writing it.
+    #[inline]
+    pub fn set_changed(&mut self) {
+        if let Some(tick) = self.tick {
+            self.changed.set(tick);
+        }
+    }
+
+    /// Returns the component without marking it as changed, e.g. to update a cache which other systems don't care about.
+    #[inline]
+    #[must_use]
+    pub fn bypass_change_detection(&mut self) -> &mut T {
+        self.value
+    }
+
+    /// Returns the component for the rest of the iteration and marks it as changed.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(mut self) -> &'a mut T {
+        self.set_changed();
+        self.value
+    }
+
+    /// Tick at which the component was last changed.
+    #[inline]
+    #[must_use]
+    pub fn last_changed(&self) -> u64 {
+        self.changed.get()
+    }
+}
+
+impl<T> Deref for Mut<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T> DerefMut for Mut<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        self.set_changed();
+        self.value
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Mut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<T: Component> QueryItem for Mut<'_, T> {
+    type Item<'a> = Mut<'a, T>;
+    type State = (*mut T, *const Cell<u64>, Option<u64>);
+
+    #[inline(always)]
+    fn borrow(archetype: &Archetype) -> bool {
+        <&mut T>::borrow(archetype)
+    }
+
+    #[inline(always)]
+    fn release(archetype: &Archetype) {
+        <&mut T>::release(archetype);
+    }
+
+    fn access(access: &mut Access) {
+        <&mut T>::access(access);
+    }
+
+    #[inline(always)]
+    unsafe fn state(archetype: &Archetype, tick: Option<u64>) -> Self::State {
+        let column = archetype.column(&ComponentId::of::<T>()).unwrap();
+        unsafe { (column.as_mut_ptr(), column.ticks_ptr(), tick) }
+    }
+
+    #[inline(always)]
+    unsafe fn fetch<'a>(state: &mut Self::State) -> Self::Item<'a> {
+        let (data, ticks, tick) = state;
+        unsafe {
+            let item = Mut {
+                value: &mut **data,
+                changed: &**ticks,
+                tick: *tick,
+            };
+            *data = data.add(1);
+            *ticks = ticks.add(1);
+            item
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn skip(state: &mut Self::State, rows: usize) {
+        unsafe { <&mut T>::skip(state, rows) }
+    }
+}
+
+impl<T: Component> Filter for Mut<'_, T> {
+    #[inline(always)]
+    fn bitmask(world: &World) -> (ComponentMask, ComponentMask) {
+        <&mut T>::bitmask(world)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Mut;
+    use crate::{
+        query::{Changed, QueryData},
+        world::{Component, Entity, World},
+    };
+
+    #[derive(Debug, PartialEq)]
+    struct Health(u32);
+
+    impl Component for Health {}
+
+    /// Returns a world with four healthy entities and a query which has already consumed their insertion.
+    fn consumed() -> (World, Vec<Entity>, QueryData<Entity, Changed<Health>>) {
+        let mut world = World::new();
+        let entities = (0..4).map(|health| world.spawn(Health(health))).collect();
+        let mut changed = QueryData::<Entity, Changed<Health>>::new(&world);
+        assert_eq!(changed.iter(&world).count(), 4);
+        (world, entities, changed)
+    }
+
+    #[test]
+    fn reading_through_mut_leaves_rows_unchanged() {
+        let (mut world, _, mut changed) = consumed();
+
+        let total: u32 = world
+            .query::<Mut<Health>>()
+            .iter(&world)
+            .map(|health| health.0)
+            .sum();
+        assert_eq!(total, 6);
+        assert_eq!(changed.iter(&world).count(), 0);
+    }
+
+    #[test]
+    fn writing_through_mut_marks_only_written_rows() {
+        let (mut world, entities, mut changed) = consumed();
+
+        for mut health in world.query::<Mut<Health>>().iter(&world) {
+            if health.0 % 2 == 1 {
+                health.0 += 10;
+            }
+        }
+        let mut marked = changed.collect_entities(&world);
+        marked.sort_unstable_by_key(|entity| entity.index);
+        assert_eq!(marked, [entities[1], entities[3]]);
+        assert_eq!(
+            world.get_component::<Health>(entities[3]),
+            Some(&Health(13))
+        );
+
+        // The next tick sees no new changes
+        assert_eq!(changed.iter(&world).count(), 0);
+    }
+
+    #[test]
+    fn escape_hatches_mark_or_skip_explicitly() {
+        let (mut world, entities, mut changed) = consumed();
+
+        let mut query = world.query::<(Entity, Mut<Health>)>();
+        for (entity, mut health) in query.iter(&world) {
+            if entity == entities[0] {
+                health.set_changed();
+            } else if entity == entities[1] {
+                health.into_inner().0 = 20;
+            } else {
+                health.bypass_change_detection().0 = 30;
+            }
+        }
+        let mut marked = changed.collect_entities(&world);
+        marked.sort_unstable_by_key(|entity| entity.index);
+        assert_eq!(marked, [entities[0], entities[1]]);
+        assert_eq!(
+            world.get_component::<Health>(entities[2]),
+            Some(&Health(30))
+        );
+    }
+
+    #[test]
+    fn last_changed_reports_the_tick_of_the_write() {
+        let (mut world, entities, _) = consumed();
+        let inserted = world
+            .component_ticks::<Health>(entities[0])
+            .unwrap()
+            .changed;
+
+        let mut query = world.query::<Mut<Health>>();
+        let mut health = query.iter(&world).next().unwrap();
+        assert_eq!(health.last_changed(), inserted);
+        health.0 = 1;
+        assert_eq!(health.last_changed(), world.change_tick());
+        assert!(health.last_changed() > inserted);
+    }
+
+    #[test]
+    fn mut_writes_no_ticks_without_change_detection() {
+        let mut world = World::builder().change_detection(false).build();
+        let entity = world.spawn(Health(1));
+        let before = world.component_ticks::<Health>(entity).unwrap();
+
+        for mut health in world.query::<Mut<Health>>().iter(&world) {
+            health.0 = 2;
+            health.set_changed();
+        }
+        assert_eq!(world.get_component::<Health>(entity), Some(&Health(2)));
+        assert_eq!(world.component_ticks::<Health>(entity).unwrap(), before);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::world::{ComponentId, Entity, World};
+
+/// A component-level change published by [`World::flush`]. Only components registered with [`World::register_serializable`] are reported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// The component was inserted or mutably accessed, the payload holds its serialized value.
+    Changed {
+        entity: Entity,
+        component: ComponentId,
+        name: &'static str,
+        payload: Vec<u8>,
+        tick: u64,
+    },
+    /// The component was removed from an entity which is still alive.
+    Removed {
+        entity: Entity,
+        component: ComponentId,
+        name: &'static str,
+        tick: u64,
+    },
+    /// The entity was despawned together with all of its components.
+    Despawned { entity: Entity, tick: u64 },
+}
+
+/// Receives the changes published by every [`World::flush`]. Removals and despawns come first in the order they happened, followed by the changed values.
+pub trait ChangeSink: 'static {
+    fn on_changes(&mut self, changes: &[Change]);
+}
+
+impl<F: FnMut(&[Change]) + 'static> ChangeSink for F {
+    fn on_changes(&mut self, changes: &[Change]) {
+        self(changes)
+    }
+}
+
+/// Handle returned by [`World::subscribe`], used to remove the sink again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+/// Subscribed sinks and the changes recorded for them since the last flush.
+#[derive(Default)]
+pub(crate) struct ChangeLog {
+    sinks: Vec<(SubscriptionId, Box<dyn ChangeSink>)>,
+    next_id: u64,
+    pending: Vec<Change>,
+    last_flush: u64,
+}
+
+impl World {
+    /// Registers a sink which receives the component changes published by each [`World::flush`].
+    /// The first flush after subscribing reports the changes made since the previous flush, serialize the world first to start from its full state.
+    pub fn subscribe(&mut self, sink: impl ChangeSink) -> SubscriptionId {
+        let log = &mut self.change_log;
+        let id = SubscriptionId(log.next_id);
+        log.next_id += 1;
+        log.sinks.push((id, Box::new(sink)));
+        id
+    }
+
+    /// Removes a sink registered with [`World::subscribe`], returns `false` when it was removed already.
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let log = &mut self.change_log;
+        let len = log.sinks.len();
+        log.sinks.retain(|(sink_id, _)| *sink_id != id);
+        if log.sinks.is_empty() {
+            log.pending.clear();
+        }
+        log.sinks.len() != len
+    }
+
+    /// Ends the frame: applies the despawns queued by [`World::despawn_deferred`], publishes the changes made since the previous flush to every subscribed sink,
+    /// ends the recorded frame, drops the transient values and advances the change tick.
+    pub fn flush(&mut self) {
+        self.apply_deferred_despawns();
+
+        if !self.change_log.sinks.is_empty() {
+            let mut changes = std::mem::take(&mut self.change_log.pending);
+            self.collect_changed(self.change_log.last_flush, &mut changes);
+
+            for (_, sink) in &mut self.change_log.sinks {
+                sink.on_changes(&changes);
+            }
+        }
+
+        self.log_frame();
+        self.clear_transients();
+        self.change_log.last_flush = self.increment_change_tick();
+    }
+
+    /// Serializes every component changed after the given tick.
+    fn collect_changed(&self, since: u64, changes: &mut Vec<Change>) {
+        for archetype in self.archetypes() {
+            for column in self.serializable_columns(archetype, "flush") {
+                for (row, entity) in archetype.entities().iter().enumerate() {
+                    let tick = column.column.tick(row);
+                    if tick <= since {
+                        continue;
+                    }
+
+                    let mut payload = Vec::new();
+                    column
+                        .encode(row, &mut payload)
+                        .expect("Failed to serialize a changed component");
+                    changes.push(Change::Changed {
+                        entity: *entity,
+                        component: column.id,
+                        name: column.fns.name,
+                        payload,
+                        tick,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Records the removal of a component if anyone is subscribed and the component is serializable.
+    pub(crate) fn record_removed(&mut self, entity: Entity, component: ComponentId) {
+        if self.change_log.sinks.is_empty() {
+            return;
+        }
+
+        if let Some(fns) = self.serializers.get(&component) {
+            self.change_log.pending.push(Change::Removed {
+                entity,
+                component,
+                name: fns.name,
+                tick: self.change_tick(),
+            });
+        }
+    }
+
+    /// Records a despawn if anyone is subscribed.
+    pub(crate) fn record_despawned(&mut self, entity: Entity) {
+        if self.change_log.sinks.is_empty() {
+            return;
+        }
+
+        self.change_log.pending.push(Change::Despawned {
+            entity,
+            tick: self.change_tick(),
+        });
+    }
+}
@@ -0,0 +1,458 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::world::{ComponentId, Entity, EntityMeta, Location, RETIRED, World};
+
+/// Limits of the undo history recorded by [`World::push_checkpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckpointConfig {
+    /// Maximum number of checkpoints which can be undone.
+    pub max_depth: usize,
+    /// Maximum number of bytes kept by the stored diffs, the oldest checkpoints are dropped first when it is exceeded.
+    pub memory_budget: usize,
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: 100,
+            memory_budget: 64 * 1024 * 1024,
+        }
+    }
+}
+
+/// Serialized components of a single entity, sorted by type.
+type Record = Vec<(ComponentId, Vec<u8>)>;
+
+/// Serializable state of the whole world, including the entity allocator.
+struct Image {
+    records: HashMap<Entity, Record>,
+    generations: Vec<usize>,
+    free: Vec<usize>,
+}
+
+impl Image {
+    /// Computes the diff which turns this image into the target one.
+    fn diff(&self, target: &Image) -> Diff {
+        let mut records = Vec::new();
+        for (entity, record) in &target.records {
+            if self.records.get(entity) != Some(record) {
+                records.push((*entity, Some(record.clone())));
+            }
+        }
+        for entity in self.records.keys() {
+            if !target.records.contains_key(entity) {
+                records.push((*entity, None));
+            }
+        }
+
+        let generations = target
+            .generations
+            .iter()
+            .enumerate()
+            .filter(|(index, generation)| self.generations.get(*index) != Some(generation))
+            .map(|(index, generation)| (index, *generation))
+            .collect();
+
+        Diff {
+            records,
+            generations,
+            len: target.generations.len(),
+            free: target.free.clone(),
+        }
+    }
+
+    /// Applies the diff and returns the inverse one, which turns the image back.
+    fn apply(&mut self, diff: Diff) -> Diff {
+        let mut inverse = Diff {
+            records: Vec::with_capacity(diff.records.len()),
+            generations: Vec::new(),
+            len: self.generations.len(),
+            free: std::mem::replace(&mut self.free, diff.free),
+        };
+
+        for (entity, record) in diff.records {
+            let old = match record {
+                Some(record) => self.records.insert(entity, record),
+                None => self.records.remove(&entity),
+            };
+            inverse.records.push((entity, old));
+        }
+
+        // Slots which are cut off must come back when the inverse is applied
+        for index in diff.len..self.generations.len() {
+            inverse.generations.push((index, self.generations[index]));
+        }
+        self.generations.resize(diff.len, 0);
+
+        for (index, generation) in diff.generations {
+            if index < inverse.len {
+                inverse.generations.push((index, self.generations[index]));
+            }
+            self.generations[index] = generation;
+        }
+
+        inverse
+    }
+}
+
+/// Changes turning one [`Image`] into another, `None` records are entities which are not alive in the target.
+struct Diff {
+    records: Vec<(Entity, Option<Record>)>,
+    generations: Vec<(usize, usize)>,
+    len: usize,
+    free: Vec<usize>,
+}
+
+impl Diff {
+    /// Approximate number of bytes kept alive by the diff.
+    fn size(&self) -> usize {
+        let records: usize = self
+            .records
+            .iter()
+            .map(|(_, record)| {
+                std::mem::size_of::<(Entity, Option<Record>)>()
+                    + record
+                        .iter()
+                        .flatten()
+                        .map(|(_, bytes)| {
+                            std::mem::size_of::<(ComponentId, Vec<u8>)>() + bytes.len()
+                        })
+                        .sum::<usize>()
+            })
+            .sum();
+
+        records
+            + self.generations.len() * std::mem::size_of::<(usize, usize)>()
+            + self.free.len() * std::mem::size_of::<usize>()
+    }
+}
+
+/// Undo and redo history of a [`World`].
+#[derive(Default)]
+pub(crate) struct Checkpoints {
+    config: CheckpointConfig,
+    current: Option<Image>,
+    undo: VecDeque<Diff>,
+    redo: Vec<Diff>,
+    used: usize,
+}
+
+impl Checkpoints {
+    /// Drops the oldest checkpoints until the history fits into the configured limits.
+    fn trim(&mut self) {
+        while self.undo.len() > self.config.max_depth
+            || (self.used > self.config.memory_budget && !self.undo.is_empty())
+        {
+            let diff = self.undo.pop_front().unwrap();
+            self.used -= diff.size();
+        }
+    }
+}
+
+impl World {
+    /// Sets the limits of the undo history, dropping the oldest checkpoints if they are exceeded already.
+    pub fn configure_checkpoints(&mut self, config: CheckpointConfig) {
+        self.checkpoints.config = config;
+        self.checkpoints.trim();
+    }
+
+    /// Records the current state of the world as a checkpoint which [`World::undo`] can return to, and clears the redo history.
+    /// Only components registered with [`World::register_serializable`] are captured. Checkpoints are stored as diffs against each other.
+    pub fn push_checkpoint(&mut self) {
+        let image = self.capture();
+        let checkpoints = &mut self.checkpoints;
+
+        if let Some(current) = checkpoints.current.take() {
+            let diff = image.diff(&current);
+            checkpoints.used += diff.size();
+            checkpoints.undo.push_back(diff);
+        }
+        checkpoints.current = Some(image);
+
+        for diff in checkpoints.redo.drain(..) {
+            checkpoints.used -= diff.size();
+        }
+        checkpoints.trim();
+    }
+
+    /// Returns the world to the previous checkpoint, discarding any changes made since the last one.
+    /// Returns `false` when there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(diff) = self.checkpoints.undo.pop_back() else {
+            return false;
+        };
+        self.checkpoints.used -= diff.size();
+
+        // Undo history only exists when at least two checkpoints were pushed
+        let mut current = self.checkpoints.current.take().unwrap();
+        let inverse = current.apply(diff);
+        self.checkpoints.used += inverse.size();
+        self.checkpoints.redo.push(inverse);
+
+        self.restore_image(&current);
+        self.checkpoints.current = Some(current);
+        true
+    }
+
+    /// Returns the world to the checkpoint which was undone last, discarding any changes made since.
+    /// Returns `false` when there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(diff) = self.checkpoints.redo.pop() else {
+            return false;
+        };
+        self.checkpoints.used -= diff.size();
+
+        let mut current = self.checkpoints.current.take().unwrap();
+        let inverse = current.apply(diff);
+        self.checkpoints.used += inverse.size();
+        self.checkpoints.undo.push_back(inverse);
+
+        self.restore_image(&current);
+        self.checkpoints.current = Some(current);
+        self.checkpoints.trim();
+        true
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn can_undo(&self) -> bool {
+        !self.checkpoints.undo.is_empty()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn can_redo(&self) -> bool {
+        !self.checkpoints.redo.is_empty()
+    }
+
+    /// Forgets every checkpoint, the next [`World::push_checkpoint`] starts a new history.
+    pub fn clear_checkpoints(&mut self) {
+        self.checkpoints = Checkpoints {
+            config: self.checkpoints.config,
+            ..Default::default()
+        };
+    }
+
+    /// Serializes every alive entity together with the allocator state.
+    fn capture(&self) -> Image {
+        let mut records = HashMap::new();
+
+        for archetype in self.archetypes() {
+            let columns = self.serializable_columns(archetype, "checkpoint");
+            for (row, entity) in archetype.entities().iter().enumerate() {
+                let record = columns
+                    .iter()
+                    .map(|column| {
+                        let mut bytes = Vec::new();
+                        column
+                            .encode(row, &mut bytes)
+                            .expect("Failed to serialize a component for a checkpoint");
+                        (column.id, bytes)
+                    })
+                    .collect();
+                records.insert(*entity, record);
+            }
+        }
+
+        // Alive entities without components are not stored in any archetype
+        let free: HashSet<_> = self.entities.free.iter().copied().collect();
+        for (index, meta) in self.entities.metas.iter().enumerate() {
+            if meta.location == Location::EMPTY
+                && meta.generation != RETIRED
+                && !free.contains(&index)
+            {
+                let entity = Entity::new(index, meta.generation, self.id());
+                records.insert(entity, Vec::new());
+            }
+        }
+
+        Image {
+            records,
+            generations: self
+                .entities
+                .metas
+                .iter()
+                .map(|meta| meta.generation)
+                .collect(),
+            free: self.entities.free.clone(),
+        }
+    }
+
+    /// Changes the world to match the image, touching only the entities which differ from it.
+    fn restore_image(&mut self, image: &Image) {
+        let live = self.capture();
+
+        for entity in live.records.keys() {
+            if !image.records.contains_key(entity) {
+                self.despawn_entity(*entity);
+            }
+        }
+
+        // Every slot which is cut off or changes its generation is dead and empty at this point
+        self.entities
+            .metas
+            .resize(image.generations.len(), EntityMeta::FREE);
+        for (meta, generation) in self.entities.metas.iter_mut().zip(&image.generations) {
+            meta.generation = *generation;
+        }
+        self.entities.free.clone_from(&image.free);
+        self.entities.mark_free();
+        self.entities.recount_retired();
+
+        for (entity, record) in &image.records {
+            let old = live.records.get(entity);
+            if old == Some(record) {
+                continue;
+            }
+            self.write_record(*entity, old, record);
+        }
+    }
+
+    /// Replaces the serializable components of the entity with the ones in the record.
+    fn write_record(&mut self, entity: Entity, old: Option<&Record>, record: &Record) {
+        for (id, _) in old.into_iter().flatten() {
+            if record.iter().all(|(new_id, _)| new_id != id)
+                && let Some(fns) = self.serializers.get(id).copied()
+            {
+                (fns.remove)(self, entity);
+            }
+        }
+
+        for (id, bytes) in record {
+            let unchanged = old
+                .into_iter()
+                .flatten()
+                .any(|(old_id, old_bytes)| old_id == id && old_bytes == bytes);
+            if unchanged {
+                continue;
+            }
+
+            if let Some(fns) = self.serializers.get(id).copied() {
+                (fns.decode)(self, entity, &mut bytes.as_slice())
+                    .expect("Failed to deserialize a component from a checkpoint");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Read, Write};
+
+    use super::CheckpointConfig;
+    use crate::{
+        serialize::{Decode, Encode},
+        world::{Component, World},
+    };
+
+    #[derive(Debug, PartialEq)]
+    struct Health(u32);
+
+    impl Component for Health {}
+
+    impl Encode for Health {
+        fn encode(&self, writer: &mut dyn Write) -> io::Result<()> {
+            self.0.encode(writer)
+        }
+    }
+
+    impl Decode for Health {
+        fn decode(reader: &mut dyn Read) -> io::Result<Self> {
+            u32::decode(reader).map(Health)
+        }
+    }
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.register_serializable::<Health>();
+        world
+    }
+
+    #[test]
+    fn undo_and_redo_restore_components_and_entities() {
+        let mut world = world();
+        let kept = world.spawn(Health(10));
+        world.push_checkpoint();
+
+        world.get_component_mut::<Health>(kept).unwrap().0 = 5;
+        let spawned = world.spawn(Health(1));
+        world.push_checkpoint();
+        assert!(world.can_undo());
+
+        assert!(world.undo());
+        assert_eq!(world.get_component::<Health>(kept), Some(&Health(10)));
+        assert!(!world.is_alive(spawned));
+        assert!(!world.can_undo());
+        assert!(world.can_redo());
+
+        assert!(world.redo());
+        assert_eq!(world.get_component::<Health>(kept), Some(&Health(5)));
+        assert_eq!(world.get_component::<Health>(spawned), Some(&Health(1)));
+        assert!(!world.can_redo());
+    }
+
+    #[test]
+    fn undo_brings_back_despawned_entities_with_their_ids() {
+        let mut world = world();
+        let entity = world.spawn(Health(3));
+        world.push_checkpoint();
+
+        world.despawn_entity(entity);
+        let reused = world.spawn(Health(4));
+        world.push_checkpoint();
+
+        assert!(world.undo());
+        assert!(world.is_alive(entity));
+        assert_eq!(world.get_component::<Health>(entity), Some(&Health(3)));
+        if reused != entity {
+            assert!(!world.is_alive(reused));
+        }
+    }
+
+    #[test]
+    fn undo_discards_changes_after_the_last_checkpoint() {
+        let mut world = world();
+        let entity = world.spawn(Health(1));
+        world.push_checkpoint();
+        world.get_component_mut::<Health>(entity).unwrap().0 = 2;
+        world.push_checkpoint();
+
+        world.get_component_mut::<Health>(entity).unwrap().0 = 3;
+        world.spawn(Health(9));
+        assert!(world.undo());
+        assert_eq!(world.get_component::<Health>(entity), Some(&Health(1)));
+        assert_eq!(world.query::<&Health>().iter(&world).count(), 1);
+    }
+
+    #[test]
+    fn pushing_a_checkpoint_clears_the_redo_history() {
+        let mut world = world();
+        world.push_checkpoint();
+        world.spawn(Health(1));
+        world.push_checkpoint();
+
+        assert!(world.undo());
+        world.push_checkpoint();
+        assert!(!world.can_redo());
+        assert!(!world.redo());
+    }
+
+    #[test]
+    fn history_is_limited_to_the_configured_depth() {
+        let mut world = world();
+        world.configure_checkpoints(CheckpointConfig {
+            max_depth: 2,
+            ..Default::default()
+        });
+        let entity = world.spawn(Health(0));
+        for value in 0..5 {
+            world.get_component_mut::<Health>(entity).unwrap().0 = value;
+            world.push_checkpoint();
+        }
+
+        assert!(world.undo());
+        assert!(world.undo());
+        assert!(!world.undo());
+        assert_eq!(world.get_component::<Health>(entity), Some(&Health(2)));
+    }
+}
@@ -0,0 +1,346 @@
+use std::marker::PhantomData;
+
+use crate::{
+    archetype::Archetype,
+    query::{Filter, QueryData, QueryItem},
+    world::{Component, Entity, World},
+};
+
+/// Query items which can be fetched for a whole archetype at once as slices, see [`QueryData::iter_chunks`].
+pub trait ChunkItem: QueryItem {
+    type Slices<'a>;
+
+    /// Fetches the next `len` rows, mutable items mark every row as changed.
+    ///
+    /// # Safety
+    /// Caller must ensure that the state has `len` rows left within the archetype it was created for.
+    unsafe fn slices<'a>(state: &mut Self::State, len: usize) -> Self::Slices<'a>;
+}
+
+impl<T: Component> ChunkItem for &T {
+    type Slices<'a> = &'a [T];
+
+    #[inline(always)]
+    unsafe fn slices<'a>(state: &mut Self::State, len: usize) -> Self::Slices<'a> {
+        unsafe {
+            let slice = std::slice::from_raw_parts(*state, len);
+            Self::skip(state, len);
+            slice
+        }
+    }
+}
+
+impl<T: Component> ChunkItem for &mut T {
+    type Slices<'a> = &'a mut [T];
+
+    #[inline(always)]
+    unsafe fn slices<'a>(state: &mut Self::State, len: usize) -> Self::Slices<'a> {
+        let (data, ticks, tick) = *state;
+        unsafe {
+            if let Some(tick) = tick {
+                std::slice::from_raw_parts(ticks, len)
+                    .iter()
+                    .for_each(|changed| changed.set(tick));
+            }
+            let slice = std::slice::from_raw_parts_mut(data, len);
+            Self::skip(state, len);
+            slice
+        }
+    }
+}
+
+impl<T: Component> ChunkItem for Option<&T> {
+    type Slices<'a> = Option<&'a [T]>;
+
+    #[inline(always)]
+    unsafe fn slices<'a>(state: &mut Self::State, len: usize) -> Self::Slices<'a> {
+        state
+            .as_mut()
+            .map(|state| unsafe { <&T>::slices(state, len) })
+    }
+}
+
+impl<T: Component> ChunkItem for Option<&mut T> {
+    type Slices<'a> = Option<&'a mut [T]>;
+
+    #[inline(always)]
+    unsafe fn slices<'a>(state: &mut Self::State, len: usize) -> Self::Slices<'a> {
+        state
+            .as_mut()
+            .map(|state| unsafe { <&mut T>::slices(state, len) })
+    }
+}
+
+impl ChunkItem for Entity {
+    type Slices<'a> = &'a [Entity];
+
+    #[inline(always)]
+    unsafe fn slices<'a>(state: &mut Self::State, len: usize) -> Self::Slices<'a> {
+        unsafe {
+            let slice = std::slice::from_raw_parts(*state, len);
+            *state = state.add(len);
+            slice
+        }
+    }
+}
+
+macro_rules! impl_chunk_tuple {
+    ($($name:ident),*) => {
+        impl<$($name: ChunkItem),*> ChunkItem for ($($name,)*) {
+            type Slices<'a> = ($($name::Slices<'a>,)*);
+
+            #[inline(always)]
+            unsafe fn slices<'a>(state: &mut Self::State, len: usize) -> Self::Slices<'a> {
+                #[allow(non_snake_case)]
+                let ($($name,)*) = state;
+                unsafe { ($($name::slices($name, len),)*) }
+            }
+        }
+    };
+}
+
+impl_chunk_tuple!(A, B);
+impl_chunk_tuple!(A, B, C);
+impl_chunk_tuple!(A, B, C, D);
+impl_chunk_tuple!(A, B, C, D, E);
+impl_chunk_tuple!(A, B, C, D, E, F);
+impl_chunk_tuple!(A, B, C, D, E, F, G);
+impl_chunk_tuple!(A, B, C, D, E, F, G, H);
+
+/// Iterator over the matching archetypes as the slice of their entities and the slices of the items,
+/// returned by [`QueryData::iter_chunks`] and [`World::query_chunks`]. Empty archetypes are skipped.
+/// Chunks never span archetypes, with [`QueryData::iter_chunks_of`] archetypes are split into several chunks.
+pub struct ChunkIter<'a, Q: ChunkItem> {
+    /// Matching archetypes with at least one row, every one of them is borrowed.
+    archetypes: Vec<&'a Archetype>,
+    cursor: usize,
+    /// Fetch state and first row of the next chunk of the current archetype.
+    state: Option<Q::State>,
+    row: usize,
+    size: usize,
+    tick: Option<u64>,
+    _marker: PhantomData<Q>,
+}
+
+impl<'a, Q: ChunkItem> ChunkIter<'a, Q> {
+    fn new(archetypes: Vec<&'a Archetype>, tick: Option<u64>, size: usize) -> Self {
+        for (borrowed, archetype) in archetypes.iter().enumerate() {
+            if !Q::borrow(archetype) {
+                for archetype in &archetypes[..borrowed] {
+                    Q::release(archetype);
+                }
+                panic!("Conflicting Queries Detected");
+            }
+        }
+
+        Self {
+            archetypes,
+            cursor: 0,
+            state: None,
+            row: 0,
+            size,
+            tick,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, Q: ChunkItem> Iterator for ChunkIter<'a, Q> {
+    type Item = (&'a [Entity], Q::Slices<'a>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let archetype = *self.archetypes.get(self.cursor)?;
+        let start = self.row;
+        let len = self.size.min(archetype.count() - start);
+        // SAFETY: The archetype matches the query, its columns are borrowed until the iterator is dropped and every item is fetched once
+        let slices = unsafe {
+            let state = self
+                .state
+                .get_or_insert_with(|| Q::state(archetype, self.tick));
+            Q::slices(state, len)
+        };
+
+        self.row += len;
+        if self.row == archetype.count() {
+            self.cursor += 1;
+            self.row = 0;
+            self.state = None;
+        }
+        Some((&archetype.entities()[start..start + len], slices))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.archetypes[self.cursor..]
+            .iter()
+            .map(|archetype| archetype.count().div_ceil(self.size))
+            .sum::<usize>()
+            - self.row / self.size;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<Q: ChunkItem> ExactSizeIterator for ChunkIter<'_, Q> {}
+
+impl<Q: ChunkItem> Drop for ChunkIter<'_, Q> {
+    fn drop(&mut self) {
+        for archetype in &self.archetypes {
+            Q::release(archetype);
+        }
+    }
+}
+
+impl<Q: ChunkItem, F: Filter> QueryData<Q, F> {
+    /// Iterates over the matching archetypes, yielding their entities with a slice of every item, e.g. `(&[Entity], (&mut [Position], &[Velocity]))`,
+    /// for SIMD or manual batching. Mutable slices mark every row of the archetype as changed. Panics with row filters like [`Changed`](crate::query::Changed).
+    pub fn iter_chunks<'a>(&'a mut self, world: &'a World) -> ChunkIter<'a, Q> {
+        self.iter_chunks_of(world, usize::MAX)
+    }
+
+    /// Iterates over the matching archetypes like [`QueryData::iter_chunks`], splitting them into chunks of at most `size` rows,
+    /// e.g. to keep the chunks within a cache budget. Panics when `size` is zero or with row filters.
+    pub fn iter_chunks_of<'a>(&'a mut self, world: &'a World, size: usize) -> ChunkIter<'a, Q> {
+        assert!(size > 0, "Chunk size must be greater than zero");
+        assert!(
+            !F::filters_rows(),
+            "Cannot iterate the chunks of a query with row filters"
+        );
+        self.update_cache(world);
+        let (tick, _) = self.advance(world);
+        let archetypes = world.archetypes();
+        let matching = self
+            .matching()
+            .iter()
+            .map(|index| &archetypes[*index])
+            .filter(|archetype| archetype.count() > 0)
+            .collect();
+        ChunkIter::new(matching, tick, size)
+    }
+}
+
+impl World {
+    /// Iterates over the chunks of a query without keeping its cache, like [`QueryData::iter_chunks`].
+    pub fn query_chunks<Q: ChunkItem>(&self) -> ChunkIter<'_, Q> {
+        let filter = QueryData::<Q>::archetype_filter(self);
+        let matching = self
+            .ordered_archetypes()
+            .filter(|archetype| archetype.count() > 0 && filter.matches(archetype.bitmask()))
+            .collect();
+        ChunkIter::new(
+            matching,
+            self.change_detection().then(|| self.change_tick()),
+            usize::MAX,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        query::{Changed, QueryData},
+        world::{Component, Entity, World},
+    };
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Value(u32);
+    struct Marker;
+
+    impl Component for Value {}
+    impl Component for Marker {}
+
+    /// Spawns five entities without and three with the marker.
+    fn world() -> World {
+        let mut world = World::new();
+        for value in 0..8 {
+            if value < 5 {
+                world.spawn(Value(value));
+            } else {
+                world.spawn((Value(value), Marker));
+            }
+        }
+        world
+    }
+
+    #[test]
+    fn chunks_cover_whole_archetypes() {
+        let mut world = world();
+        let mut query = world.query::<(Entity, &Value)>();
+        let chunks: Vec<_> = query
+            .iter_chunks(&world)
+            .map(|(entities, (ids, values))| {
+                assert_eq!(entities, ids);
+                values.iter().map(|value| value.0).collect::<Vec<_>>()
+            })
+            .collect();
+        assert_eq!(chunks, [vec![0, 1, 2, 3, 4], vec![5, 6, 7]]);
+    }
+
+    #[test]
+    fn sized_chunks_split_archetypes_at_the_boundaries() {
+        let mut world = world();
+        let mut query = world.query::<(Entity, &Value)>();
+        let mut chunks = query.iter_chunks_of(&world, 2);
+        assert_eq!(chunks.size_hint(), (5, Some(5)));
+        chunks.next();
+        assert_eq!(chunks.size_hint(), (4, Some(4)));
+        drop(chunks);
+
+        let chunks: Vec<_> = query
+            .iter_chunks_of(&world, 2)
+            .map(|(entities, (ids, values))| {
+                assert_eq!(entities, ids);
+                values.iter().map(|value| value.0).collect::<Vec<_>>()
+            })
+            .collect();
+        assert_eq!(
+            chunks,
+            [vec![0, 1], vec![2, 3], vec![4], vec![5, 6], vec![7]]
+        );
+    }
+
+    #[test]
+    fn mutable_chunks_write_and_mark_every_row() {
+        let mut world = world();
+        let mut query = world.query::<&mut Value>();
+        for (_, values) in query.iter_chunks_of(&world, 3) {
+            values.iter_mut().for_each(|value| value.0 += 10);
+        }
+
+        let mut changed = QueryData::<&Value, Changed<Value>>::new(&world);
+        let mut values: Vec<_> = changed.iter(&world).map(|value| value.0).collect();
+        values.sort_unstable();
+        assert_eq!(values, (10..18).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn empty_archetypes_are_skipped() {
+        let mut world = World::new();
+        let despawned = world.spawn((Value(0), Marker));
+        world.spawn(Value(1));
+        world.despawn_entity(despawned);
+
+        let mut query = world.query::<&Value>();
+        let chunks: Vec<_> = query
+            .iter_chunks_of(&world, 4)
+            .map(|(_, values)| values)
+            .collect();
+        assert_eq!(chunks, [&[Value(1)][..]]);
+        assert_eq!(world.query_chunks::<&Value>().len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Chunk size must be greater than zero")]
+    fn chunks_must_not_be_empty() {
+        let mut world = world();
+        let mut query = world.query::<&Value>();
+        query.iter_chunks_of(&world, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Conflicting Queries Detected")]
+    fn chunks_keep_the_columns_borrowed() {
+        let world = world();
+        let _chunks = world.query_chunks::<&mut Value>();
+        world.query_chunks::<&Value>();
+    }
+}
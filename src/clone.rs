@@ -0,0 +1,191 @@
+use std::fmt;
+
+use crate::{
+    archetype::Archetype,
+    blob_data::{BlobData, ColumnGuard},
+    components::ComponentInfo,
+    hooks::Hook,
+    world::{Component, ComponentId, Entity, Location, World},
+};
+
+/// Type-erased functions cloning the values of a column.
+#[derive(Clone, Copy)]
+pub(crate) struct CloneFns {
+    /// Clones every value of a column into another column of the same type.
+    pub(crate) clone_column: fn(&BlobData, &mut BlobData),
+    /// Pushes a clone of the value in the given row to the end of the same column.
+    pub(crate) clone_row: fn(&mut BlobData, usize, u64),
+}
+
+impl CloneFns {
+    fn of<T: Component + Clone>() -> Self {
+        fn clone_column<T: Clone>(source: &BlobData, target: &mut BlobData) {
+            let mut row = 0;
+            while let Some(value) = source.get::<T>(row) {
+                target.push_with_ticks(value.clone(), source.ticks(row));
+                row += 1;
+            }
+        }
+
+        fn clone_row<T: Clone>(column: &mut BlobData, row: usize, tick: u64) {
+            let value = column.get::<T>(row).unwrap().clone();
+            column.push(value, tick);
+        }
+
+        Self {
+            clone_column: clone_column::<T>,
+            clone_row: clone_row::<T>,
+        }
+    }
+}
+
+/// Returned when a clone operation encounters components without a clone function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloneError {
+    /// Type names of every component which could not be cloned, each listed once.
+    pub components: Vec<&'static str>,
+}
+
+impl fmt::Display for CloneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "components not registered as cloneable: {}",
+            self.components.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for CloneError {}
+
+impl World {
+    /// Registers the component together with its clone function, which is required by clone operations like [`World::clone_world`].
+    /// Registering a component which is registered already only adds the clone function.
+    pub fn register_cloneable<T: Component + Clone>(&mut self) {
+        self.register_component::<T>();
+        // The component was just registered, so it has an entry
+        self.component_info_mut(&ComponentId::of::<T>())
+            .unwrap()
+            .clone = Some(CloneFns::of::<T>());
+    }
+
+    #[must_use]
+    pub fn is_cloneable<T: Component>(&self) -> bool {
+        self.component_info(&ComponentId::of::<T>())
+            .is_some_and(|info| info.clone.is_some())
+    }
+
+    /// Checks that every component stored in the given archetypes can be cloned.
+    pub(crate) fn check_cloneable<'a>(
+        &self,
+        archetypes: impl IntoIterator<Item = &'a Archetype>,
+    ) -> Result<(), CloneError> {
+        let components = self.missing_components(archetypes, |info| info.clone.is_some());
+        if components.is_empty() {
+            Ok(())
+        } else {
+            Err(CloneError { components })
+        }
+    }
+
+    /// Returns the sorted names of the components stored in the given archetypes whose registry entry doesn't satisfy the predicate.
+    pub(crate) fn missing_components<'a>(
+        &self,
+        archetypes: impl IntoIterator<Item = &'a Archetype>,
+        registered: impl Fn(&ComponentInfo) -> bool,
+    ) -> Vec<&'static str> {
+        let mut components = Vec::new();
+        for archetype in archetypes {
+            for (id, _) in archetype.columns() {
+                let Some(info) = self.component_info(id) else {
+                    continue;
+                };
+                if !registered(info) && !components.contains(&info.name) {
+                    components.push(info.name);
+                }
+            }
+        }
+        components.sort_unstable();
+        components
+    }
+
+    /// Creates an independent copy of every entity and component, keeping the entity ids and change ticks.
+    /// The registries and timed components are copied as well, subscriptions, callbacks, checkpoints, transient values, resources and the entity allocator are not.
+    ///
+    /// Fails without cloning anything when the world contains components which are not registered with [`World::register_cloneable`].
+    pub fn clone_world(&self) -> Result<World, CloneError> {
+        self.check_cloneable(self.archetypes())?;
+
+        Ok(self.clone_structure(self.clone_archetypes()))
+    }
+
+    /// Spawns a new entity with clones of every component of the given one, the clones are marked as changed.
+    /// Fails without spawning anything when some component is not registered with [`World::register_cloneable`].
+    /// Panics when the entity is dead or a quota is exhausted.
+    pub fn clone_entity(&mut self, entity: Entity) -> Result<Entity, CloneError> {
+        assert!(self.is_alive(entity), "{entity:?} does not exist");
+        let Some(archetype) = self.archetype_of(entity) else {
+            return Ok(self.spawn_empty());
+        };
+        self.check_cloneable([archetype])?;
+
+        let location = self.entities.metas[entity.index].location;
+        if let Err(error) = self.check_quota(Some(location.archetype)) {
+            panic!("{error}");
+        }
+
+        let fns: Vec<_> = archetype
+            .columns()
+            .map(|(id, _)| {
+                (
+                    *id,
+                    self.component_info(id).and_then(|info| info.clone).unwrap(),
+                )
+            })
+            .collect();
+        let tick = self.change_tick();
+        let clone = self.alloc_entity();
+
+        let archetype = &mut self.archetypes_mut()[location.archetype];
+        let row = archetype.count();
+        for (id, fns) in fns {
+            (fns.clone_row)(archetype.column_mut(&id).unwrap(), location.row, tick);
+        }
+        archetype.insert_row(clone);
+
+        self.entities.metas[clone.index].location = Location {
+            archetype: location.archetype,
+            row,
+        };
+        self.index_name(clone);
+        self.log_spawn(clone);
+        let bitmask = self.archetypes()[location.archetype].bitmask().clone();
+        self.run_hooks(clone, Hook::Add, bitmask);
+        Ok(clone)
+    }
+
+    /// Copies every archetype with its entities, components and change ticks. Every component must have a clone function.
+    pub(crate) fn clone_archetypes(&self) -> Vec<Archetype> {
+        let mut archetypes = Vec::with_capacity(self.archetypes().len());
+        for archetype in self.archetypes() {
+            let mut clone = Archetype::new(archetype.bitmask().clone());
+            clone.set_compact_until(archetype.compact_until);
+
+            for (id, column) in archetype.columns() {
+                // Callers check every column up front
+                let fns = self.component_info(id).and_then(|info| info.clone).unwrap();
+
+                let column = ColumnGuard::new(column, "clone");
+                clone.with(*id, *column.type_info());
+                (fns.clone_column)(&column, clone.column_mut(id).unwrap());
+            }
+
+            for entity in archetype.entities() {
+                clone.insert_row(*entity);
+            }
+            archetypes.push(clone);
+        }
+
+        archetypes
+    }
+}
@@ -0,0 +1,236 @@
+use crate::{
+    archetype::Archetype,
+    blob_data::ColumnGuard,
+    query::{ArchetypeFilter, Filter},
+    world::{Component, ComponentId, Entity, World},
+};
+
+/// Values of a single exported column, stored contiguously by their primitive type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnData {
+    Bool(Vec<bool>),
+    Int(Vec<i64>),
+    UInt(Vec<u64>),
+    Float(Vec<f64>),
+    Utf8(Vec<String>),
+}
+
+impl ColumnData {
+    #[must_use]
+    pub fn len(&self) -> usize {
+        match self {
+            ColumnData::Bool(values) => values.len(),
+            ColumnData::Int(values) => values.len(),
+            ColumnData::UInt(values) => values.len(),
+            ColumnData::Float(values) => values.len(),
+            ColumnData::Utf8(values) => values.len(),
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Pushes the default value of the column type, used for rows which have no value.
+    fn push_null(&mut self) {
+        match self {
+            ColumnData::Bool(values) => values.push(false),
+            ColumnData::Int(values) => values.push(0),
+            ColumnData::UInt(values) => values.push(0),
+            ColumnData::Float(values) => values.push(0.0),
+            ColumnData::Utf8(values) => values.push(String::new()),
+        }
+    }
+}
+
+/// Primitive value which can be stored in a [`ColumnData`].
+pub trait Scalar: 'static {
+    /// Creates an empty column of the matching type.
+    fn column() -> ColumnData;
+    /// Pushes the value into a column created by [`Scalar::column`].
+    /// It takes no `self`, so it never shadows methods like `String::push` when the prelude is imported.
+    fn append(data: &mut ColumnData, value: Self);
+}
+
+macro_rules! impl_scalar {
+    ($variant:ident, $as:ty, $($ty:ty),*) => {
+        $(
+            impl Scalar for $ty {
+                fn column() -> ColumnData {
+                    ColumnData::$variant(Vec::new())
+                }
+
+                fn append(data: &mut ColumnData, value: Self) {
+                    if let ColumnData::$variant(values) = data {
+                        values.push(value as $as);
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_scalar!(Int, i64, i8, i16, i32, i64, isize);
+impl_scalar!(UInt, u64, u8, u16, u32, u64, usize);
+impl_scalar!(Float, f64, f32, f64);
+
+impl Scalar for bool {
+    fn column() -> ColumnData {
+        ColumnData::Bool(Vec::new())
+    }
+
+    fn append(data: &mut ColumnData, value: Self) {
+        if let ColumnData::Bool(values) = data {
+            values.push(value);
+        }
+    }
+}
+
+impl Scalar for String {
+    fn column() -> ColumnData {
+        ColumnData::Utf8(Vec::new())
+    }
+
+    fn append(data: &mut ColumnData, value: Self) {
+        if let ColumnData::Utf8(values) = data {
+            values.push(value);
+        }
+    }
+}
+
+/// A named column of a [`RecordBatch`], `validity` is `false` for the rows whose entity has no such component.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub name: String,
+    pub data: ColumnData,
+    pub validity: Vec<bool>,
+}
+
+/// Columns exported by [`World::export_columns`], every column has one value per entity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordBatch {
+    pub entities: Vec<Entity>,
+    pub columns: Vec<Column>,
+}
+
+impl RecordBatch {
+    #[must_use]
+    pub fn num_rows(&self) -> usize {
+        self.entities.len()
+    }
+
+    #[must_use]
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+}
+
+/// Appends the values of a column for every row of an archetype.
+type FillColumn = Box<dyn Fn(&Archetype, &mut ColumnData, &mut Vec<bool>)>;
+
+struct ColumnFns {
+    name: String,
+    empty: fn() -> ColumnData,
+    fill: FillColumn,
+}
+
+/// Describes which entities and component fields [`World::export_columns`] exports.
+pub struct ColumnarConfig {
+    filter: fn(&World) -> ArchetypeFilter,
+    columns: Vec<ColumnFns>,
+}
+
+impl Default for ColumnarConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ColumnarConfig {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            filter: ArchetypeFilter::of::<()>,
+            columns: Vec::new(),
+        }
+    }
+
+    /// Only exports entities matching the filter `F`, e.g. `With<Body>`. Panics with row filters like [`Changed`](crate::query::Changed).
+    #[must_use]
+    pub fn filter<F: Filter>(mut self) -> Self {
+        assert!(
+            !F::filters_rows(),
+            "Cannot export through a filter with row filters"
+        );
+        self.filter = ArchetypeFilter::of::<F>;
+        self
+    }
+
+    /// Exports the value returned by `get` for the component `T` as a column with the given name.
+    /// Call it once per field to split a component into several columns.
+    #[must_use]
+    pub fn column<T: Component, V: Scalar>(
+        mut self,
+        name: impl Into<String>,
+        get: fn(&T) -> V,
+    ) -> Self {
+        self.columns.push(ColumnFns {
+            name: name.into(),
+            empty: V::column,
+            fill: Box::new(move |archetype, data, validity| {
+                let count = archetype.count();
+                let Some(column) = archetype.column(&ComponentId::of::<T>()) else {
+                    for _ in 0..count {
+                        data.push_null();
+                    }
+                    validity.resize(validity.len() + count, false);
+                    return;
+                };
+
+                let column = ColumnGuard::new(column, "export");
+                for row in 0..count {
+                    V::append(data, get(column.get::<T>(row).unwrap()));
+                }
+                validity.resize(validity.len() + count, true);
+            }),
+        });
+        self
+    }
+}
+
+impl World {
+    /// Exports the configured columns of every entity matching the filter into a single batch, archetype by archetype.
+    pub fn export_columns(&self, config: &ColumnarConfig) -> RecordBatch {
+        let filter = (config.filter)(self);
+        let archetypes: Vec<_> = self
+            .archetypes()
+            .iter()
+            .filter(|archetype| archetype.count() > 0 && filter.matches(archetype.bitmask()))
+            .collect();
+
+        let entities = archetypes
+            .iter()
+            .flat_map(|archetype| archetype.entities().iter().copied())
+            .collect();
+
+        let columns = config
+            .columns
+            .iter()
+            .map(|fns| {
+                let mut data = (fns.empty)();
+                let mut validity = Vec::new();
+                for archetype in &archetypes {
+                    (fns.fill)(archetype, &mut data, &mut validity);
+                }
+                Column {
+                    name: fns.name.clone(),
+                    data,
+                    validity,
+                }
+            })
+            .collect();
+
+        RecordBatch { entities, columns }
+    }
+}
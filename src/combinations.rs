@@ -0,0 +1,349 @@
+use std::{array, marker::PhantomData};
+
+use crate::{
+    archetype::Archetype,
+    query::{Filter, QueryData, QueryItem, ReadOnlyQueryItem},
+    world::World,
+};
+
+/// Positions of the current combination within the matching rows, shared by both combination iterators.
+struct Positions<Q: QueryItem, const K: usize> {
+    /// Fetch state of the first row of every non-empty matching archetype.
+    states: Vec<Q::State>,
+    /// Running totals of the archetype counts, used to map a position to its archetype.
+    ends: Vec<usize>,
+    /// Strictly increasing positions of the items in the current combination.
+    indices: [usize; K],
+    done: bool,
+}
+
+impl<Q: QueryItem, const K: usize> Positions<Q, K> {
+    /// # Safety
+    /// Caller must ensure that the archetypes match the query and that their columns are borrowed.
+    unsafe fn new(archetypes: &[Archetype], matching: &[usize], tick: Option<u64>) -> Self {
+        let mut states = Vec::with_capacity(matching.len());
+        let mut ends = Vec::with_capacity(matching.len());
+        let mut total = 0;
+        for index in matching {
+            let archetype = &archetypes[*index];
+            if archetype.count() == 0 {
+                continue;
+            }
+            total += archetype.count();
+            ends.push(total);
+            states.push(unsafe { Q::state(archetype, tick) });
+        }
+
+        Self {
+            states,
+            ends,
+            indices: array::from_fn(|i| i),
+            done: K > total,
+        }
+    }
+
+    /// # Safety
+    /// Caller must ensure that items with mutable access fetched for a previous combination are no longer used.
+    unsafe fn next<'w>(&mut self) -> Option<[Q::Item<'w>; K]> {
+        if self.done {
+            return None;
+        }
+        // SAFETY: Positions of one combination are distinct, so its items never alias
+        let items = array::from_fn(|i| unsafe { self.fetch(self.indices[i]) });
+        self.advance();
+        Some(items)
+    }
+
+    unsafe fn fetch<'w>(&self, position: usize) -> Q::Item<'w> {
+        let archetype = self.ends.partition_point(|end| *end <= position);
+        let start = if archetype == 0 {
+            0
+        } else {
+            self.ends[archetype - 1]
+        };
+        let mut state = self.states[archetype].clone();
+        unsafe {
+            Q::skip(&mut state, position - start);
+            Q::fetch(&mut state)
+        }
+    }
+
+    /// Moves to the next combination in lexicographic order of the positions.
+    fn advance(&mut self) {
+        let total = self.ends.last().copied().unwrap_or(0);
+        // The rightmost position which can still move, every later position follows right after it
+        let Some(moved) = (0..K).rev().find(|i| self.indices[*i] < total - K + *i) else {
+            self.done = true;
+            return;
+        };
+        self.indices[moved] += 1;
+        for i in moved + 1..K {
+            self.indices[i] = self.indices[i - 1] + 1;
+        }
+    }
+
+    /// Number of combinations left, saturated at `usize::MAX`.
+    fn remaining(&self) -> usize {
+        if self.done {
+            return 0;
+        }
+        let total = self.ends.last().copied().unwrap_or(0);
+        // Combinations after the current one are counted position by position, like digits of a number
+        let mut remaining: usize = 1;
+        for (i, index) in self.indices.iter().enumerate() {
+            let after = total - index - 1;
+            let left = K - i - 1;
+            remaining = remaining.saturating_add(binomial(after, left + 1));
+        }
+        remaining
+    }
+}
+
+/// Number of ways to pick `k` of `n` items, saturated at `usize::MAX`.
+fn binomial(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+    let k = k.min(n - k);
+    let mut result: u128 = 1;
+    for i in 0..k {
+        result = result * (n - i) as u128 / (i + 1) as u128;
+        if result > usize::MAX as u128 {
+            return usize::MAX;
+        }
+    }
+    result as usize
+}
+
+/// Iterator over every unordered `K`-tuple of distinct matching items, returned by [`QueryData::iter_combinations`].
+pub struct Combinations<'a, Q: ReadOnlyQueryItem, F: Filter, const K: usize> {
+    data: &'a QueryData<Q, F>,
+    archetypes: &'a [Archetype],
+    positions: Positions<Q, K>,
+    _marker: PhantomData<F>,
+}
+
+impl<'a, Q: ReadOnlyQueryItem, F: Filter, const K: usize> Iterator for Combinations<'a, Q, F, K> {
+    type Item = [Q::Item<'a>; K];
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        // SAFETY: Read only items can be fetched again while earlier ones are alive
+        unsafe { self.positions.next() }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.positions.remaining();
+        (remaining, (remaining < usize::MAX).then_some(remaining))
+    }
+}
+
+impl<Q: ReadOnlyQueryItem, F: Filter, const K: usize> Drop for Combinations<'_, Q, F, K> {
+    fn drop(&mut self) {
+        self.data.release(self.archetypes);
+    }
+}
+
+/// Combinations of distinct matching items with mutable access, returned by [`QueryData::iter_combinations_mut`].
+/// Every combination borrows the iterator, so items of different combinations never alias.
+pub struct CombinationsMut<'a, Q: QueryItem, F: Filter, const K: usize> {
+    data: &'a QueryData<Q, F>,
+    archetypes: &'a [Archetype],
+    positions: Positions<Q, K>,
+    _marker: PhantomData<F>,
+}
+
+impl<Q: QueryItem, F: Filter, const K: usize> CombinationsMut<'_, Q, F, K> {
+    /// Returns the next combination, e.g. `while let Some([a, b]) = pairs.fetch_next() { .. }`.
+    #[inline]
+    pub fn fetch_next(&mut self) -> Option<[Q::Item<'_>; K]> {
+        // SAFETY: The items borrow the iterator, so the previous combination was dropped already
+        unsafe { self.positions.next() }
+    }
+
+    /// Number of combinations left, saturated at `usize::MAX`.
+    #[inline]
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.positions.remaining()
+    }
+}
+
+impl<Q: QueryItem, F: Filter, const K: usize> Drop for CombinationsMut<'_, Q, F, K> {
+    fn drop(&mut self) {
+        self.data.release(self.archetypes);
+    }
+}
+
+impl<'a, Q: QueryItem, F: Filter> QueryData<Q, F> {
+    /// Iterates over every unordered `K`-tuple of distinct matching items, e.g. `for [a, b] in query.iter_combinations::<2>(&world)`
+    /// for collision pairs. Items come in iteration order within a tuple, and no tuple is repeated in another order.
+    /// Panics when `K` is zero or with row filters like [`Changed`](crate::query::Changed).
+    pub fn iter_combinations<const K: usize>(
+        &'a mut self,
+        world: &'a World,
+    ) -> Combinations<'a, Q, F, K>
+    where
+        Q: ReadOnlyQueryItem,
+    {
+        let (archetypes, positions) = self.begin_combinations(world);
+        Combinations {
+            data: self,
+            archetypes,
+            positions,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Like [`QueryData::iter_combinations`] for items with mutable access, which are fetched one combination at a time with
+    /// [`CombinationsMut::fetch_next`] since two combinations can share an item.
+    pub fn iter_combinations_mut<const K: usize>(
+        &'a mut self,
+        world: &'a mut World,
+    ) -> CombinationsMut<'a, Q, F, K> {
+        let (archetypes, positions) = self.begin_combinations(world);
+        CombinationsMut {
+            data: self,
+            archetypes,
+            positions,
+            _marker: PhantomData,
+        }
+    }
+
+    fn begin_combinations<const K: usize>(
+        &mut self,
+        world: &'a World,
+    ) -> (&'a [Archetype], Positions<Q, K>) {
+        assert!(K > 0, "Combinations must have at least one item");
+        assert!(
+            !F::filters_rows(),
+            "Cannot combine the items of a query with row filters"
+        );
+        self.update_cache(world);
+        let (tick, _) = self.advance(world);
+        let archetypes = world.archetypes();
+        self.borrow(archetypes);
+        // SAFETY: The matching archetypes were just borrowed
+        let positions = unsafe { Positions::new(archetypes, &self.matching(), tick) };
+        (archetypes, positions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use crate::world::{Component, World};
+
+    #[derive(Debug, PartialEq)]
+    struct Value(u32);
+    struct Marker;
+
+    impl Component for Value {}
+    impl Component for Marker {}
+
+    /// Spawns entities with the values `0..count`, every other one in a second archetype.
+    fn world(count: u32) -> World {
+        let mut world = World::new();
+        world.register_component::<Value>();
+        for value in 0..count {
+            if value % 2 == 0 {
+                world.spawn(Value(value));
+            } else {
+                world.spawn((Value(value), Marker));
+            }
+        }
+        world
+    }
+
+    #[test]
+    fn pairs_are_distinct_and_never_repeated() {
+        let mut world = world(5);
+        let mut query = world.query::<&Value>();
+        let pairs: Vec<_> = query
+            .iter_combinations::<2>(&world)
+            .map(|[a, b]| (a.0, b.0))
+            .collect();
+
+        assert_eq!(pairs.len(), 10);
+        assert!(pairs.iter().all(|(a, b)| a != b));
+        let unordered: BTreeSet<_> = pairs.iter().map(|(a, b)| (*a.min(b), *a.max(b))).collect();
+        assert_eq!(unordered.len(), 10);
+    }
+
+    #[test]
+    fn size_hints_count_the_combinations_left() {
+        let mut world = world(6);
+        let mut query = world.query::<&Value>();
+        let mut triples = query.iter_combinations::<3>(&world);
+        assert_eq!(triples.size_hint(), (20, Some(20)));
+        triples.next();
+        assert_eq!(triples.size_hint(), (19, Some(19)));
+        assert_eq!(triples.count(), 19);
+    }
+
+    #[test]
+    fn too_few_items_have_no_combinations() {
+        for count in [0, 1] {
+            let mut world = world(count);
+            let mut query = world.query::<&Value>();
+            let mut pairs = query.iter_combinations::<2>(&world);
+            assert_eq!(pairs.size_hint(), (0, Some(0)));
+            assert!(pairs.next().is_none());
+            drop(pairs);
+            assert_eq!(query.iter_combinations::<1>(&world).count(), count as usize);
+        }
+    }
+
+    #[test]
+    fn mutable_combinations_write_every_item() {
+        let mut world = world(4);
+        let mut query = world.query::<&mut Value>();
+        let mut pairs = query.iter_combinations_mut::<2>(&mut world);
+        assert_eq!(pairs.remaining(), 6);
+        while let Some([a, b]) = pairs.fetch_next() {
+            a.0 += 10;
+            b.0 += 10;
+        }
+        assert_eq!(pairs.remaining(), 0);
+        drop(pairs);
+
+        // Every item is part of three of the six pairs
+        let mut values: Vec<_> = world
+            .query::<&Value>()
+            .iter(&world)
+            .map(|value| value.0)
+            .collect();
+        values.sort_unstable();
+        assert_eq!(values, [30, 31, 32, 33]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Conflicting Queries Detected")]
+    fn combinations_keep_the_columns_borrowed() {
+        let mut world = world(3);
+        let mut query = world.query::<&Value>();
+        let mut writer = world.query::<&mut Value>();
+
+        let _pairs = query.iter_combinations::<2>(&world);
+        writer.iter(&world).count();
+    }
+
+    #[test]
+    fn dropped_combinations_release_the_columns() {
+        let mut world = world(3);
+        let mut query = world.query::<&Value>();
+        let mut writer = world.query::<&mut Value>();
+
+        assert_eq!(query.iter_combinations::<2>(&world).count(), 3);
+        assert_eq!(writer.iter(&world).count(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Combinations must have at least one item")]
+    fn combinations_must_have_an_item() {
+        let mut world = world(3);
+        let mut query = world.query::<&Value>();
+        query.iter_combinations::<0>(&world).count();
+    }
+}
@@ -0,0 +1,109 @@
+use std::fmt;
+
+use crate::{
+    bundle::Bundle,
+    world::{Component, Entity, World},
+};
+
+type Command = Box<dyn FnOnce(&mut World) + Send>;
+
+/// Structural changes recorded while the world is borrowed, e.g. while iterating a query, and executed later by [`World::apply`].
+/// Commands run in the order they were recorded, commands for entities which are dead by then are ignored.
+#[derive(Default)]
+pub struct CommandBuffer {
+    commands: Vec<Command>,
+}
+
+impl CommandBuffer {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawns an entity with the bundle, like [`World::spawn`].
+    pub fn spawn<B: Bundle + Send + 'static>(&mut self, bundle: B) -> &mut Self {
+        self.push(move |world| {
+            world.spawn(bundle);
+        })
+    }
+
+    /// Inserts the component into the entity, like [`World::insert_component`].
+    pub fn insert<T: Component + Send>(&mut self, entity: Entity, component: T) -> &mut Self {
+        self.push(move |world| world.insert_component(entity, component))
+    }
+
+    /// Inserts every component of the bundle into the entity, like [`World::insert_bundle`].
+    pub fn insert_bundle<B: Bundle + Send + 'static>(
+        &mut self,
+        entity: Entity,
+        bundle: B,
+    ) -> &mut Self {
+        self.push(move |world| world.insert_bundle(entity, bundle))
+    }
+
+    /// Removes the component from the entity, like [`World::remove_component`].
+    pub fn remove<T: Component>(&mut self, entity: Entity) -> &mut Self {
+        self.push(move |world| {
+            world.remove_component::<T>(entity);
+        })
+    }
+
+    /// Removes every component of the bundle from the entity, like [`World::remove_bundle`].
+    pub fn remove_bundle<B: Bundle + 'static>(&mut self, entity: Entity) -> &mut Self {
+        self.push(move |world| {
+            world.remove_bundle::<B>(entity);
+        })
+    }
+
+    /// Despawns the entity, like [`World::despawn_entity`].
+    pub fn despawn(&mut self, entity: Entity) -> &mut Self {
+        self.push(move |world| {
+            world.despawn_entity(entity);
+        })
+    }
+
+    /// Records a custom command, e.g. `commands.push(move |world| world.insert_resource(score))`.
+    pub fn push(&mut self, command: impl FnOnce(&mut World) + Send + 'static) -> &mut Self {
+        self.commands.push(Box::new(command));
+        self
+    }
+
+    /// Moves every command of the other buffer to the end of this one.
+    pub fn append(&mut self, other: &mut CommandBuffer) {
+        self.commands.append(&mut other.commands);
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Drops every recorded command without running it.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+}
+
+impl fmt::Debug for CommandBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CommandBuffer")
+            .field("len", &self.commands.len())
+            .finish()
+    }
+}
+
+impl World {
+    /// Runs the commands of the buffer in the order they were recorded, leaving it empty for reuse.
+    pub fn apply(&mut self, commands: &mut CommandBuffer) {
+        for command in commands.commands.drain(..) {
+            command(self);
+        }
+    }
+}
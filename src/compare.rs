@@ -0,0 +1,342 @@
+use std::hash::{Hash, Hasher};
+
+use crate::{
+    blob_data::ColumnGuard,
+    query::Filter,
+    world::{Component, ComponentId, Entity, World},
+};
+
+/// Type-erased equality of two values of the same component type.
+pub(crate) type EqFn = unsafe fn(*const u8, *const u8) -> bool;
+/// Type-erased hashing of a component value.
+pub(crate) type HashFn = unsafe fn(*const u8, &mut dyn Hasher);
+
+/// Hash function of a component and the name identifying it in [`World::state_hash`].
+#[derive(Clone, Copy)]
+pub(crate) struct HashFns {
+    pub(crate) name: &'static str,
+    pub(crate) hash: HashFn,
+}
+
+unsafe fn eq<T: PartialEq>(a: *const u8, b: *const u8) -> bool {
+    unsafe { *a.cast::<T>() == *b.cast::<T>() }
+}
+
+unsafe fn hash<T: Hash>(value: *const u8, mut state: &mut dyn Hasher) {
+    unsafe { (*value.cast::<T>()).hash(&mut state) }
+}
+
+/// 64-bit FNV-1a hasher writing integers as little endian, so the result is the same on every platform and Rust version.
+struct StableHasher(u64);
+
+impl StableHasher {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_u64(i as u64);
+    }
+}
+
+impl World {
+    /// Registers the equality function of the component, used to compare type-erased values e.g. by [`World::set_if_neq_by_id`].
+    pub fn register_comparable<T: Component + PartialEq>(&mut self) {
+        self.register_component::<T>();
+        // The component was just registered, so it has an entry
+        self.component_info_mut(&ComponentId::of::<T>()).unwrap().eq = Some(eq::<T>);
+    }
+
+    /// Registers the hash function of the component, used to hash type-erased values, e.g. `world.register_hashable::<Position>("position")`.
+    /// The name identifies the component in [`World::state_hash`], so peers comparing hashes must register it under the same name.
+    /// Panics when another component is hashable under the name.
+    pub fn register_hashable<T: Component + Hash>(&mut self, name: &'static str) {
+        let id = ComponentId::of::<T>();
+        let taken = self
+            .components()
+            .iter()
+            .any(|info| info.id != id && info.hash.is_some_and(|fns| fns.name == name));
+        assert!(!taken, "Another component is hashable as `{name}`");
+
+        self.register_component::<T>();
+        self.component_info_mut(&id).unwrap().hash = Some(HashFns {
+            name,
+            hash: hash::<T>,
+        });
+    }
+
+    #[must_use]
+    pub fn is_comparable<T: Component>(&self) -> bool {
+        self.component_info(&ComponentId::of::<T>())
+            .is_some_and(|info| info.eq.is_some())
+    }
+
+    #[must_use]
+    pub fn is_hashable<T: Component>(&self) -> bool {
+        self.component_info(&ComponentId::of::<T>())
+            .is_some_and(|info| info.hash.is_some())
+    }
+
+    /// Compares two values of the component with its registered equality function, returns `None` when it has none.
+    ///
+    /// # Safety
+    /// Caller must ensure that both pointers point to valid values of the component identified by `id`.
+    #[must_use]
+    pub(crate) unsafe fn component_eq(
+        &self,
+        id: &ComponentId,
+        a: *const u8,
+        b: *const u8,
+    ) -> Option<bool> {
+        let eq = self.component_info(id)?.eq?;
+        Some(unsafe { eq(a, b) })
+    }
+
+    /// Feeds the value into the hasher with the registered hash function, returns `false` when the component has none.
+    ///
+    /// # Safety
+    /// Caller must ensure that the pointer points to a valid value of the component identified by `id`.
+    pub(crate) unsafe fn component_hash(
+        &self,
+        id: &ComponentId,
+        value: *const u8,
+        state: &mut dyn Hasher,
+    ) -> bool {
+        let Some(fns) = self.component_info(id).and_then(|info| info.hash) else {
+            return false;
+        };
+        unsafe { (fns.hash)(value, state) };
+        true
+    }
+
+    /// Feeds the component of the entity into the hasher with its registered hash function.
+    /// Returns `false` without hashing when the entity has no such component or the component is not hashable.
+    pub fn hash_component_by_id(
+        &self,
+        entity: Entity,
+        id: ComponentId,
+        state: &mut dyn Hasher,
+    ) -> bool {
+        let Some(archetype) = self.archetype_of(entity).filter(|_| self.is_alive(entity)) else {
+            return false;
+        };
+        let row = self.entities.metas[entity.index].location.row;
+        let Some(value) = archetype.get_bytes(id, row) else {
+            return false;
+        };
+
+        unsafe {
+            self.component_hash(&id, value, state) // SAFETY: The bytes come from the column of the component
+        }
+    }
+
+    /// Inserts the component only when the entity has no equal one already, so unchanged values are not marked as changed.
+    /// Returns `true` when the component was written.
+    pub fn set_if_neq<T: Component + PartialEq>(&mut self, entity: Entity, component: T) -> bool {
+        if !self.is_alive(entity) || self.get_component::<T>(entity) == Some(&component) {
+            return false;
+        }
+
+        self.insert_component(entity, component);
+        true
+    }
+
+    /// Untyped version of [`World::set_if_neq`] for components of an existing entity, compared with the registered equality function.
+    /// Components without one are always written. Returns `true` when the value was moved into the component and marked as changed,
+    /// otherwise the caller still owns the value. It also returns `false` when the entity has no such component.
+    ///
+    /// # Safety
+    /// Caller must ensure that `value` points to a valid value of the component identified by `id`, and must not use or drop it when `true` is returned.
+    pub unsafe fn set_if_neq_by_id(
+        &mut self,
+        entity: Entity,
+        id: ComponentId,
+        value: *const u8,
+    ) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+        let row = self.entities.metas[entity.index].location.row;
+        let Some(archetype) = self.archetype_of(entity) else {
+            return false;
+        };
+        let Some(column) = archetype.column(&id) else {
+            return false;
+        };
+
+        unsafe {
+            let current = column.get_bytes(row);
+            if self.component_eq(&id, current, value) == Some(true) {
+                return false;
+            }
+
+            // SAFETY: The world is borrowed mutably, so nothing else references the value
+            let info = column.type_info();
+            info.call_drop(current);
+            std::ptr::copy_nonoverlapping(value, current, info.size);
+        }
+        column.set_tick(row, self.change_tick());
+        true
+    }
+
+    /// Produces a stable hash of the hashable components of every entity matching the filter, e.g. `state_hash::<With<Synced>>()`.
+    /// Entities are visited by id and components by their registered name, so worlds with the same state hash the same regardless of archetype or spawn order.
+    /// Ids and lengths are hashed as fixed-width little endian integers, so lockstep peers on any platform or compiler version can compare hashes each tick to detect desyncs.
+    /// Components not registered with [`World::register_hashable`] are skipped.
+    #[must_use]
+    pub fn state_hash<F: Filter>(&self) -> u64 {
+        let mut rows = Vec::new();
+        let mut columns = Vec::new();
+
+        for (index, archetype) in self.matching_archetypes::<F>().enumerate() {
+            let mut hashable: Vec<_> = archetype
+                .columns()
+                .filter_map(|(id, column)| Some((self.component_info(id)?.hash?, column)))
+                .collect();
+            hashable.sort_unstable_by_key(|(fns, _)| fns.name);
+            let hashable: Vec<_> = hashable
+                .into_iter()
+                .map(|(fns, column)| (fns, ColumnGuard::new(column, "hash")))
+                .collect();
+
+            rows.extend(
+                archetype
+                    .entities()
+                    .iter()
+                    .enumerate()
+                    .map(|(row, entity)| (*entity, index, row)),
+            );
+            columns.push(hashable);
+        }
+        rows.sort_unstable_by_key(|(entity, _, _)| (entity.index, entity.generation));
+
+        let mut state = StableHasher::new();
+        for (entity, index, row) in rows {
+            state.write_u64(entity.index as u64);
+            state.write_u64(entity.generation as u64);
+
+            for (fns, column) in &columns[index] {
+                state.write_u64(fns.name.len() as u64);
+                state.write(fns.name.as_bytes());
+                unsafe {
+                    (fns.hash)(column.get_bytes(row), &mut state); // SAFETY: The row is within bounds and the function belongs to the column's type
+                }
+            }
+        }
+        state.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::world::{Component, World};
+
+    #[derive(Hash)]
+    struct Position(i32, i32);
+    #[derive(Hash)]
+    struct Label(String);
+
+    impl Component for Position {}
+    impl Component for Label {}
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.register_hashable::<Position>("position");
+        world.register_hashable::<Label>("label");
+        world
+    }
+
+    #[test]
+    fn state_hash_ignores_archetype_layout() {
+        let mut a = world();
+        let first = a.spawn(Position(1, 2));
+        a.spawn(Position(3, 4));
+        a.insert_component(first, Label("first".into()));
+
+        // Another registration order and archetype history ending in the same state
+        let mut b = World::new();
+        b.register_hashable::<Label>("label");
+        b.register_hashable::<Position>("position");
+        let first = b.spawn((Position(1, 2), Label("first".into())));
+        b.spawn(Position(3, 4));
+        assert_eq!(first.index, 0);
+
+        assert_eq!(a.state_hash::<()>(), b.state_hash::<()>());
+    }
+
+    #[test]
+    fn state_hash_changes_with_the_state() {
+        let mut world = world();
+        let entity = world.spawn(Position(1, 2));
+        let before = world.state_hash::<()>();
+
+        world.get_component_mut::<Position>(entity).unwrap().0 = 5;
+        assert_ne!(world.state_hash::<()>(), before);
+    }
+
+    #[test]
+    fn state_hash_is_stable() {
+        let mut world = world();
+        world.spawn((Position(1, -2), Label("a".into())));
+        world.spawn(Position(3, 4));
+
+        // Pinned so a change of the hashed layout, or a platform dependent one, is noticed
+        assert_eq!(world.state_hash::<()>(), 1_500_170_058_474_138_056);
+    }
+
+    #[test]
+    #[should_panic(expected = "Another component is hashable as `position`")]
+    fn hash_names_are_unique() {
+        let mut world = world();
+        world.register_hashable::<Label>("position");
+    }
+}
@@ -0,0 +1,242 @@
+use std::{any::Any, collections::HashMap, sync::Arc};
+
+use crate::{
+    blob_data::TypeInfo,
+    clone::CloneFns,
+    compare::{EqFn, HashFns},
+    hooks::{ComponentHooks, Hook, HookMasks},
+    mask::ComponentMask,
+    world::{ComponentId, World},
+};
+
+/// Registry entry of a component type, listed by [`Components`].
+#[derive(Clone)]
+pub struct ComponentInfo {
+    pub(crate) id: ComponentId,
+    pub(crate) name: &'static str,
+    pub(crate) bit: ComponentMask,
+    pub(crate) type_info: TypeInfo,
+    pub(crate) clone: Option<CloneFns>,
+    pub(crate) eq: Option<EqFn>,
+    pub(crate) hash: Option<HashFns>,
+    /// The component is `Send + Sync`, so it can be read from other threads through a [`FrozenWorld`](crate::freeze::FrozenWorld).
+    pub(crate) shareable: bool,
+    /// The component is `Send + Sync`, so systems accessing it can run on the worker threads of a parallel [`Schedule`](crate::schedule::Schedule).
+    pub(crate) thread_safe: bool,
+    pub(crate) hooks: ComponentHooks,
+    /// Rows reserved in the columns of new archetypes, see [`WorldBuilder::column_capacity`](crate::builder::WorldBuilder::column_capacity).
+    pub(crate) column_capacity: usize,
+    pub(crate) metadata: Option<Arc<dyn Any + Send + Sync>>,
+}
+
+impl ComponentInfo {
+    /// Creates an entry without any optional functions, the bit is assigned when it is registered.
+    pub(crate) fn new(id: ComponentId, name: &'static str, type_info: TypeInfo) -> Self {
+        Self {
+            id,
+            name,
+            bit: ComponentMask::EMPTY,
+            type_info,
+            clone: None,
+            eq: None,
+            hash: None,
+            shareable: false,
+            thread_safe: false,
+            hooks: ComponentHooks::default(),
+            column_capacity: 0,
+            metadata: None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    /// Rust type name of the component, or `<dynamic>` for components registered with [`World::register_dynamic`].
+    #[inline]
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Bit of the component in archetype and filter masks.
+    #[inline]
+    #[must_use]
+    pub fn bit(&self) -> &ComponentMask {
+        &self.bit
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn type_info(&self) -> &TypeInfo {
+        &self.type_info
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_cloneable(&self) -> bool {
+        self.clone.is_some()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_comparable(&self) -> bool {
+        self.eq.is_some()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_hashable(&self) -> bool {
+        self.hash.is_some()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_shareable(&self) -> bool {
+        self.shareable
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_thread_safe(&self) -> bool {
+        self.thread_safe
+    }
+
+    /// Returns the metadata set with [`World::set_component_metadata`] when it is of type `M`.
+    #[must_use]
+    pub fn metadata<M: Any>(&self) -> Option<&M> {
+        self.metadata.as_deref()?.downcast_ref()
+    }
+}
+
+impl std::fmt::Debug for ComponentInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ComponentInfo")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("bit", &self.bit)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Every component type registered in a [`World`], in registration order, returned by [`World::components`].
+#[derive(Clone, Default)]
+pub struct Components {
+    infos: Vec<ComponentInfo>,
+    indices: HashMap<ComponentId, usize>,
+    pub(crate) hooks: HookMasks,
+    /// Hooks of the components dropped by [`Components::clear`] and the kinds they were marked for, restored when they are registered again.
+    retained: HashMap<ComponentId, (ComponentHooks, Vec<Hook>)>,
+}
+
+impl Components {
+    /// Stores the entry and assigns the next bit to it, or returns the bit of the already registered entry.
+    pub(crate) fn register(&mut self, mut info: ComponentInfo) -> ComponentMask {
+        if let Some(index) = self.indices.get(&info.id) {
+            return self.infos[*index].bit.clone();
+        }
+        info.bit = ComponentMask::bit(self.infos.len());
+        if let Some((hooks, hooked)) = self.retained.remove(&info.id) {
+            info.hooks = hooks;
+            for hook in hooked {
+                self.hooks.mark(hook, &info.bit);
+            }
+        }
+        self.indices.insert(info.id, self.infos.len());
+        self.infos.push(info);
+        self.infos.last().unwrap().bit.clone()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn get(&self, id: ComponentId) -> Option<&ComponentInfo> {
+        self.infos.get(*self.indices.get(&id)?)
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn get_mut(&mut self, id: ComponentId) -> Option<&mut ComponentInfo> {
+        self.infos.get_mut(*self.indices.get(&id)?)
+    }
+
+    /// Returns the entry of the component with the given bit index.
+    #[inline]
+    #[must_use]
+    pub(crate) fn at(&self, bit: usize) -> &ComponentInfo {
+        &self.infos[bit]
+    }
+
+    /// Returns the entry of the component with the given type name.
+    #[must_use]
+    pub fn find(&self, name: &str) -> Option<&ComponentInfo> {
+        self.infos.iter().find(|info| info.name == name)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, id: ComponentId) -> bool {
+        self.indices.contains_key(&id)
+    }
+
+    /// Iterates over the entries in bit order.
+    pub fn iter(&self) -> std::slice::Iter<'_, ComponentInfo> {
+        self.infos.iter()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.infos.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.infos.is_empty()
+    }
+
+    /// Drops every entry, keeping their hooks and observer marks for when the components are registered again.
+    pub(crate) fn clear(&mut self) {
+        for info in &self.infos {
+            let hooked: Vec<_> = Hook::ALL
+                .into_iter()
+                .filter(|hook| self.hooks.get(*hook).contains(&info.bit))
+                .collect();
+            if !hooked.is_empty() {
+                self.retained.insert(info.id, (info.hooks, hooked));
+            }
+        }
+        self.infos.clear();
+        self.indices.clear();
+        self.hooks = HookMasks::default();
+    }
+}
+
+impl<'a> IntoIterator for &'a Components {
+    type Item = &'a ComponentInfo;
+    type IntoIter = std::slice::Iter<'a, ComponentInfo>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl World {
+    /// Returns the registry of every component type registered in the world, e.g. for inspectors listing what can be added to an entity.
+    #[inline]
+    #[must_use]
+    pub fn components(&self) -> &Components {
+        &self.components
+    }
+
+    /// Attaches user metadata to the registered component, e.g. a display name or editor widget, replacing the previous one.
+    /// It can be read back with [`ComponentInfo::metadata`]. Panics when the component is not registered.
+    pub fn set_component_metadata(&mut self, id: ComponentId, metadata: impl Any + Send + Sync) {
+        self.components
+            .get_mut(id)
+            .expect("Cannot set metadata of a component which is not registered")
+            .metadata = Some(Arc::new(metadata));
+    }
+}
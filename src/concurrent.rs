@@ -0,0 +1,347 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    hash::{BuildHasher, RandomState},
+    sync::{
+        Mutex, PoisonError,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use crate::{
+    archetype::ArchetypeId,
+    bundle::Bundle,
+    quota::QuotaError,
+    world::{ComponentId, Entity, EntityMeta, Location, World, WorldId},
+};
+
+/// Number of staging areas, threads are spread over them by their id so they rarely wait for each other.
+const STRIPES: usize = 16;
+
+/// Bundles of one type staged by a [`ConcurrentSpawner`].
+trait Staged: Send {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    /// Moves the bundles staged in another stripe, which must be of the same type, to the end of this stage.
+    fn absorb(&mut self, other: Box<dyn Staged>);
+    fn merge(self: Box<Self>, world: &mut World);
+}
+
+impl<B: Bundle + Send + 'static> Staged for Vec<(Entity, B)> {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn absorb(&mut self, mut other: Box<dyn Staged>) {
+        self.append(other.as_any_mut().downcast_mut::<Self>().unwrap());
+    }
+
+    fn merge(mut self: Box<Self>, world: &mut World) {
+        B::register(world);
+        let index = world.archetype_index(&B::bitmask(world));
+
+        // Threads stage in any order, sorting the bundles of every stripe together keeps the rows in id order
+        self.sort_unstable_by_key(|(entity, _)| entity.index);
+        for (entity, bundle) in *self {
+            world.put_in_archetype(entity, bundle, index);
+        }
+    }
+}
+
+/// Remaining room of an archetype with a quota, see [`World::set_archetype_limit`].
+struct ArchetypeQuota {
+    /// Sorted ids of the components of the archetype.
+    components: Vec<ComponentId>,
+    index: usize,
+    limit: usize,
+    remaining: AtomicUsize,
+}
+
+type Stage = HashMap<TypeId, Box<dyn Staged>>;
+
+/// Spawns entities from several threads at once, see [`World::spawn_concurrent`].
+/// Entity ids are reserved atomically and the bundles are staged per thread, then moved into the archetypes when the scope ends.
+pub struct ConcurrentSpawner {
+    start: usize,
+    next: AtomicUsize,
+    /// Entity quota of the world and the number of entities it still has room for.
+    limit: Option<(usize, usize)>,
+    archetype_quotas: Vec<ArchetypeQuota>,
+    world: WorldId,
+    stripes: [Mutex<Stage>; STRIPES],
+    hasher: RandomState,
+}
+
+impl ConcurrentSpawner {
+    /// Reserves an entity id and stages the bundle, which is stored in the world when the scope of [`World::spawn_concurrent`] ends.
+    /// Panics when the entity quota is exhausted, use [`ConcurrentSpawner::try_spawn`] to handle it.
+    pub fn spawn<B: Bundle + Send + 'static>(&self, bundle: B) -> Entity {
+        match self.try_spawn(bundle) {
+            Ok(entity) => entity,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// Spawns an entity like [`ConcurrentSpawner::spawn`], but reports an error instead of panicking when a quota is exhausted.
+    pub fn try_spawn<B: Bundle + Send + 'static>(&self, bundle: B) -> Result<Entity, QuotaError> {
+        let quota = self.reserve_in_archetype::<B>()?;
+        let index = self.reserve().inspect_err(|_| {
+            if let Some(quota) = quota {
+                quota.remaining.fetch_add(1, Ordering::Relaxed);
+            }
+        })?;
+        let entity = Entity::new(index, 0, self.world);
+
+        let stripe = self.hasher.hash_one(std::thread::current().id()) as usize % STRIPES;
+        let mut stage = self.stripes[stripe]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        stage
+            .entry(TypeId::of::<B>())
+            .or_insert_with(|| Box::new(Vec::<(Entity, B)>::new()))
+            .as_any_mut()
+            .downcast_mut::<Vec<(Entity, B)>>()
+            .unwrap()
+            .push((entity, bundle));
+
+        Ok(entity)
+    }
+
+    /// Number of entities spawned so far.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.next.load(Ordering::Relaxed) - self.start
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Takes one row of the quota of the bundle's archetype, if it has one.
+    fn reserve_in_archetype<B: Bundle>(&self) -> Result<Option<&ArchetypeQuota>, QuotaError> {
+        if self.archetype_quotas.is_empty() {
+            return Ok(None);
+        }
+        let mut components = B::component_ids();
+        components.sort_unstable();
+        components.dedup();
+        let Some(quota) = self
+            .archetype_quotas
+            .iter()
+            .find(|quota| quota.components == components)
+        else {
+            return Ok(None);
+        };
+
+        quota
+            .remaining
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |remaining| {
+                remaining.checked_sub(1)
+            })
+            .map_err(|_| QuotaError::Archetype {
+                id: ArchetypeId(quota.index),
+                limit: quota.limit,
+            })?;
+        Ok(Some(quota))
+    }
+
+    fn reserve(&self) -> Result<usize, QuotaError> {
+        let Some((limit, room)) = self.limit else {
+            return Ok(self.next.fetch_add(1, Ordering::Relaxed));
+        };
+
+        self.next
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |next| {
+                (next - self.start < room).then_some(next + 1)
+            })
+            .map_err(|_| QuotaError::Entities { limit })
+    }
+}
+
+impl World {
+    /// Runs the closure with a spawner which can be shared between threads, e.g. the workers of [`std::thread::scope`] generating content.
+    /// Spawned entities get fresh ids right away, but they only become part of the world when the closure returns.
+    /// Entities spawned with the same bundle type are stored in id order, quotas are checked while spawning.
+    pub fn spawn_concurrent<R>(&mut self, f: impl FnOnce(&ConcurrentSpawner) -> R) -> R {
+        // New ids are taken after the last slot, so reservations never touch the free list
+        let start = self.entities.metas.len();
+        let spawner = ConcurrentSpawner {
+            start,
+            next: AtomicUsize::new(start),
+            limit: self
+                .entity_limit()
+                .map(|limit| (limit, limit.saturating_sub(self.entities.len()))),
+            archetype_quotas: self.archetype_quotas(),
+            world: self.id(),
+            stripes: std::array::from_fn(|_| Mutex::new(HashMap::new())),
+            hasher: RandomState::new(),
+        };
+
+        let result = f(&spawner);
+
+        let end = spawner.next.into_inner();
+        self.entities.metas.resize(
+            end,
+            EntityMeta {
+                generation: 0,
+                location: Location::EMPTY,
+                free: false,
+            },
+        );
+        let mut merged = Stage::new();
+        for stripe in spawner.stripes {
+            let stage = stripe.into_inner().unwrap_or_else(PoisonError::into_inner);
+            for (id, staged) in stage {
+                match merged.get_mut(&id) {
+                    Some(existing) => existing.absorb(staged),
+                    None => {
+                        merged.insert(id, staged);
+                    }
+                }
+            }
+        }
+        for staged in merged.into_values() {
+            staged.merge(self);
+        }
+
+        result
+    }
+
+    /// Remaining room of every archetype with a quota.
+    fn archetype_quotas(&self) -> Vec<ArchetypeQuota> {
+        self.quotas
+            .archetypes
+            .iter()
+            .map(|(index, limit)| {
+                let archetype = &self.archetypes()[*index];
+                let mut components: Vec<_> = self
+                    .components()
+                    .iter()
+                    .filter(|info| archetype.bitmask().contains(info.bit()))
+                    .map(|info| info.id())
+                    .collect();
+                components.sort_unstable();
+                ArchetypeQuota {
+                    components,
+                    index: *index,
+                    limit: *limit,
+                    remaining: AtomicUsize::new(limit.saturating_sub(archetype.count())),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::{
+        quota::QuotaError,
+        world::{Component, Entity, World},
+    };
+
+    #[derive(Debug, PartialEq)]
+    struct Value(usize);
+    #[derive(Debug, PartialEq)]
+    struct Marker;
+
+    impl Component for Value {}
+    impl Component for Marker {}
+
+    #[test]
+    fn entities_spawned_from_many_threads_are_stored() {
+        let mut world = World::new();
+        let existing = world.spawn(Value(usize::MAX));
+
+        let mut spawned: Vec<Entity> = world.spawn_concurrent(|spawner| {
+            thread::scope(|scope| {
+                let workers: Vec<_> = (0..4)
+                    .map(|worker| {
+                        scope.spawn(move || {
+                            (0..100)
+                                .map(|i| spawner.spawn(Value(worker * 100 + i)))
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                    .collect();
+                workers
+                    .into_iter()
+                    .flat_map(|worker| worker.join().unwrap())
+                    .collect()
+            })
+        });
+
+        assert_eq!(spawned.len(), 400);
+        assert!(world.is_alive(existing));
+        for entity in &spawned {
+            assert!(world.is_alive(*entity));
+            assert!(world.get_component::<Value>(*entity).is_some());
+        }
+        spawned.sort_unstable_by_key(|entity| entity.index);
+        spawned.dedup();
+        assert_eq!(spawned.len(), 400);
+
+        let values: Vec<usize> = world
+            .query::<&Value>()
+            .iter(&world)
+            .map(|value| value.0)
+            .collect();
+        assert_eq!(values.len(), 401);
+    }
+
+    #[test]
+    fn rows_are_in_id_order_across_threads() {
+        let mut world = World::new();
+        world.spawn_concurrent(|spawner| {
+            thread::scope(|scope| {
+                for _ in 0..4 {
+                    scope.spawn(|| {
+                        for _ in 0..50 {
+                            spawner.spawn((Value(0), Marker));
+                        }
+                    });
+                }
+            });
+        });
+
+        let ids: Vec<usize> = world
+            .query::<Entity>()
+            .iter(&world)
+            .map(|entity| entity.index)
+            .collect();
+        assert_eq!(ids, (0..200).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn entity_quota_is_checked_while_spawning() {
+        let mut world = World::new();
+        world.set_entity_limit(Some(3));
+        world.spawn(Value(0));
+
+        let results: Vec<_> =
+            world.spawn_concurrent(|spawner| (0..4).map(|i| spawner.try_spawn(Value(i))).collect());
+        assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 2);
+        assert_eq!(results[3], Err(QuotaError::Entities { limit: 3 }));
+        assert_eq!(world.iter_entities().count(), 3);
+    }
+
+    #[test]
+    fn archetype_quota_is_checked_while_spawning() {
+        let mut world = World::new();
+        let id = world.preregister_archetype::<(Value, Marker)>();
+        world.set_archetype_limit(id, Some(2));
+
+        let (limited, other) = world.spawn_concurrent(|spawner| {
+            let limited: Vec<_> = (0..3)
+                .map(|i| spawner.try_spawn((Marker, Value(i))))
+                .collect();
+            (limited, spawner.try_spawn(Value(9)))
+        });
+        assert!(limited[0].is_ok() && limited[1].is_ok());
+        assert_eq!(limited[2], Err(QuotaError::Archetype { id, limit: 2 }));
+        assert!(other.is_ok());
+        assert_eq!(world.iter_entities().count(), 3);
+        assert_eq!(world.query::<&Marker>().iter(&world).count(), 2);
+    }
+}
@@ -0,0 +1,173 @@
+use std::{collections::HashSet, fmt};
+
+use crate::{
+    mask::ComponentMask,
+    world::{Location, RETIRED, World},
+};
+
+/// Returned by [`World::check_consistency`] when the internal bookkeeping of the world is corrupted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsistencyError {
+    /// Description of every problem found, in the order they were found.
+    pub problems: Vec<String>,
+}
+
+impl fmt::Display for ConsistencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "world is inconsistent: {}", self.problems.join("; "))
+    }
+}
+
+impl std::error::Error for ConsistencyError {}
+
+impl World {
+    /// Validates the free list, the entity locations, the archetype rows and columns and the archetype lookup against each other,
+    /// e.g. after every frame in debug builds to catch a corruption close to where it happened. It visits every entity, so it is slow.
+    pub fn check_consistency(&self) -> Result<(), ConsistencyError> {
+        let mut problems = Vec::new();
+        let entities = &self.entities;
+        let archetypes = self.archetypes();
+
+        let mut free = HashSet::new();
+        for index in &entities.free {
+            if *index >= entities.metas.len() {
+                problems.push(format!("free index {index} is out of bounds"));
+            } else if !free.insert(*index) {
+                problems.push(format!("free index {index} is listed twice"));
+            } else if entities.metas[*index].location != Location::EMPTY {
+                problems.push(format!("free index {index} has a location"));
+            } else if entities.metas[*index].generation == RETIRED {
+                problems.push(format!("free index {index} is retired"));
+            }
+        }
+        for (index, meta) in entities.metas.iter().enumerate() {
+            if meta.free != free.contains(&index) {
+                problems.push(format!(
+                    "index {index} is marked {} but is {}in the free list",
+                    if meta.free { "free" } else { "used" },
+                    if free.contains(&index) { "" } else { "not " }
+                ));
+            }
+        }
+
+        let retired = entities
+            .metas
+            .iter()
+            .filter(|meta| meta.generation == RETIRED)
+            .count();
+        if retired != entities.retired {
+            problems.push(format!(
+                "{retired} slots are retired but {} are counted",
+                entities.retired
+            ));
+        }
+
+        for entity in entities.alive() {
+            let location = entities.metas[entity.index].location;
+            if location == Location::EMPTY {
+                continue;
+            }
+
+            let Some(archetype) = archetypes.get(location.archetype) else {
+                problems.push(format!(
+                    "{entity:?} points to missing archetype {}",
+                    location.archetype
+                ));
+                continue;
+            };
+            match archetype.entities().get(location.row) {
+                Some(row) if *row == entity => {}
+                Some(row) => problems.push(format!(
+                    "{entity:?} points to row {} of archetype {}, which stores {row:?}",
+                    location.row, location.archetype
+                )),
+                None => problems.push(format!(
+                    "{entity:?} points to missing row {} of archetype {}",
+                    location.row, location.archetype
+                )),
+            }
+        }
+
+        for (index, archetype) in archetypes.iter().enumerate() {
+            let count = archetype.count();
+            if archetype.entities().len() != count {
+                problems.push(format!(
+                    "archetype {index} stores {} rows but counts {count}",
+                    archetype.entities().len()
+                ));
+            }
+
+            for (row, entity) in archetype.entities().iter().enumerate() {
+                let expected = Location {
+                    archetype: index,
+                    row,
+                };
+                let alive = !free.contains(&entity.index)
+                    && entities
+                        .metas
+                        .get(entity.index)
+                        .is_some_and(|meta| meta.generation == entity.generation);
+                if !alive {
+                    problems.push(format!(
+                        "row {row} of archetype {index} stores dead {entity:?}"
+                    ));
+                } else if entities.metas[entity.index].location != expected {
+                    problems.push(format!(
+                        "row {row} of archetype {index} stores {entity:?}, which points elsewhere"
+                    ));
+                }
+            }
+
+            let mut bits = ComponentMask::EMPTY;
+            for (id, column) in archetype.columns() {
+                let name = self
+                    .component_info(id)
+                    .map_or("<unregistered>", |info| info.name);
+                match self.bit_of_id(id) {
+                    Some(bit) if archetype.bitmask().intersects(&bit) => bits |= bit,
+                    _ => problems.push(format!(
+                        "archetype {index} has a column of {name} outside of its bitmask"
+                    )),
+                }
+
+                let (len, ticks, added) = column.lens();
+                if len != count || ticks != count || added != count {
+                    problems.push(format!(
+                        "column of {name} in archetype {index} stores {len} values, {ticks} change ticks and {added} added ticks for {count} rows"
+                    ));
+                }
+            }
+            // Columns are created with the first entity
+            if count > 0 && bits != *archetype.bitmask() {
+                problems.push(format!(
+                    "archetype {index} is missing columns of its bitmask {:?}",
+                    archetype.bitmask().difference(&bits)
+                ));
+            }
+
+            if self.archetype_map.get(archetype.bitmask()) != Some(&index) {
+                problems.push(format!(
+                    "archetype {index} is not found by its bitmask {:?}",
+                    archetype.bitmask()
+                ));
+            }
+        }
+
+        for (bitmask, index) in &self.archetype_map {
+            if archetypes
+                .get(*index)
+                .is_none_or(|archetype| archetype.bitmask() != bitmask)
+            {
+                problems.push(format!(
+                    "bitmask {bitmask:?} maps to archetype {index} with another bitmask"
+                ));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConsistencyError { problems })
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::HashSet;
+
+use crate::{
+    archetype::Archetype,
+    blob_data::{BlobData, ColumnGuard},
+    snapshot::WorldSnapshot,
+    world::{ComponentId, Entity, World},
+};
+
+/// A component of an entity listed by a [`WorldDiff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComponentDiff {
+    pub entity: Entity,
+    pub component: ComponentId,
+    pub name: &'static str,
+}
+
+/// Changes of a [`World`] since a [`WorldSnapshot`], returned by [`World::diff`]. Every list is sorted by entity id.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorldDiff {
+    /// Entities alive now which were not alive in the snapshot.
+    pub spawned: Vec<Entity>,
+    /// Entities alive in the snapshot which are not alive anymore.
+    pub despawned: Vec<Entity>,
+    /// Components which the entity didn't have in the snapshot, including every component of spawned entities.
+    pub added: Vec<ComponentDiff>,
+    /// Components which the entity had in the snapshot and has lost since, despawned entities are not listed.
+    pub removed: Vec<ComponentDiff>,
+    /// Components whose value changed since the snapshot.
+    pub changed: Vec<ComponentDiff>,
+}
+
+impl WorldDiff {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.spawned.is_empty()
+            && self.despawned.is_empty()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+    }
+}
+
+impl World {
+    /// Lists the entities spawned and despawned and the components added, removed and changed since the snapshot was taken,
+    /// e.g. to replicate only what changed or to show an editor change list. The snapshot should come from this world or a clone of it.
+    ///
+    /// Values of components registered with [`World::register_comparable`] are compared, other components count as changed when they were
+    /// written after the snapshot, even if the value is the same.
+    #[must_use]
+    pub fn diff(&self, snapshot: &WorldSnapshot) -> WorldDiff {
+        let mut diff = WorldDiff::default();
+        let free: HashSet<_> = snapshot.entities.free.iter().copied().collect();
+
+        for entity in self.entities.alive() {
+            let location = self.entities.metas[entity.index].location;
+            let current = self.archetypes().get(location.archetype);
+
+            // The handle may come from another world, so only the index and generation are compared
+            let old_location = snapshot
+                .entities
+                .metas
+                .get(entity.index)
+                .filter(|meta| {
+                    meta.generation == entity.generation && !free.contains(&entity.index)
+                })
+                .map(|meta| meta.location);
+            let Some(old_location) = old_location else {
+                diff.spawned.push(entity);
+                for (id, _) in current.into_iter().flat_map(Archetype::columns) {
+                    diff.added.push(self.component_diff(entity, *id));
+                }
+                continue;
+            };
+            let old = snapshot.archetypes.get(old_location.archetype);
+
+            for (id, column) in current.into_iter().flat_map(Archetype::columns) {
+                match old.and_then(|old| old.column(id)) {
+                    None => diff.added.push(self.component_diff(entity, *id)),
+                    Some(old_column) => {
+                        if self.value_changed(
+                            id,
+                            column,
+                            location.row,
+                            old_column,
+                            old_location.row,
+                            snapshot.change_tick(),
+                        ) {
+                            diff.changed.push(self.component_diff(entity, *id));
+                        }
+                    }
+                }
+            }
+
+            for (id, _) in old.into_iter().flat_map(Archetype::columns) {
+                if current.and_then(|current| current.column(id)).is_none() {
+                    diff.removed.push(self.component_diff(entity, *id));
+                }
+            }
+        }
+
+        diff.despawned = snapshot
+            .entities
+            .alive()
+            .filter(|entity| {
+                self.entities
+                    .metas
+                    .get(entity.index)
+                    .is_none_or(|meta| meta.generation != entity.generation)
+            })
+            .collect();
+
+        // Columns are visited in no particular order
+        for components in [&mut diff.added, &mut diff.removed, &mut diff.changed] {
+            components.sort_unstable_by_key(|component| {
+                (
+                    component.entity.index,
+                    component.entity.generation,
+                    component.name,
+                )
+            });
+        }
+        diff
+    }
+
+    fn component_diff(&self, entity: Entity, component: ComponentId) -> ComponentDiff {
+        ComponentDiff {
+            entity,
+            component,
+            name: self
+                .component_info(&component)
+                .map_or("<unregistered>", |info| info.name),
+        }
+    }
+
+    /// Compares the current value in the column with the one in the snapshot column.
+    fn value_changed(
+        &self,
+        id: &ComponentId,
+        column: &BlobData,
+        row: usize,
+        old_column: &BlobData,
+        old_row: usize,
+        since: u64,
+    ) -> bool {
+        let equal = {
+            let column = ColumnGuard::new(column, "diff");
+            unsafe {
+                // SAFETY: Both rows are within bounds and both columns store the component identified by `id`
+                self.component_eq(id, column.get_bytes(row), old_column.get_bytes(old_row))
+            }
+        };
+
+        match equal {
+            Some(equal) => !equal,
+            None => column.tick(row) > since,
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use crate::{
+    mask::ComponentMask,
+    query::Filter,
+    world::{Component, Entity, World},
+};
+
+/// Marker of a temporarily deactivated entity. Queries skip disabled entities unless they require the marker or include [`IncludeDisabled`],
+/// so an entity can be paused without despawning and respawning its component data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Disabled;
+
+impl Component for Disabled {}
+
+/// Filter which makes a query match disabled entities too, e.g. `QueryData<&Health, IncludeDisabled>`.
+pub struct IncludeDisabled;
+
+impl Filter for IncludeDisabled {
+    #[inline(always)]
+    fn bitmask(_world: &World) -> (ComponentMask, ComponentMask) {
+        (ComponentMask::EMPTY, ComponentMask::EMPTY)
+    }
+
+    #[inline(always)]
+    fn includes_disabled() -> bool {
+        true
+    }
+}
+
+impl World {
+    /// Hides the entity from queries by inserting the [`Disabled`] marker.
+    pub fn disable(&mut self, entity: Entity) {
+        if !self.is_disabled(entity) {
+            self.insert_component(entity, Disabled);
+        }
+    }
+
+    /// Makes a disabled entity visible to queries again.
+    pub fn enable(&mut self, entity: Entity) {
+        self.remove_component::<Disabled>(entity);
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_disabled(&self, entity: Entity) -> bool {
+        self.has_component::<Disabled>(entity)
+    }
+}
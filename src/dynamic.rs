@@ -0,0 +1,404 @@
+use std::alloc::Layout;
+
+use crate::{
+    archetype::Archetype,
+    blob_data::{BlobData, TypeInfo},
+    components::ComponentInfo,
+    disabled::Disabled,
+    hooks::Hook,
+    mask::ComponentMask,
+    world::{ComponentId, Entity, World},
+};
+
+/// Access of a dynamic query to one component, see [`World::query_dynamic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComponentAccess {
+    /// Fetches the component for reading.
+    Read(ComponentId),
+    /// Fetches the component for writing, rows are marked as changed when they are accessed mutably.
+    Write(ComponentId),
+    /// Requires the component without fetching it.
+    With(ComponentId),
+    /// Skips entities with the component.
+    Without(ComponentId),
+}
+
+/// Iterator of a query over components chosen at runtime, returned by [`World::query_dynamic`].
+/// Rows are fetched one at a time with [`DynQueryIter::fetch_next`], so mutable access to a row never outlives the next fetch.
+pub struct DynQueryIter<'w> {
+    /// Matching archetypes with at least one row, their fetched columns are borrowed.
+    archetypes: Vec<&'w Archetype>,
+    /// Fetched components and whether they are written, in the order of the access list.
+    fetched: Vec<(ComponentId, bool)>,
+    /// Fetched columns of the current archetype.
+    columns: Vec<&'w BlobData>,
+    cursor: usize,
+    row: usize,
+    tick: Option<u64>,
+}
+
+impl<'w> DynQueryIter<'w> {
+    fn new(
+        archetypes: Vec<&'w Archetype>,
+        fetched: Vec<(ComponentId, bool)>,
+        tick: Option<u64>,
+    ) -> Self {
+        let mut iter = Self {
+            archetypes: Vec::new(),
+            fetched,
+            columns: Vec::new(),
+            cursor: 0,
+            row: 0,
+            tick,
+        };
+        for archetype in archetypes {
+            for (borrowed, (id, write)) in iter.fetched.iter().enumerate() {
+                let column = archetype.column(id).unwrap();
+                let free = if *write {
+                    column.borrow_mut()
+                } else {
+                    column.borrow()
+                };
+                if !free {
+                    Self::release(archetype, &iter.fetched[..borrowed]);
+                    // Dropping the iterator releases the archetypes borrowed before
+                    drop(iter);
+                    panic!("Conflicting Queries Detected");
+                }
+            }
+            iter.archetypes.push(archetype);
+        }
+        iter
+    }
+
+    fn release(archetype: &Archetype, fetched: &[(ComponentId, bool)]) {
+        for (id, write) in fetched {
+            let column = archetype.column(id).unwrap();
+            if *write {
+                column.release_mut();
+            } else {
+                column.release();
+            }
+        }
+    }
+
+    /// Returns the next matching row.
+    pub fn fetch_next(&mut self) -> Option<DynRow<'_>> {
+        loop {
+            let archetype = *self.archetypes.get(self.cursor)?;
+            if self.columns.is_empty() && !self.fetched.is_empty() {
+                self.columns.extend(
+                    self.fetched
+                        .iter()
+                        .map(|(id, _)| archetype.column(id).unwrap()),
+                );
+            }
+            if self.row < archetype.count() {
+                self.row += 1;
+                return Some(DynRow {
+                    entity: archetype.entities()[self.row - 1],
+                    row: self.row - 1,
+                    columns: &self.columns,
+                    fetched: &self.fetched,
+                    tick: self.tick,
+                });
+            }
+
+            self.cursor += 1;
+            self.row = 0;
+            self.columns.clear();
+        }
+    }
+}
+
+impl Drop for DynQueryIter<'_> {
+    fn drop(&mut self) {
+        for archetype in &self.archetypes {
+            Self::release(archetype, &self.fetched);
+        }
+    }
+}
+
+/// Row of a dynamic query, its components are indexed by their position among the [`ComponentAccess::Read`] and [`ComponentAccess::Write`] accesses.
+pub struct DynRow<'a> {
+    entity: Entity,
+    row: usize,
+    columns: &'a [&'a BlobData],
+    fetched: &'a [(ComponentId, bool)],
+    tick: Option<u64>,
+}
+
+impl DynRow<'_> {
+    #[inline]
+    #[must_use]
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    /// Number of fetched components.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    /// Returns the id of the fetched component at the given position.
+    #[inline]
+    #[must_use]
+    pub fn id(&self, index: usize) -> ComponentId {
+        self.fetched[index].0
+    }
+
+    /// Returns a pointer to the fetched component at the given position, valid until the next fetch.
+    #[inline]
+    #[must_use]
+    pub fn ptr(&self, index: usize) -> *const u8 {
+        // SAFETY: The row is within the archetype of the column
+        unsafe { self.columns[index].get_bytes(self.row) }
+    }
+
+    /// Returns a mutable pointer to the written component at the given position and marks it as changed.
+    /// Panics when the component was not requested with [`ComponentAccess::Write`].
+    #[must_use]
+    pub fn ptr_mut(&mut self, index: usize) -> *mut u8 {
+        assert!(
+            self.fetched[index].1,
+            "Component is not fetched for writing"
+        );
+        let column = self.columns[index];
+        if let Some(tick) = self.tick {
+            column.set_tick(self.row, tick);
+        }
+        // SAFETY: The row is within the archetype of the column, which is borrowed mutably
+        unsafe { column.get_bytes(self.row) }
+    }
+
+    /// Returns the bytes of the fetched component at the given position.
+    ///
+    /// # Safety
+    /// Every byte of the component must be initialized, so its type must have no padding, e.g. an integer or an array of them.
+    #[must_use]
+    pub unsafe fn bytes(&self, index: usize) -> &[u8] {
+        let size = self.columns[index].type_info().size();
+        // SAFETY: The pointer points to a value of `size` bytes, which isn't written while the row is borrowed, and the caller guarantees they are initialized
+        unsafe { std::slice::from_raw_parts(self.ptr(index), size) }
+    }
+
+    /// Returns the bytes of the written component at the given position and marks it as changed, like [`DynRow::ptr_mut`].
+    /// Panics when the component was not requested with [`ComponentAccess::Write`].
+    ///
+    /// # Safety
+    /// Like [`DynRow::bytes`], and the bytes written through the slice must form a valid value of the component,
+    /// e.g. no pointer, reference, `bool` or enum may be given bytes it can't hold. The old value is not dropped.
+    #[must_use]
+    pub unsafe fn bytes_mut(&mut self, index: usize) -> &mut [u8] {
+        let size = self.columns[index].type_info().size();
+        // SAFETY: The column is borrowed mutably and the row is borrowed by the returned slice, the caller guarantees the bytes are initialized
+        unsafe { std::slice::from_raw_parts_mut(self.ptr_mut(index), size) }
+    }
+}
+
+impl World {
+    /// Registers a component type which only exists at runtime, e.g. one defined by a script or an editor, and returns its id.
+    /// Its values are stored with the given layout and dropped with `drop` when they are overwritten, removed or despawned.
+    /// Every call registers a new component.
+    pub fn register_dynamic(&mut self, layout: Layout, drop: unsafe fn(*mut u8)) -> ComponentId {
+        let id = ComponentId::dynamic(self.components().len());
+        self.register_info(ComponentInfo::new(
+            id,
+            "<dynamic>",
+            TypeInfo::new(layout.size(), layout.align(), drop),
+        ));
+        id
+    }
+
+    /// Moves the value behind `ptr` into the dynamic component of the entity, like [`World::insert_component`] does for Rust types.
+    /// Does nothing when the entity is dead. Panics when the component is not registered.
+    ///
+    /// # Safety
+    /// `ptr` must point to a valid value of the layout the component was registered with, it must not be used or dropped afterwards.
+    pub unsafe fn insert_dynamic(&mut self, entity: Entity, id: ComponentId, ptr: *mut u8) {
+        let bit = self
+            .bit_of_id(&id)
+            .expect("Cannot insert a component which is not registered");
+        if !self.is_alive(entity) {
+            return;
+        }
+
+        let has = |world: &World| {
+            world
+                .archetype_of(entity)
+                .is_some_and(|archetype| archetype.bitmask().intersects(&bit))
+        };
+        let info = self.component_info(&id).unwrap().type_info;
+        // Replace hooks still see the old value, the new one is dropped when they despawn the entity
+        if has(self) {
+            self.run_hooks(entity, Hook::Replace, bit.clone());
+            if !self.is_alive(entity) {
+                unsafe {
+                    info.call_drop(ptr); // SAFETY: The caller moved the value in, and it is not stored anywhere
+                }
+                return;
+            }
+        }
+
+        let replaced = has(self);
+        unsafe {
+            self.insert_bytes(entity, id, &bit, info, ptr); // SAFETY: The caller guarantees the value matches the layout
+        }
+        self.log_insert(entity, id);
+
+        if !replaced {
+            self.run_hooks(entity, Hook::Add, bit);
+        }
+    }
+
+    /// Returns a pointer to the dynamic component of the entity. It stays valid until the entity changes its archetype or the component is written.
+    #[must_use]
+    pub fn get_dynamic(&self, entity: Entity, id: ComponentId) -> Option<*const u8> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+
+        let location = self.entities.metas[entity.index].location;
+        let archetype = self.archetypes().get(location.archetype)?;
+        archetype
+            .get_bytes(id, location.row)
+            .map(<*mut u8>::cast_const)
+    }
+
+    /// Returns a mutable pointer to the dynamic component of the entity and marks it as changed.
+    #[must_use]
+    pub fn get_dynamic_mut(&mut self, entity: Entity, id: ComponentId) -> Option<*mut u8> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+
+        let tick = self.change_tick();
+        let location = self.entities.metas[entity.index].location;
+        let archetype = self.archetypes().get(location.archetype)?;
+        let bytes = archetype.get_bytes(id, location.row)?;
+        archetype.column(&id).unwrap().set_tick(location.row, tick);
+        Some(bytes)
+    }
+
+    /// Removes and drops the dynamic component of the entity. Returns `false` when the entity doesn't have it.
+    pub fn remove_dynamic(&mut self, entity: Entity, id: ComponentId) -> bool {
+        self.run_remove_hooks(entity, &[id]);
+        self.remove_ids(entity, &[id], |bytes, _, info| unsafe {
+            info.call_drop(bytes); // SAFETY: The bytes were just moved out of the column
+        })
+    }
+
+    /// Queries the components chosen at runtime, e.g. by a script or an editor which can't name Rust types.
+    /// Matches the entities with every read, written or required component and none of the excluded ones, disabled entities are skipped
+    /// unless [`Disabled`] is required. Panics when a fetched column is borrowed mutably elsewhere or written twice.
+    pub fn query_dynamic(&self, access: &[ComponentAccess]) -> DynQueryIter<'_> {
+        let mut required = ComponentMask::EMPTY;
+        let mut excluded = ComponentMask::EMPTY;
+        let mut fetched = Vec::new();
+        let mut unregistered = false;
+        for access in access {
+            match access {
+                ComponentAccess::Read(id)
+                | ComponentAccess::Write(id)
+                | ComponentAccess::With(id) => {
+                    match self.bit_of_id(id) {
+                        Some(bit) => required |= bit,
+                        None => unregistered = true,
+                    }
+                    if !matches!(access, ComponentAccess::With(_)) {
+                        fetched.push((*id, matches!(access, ComponentAccess::Write(_))));
+                    }
+                }
+                ComponentAccess::Without(id) => excluded |= self.bit_of_id(id).unwrap_or_default(),
+            }
+        }
+        excluded |= self
+            .bit_of::<Disabled>()
+            .unwrap_or_default()
+            .difference(&required);
+
+        let tick = self.change_detection().then(|| self.change_tick());
+        // Components which are not registered are on no entity
+        let archetypes = if unregistered {
+            Vec::new()
+        } else {
+            self.ordered_archetypes()
+                .filter(|archetype| {
+                    archetype.count() > 0 && archetype.bitmask().matches(&required, &excluded)
+                })
+                .collect()
+        };
+        DynQueryIter::new(archetypes, fetched, tick)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::alloc::Layout;
+
+    use super::ComponentAccess;
+    use crate::world::{ComponentId, World};
+
+    unsafe fn drop_u32(_: *mut u8) {}
+
+    fn world_with(values: &[u32]) -> (World, ComponentId) {
+        let mut world = World::new();
+        let id = world.register_dynamic(Layout::new::<u32>(), drop_u32);
+        for value in values {
+            let entity = world.spawn_empty();
+            let mut value = *value;
+            unsafe {
+                world.insert_dynamic(entity, id, (&raw mut value).cast()); // SAFETY: The value is a u32
+            }
+        }
+        (world, id)
+    }
+
+    #[test]
+    fn row_bytes_read_and_write_components_without_padding() {
+        let (world, id) = world_with(&[1, 2]);
+        let mut query = world.query_dynamic(&[ComponentAccess::Write(id)]);
+        while let Some(mut row) = query.fetch_next() {
+            // SAFETY: u32 has no padding and every bit pattern is a valid u32
+            let bytes = unsafe { row.bytes_mut(0) };
+            let value = u32::from_ne_bytes(bytes.try_into().unwrap());
+            bytes.copy_from_slice(&(value * 10).to_ne_bytes());
+        }
+        drop(query);
+
+        let mut query = world.query_dynamic(&[ComponentAccess::Read(id)]);
+        let mut values = Vec::new();
+        while let Some(row) = query.fetch_next() {
+            // SAFETY: u32 has no padding
+            let bytes = unsafe { row.bytes(0) };
+            values.push(u32::from_ne_bytes(bytes.try_into().unwrap()));
+        }
+        assert_eq!(values, [10, 20]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Component is not fetched for writing")]
+    fn row_bytes_of_read_components_are_not_writable() {
+        let (world, id) = world_with(&[1]);
+        let mut query = world.query_dynamic(&[ComponentAccess::Read(id)]);
+        let mut row = query.fetch_next().unwrap();
+        // SAFETY: u32 has no padding, the call panics before any byte is written
+        let _ = unsafe { row.bytes_mut(0) };
+    }
+
+    #[test]
+    #[should_panic(expected = "Conflicting Queries Detected")]
+    fn rows_written_elsewhere_are_not_fetched() {
+        let (world, id) = world_with(&[1]);
+        let _writer = world.query_dynamic(&[ComponentAccess::Write(id)]);
+        let _reader = world.query_dynamic(&[ComponentAccess::Read(id)]);
+    }
+}
@@ -0,0 +1,428 @@
+use crate::{
+    archetype::{Archetype, ArchetypeId},
+    world::{Component, ComponentId, Entity, Location, World},
+};
+
+/// A component type or a tuple of them, fetched together from one entity by [`World::get_components`] and [`World::get_components_mut`].
+pub trait ComponentSet {
+    type Refs<'a>;
+    type Muts<'a>;
+
+    /// Returns references to the components in the given row, or `None` when the archetype lacks any of them.
+    fn fetch(archetype: &Archetype, row: usize) -> Option<Self::Refs<'_>>;
+
+    /// Returns mutable references to the components in the given row and marks them as changed at the given tick.
+    /// Panics when a component type is listed more than once.
+    ///
+    /// # Safety
+    /// Caller must ensure that nothing else references the components in the row while the references are alive.
+    unsafe fn fetch_mut(archetype: &Archetype, row: usize, tick: u64) -> Option<Self::Muts<'_>>;
+}
+
+impl<T0: Component> ComponentSet for T0 {
+    type Refs<'a> = &'a T0;
+    type Muts<'a> = &'a mut T0;
+
+    fn fetch(archetype: &Archetype, row: usize) -> Option<Self::Refs<'_>> {
+        archetype.get(row)
+    }
+
+    unsafe fn fetch_mut(archetype: &Archetype, row: usize, tick: u64) -> Option<Self::Muts<'_>> {
+        let typeid = ComponentId::of::<T0>();
+        let bytes = archetype.get_bytes(typeid, row)?;
+        archetype.column(&typeid).unwrap().set_tick(row, tick);
+        Some(unsafe { &mut *bytes.cast() })
+    }
+}
+
+macro_rules! impl_component_set_tuple {
+    ($($name:ident),*) => {
+        impl<$($name: Component),*> ComponentSet for ($($name,)*) {
+            type Refs<'a> = ($(&'a $name,)*);
+            type Muts<'a> = ($(&'a mut $name,)*);
+
+            fn fetch(archetype: &Archetype, row: usize) -> Option<Self::Refs<'_>> {
+                Some(($(archetype.get::<$name>(row)?,)*))
+            }
+
+            unsafe fn fetch_mut(archetype: &Archetype, row: usize, tick: u64) -> Option<Self::Muts<'_>> {
+                let ids = [$(ComponentId::of::<$name>()),*];
+                for (index, id) in ids.iter().enumerate() {
+                    assert!(!ids[..index].contains(id), "A component is listed more than once in {}", std::any::type_name::<Self>());
+                }
+
+                #[allow(non_snake_case)]
+                let ($($name,)*) = ($(archetype.get_bytes(ComponentId::of::<$name>(), row)?,)*);
+                for id in &ids {
+                    archetype.column(id).unwrap().set_tick(row, tick);
+                }
+                // SAFETY: The component types are distinct, so every reference points to another column
+                unsafe { Some(($(&mut *$name.cast::<$name>(),)*)) }
+            }
+        }
+    };
+}
+
+impl_component_set_tuple!(A, B);
+impl_component_set_tuple!(A, B, C);
+impl_component_set_tuple!(A, B, C, D);
+impl_component_set_tuple!(A, B, C, D, E);
+impl_component_set_tuple!(A, B, C, D, E, F);
+impl_component_set_tuple!(A, B, C, D, E, F, G);
+impl_component_set_tuple!(A, B, C, D, E, F, G, H);
+impl_component_set_tuple!(A, B, C, D, E, F, G, H, I);
+impl_component_set_tuple!(A, B, C, D, E, F, G, H, I, J);
+impl_component_set_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+impl_component_set_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
+impl_component_set_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M);
+impl_component_set_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N);
+impl_component_set_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O);
+impl_component_set_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P);
+
+/// Read-only access to the components of one entity, returned by [`World::entity`].
+#[derive(Clone, Copy)]
+pub struct EntityRef<'w> {
+    world: &'w World,
+    entity: Entity,
+}
+
+impl<'w> EntityRef<'w> {
+    #[inline]
+    #[must_use]
+    pub fn id(&self) -> Entity {
+        self.entity
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn get<T: Component>(&self) -> Option<&'w T> {
+        self.world.get_component(self.entity)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn contains<T: Component>(&self) -> bool {
+        self.world.has_component::<T>(self.entity)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn archetype_id(&self) -> Option<ArchetypeId> {
+        self.world.archetype_id_of(self.entity)
+    }
+}
+
+/// Mutable access to one entity, returned by [`World::entity_mut`]. Structural changes can be chained, e.g. `world.entity_mut(e).insert(A).remove::<B>()`.
+pub struct EntityMut<'w> {
+    world: &'w mut World,
+    entity: Entity,
+}
+
+impl EntityMut<'_> {
+    #[inline]
+    #[must_use]
+    pub fn id(&self) -> Entity {
+        self.entity
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn get<T: Component>(&self) -> Option<&T> {
+        self.world.get_component(self.entity)
+    }
+
+    /// Returns a mutable reference to the component and marks it as changed.
+    #[inline]
+    #[must_use]
+    pub fn get_mut<T: Component>(&mut self) -> Option<&mut T> {
+        self.world.get_component_mut(self.entity)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn contains<T: Component>(&self) -> bool {
+        self.world.has_component::<T>(self.entity)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn archetype_id(&self) -> Option<ArchetypeId> {
+        self.world.archetype_id_of(self.entity)
+    }
+
+    /// Inserts the component like [`World::insert_component`].
+    pub fn insert<T: Component>(&mut self, component: T) -> &mut Self {
+        self.world.insert_component(self.entity, component);
+        self
+    }
+
+    /// Removes the component like [`World::remove_component`].
+    pub fn remove<T: Component>(&mut self) -> &mut Self {
+        self.world.remove_component::<T>(self.entity);
+        self
+    }
+
+    /// Despawns the entity, consuming the handle.
+    pub fn despawn(self) {
+        self.world.despawn_entity(self.entity);
+    }
+
+    /// Returns the world, e.g. to spawn related entities while holding the handle.
+    #[inline]
+    #[must_use]
+    pub fn world(&mut self) -> &mut World {
+        self.world
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn as_readonly(&self) -> EntityRef<'_> {
+        EntityRef {
+            world: self.world,
+            entity: self.entity,
+        }
+    }
+}
+
+impl World {
+    /// Returns several components of the entity with a single lookup, e.g. `get_components::<(Position, Velocity)>(entity)`.
+    /// Returns `None` when the entity is dead or lacks any of them.
+    #[must_use]
+    pub fn get_components<S: ComponentSet>(&self, entity: Entity) -> Option<S::Refs<'_>> {
+        let location = self.location_of(entity)?;
+        S::fetch(&self.archetypes()[location.archetype], location.row)
+    }
+
+    /// Returns mutable references to several components of the entity with a single lookup and marks them as changed.
+    /// Panics when a component type is listed more than once.
+    #[must_use]
+    pub fn get_components_mut<S: ComponentSet>(&mut self, entity: Entity) -> Option<S::Muts<'_>> {
+        let location = self.location_of(entity)?;
+        let tick = self.change_tick();
+        unsafe {
+            S::fetch_mut(&self.archetypes()[location.archetype], location.row, tick) // SAFETY: The world is borrowed mutably
+        }
+    }
+
+    /// Returns the location of an alive entity with components.
+    fn location_of(&self, entity: Entity) -> Option<Location> {
+        let location = self.entities.metas.get(entity.index)?.location;
+        (self.is_alive(entity) && location != Location::EMPTY).then_some(location)
+    }
+
+    /// Returns read-only access to the entity. Panics when the entity is dead, use [`World::get_entity`] to handle it.
+    #[must_use]
+    pub fn entity(&self, entity: Entity) -> EntityRef<'_> {
+        self.get_entity(entity)
+            .unwrap_or_else(|| panic!("{entity:?} does not exist"))
+    }
+
+    #[must_use]
+    pub fn get_entity(&self, entity: Entity) -> Option<EntityRef<'_>> {
+        self.is_alive(entity).then_some(EntityRef {
+            world: self,
+            entity,
+        })
+    }
+
+    /// Returns mutable access to the entity. Panics when the entity is dead, use [`World::get_entity_mut`] to handle it.
+    #[must_use]
+    pub fn entity_mut(&mut self, entity: Entity) -> EntityMut<'_> {
+        self.get_entity_mut(entity)
+            .unwrap_or_else(|| panic!("{entity:?} does not exist"))
+    }
+
+    #[must_use]
+    pub fn get_entity_mut(&mut self, entity: Entity) -> Option<EntityMut<'_>> {
+        self.is_alive(entity).then_some(EntityMut {
+            world: self,
+            entity,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::world::{Component, Entity, World};
+
+    #[derive(Debug, PartialEq)]
+    struct Position(u32);
+    #[derive(Debug, PartialEq)]
+    struct Velocity(u32);
+
+    impl Component for Position {}
+    impl Component for Velocity {}
+
+    fn despawned(world: &mut World) -> Entity {
+        let entity = world.spawn(Position(0));
+        world.despawn_entity(entity);
+        entity
+    }
+
+    #[test]
+    fn structural_changes_chain_on_one_handle() {
+        let mut world = World::new();
+        let entity = world.spawn(Position(1));
+
+        world
+            .entity_mut(entity)
+            .insert(Velocity(2))
+            .remove::<Position>();
+        let handle = world.entity(entity);
+        assert!(!handle.contains::<Position>());
+        assert_eq!(handle.get::<Velocity>(), Some(&Velocity(2)));
+        assert_eq!(handle.archetype_id(), world.archetype_id_of(entity));
+    }
+
+    #[test]
+    fn read_handles_outlive_each_other() {
+        let mut world = World::new();
+        let first = world.spawn(Position(1));
+        let second = world.spawn((Position(2), Velocity(2)));
+
+        // Shared handles only borrow the world immutably, so any number of them coexist
+        let (first, second) = (world.entity(first), world.entity(second));
+        let position = first.get::<Position>().unwrap();
+        assert_eq!(second.get::<Position>(), Some(&Position(2)));
+        assert_eq!(position, &Position(1));
+        assert_eq!(world.entity_count(), 2);
+    }
+
+    #[test]
+    fn mutable_handles_mark_components_as_changed() {
+        let mut world = World::new();
+        let entity = world.spawn(Position(1));
+        world.increment_change_tick();
+
+        let mut handle = world.entity_mut(entity);
+        handle.get_mut::<Position>().unwrap().0 = 2;
+        assert_eq!(handle.as_readonly().get::<Position>(), Some(&Position(2)));
+        let ticks = world.component_ticks::<Position>(entity).unwrap();
+        assert_eq!(ticks.added, 1);
+        assert_eq!(ticks.changed, 2);
+    }
+
+    #[test]
+    fn handles_of_entities_without_components_are_empty() {
+        let mut world = World::new();
+        let entity = world.spawn_empty();
+
+        let handle = world.entity(entity);
+        assert!(!handle.contains::<Position>());
+        assert_eq!(handle.get::<Position>(), None);
+        world.entity_mut(entity).insert(Position(1));
+        assert_eq!(world.get_component::<Position>(entity), Some(&Position(1)));
+    }
+
+    #[test]
+    fn despawned_entities_have_no_handles() {
+        let mut world = World::new();
+        let entity = world.spawn(Position(1));
+        world.entity_mut(entity).despawn();
+
+        assert!(!world.is_alive(entity));
+        assert!(world.get_entity(entity).is_none());
+        assert!(world.get_entity_mut(entity).is_none());
+
+        // A recycled slot does not revive the old handle
+        let recycled = world.spawn(Position(2));
+        assert_eq!(recycled.index, entity.index);
+        assert!(world.get_entity(entity).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "does not exist")]
+    fn entity_panics_on_despawned_entities() {
+        let mut world = World::new();
+        let entity = despawned(&mut world);
+        let _ = world.entity(entity);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not exist")]
+    fn entity_mut_panics_on_despawned_entities() {
+        let mut world = World::new();
+        let entity = despawned(&mut world);
+        let _ = world.entity_mut(entity);
+    }
+
+    #[test]
+    fn component_sets_are_fetched_together() {
+        let mut world = World::new();
+        let entity = world.spawn((Position(1), Velocity(2)));
+        let partial = world.spawn(Position(3));
+
+        assert_eq!(
+            world.get_components::<(Position, Velocity)>(entity),
+            Some((&Position(1), &Velocity(2)))
+        );
+        assert_eq!(world.get_components::<Velocity>(entity), Some(&Velocity(2)));
+        assert_eq!(world.get_components::<(Position, Velocity)>(partial), None);
+        assert_eq!(
+            world.get_components_mut::<(Position, Velocity)>(partial),
+            None
+        );
+    }
+
+    #[test]
+    fn mutable_component_sets_are_marked_as_changed() {
+        let mut world = World::new();
+        let entity = world.spawn((Position(1), Velocity(2)));
+        world.increment_change_tick();
+
+        let (position, velocity) = world
+            .get_components_mut::<(Position, Velocity)>(entity)
+            .unwrap();
+        std::mem::swap(&mut position.0, &mut velocity.0);
+        assert_eq!(
+            world.get_components::<(Velocity, Position)>(entity),
+            Some((&Velocity(1), &Position(2)))
+        );
+        assert_eq!(
+            world.component_ticks::<Position>(entity).unwrap().changed,
+            2
+        );
+        assert_eq!(
+            world.component_ticks::<Velocity>(entity).unwrap().changed,
+            2
+        );
+    }
+
+    #[test]
+    fn component_sets_of_despawned_entities_are_none() {
+        let mut world = World::new();
+        let entity = despawned(&mut world);
+        let empty = world.spawn_empty();
+
+        assert_eq!(world.get_components::<Position>(entity), None);
+        assert_eq!(world.get_components_mut::<Position>(entity), None);
+        assert_eq!(world.get_components::<Position>(empty), None);
+        // The recycled slot belongs to another entity now
+        world.spawn(Position(1));
+        assert_eq!(world.get_components_mut::<Position>(entity), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "A component is listed more than once")]
+    fn mutable_component_sets_reject_aliased_components() {
+        let mut world = World::new();
+        let entity = world.spawn((Position(1), Velocity(2)));
+        let _ = world.get_components_mut::<(Position, Velocity, Position)>(entity);
+    }
+
+    #[test]
+    fn aliased_components_are_not_marked_when_rejected() {
+        let mut world = World::new();
+        let entity = world.spawn(Position(1));
+        world.increment_change_tick();
+
+        let aliased = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _ = world.get_components_mut::<(Position, Position)>(entity);
+        }));
+        assert!(aliased.is_err());
+        assert_eq!(
+            world.component_ticks::<Position>(entity).unwrap().changed,
+            1
+        );
+    }
+}
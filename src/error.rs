@@ -0,0 +1,86 @@
+use std::{error::Error, fmt};
+
+/// Error returned by a fallible system, e.g. `fn load(assets: Res<Assets>) -> Result<(), AssetError>`.
+pub type BoxedError = Box<dyn Error + Send + Sync>;
+
+/// What a [`System`](crate::system::System) run returns, `Ok` for systems which can't fail.
+pub type SystemResult = Result<(), BoxedError>;
+
+/// An error returned by a system of a [`Schedule`](crate::schedule::Schedule), passed to its error handler.
+#[derive(Debug)]
+pub struct SystemError {
+    system: &'static str,
+    error: BoxedError,
+}
+
+impl SystemError {
+    pub(crate) fn new(system: &'static str, error: BoxedError) -> Self {
+        Self { system, error }
+    }
+
+    /// Name of the system which failed.
+    #[inline]
+    #[must_use]
+    pub fn system(&self) -> &'static str {
+        self.system
+    }
+
+    /// The error returned by the system.
+    #[inline]
+    #[must_use]
+    pub fn error(&self) -> &(dyn Error + Send + Sync + 'static) {
+        self.error.as_ref()
+    }
+
+    #[must_use]
+    pub fn into_error(self) -> BoxedError {
+        self.error
+    }
+}
+
+impl fmt::Display for SystemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "system {} failed: {}", self.system, self.error)
+    }
+}
+
+impl Error for SystemError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.error.as_ref())
+    }
+}
+
+/// How a [`Schedule`](crate::schedule::Schedule) continues after its error handler ran.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnError {
+    /// The other systems run as usual.
+    Continue,
+    /// No further system starts in this run of the schedule, the deferred changes of the systems which ran are still applied.
+    Abort,
+}
+
+/// Panics with the error, the default error handler of schedules.
+pub fn panic_on_error(error: SystemError) -> OnError {
+    panic!("{error}")
+}
+
+/// Prints the error to the standard error and continues the run.
+pub fn log_error(error: SystemError) -> OnError {
+    eprintln!("{error}");
+    OnError::Continue
+}
+
+/// Prints the error to the standard error and aborts the run.
+pub fn abort_on_error(error: SystemError) -> OnError {
+    eprintln!("{error}");
+    OnError::Abort
+}
+
+/// The error handler of a schedule, see [`Schedule::set_error_handler`](crate::schedule::Schedule::set_error_handler).
+pub(crate) struct ErrorHandler(pub(crate) Box<dyn FnMut(SystemError) -> OnError + Send>);
+
+impl Default for ErrorHandler {
+    fn default() -> Self {
+        Self(Box::new(panic_on_error))
+    }
+}
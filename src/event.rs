@@ -0,0 +1,524 @@
+use std::{collections::VecDeque, fmt, marker::PhantomData, mem};
+
+use crate::{
+    app::App,
+    resource::{Res, ResMut},
+    system::{SystemAccess, SystemParam},
+    world::World,
+};
+
+/// Events sent in one frame, with the id of the first one.
+#[derive(Debug, Clone)]
+struct Buffer<T> {
+    start: usize,
+    events: VecDeque<T>,
+}
+
+impl<T> Default for Buffer<T> {
+    fn default() -> Self {
+        Self {
+            start: 0,
+            events: VecDeque::new(),
+        }
+    }
+}
+
+impl<T> Buffer<T> {
+    fn pop_front(&mut self) -> Option<T> {
+        let event = self.events.pop_front()?;
+        self.start += 1;
+        Some(event)
+    }
+}
+
+/// How long an [`Events`] resource keeps its events, set with [`Events::with_retention`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EventRetention {
+    /// Events are dropped by the second [`Events::update`] after they were sent, for events handled within a frame.
+    #[default]
+    Frames,
+    /// Events are kept until [`Events::clear`] or [`Events::drain`], updates don't drop them.
+    Manual,
+    /// At most the given number of events is kept, sending more drops the oldest ones, e.g. for a log of recent events.
+    /// Updates don't drop them.
+    Bounded(usize),
+}
+
+/// Events of type `T` stored as a resource, sent by some systems and read by others with [`EventWriter`] and [`EventReader`].
+///
+/// Events are double buffered, [`Events::update`] is called once per frame and drops the events sent before the previous update,
+/// so every event can be read during the frame it was sent in and the next one, whichever order the systems run in.
+/// Other [retention policies](EventRetention) keep events longer.
+#[derive(Debug, Clone)]
+pub struct Events<T> {
+    retention: EventRetention,
+    /// Events sent before the latest update.
+    previous: Buffer<T>,
+    /// Events sent since the latest update.
+    current: Buffer<T>,
+    /// Number of events sent so far, the id of the next event.
+    count: usize,
+}
+
+impl<T> Default for Events<T> {
+    fn default() -> Self {
+        Self {
+            retention: EventRetention::Frames,
+            previous: Buffer::default(),
+            current: Buffer::default(),
+            count: 0,
+        }
+    }
+}
+
+impl<T> Events<T> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates events kept according to the policy. Panics when a bounded policy keeps no events.
+    #[must_use]
+    pub fn with_retention(retention: EventRetention) -> Self {
+        let mut events = Self::new();
+        events.set_retention(retention);
+        events
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn retention(&self) -> EventRetention {
+        self.retention
+    }
+
+    /// Changes the policy, dropping the oldest events over a new bound. Panics when a bounded policy keeps no events.
+    pub fn set_retention(&mut self, retention: EventRetention) {
+        if let EventRetention::Bounded(capacity) = retention {
+            assert!(capacity > 0, "Bounded events must keep at least one event");
+        }
+        self.retention = retention;
+        self.trim();
+    }
+
+    pub fn send(&mut self, event: T) {
+        self.current.events.push_back(event);
+        self.count += 1;
+        self.trim();
+    }
+
+    /// Drops the oldest events over the bound of a bounded policy.
+    fn trim(&mut self) {
+        let EventRetention::Bounded(capacity) = self.retention else {
+            return;
+        };
+        while self.len() > capacity {
+            if self.previous.pop_front().is_none() {
+                self.current.pop_front();
+            }
+        }
+    }
+
+    pub fn send_batch(&mut self, events: impl IntoIterator<Item = T>) {
+        for event in events {
+            self.send(event);
+        }
+    }
+
+    /// Drops the events sent before the previous update, keeping the ones sent since then for one more frame.
+    /// Does nothing unless the retention policy is [`EventRetention::Frames`].
+    pub fn update(&mut self) {
+        if self.retention != EventRetention::Frames {
+            return;
+        }
+        mem::swap(&mut self.previous, &mut self.current);
+        self.current.events.clear();
+        self.current.start = self.count;
+    }
+
+    /// Updates the events resource, add it to a schedule once, e.g. `schedule.add_system(Events::<Collision>::update_system)`.
+    pub fn update_system(mut events: ResMut<Events<T>>)
+    where
+        T: Send + Sync + 'static,
+    {
+        events.update();
+    }
+
+    /// Every stored event from the oldest one, regardless of cursors.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.previous.events.iter().chain(&self.current.events)
+    }
+
+    /// Number of stored events.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.previous.events.len() + self.current.events.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops every stored event, cursors skip them.
+    pub fn clear(&mut self) {
+        self.previous.events.clear();
+        self.current.events.clear();
+        self.previous.start = self.count;
+        self.current.start = self.count;
+    }
+
+    /// Removes every stored event from the oldest one, cursors skip them.
+    pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
+        self.previous.start = self.count;
+        self.current.start = self.count;
+        self.previous
+            .events
+            .drain(..)
+            .chain(self.current.events.drain(..))
+    }
+
+    /// Events with ids from `from` on.
+    fn since(&self, from: usize) -> impl Iterator<Item = &T> {
+        let skip = |buffer: &Buffer<T>| from.saturating_sub(buffer.start).min(buffer.events.len());
+        self.previous
+            .events
+            .range(skip(&self.previous)..)
+            .chain(self.current.events.range(skip(&self.current)..))
+    }
+}
+
+/// Position of a reader in an [`Events`] resource, every cursor reads every event once.
+/// Events dropped before the cursor read them are missed.
+pub struct EventCursor<T> {
+    /// Id of the next event to read.
+    next: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for EventCursor<T> {
+    fn default() -> Self {
+        Self {
+            next: 0,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for EventCursor<T> {
+    fn clone(&self) -> Self {
+        Self {
+            next: self.next,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for EventCursor<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventCursor")
+            .field("next", &self.next)
+            .finish()
+    }
+}
+
+impl<T> EventCursor<T> {
+    /// Returns the events which weren't read yet and moves the cursor past them.
+    pub fn read<'a>(&mut self, events: &'a Events<T>) -> impl Iterator<Item = &'a T> + use<'a, T> {
+        let from = self.next;
+        self.next = events.count;
+        events.since(from)
+    }
+
+    /// Number of events which weren't read yet.
+    #[must_use]
+    pub fn len(&self, events: &Events<T>) -> usize {
+        events.since(self.next).count()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self, events: &Events<T>) -> bool {
+        self.len(events) == 0
+    }
+
+    /// Skips the events which weren't read yet.
+    pub fn clear(&mut self, events: &Events<T>) {
+        self.next = events.count;
+    }
+}
+
+/// Reads the events of type `T` sent since the previous run of the system, e.g. `fn damage(mut hits: EventReader<Hit>)`.
+/// Every system using it has its own cursor.
+pub struct EventReader<'w, 's, T> {
+    events: Res<'w, Events<T>>,
+    cursor: &'s mut EventCursor<T>,
+}
+
+impl<T> EventReader<'_, '_, T> {
+    /// Returns the events which weren't read yet.
+    pub fn read(&mut self) -> impl Iterator<Item = &T> {
+        self.cursor.read(&self.events)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cursor.len(&self.events)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cursor.is_empty(&self.events)
+    }
+
+    /// Skips the events which weren't read yet.
+    pub fn clear(&mut self) {
+        self.cursor.clear(&self.events);
+    }
+}
+
+// SAFETY: Like `Res`, the cursor belongs to the system
+unsafe impl<T: Send + Sync + 'static> SystemParam for EventReader<'_, '_, T> {
+    type State = EventCursor<T>;
+    type Item<'w, 's> = EventReader<'w, 's, T>;
+
+    fn init(_world: &mut World, access: &mut SystemAccess) -> Self::State {
+        access.add_resource_read::<Events<T>>();
+        EventCursor::default()
+    }
+
+    fn fetch<'w, 's>(state: &'s mut Self::State, world: &'w World) -> Self::Item<'w, 's> {
+        EventReader {
+            events: <Res<'w, Events<T>> as SystemParam>::fetch(&mut (), world),
+            cursor: state,
+        }
+    }
+}
+
+/// Sends events of type `T`, e.g. `fn collide(mut hits: EventWriter<Hit>)`.
+pub struct EventWriter<'w, T>(ResMut<'w, Events<T>>);
+
+impl<T> EventWriter<'_, T> {
+    pub fn send(&mut self, event: T) {
+        self.0.send(event);
+    }
+
+    pub fn send_batch(&mut self, events: impl IntoIterator<Item = T>) {
+        self.0.send_batch(events);
+    }
+}
+
+// SAFETY: Like `ResMut`
+unsafe impl<T: Send + Sync + 'static> SystemParam for EventWriter<'_, T> {
+    type State = ();
+    type Item<'w, 's> = EventWriter<'w, T>;
+
+    fn init(world: &mut World, access: &mut SystemAccess) -> Self::State {
+        <ResMut<'_, Events<T>> as SystemParam>::init(world, access);
+    }
+
+    fn fetch<'w, 's>(state: &'s mut Self::State, world: &'w World) -> Self::Item<'w, 's> {
+        EventWriter(<ResMut<'w, Events<T>> as SystemParam>::fetch(state, world))
+    }
+}
+
+impl World {
+    /// Inserts an empty [`Events`] resource for the type unless the world already has one.
+    pub fn add_event<T: 'static>(&mut self) {
+        if !self.contains_resource::<Events<T>>() {
+            self.insert_resource(Events::<T>::new());
+        }
+    }
+
+    /// Inserts an empty [`Events`] resource kept according to the policy, or sets the policy of the existing one.
+    pub fn add_event_with_retention<T: 'static>(&mut self, retention: EventRetention) {
+        if let Some(mut events) = self.get_resource_mut::<Events<T>>() {
+            events.set_retention(retention);
+            return;
+        }
+        self.insert_resource(Events::<T>::with_retention(retention));
+    }
+
+    /// Sends an event into the [`Events`] resource of its type. Panics when the resource is missing.
+    pub fn send_event<T: 'static>(&mut self, event: T) {
+        self.get_resource_mut::<Events<T>>()
+            .expect("Events weren't added with World::add_event")
+            .send(event);
+    }
+}
+
+impl App {
+    /// Adds the [`Events`] resource for the type and a system updating it to the schedule.
+    /// Add events before the systems using them, so the update runs first in every frame.
+    pub fn add_event<T: Send + Sync + 'static>(&mut self) -> &mut Self {
+        if !self.world().contains_resource::<Events<T>>() {
+            self.world_mut().add_event::<T>();
+            self.add_systems(Events::<T>::update_system);
+        }
+        self
+    }
+
+    /// Adds the [`Events`] resource for the type kept according to the policy, like [`App::add_event`].
+    pub fn add_event_with_retention<T: Send + Sync + 'static>(
+        &mut self,
+        retention: EventRetention,
+    ) -> &mut Self {
+        if !self.world().contains_resource::<Events<T>>() {
+            self.add_systems(Events::<T>::update_system);
+        }
+        self.world_mut().add_event_with_retention::<T>(retention);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EventCursor, EventReader, EventRetention, EventWriter, Events};
+    use crate::{
+        app::App,
+        resource::{Res, ResMut},
+    };
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Hit(u32);
+
+    #[derive(Default)]
+    struct Received(Vec<u32>);
+    #[derive(Default)]
+    struct Frame(u32);
+
+    fn read(cursor: &mut EventCursor<Hit>, events: &Events<Hit>) -> Vec<u32> {
+        cursor.read(events).map(|hit| hit.0).collect()
+    }
+
+    #[test]
+    fn events_live_for_two_updates() {
+        let mut events = Events::new();
+        let mut cursor = EventCursor::default();
+        events.send(Hit(1));
+        events.update();
+        events.send(Hit(2));
+        assert_eq!(events.iter().copied().collect::<Vec<_>>(), [Hit(1), Hit(2)]);
+
+        events.update();
+        assert_eq!(events.len(), 1);
+        // The first event was dropped before the cursor read it
+        assert_eq!(read(&mut cursor, &events), [2]);
+        events.update();
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn cursors_read_every_event_once() {
+        let mut events = Events::new();
+        let (mut early, mut late) = (EventCursor::default(), EventCursor::default());
+        events.send_batch([Hit(1), Hit(2)]);
+        assert_eq!(read(&mut early, &events), [1, 2]);
+
+        events.update();
+        events.send(Hit(3));
+        assert_eq!(early.len(&events), 1);
+        assert_eq!(read(&mut early, &events), [3]);
+        assert!(early.is_empty(&events));
+        assert_eq!(read(&mut late, &events), [1, 2, 3]);
+
+        events.send(Hit(4));
+        late.clear(&events);
+        assert!(read(&mut late, &events).is_empty());
+        assert_eq!(read(&mut early, &events), [4]);
+    }
+
+    #[test]
+    fn cleared_and_drained_events_are_skipped() {
+        let mut events = Events::new();
+        let mut cursor = EventCursor::default();
+        events.send_batch([Hit(1), Hit(2)]);
+        events.update();
+        events.send(Hit(3));
+
+        assert_eq!(events.drain().collect::<Vec<_>>(), [Hit(1), Hit(2), Hit(3)]);
+        assert!(read(&mut cursor, &events).is_empty());
+        events.send(Hit(4));
+        events.clear();
+        events.send(Hit(5));
+        assert_eq!(read(&mut cursor, &events), [5]);
+    }
+
+    #[test]
+    fn manual_events_survive_updates() {
+        let mut events = Events::with_retention(EventRetention::Manual);
+        events.send(Hit(1));
+        for _ in 0..3 {
+            events.update();
+        }
+        events.send(Hit(2));
+        assert_eq!(read(&mut EventCursor::default(), &events), [1, 2]);
+    }
+
+    #[test]
+    fn bounded_events_drop_the_oldest_ones() {
+        let mut events = Events::with_retention(EventRetention::Bounded(3));
+        let mut cursor = EventCursor::default();
+        events.send_batch((1..=2).map(Hit));
+        assert_eq!(read(&mut cursor, &events), [1, 2]);
+        events.send_batch((3..=6).map(Hit));
+        events.update();
+        assert_eq!(read(&mut cursor, &events), [4, 5, 6]);
+
+        // Tightening the bound drops the oldest events right away
+        events.set_retention(EventRetention::Bounded(1));
+        assert_eq!(events.iter().copied().collect::<Vec<_>>(), [Hit(6)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Bounded events must keep at least one event")]
+    fn bounded_events_keep_at_least_one_event() {
+        let _ = Events::<Hit>::with_retention(EventRetention::Bounded(0));
+    }
+
+    #[test]
+    fn worlds_add_events_once() {
+        let mut app = App::new();
+        app.world_mut().add_event::<Hit>();
+        app.world_mut().send_event(Hit(1));
+        app.world_mut().add_event::<Hit>();
+        app.world_mut()
+            .add_event_with_retention::<Hit>(EventRetention::Manual);
+
+        let events = app.world().get_resource::<Events<Hit>>().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events.retention(), EventRetention::Manual);
+    }
+
+    fn receive(mut hits: EventReader<Hit>, mut received: ResMut<Received>) {
+        received.0.extend(hits.read().map(|hit| hit.0));
+    }
+
+    fn send(mut hits: EventWriter<Hit>, frame: Res<Frame>) {
+        hits.send(Hit(frame.0));
+    }
+
+    fn count(mut frame: ResMut<Frame>) {
+        frame.0 += 1;
+    }
+
+    #[test]
+    fn systems_receive_every_event_once_across_frames() {
+        let mut app = App::new();
+        app.init_resource::<Received>()
+            .init_resource::<Frame>()
+            .add_event::<Hit>()
+            .add_systems(receive)
+            .add_systems(send)
+            .add_systems(count);
+
+        for _ in 0..4 {
+            app.update();
+        }
+        // Conflicting systems run in the order they were added, so the reader sees each event in the frame after it was sent
+        let received = &app.world().get_resource::<Received>().unwrap().0;
+        assert_eq!(received, &[0, 1, 2]);
+        let events = app.world().get_resource::<Events<Hit>>().unwrap();
+        assert_eq!(events.iter().copied().collect::<Vec<_>>(), [Hit(2), Hit(3)]);
+    }
+}
@@ -0,0 +1,465 @@
+use std::{
+    any::Any,
+    collections::BTreeSet,
+    panic::{self, AssertUnwindSafe},
+    sync::{Condvar, Mutex, MutexGuard, PoisonError},
+    thread,
+};
+
+use crate::{
+    components::ComponentInfo,
+    error::{ErrorHandler, OnError},
+    schedule::{Schedule, SystemConfig},
+    system::SystemAccess,
+    world::{Component, ComponentId, World},
+};
+
+/// The world shared by the threads of a parallel run.
+struct SharedWorld<'w>(&'w World);
+
+// SAFETY: Systems only touch what their access declares and two systems with conflicting access never run at the same time,
+// resources of systems on the workers are `Send + Sync`, systems on the workers only access thread safe components, component data is only touched
+// through the atomic borrow flags, and the rest of the state reachable through `&World` is atomic or behind locks
+unsafe impl Sync for SharedWorld<'_> {}
+
+/// Progress of a parallel run, shared by its threads.
+struct Progress {
+    /// Number of unfinished dependencies of every system.
+    remaining: Vec<usize>,
+    /// Systems whose dependencies finished, which any thread may run.
+    ready: BTreeSet<usize>,
+    /// Systems whose dependencies finished, which have to run on the calling thread.
+    ready_local: BTreeSet<usize>,
+    finished: usize,
+    /// The first panic of a system, which stops the run and is resumed on the calling thread.
+    panic: Option<Box<dyn Any + Send>>,
+    /// Set when the error handler aborted the run, no further system starts.
+    aborted: bool,
+}
+
+struct Run<'a, 'w> {
+    systems: Vec<Mutex<&'a mut SystemConfig>>,
+    /// Systems whose run conditions hold, the others finish without running.
+    enabled: &'a [bool],
+    /// Number of systems in the segment being run.
+    count: usize,
+    dependents: Vec<Vec<usize>>,
+    local: Vec<bool>,
+    world: SharedWorld<'w>,
+    error_handler: Mutex<&'a mut ErrorHandler>,
+    progress: Mutex<Progress>,
+    changed: Condvar,
+}
+
+impl Run<'_, '_> {
+    fn progress(&self) -> MutexGuard<'_, Progress> {
+        self.progress.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Runs ready systems until every system finished or one panicked, the calling thread also takes the local systems.
+    fn work(&self, calling: bool) {
+        loop {
+            let index = {
+                let mut progress = self.progress();
+                loop {
+                    if progress.panic.is_some()
+                        || progress.aborted
+                        || progress.finished == self.count
+                    {
+                        return;
+                    }
+                    let next = if calling {
+                        progress.ready_local.pop_first()
+                    } else {
+                        None
+                    };
+                    if let Some(index) = next.or_else(|| progress.ready.pop_first()) {
+                        break index;
+                    }
+                    progress = self
+                        .changed
+                        .wait(progress)
+                        .unwrap_or_else(PoisonError::into_inner);
+                }
+            };
+
+            // The error handler runs inside, so a panicking handler stops the run like a panicking system
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                let mut config = self.systems[index]
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner);
+                let result = if self.enabled[index] {
+                    config.run(self.world.0)
+                } else {
+                    Ok(())
+                };
+                result.map_or_else(
+                    |error| {
+                        let mut handler = self
+                            .error_handler
+                            .lock()
+                            .unwrap_or_else(PoisonError::into_inner);
+                        (handler.0)(error)
+                    },
+                    |()| OnError::Continue,
+                )
+            }));
+
+            let mut progress = self.progress();
+            match result {
+                Ok(OnError::Abort) => progress.aborted = true,
+                Ok(OnError::Continue) => {
+                    progress.finished += 1;
+                    for dependent in &self.dependents[index] {
+                        progress.remaining[*dependent] -= 1;
+                        if progress.remaining[*dependent] == 0 {
+                            if self.local[*dependent] {
+                                progress.ready_local.insert(*dependent);
+                            } else {
+                                progress.ready.insert(*dependent);
+                            }
+                        }
+                    }
+                }
+                Err(payload) => {
+                    progress.panic.get_or_insert(payload);
+                }
+            }
+            self.changed.notify_all();
+        }
+    }
+}
+
+/// Returns `true` when the system isn't bound to the calling thread and every component it accesses may be touched from another thread.
+fn runs_anywhere(world: &World, access: &SystemAccess) -> bool {
+    if access.is_main_thread() {
+        return false;
+    }
+    let components = access.components();
+    components.reads().chain(components.writes()).all(|id| {
+        world
+            .components()
+            .get(id)
+            .is_none_or(ComponentInfo::is_thread_safe)
+    })
+}
+
+impl Schedule {
+    /// Runs the systems of a segment of the order on scoped worker threads, every system starts as soon as the systems it depends on finished.
+    /// Returns `true` when the error handler aborted the run.
+    /// Systems accessing components which aren't thread safe or [`NonSend`](crate::system::NonSend) resources run on the calling thread.
+    pub(crate) fn run_parallel(
+        &mut self,
+        world: &World,
+        threads: usize,
+        segment: &[usize],
+        enabled: &[bool],
+    ) -> bool {
+        let threads = match threads {
+            0 => thread::available_parallelism().map_or(1, usize::from),
+            threads => threads,
+        };
+        let local: Vec<bool> = self
+            .systems
+            .iter()
+            .map(|config| !runs_anywhere(world, config.system.access()))
+            .collect();
+
+        // Dependencies outside of the segment finished in earlier segments
+        let mut in_segment = vec![false; self.systems.len()];
+        for index in segment {
+            in_segment[*index] = true;
+        }
+        let mut dependents = vec![Vec::new(); self.systems.len()];
+        let mut remaining = vec![0; self.systems.len()];
+        for index in segment {
+            for dependency in &self.dependencies[*index] {
+                if in_segment[*dependency] {
+                    dependents[*dependency].push(*index);
+                    remaining[*index] += 1;
+                }
+            }
+        }
+        let (ready_local, ready) = segment
+            .iter()
+            .copied()
+            .filter(|index| remaining[*index] == 0)
+            .partition(|index| local[*index]);
+
+        let run = Run {
+            systems: self.systems.iter_mut().map(Mutex::new).collect(),
+            enabled,
+            count: segment.len(),
+            dependents,
+            local,
+            world: SharedWorld(world),
+            error_handler: Mutex::new(&mut self.error_handler),
+            progress: Mutex::new(Progress {
+                remaining,
+                ready,
+                ready_local,
+                finished: 0,
+                panic: None,
+                aborted: false,
+            }),
+            changed: Condvar::new(),
+        };
+
+        thread::scope(|scope| {
+            for _ in 1..threads {
+                scope.spawn(|| run.work(false));
+            }
+            run.work(true);
+        });
+
+        let mut progress = run.progress();
+        if let Some(payload) = progress.panic.take() {
+            panic::resume_unwind(payload);
+        }
+        progress.aborted
+    }
+}
+
+impl World {
+    /// Marks the component as `Send + Sync`, so systems accessing it can run on the worker threads of a parallel [`Schedule`].
+    /// Systems accessing components which aren't marked run on the thread calling [`Schedule::run`].
+    pub fn register_thread_safe<T: Component + Send + Sync>(&mut self) {
+        self.register_component::<T>();
+        // The component was just registered, so it has an entry
+        self.component_info_mut(&ComponentId::of::<T>())
+            .unwrap()
+            .thread_safe = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fmt,
+        panic::{self, AssertUnwindSafe},
+        sync::{
+            Barrier, Mutex,
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+        },
+        thread::{self, ThreadId},
+        time::Duration,
+    };
+
+    use crate::{
+        error::OnError,
+        resource::Res,
+        schedule::{Executor, IntoSystemConfig, Schedule},
+        system::{Commands, NonSend, Query},
+        world::{Component, World},
+    };
+
+    struct Value(u32);
+    struct Plain;
+
+    impl Component for Value {}
+    impl Component for Plain {}
+
+    #[derive(Default)]
+    struct Log(Mutex<Vec<&'static str>>);
+
+    impl Log {
+        fn push(&self, name: &'static str) {
+            self.0.lock().unwrap().push(name);
+        }
+
+        fn take(&self) -> Vec<&'static str> {
+            std::mem::take(&mut self.0.lock().unwrap())
+        }
+    }
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.register_thread_safe::<Value>();
+        world.insert_resource(Log::default());
+        world
+    }
+
+    fn first(log: Res<Log>) {
+        log.push("first");
+    }
+
+    fn second(log: Res<Log>) {
+        log.push("second");
+    }
+
+    fn third(log: Res<Log>) {
+        log.push("third");
+    }
+
+    #[test]
+    fn systems_run_after_their_dependencies() {
+        let mut world = world();
+        let mut schedule = Schedule::new();
+        schedule
+            .set_executor(Executor::Parallel(4))
+            .add_system(third.after(second))
+            .add_system(second.after(first))
+            .add_system(first);
+
+        for _ in 0..10 {
+            schedule.run(&mut world);
+            let log = world.get_resource::<Log>().unwrap().take();
+            assert_eq!(log, ["first", "second", "third"]);
+        }
+    }
+
+    #[derive(Default)]
+    struct Tracker {
+        active: AtomicUsize,
+        overlapped: AtomicBool,
+    }
+
+    impl Tracker {
+        fn enter(&self) {
+            if self.active.fetch_add(1, Ordering::SeqCst) > 0 {
+                self.overlapped.store(true, Ordering::SeqCst);
+            }
+            thread::sleep(Duration::from_millis(5));
+            self.active.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    fn increment(tracker: Res<Tracker>, mut query: Query<&mut Value>) {
+        tracker.enter();
+        for value in query.iter() {
+            value.0 += 1;
+        }
+    }
+
+    fn double(tracker: Res<Tracker>, mut query: Query<&mut Value>) {
+        tracker.enter();
+        for value in query.iter() {
+            value.0 *= 2;
+        }
+    }
+
+    #[test]
+    fn conflicting_systems_never_overlap() {
+        let mut world = world();
+        world.insert_resource(Tracker::default());
+        let entity = world.spawn(Value(1));
+        let mut schedule = Schedule::new();
+        schedule
+            .set_executor(Executor::Parallel(2))
+            .add_system(increment)
+            .add_system(double);
+
+        schedule.run(&mut world);
+        let tracker = world.get_resource::<Tracker>().unwrap();
+        assert!(!tracker.overlapped.load(Ordering::SeqCst));
+        // Conflicting systems without constraints run in the order they were added
+        assert_eq!(world.get_component::<Value>(entity).unwrap().0, 4);
+    }
+
+    struct Meeting(Barrier);
+
+    fn meet_left(meeting: Res<Meeting>) {
+        meeting.0.wait();
+    }
+
+    fn meet_right(meeting: Res<Meeting>) {
+        meeting.0.wait();
+    }
+
+    #[test]
+    fn compatible_systems_run_at_the_same_time() {
+        let mut world = world();
+        world.insert_resource(Meeting(Barrier::new(2)));
+        let mut schedule = Schedule::new();
+        schedule
+            .set_executor(Executor::Parallel(2))
+            .add_system(meet_left)
+            .add_system(meet_right);
+
+        // Each system waits for the other one, so a sequential run would never finish
+        schedule.run(&mut world);
+    }
+
+    #[derive(Default)]
+    struct Threads(Mutex<Vec<ThreadId>>);
+
+    fn plain(threads: Res<Threads>, _query: Query<&Plain>) {
+        threads.0.lock().unwrap().push(thread::current().id());
+    }
+
+    fn non_send(threads: Res<Threads>, _marker: NonSend<Log>) {
+        threads.0.lock().unwrap().push(thread::current().id());
+    }
+
+    #[test]
+    fn systems_which_are_not_thread_safe_run_on_the_calling_thread() {
+        let mut world = world();
+        world.insert_resource(Threads::default());
+        world.spawn(Plain);
+        let mut schedule = Schedule::new();
+        schedule
+            .set_executor(Executor::Parallel(4))
+            .add_system(plain)
+            .add_system(non_send);
+
+        for _ in 0..5 {
+            schedule.run(&mut world);
+        }
+        let threads = world.get_resource::<Threads>().unwrap();
+        let threads = threads.0.lock().unwrap();
+        assert_eq!(threads.len(), 10);
+        assert!(threads.iter().all(|id| *id == thread::current().id()));
+    }
+
+    fn explode(log: Res<Log>) {
+        log.push("explode");
+        panic!("system exploded");
+    }
+
+    #[test]
+    fn panics_are_resumed_on_the_calling_thread() {
+        let mut world = world();
+        let mut schedule = Schedule::new();
+        schedule
+            .set_executor(Executor::Parallel(2))
+            .add_system(explode)
+            .add_system(second.after(explode));
+
+        let payload = panic::catch_unwind(AssertUnwindSafe(|| schedule.run(&mut world)))
+            .expect_err("the panic of the system reaches the caller");
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"system exploded"));
+        assert_eq!(world.get_resource::<Log>().unwrap().take(), ["explode"]);
+    }
+
+    #[derive(Debug)]
+    struct Failure;
+
+    impl fmt::Display for Failure {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("failure")
+        }
+    }
+
+    impl std::error::Error for Failure {}
+
+    fn fail(mut commands: Commands) -> Result<(), Failure> {
+        commands.spawn(Value(7));
+        Err(Failure)
+    }
+
+    #[test]
+    fn aborting_errors_stop_the_run() {
+        let mut world = world();
+        let mut schedule = Schedule::new();
+        schedule
+            .set_executor(Executor::Parallel(2))
+            .set_error_handler(|_| OnError::Abort)
+            .add_system(fail)
+            .add_system(second.after(fail));
+
+        schedule.run(&mut world);
+        assert!(world.get_resource::<Log>().unwrap().take().is_empty());
+        // Deferred changes of the systems which ran are still applied
+        assert_eq!(world.query::<&Value>().iter(&world).count(), 1);
+    }
+}
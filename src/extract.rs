@@ -0,0 +1,172 @@
+use crate::{
+    blob_data::ColumnGuard,
+    query::{ArchetypeFilter, Filter},
+    world::{Component, ComponentId, World},
+};
+
+/// Filter and tick shared by every component of a single [`World::extract_into`] call.
+struct Extraction {
+    filter: ArchetypeFilter,
+    since: u64,
+}
+
+/// Functions extracting a single component type.
+#[derive(Clone, Copy)]
+struct ExtractFns {
+    id: ComponentId,
+    copy: fn(&World, &mut World, &Extraction),
+    prune: fn(&World, &mut World, &Extraction),
+}
+
+/// Describes which entities and components [`World::extract_into`] mirrors into the target world.
+#[derive(Clone)]
+pub struct ExtractionConfig {
+    filter: fn(&World) -> ArchetypeFilter,
+    components: Vec<ExtractFns>,
+}
+
+impl Default for ExtractionConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExtractionConfig {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            filter: ArchetypeFilter::of::<()>,
+            components: Vec::new(),
+        }
+    }
+
+    /// Only extracts entities matching the filter `F`, e.g. `With<Visible>`.
+    #[must_use]
+    pub fn filter<F: Filter>(mut self) -> Self {
+        self.filter = ArchetypeFilter::of::<F>;
+        self
+    }
+
+    /// Extracts the component `T` by cloning it into the target world.
+    #[must_use]
+    pub fn component<T: Component + Clone>(mut self) -> Self {
+        if self
+            .components
+            .iter()
+            .all(|fns| fns.id != ComponentId::of::<T>())
+        {
+            self.components.push(ExtractFns {
+                id: ComponentId::of::<T>(),
+                copy: copy::<T>,
+                prune: prune::<T>,
+            });
+        }
+        self
+    }
+}
+
+impl World {
+    /// Mirrors the configured components of the matching entities into the target world, keeping the same entity ids.
+    /// Only components which changed since the previous extraction into the target are cloned. Components of entities which were despawned,
+    /// stopped matching the filter or lost the component are removed from the target, and target entities left without any components are despawned.
+    ///
+    /// The target is meant to be a mirror of this world only, spawning entities in it directly may take the ids of extracted entities and panic.
+    /// It takes over the [`WorldId`](crate::world::WorldId) of this world.
+    pub fn extract_into(&mut self, target: &mut World, config: &ExtractionConfig) {
+        // The target mirrors the entities of this world, so it accepts their handles
+        target.entities.world = self.entities.world;
+
+        let extraction = Extraction {
+            filter: (config.filter)(self),
+            since: target.extracted_tick,
+        };
+
+        // Prune first, so the slots of despawned entities are free before they are reused by extracted ones
+        let stale: Vec<_> = target
+            .entities
+            .alive()
+            .filter(|entity| !self.is_alive(*entity))
+            .collect();
+        for entity in stale {
+            target.despawn_entity(entity);
+        }
+
+        for fns in &config.components {
+            (fns.prune)(self, target, &extraction);
+        }
+        for fns in &config.components {
+            (fns.copy)(self, target, &extraction);
+        }
+
+        target.extracted_tick = self.increment_change_tick();
+    }
+}
+
+/// Clones every changed `T` of the matching entities into the target.
+fn copy<T: Component + Clone>(source: &World, target: &mut World, extraction: &Extraction) {
+    let Some(bit) = source.bit_of::<T>() else {
+        return;
+    };
+
+    for archetype in source.archetypes() {
+        let mask = archetype.bitmask();
+        if !mask.contains(&bit) || !extraction.filter.matches(mask) {
+            continue;
+        }
+
+        // The archetype has the bit of `T`, so it has the column as well
+        let column = ColumnGuard::new(
+            archetype.column(&ComponentId::of::<T>()).unwrap(),
+            "extract",
+        );
+
+        for (row, entity) in archetype.entities().iter().enumerate() {
+            if column.tick(row) <= extraction.since && target.has_component::<T>(*entity) {
+                continue;
+            }
+
+            if !target.is_alive(*entity) {
+                assert!(
+                    target.entities.alloc_at(*entity),
+                    "Extraction target has another entity in the slot of {entity:?}"
+                );
+            }
+
+            let value = column.get::<T>(row).unwrap().clone();
+            target.insert_component(*entity, value);
+        }
+    }
+}
+
+/// Removes `T` from target entities whose source no longer has it or no longer matches the filter.
+fn prune<T: Component>(source: &World, target: &mut World, extraction: &Extraction) {
+    let Some(bit) = target.bit_of::<T>() else {
+        return;
+    };
+    let source_bit = source.bit_of::<T>().unwrap_or_default();
+
+    let mut removed = Vec::new();
+    for archetype in target.archetypes() {
+        if !archetype.bitmask().intersects(&bit) {
+            continue;
+        }
+
+        for entity in archetype.entities() {
+            let keep = !source_bit.is_empty()
+                && source.archetype_of(*entity).is_some_and(|archetype| {
+                    let mask = archetype.bitmask();
+                    mask.contains(&source_bit) && extraction.filter.matches(mask)
+                });
+            if !keep {
+                removed.push(*entity);
+            }
+        }
+    }
+
+    for entity in removed {
+        target.remove_component::<T>(entity);
+        if target.is_empty(entity) {
+            target.despawn_entity(entity);
+        }
+    }
+}
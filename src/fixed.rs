@@ -0,0 +1,228 @@
+use crate::{schedule::Schedule, time::Time, world::World};
+
+/// Clock of fixed timestep schedules, stored as a resource and advanced by [`World::run_fixed`].
+/// Every run of the schedule covers exactly [`FixedTime::step`] seconds, its systems read the fixed clock with `Res<Time>`
+/// and the accumulated time with `Res<FixedTime>`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedTime {
+    step: f32,
+    accumulated: f32,
+    max_steps: Option<u32>,
+    time: Time,
+}
+
+impl Default for FixedTime {
+    /// Steps of a sixtieth of a second.
+    fn default() -> Self {
+        Self::new(1.0 / 60.0)
+    }
+}
+
+impl FixedTime {
+    /// Creates a clock running a step every `step` seconds. Panics when the step isn't positive.
+    #[must_use]
+    pub fn new(step: f32) -> Self {
+        assert!(step > 0.0, "Fixed timestep must be positive");
+        Self {
+            step,
+            accumulated: 0.0,
+            max_steps: None,
+            time: Time::default(),
+        }
+    }
+
+    /// Limits the steps run for one frame, the time left over after them is dropped so a slow frame doesn't make the next ones slower.
+    #[must_use]
+    pub fn with_max_steps(mut self, max_steps: u32) -> Self {
+        self.max_steps = Some(max_steps);
+        self
+    }
+
+    /// Seconds covered by every step.
+    #[inline]
+    #[must_use]
+    pub fn step(&self) -> f32 {
+        self.step
+    }
+
+    /// Changes the step, the accumulated time is kept. Panics when the step isn't positive.
+    pub fn set_step(&mut self, step: f32) {
+        assert!(step > 0.0, "Fixed timestep must be positive");
+        self.step = step;
+    }
+
+    /// Seconds accumulated but not yet covered by a step.
+    #[inline]
+    #[must_use]
+    pub fn accumulated(&self) -> f32 {
+        self.accumulated
+    }
+
+    /// Part of the next step already accumulated between `0` and `1`, e.g. to interpolate rendered positions between two steps.
+    #[inline]
+    #[must_use]
+    pub fn overstep_fraction(&self) -> f32 {
+        self.accumulated / self.step
+    }
+
+    /// Number of steps run so far.
+    #[inline]
+    #[must_use]
+    pub fn tick(&self) -> u64 {
+        self.time.tick()
+    }
+
+    /// Clock advanced by a step for every step, which is the [`Time`] resource while the schedule runs.
+    #[inline]
+    #[must_use]
+    pub fn time(&self) -> &Time {
+        &self.time
+    }
+
+    /// Adds the seconds of a frame, negative deltas are treated as zero.
+    pub fn accumulate(&mut self, delta: f32) {
+        self.accumulated += delta.max(0.0);
+    }
+
+    /// Takes one step from the accumulated time, returns `false` when less than a step is left.
+    pub fn expend(&mut self) -> bool {
+        if self.accumulated < self.step {
+            return false;
+        }
+        self.accumulated -= self.step;
+        self.time.advance(self.step);
+        true
+    }
+}
+
+impl World {
+    /// Adds the seconds of a frame to the [`FixedTime`] resource, inserting the default one when it is missing,
+    /// then runs the schedule once for every whole step accumulated, zero or more times. Returns the number of runs.
+    ///
+    /// While a step runs the [`Time`] resource is the fixed clock of [`FixedTime::time`], so systems see the step as their delta,
+    /// and components inserted with [`World::insert_timed`] during a step expire by the fixed clock at the start of later steps.
+    /// The world clock of [`World::update_time`] is put back after every step and is not advanced.
+    pub fn run_fixed(&mut self, schedule: &mut Schedule, delta: f32) -> u32 {
+        if !self.contains_resource::<FixedTime>() {
+            self.insert_resource(FixedTime::default());
+        }
+        let max_steps = {
+            let mut time = self.get_resource_mut::<FixedTime>().unwrap();
+            time.accumulate(delta);
+            time.max_steps
+        };
+
+        let mut steps = 0;
+        while max_steps.is_none_or(|max_steps| steps < max_steps) {
+            // A system may have removed the clock
+            let time = self
+                .get_resource_mut::<FixedTime>()
+                .and_then(|mut fixed| fixed.expend().then_some(fixed.time));
+            let Some(time) = time else {
+                return steps;
+            };
+            self.run_fixed_step(schedule, time);
+            steps += 1;
+        }
+
+        // The step limit was reached, whole steps left over are dropped
+        if let Some(mut time) = self.get_resource_mut::<FixedTime>() {
+            time.accumulated %= time.step;
+        }
+        steps
+    }
+
+    /// Runs one step with the fixed clock as the [`Time`] resource, then puts the world clock back.
+    fn run_fixed_step(&mut self, schedule: &mut Schedule, time: Time) {
+        let world_time = self.remove_resource::<Time>();
+        self.insert_resource(time);
+        self.fixed_step = true;
+        self.expire_timed();
+        schedule.run(self);
+        self.fixed_step = false;
+
+        self.remove_resource::<Time>();
+        if let Some(world_time) = world_time {
+            self.insert_resource(world_time);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FixedTime;
+    use crate::{
+        resource::{Res, ResMut},
+        schedule::Schedule,
+        system::Commands,
+        time::Time,
+        timed::Lifetime,
+        world::{Component, Entity, World},
+    };
+
+    struct Burning;
+    struct Fuel;
+
+    impl Component for Burning {}
+    impl Component for Fuel {}
+
+    #[derive(Default)]
+    struct Seen(Vec<(f32, u64, f64)>);
+
+    fn read_time(time: Res<Time>, mut seen: ResMut<Seen>) {
+        seen.0.push((time.delta(), time.tick(), time.elapsed()));
+    }
+
+    #[test]
+    fn fixed_systems_see_the_fixed_clock() {
+        let mut world = World::new();
+        world.insert_resource(Seen::default());
+        world.insert_resource(FixedTime::new(0.25));
+        world.update_time(1.0);
+        let mut schedule = Schedule::new();
+        schedule.add_system(read_time);
+
+        assert_eq!(world.run_fixed(&mut schedule, 0.5), 2);
+        assert_eq!(world.run_fixed(&mut schedule, 0.25), 1);
+        assert_eq!(
+            world.get_resource::<Seen>().unwrap().0,
+            [(0.25, 1, 0.25), (0.25, 2, 0.5), (0.25, 3, 0.75)]
+        );
+        assert_eq!(world.get_resource::<FixedTime>().unwrap().tick(), 3);
+
+        // The world clock is back in place and did not move
+        assert_eq!(world.time().tick(), 1);
+        assert_eq!(world.time().delta(), 1.0);
+    }
+
+    struct Target(Entity);
+
+    fn ignite(time: Res<Time>, target: Res<Target>, mut commands: Commands) {
+        if time.tick() == 1 {
+            let entity = target.0;
+            commands.push(move |world| world.insert_timed(entity, Burning, Lifetime::Ticks(2)));
+        }
+    }
+
+    #[test]
+    fn timed_components_of_fixed_systems_expire_by_the_fixed_clock() {
+        let mut world = World::new();
+        let entity = world.spawn(Fuel);
+        world.insert_resource(Target(entity));
+        world.insert_resource(FixedTime::new(0.25));
+        let mut schedule = Schedule::new();
+        schedule.add_system(ignite);
+
+        world.run_fixed(&mut schedule, 0.5);
+        assert!(world.has_component::<Burning>(entity));
+        // Updates of the world clock don't count against the fixed lifetime
+        for _ in 0..5 {
+            world.update_time(1.0);
+        }
+        assert!(world.has_component::<Burning>(entity));
+
+        // Inserted during the first step, so it expires at the start of the third one
+        world.run_fixed(&mut schedule, 0.25);
+        assert!(!world.has_component::<Burning>(entity));
+    }
+}
@@ -0,0 +1,146 @@
+use std::{fmt, ops::Deref, sync::Arc};
+
+use crate::{
+    snapshot::WorldSnapshot,
+    world::{Component, ComponentId, World},
+};
+
+/// Returned by [`World::freeze`] when the world contains components which can't be shared with other threads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FreezeError {
+    /// Type names of every component not registered with [`World::register_shareable`], each listed once.
+    pub components: Vec<&'static str>,
+}
+
+impl fmt::Display for FreezeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "components not registered as shareable: {}",
+            self.components.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for FreezeError {}
+
+/// Snapshot of a [`World`] created by [`World::freeze`], which can be read from several threads at once, e.g. by audio, render or IO threads,
+/// while the world keeps being updated. It dereferences to the [`WorldSnapshot`] it wraps.
+pub struct FrozenWorld(WorldSnapshot);
+
+// SAFETY: Every stored component is `Send + Sync`, which is checked by `World::freeze`,
+// and the snapshot never mutates its columns, so the change ticks and borrow flags are only read
+unsafe impl Send for FrozenWorld {}
+unsafe impl Sync for FrozenWorld {}
+
+impl Deref for FrozenWorld {
+    type Target = WorldSnapshot;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl World {
+    /// Registers the component as cloneable and marks it as safe to read from other threads, which is required by [`World::freeze`].
+    /// It is marked as thread safe too, see [`World::register_thread_safe`].
+    pub fn register_shareable<T: Component + Clone + Send + Sync>(&mut self) {
+        self.register_cloneable::<T>();
+        let info = self.component_info_mut(&ComponentId::of::<T>()).unwrap();
+        info.shareable = true;
+        info.thread_safe = true;
+    }
+
+    #[must_use]
+    pub fn is_shareable<T: Component>(&self) -> bool {
+        self.component_info(&ComponentId::of::<T>())
+            .is_some_and(|info| info.shareable)
+    }
+
+    /// Copies the entities and components into a snapshot which other threads can read concurrently, while this world keeps being updated.
+    /// The copy is a plain clone of every column, so freeze once per frame rather than per reader and share the returned [`Arc`].
+    ///
+    /// Fails without copying anything when the world contains components which are not registered with [`World::register_shareable`].
+    pub fn freeze(&self) -> Result<Arc<FrozenWorld>, FreezeError> {
+        let components = self.missing_components(self.archetypes(), |info| info.shareable);
+        if !components.is_empty() {
+            return Err(FreezeError { components });
+        }
+
+        Ok(Arc::new(FrozenWorld(self.snapshot_unchecked())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::world::{Component, World};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Position(u32);
+    #[derive(Debug, Clone, PartialEq)]
+    struct Velocity(u32);
+    #[derive(Clone)]
+    struct Handle;
+
+    impl Component for Position {}
+    impl Component for Velocity {}
+    impl Component for Handle {}
+
+    #[test]
+    fn frozen_worlds_are_read_from_other_threads() {
+        let mut world = World::new();
+        world.register_shareable::<Position>();
+        let entity = world.spawn(Position(1));
+        let frozen = world.freeze().unwrap();
+
+        *world.get_component_mut::<Position>(entity).unwrap() = Position(2);
+        world.spawn(Position(3));
+
+        let readers: Vec<_> = (0..2)
+            .map(|_| {
+                let frozen = frozen.clone();
+                thread::spawn(move || (frozen.len(), frozen.get::<Position>(entity).cloned()))
+            })
+            .collect();
+        for reader in readers {
+            // The snapshot keeps the state at the freeze
+            assert_eq!(reader.join().unwrap(), (1, Some(Position(1))));
+        }
+        assert_eq!(world.get_component::<Position>(entity), Some(&Position(2)));
+    }
+
+    #[test]
+    fn freezing_lists_the_components_which_are_not_shareable() {
+        let mut world = World::new();
+        world.register_shareable::<Position>();
+        world.register_cloneable::<Handle>();
+        world.spawn((Position(1), Velocity(1)));
+        world.spawn((Velocity(2), Handle));
+
+        let error = world.freeze().err().unwrap();
+        assert_eq!(
+            error.components,
+            [
+                std::any::type_name::<Handle>(),
+                std::any::type_name::<Velocity>(),
+            ]
+        );
+        assert!(
+            error
+                .to_string()
+                .starts_with("components not registered as shareable: ")
+        );
+    }
+
+    #[test]
+    fn shareable_components_are_cloneable_and_thread_safe() {
+        let mut world = World::new();
+        assert!(!world.is_shareable::<Position>());
+        world.register_shareable::<Position>();
+        assert!(world.is_shareable::<Position>());
+        assert!(world.snapshot().is_ok());
+        assert!(world.freeze().unwrap().is_empty());
+    }
+}
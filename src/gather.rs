@@ -0,0 +1,192 @@
+use crate::{
+    blob_data::ColumnGuard,
+    world::{Component, ComponentId, Entity, Location, World},
+};
+
+impl World {
+    /// Returns the location of every entity in the list, or `None` when some entity is dead or has no `T`.
+    fn locations_of<T: Component>(&self, entities: &[Entity]) -> Option<Vec<(usize, Location)>> {
+        let bit = self.bit_of::<T>()?;
+        entities
+            .iter()
+            .enumerate()
+            .map(|(position, entity)| {
+                let archetype = self
+                    .archetype_of(*entity)
+                    .filter(|_| self.is_alive(*entity))?;
+                (archetype.bitmask().intersects(&bit))
+                    .then(|| (position, self.entities.metas[entity.index].location))
+            })
+            .collect()
+    }
+
+    /// Appends clones of the `T` components of the listed entities to the buffer, in the order of the list, e.g. to upload them to the GPU.
+    /// The values are read archetype by archetype for locality. Returns `false` without appending anything when some entity is dead or has no `T`.
+    pub fn gather<T: Component + Clone>(&self, entities: &[Entity], out: &mut Vec<T>) -> bool {
+        let Some(mut locations) = self.locations_of::<T>(entities) else {
+            return false;
+        };
+        locations.sort_unstable_by_key(|(_, location)| (location.archetype, location.row));
+
+        out.reserve(entities.len());
+        let spare = &mut out.spare_capacity_mut()[..entities.len()];
+        for run in locations.chunk_by(|(_, a), (_, b)| a.archetype == b.archetype) {
+            let archetype = &self.archetypes()[run[0].1.archetype];
+            let column = archetype.column(&ComponentId::of::<T>()).unwrap();
+            let column = ColumnGuard::new(column, "gather from");
+            for (position, location) in run {
+                spare[*position].write(column.get::<T>(location.row).unwrap().clone());
+            }
+        }
+
+        unsafe {
+            out.set_len(out.len() + entities.len()); // SAFETY: Every position of the list was written above
+        }
+        true
+    }
+
+    /// Writes the values back into the `T` components of the listed entities, the inverse of [`World::gather`], marking them as changed.
+    /// The writes are grouped by archetype for locality. Returns `false` without writing anything when some entity is dead or has no `T`,
+    /// or when the lengths differ.
+    pub fn scatter<T: Component>(&mut self, entities: &[Entity], values: Vec<T>) -> bool {
+        if entities.len() != values.len() {
+            return false;
+        }
+        let Some(locations) = self.locations_of::<T>(entities) else {
+            return false;
+        };
+
+        let mut writes: Vec<_> = locations
+            .into_iter()
+            .zip(values)
+            .map(|((_, location), value)| (location, value))
+            .collect();
+        writes.sort_unstable_by_key(|(location, _)| (location.archetype, location.row));
+
+        let tick = self.change_tick();
+        for (location, value) in writes {
+            let archetype = &mut self.archetypes_mut()[location.archetype];
+            *archetype.get_mut::<T>(location.row, tick).unwrap() = value;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::world::{Component, Entity, World};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Name(String);
+    struct Marker;
+
+    impl Component for Name {}
+    impl Component for Marker {}
+
+    fn name(value: &str) -> Name {
+        Name(value.to_string())
+    }
+
+    /// Spawns named entities over two archetypes, alternating between them.
+    fn named(world: &mut World) -> Vec<Entity> {
+        ["a", "b", "c", "d"]
+            .iter()
+            .enumerate()
+            .map(|(index, value)| match index % 2 {
+                0 => world.spawn(name(value)),
+                _ => world.spawn((name(value), Marker)),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn gathered_values_follow_the_list_order() {
+        let mut world = World::new();
+        let entities = named(&mut world);
+
+        let mut out = vec![name("existing")];
+        let list = [entities[3], entities[0], entities[2], entities[1]];
+        assert!(world.gather(&list, &mut out));
+        assert_eq!(
+            out,
+            [name("existing"), name("d"), name("a"), name("c"), name("b")]
+        );
+    }
+
+    #[test]
+    fn gathering_fails_without_appending() {
+        let mut world = World::new();
+        let entities = named(&mut world);
+        let marker = world.spawn(Marker);
+        let dead = world.spawn(name("dead"));
+        world.despawn_entity(dead);
+
+        let mut out: Vec<Name> = Vec::new();
+        assert!(!world.gather(&[entities[0], marker], &mut out));
+        assert!(!world.gather(&[dead, entities[1]], &mut out));
+        assert!(!World::new().gather::<Name>(&[entities[0]], &mut out));
+        assert!(out.is_empty());
+        assert!(world.gather::<Name>(&[], &mut out));
+    }
+
+    #[test]
+    fn scattered_values_are_written_back_and_marked() {
+        let mut world = World::new();
+        let entities = named(&mut world);
+        world.increment_change_tick();
+
+        let list = [entities[2], entities[1]];
+        assert!(world.scatter(&list, vec![name("x"), name("y")]));
+        assert_eq!(world.get_component::<Name>(entities[2]), Some(&name("x")));
+        assert_eq!(world.get_component::<Name>(entities[1]), Some(&name("y")));
+        assert_eq!(world.get_component::<Name>(entities[0]), Some(&name("a")));
+        assert_eq!(
+            world.component_ticks::<Name>(entities[1]).unwrap().changed,
+            2
+        );
+        assert_eq!(
+            world.component_ticks::<Name>(entities[0]).unwrap().changed,
+            1
+        );
+    }
+
+    #[test]
+    fn scattering_fails_without_writing() {
+        let mut world = World::new();
+        let entities = named(&mut world);
+        let marker = world.spawn(Marker);
+
+        assert!(!world.scatter(&entities[..2], vec![name("x")]));
+        assert!(!world.scatter(&[entities[0], marker], vec![name("x"), name("y")]));
+        let mut out: Vec<Name> = Vec::new();
+        assert!(world.gather(&entities, &mut out));
+        assert_eq!(out, [name("a"), name("b"), name("c"), name("d")]);
+    }
+
+    #[test]
+    fn gather_and_scatter_round_trip() {
+        let mut world = World::new();
+        let entities = named(&mut world);
+
+        let mut out: Vec<Name> = Vec::new();
+        assert!(world.gather(&entities, &mut out));
+        for value in &mut out {
+            value.0.push('!');
+        }
+        assert!(world.scatter(&entities, out));
+        let mut back: Vec<Name> = Vec::new();
+        assert!(world.gather(&entities, &mut back));
+        assert_eq!(back, [name("a!"), name("b!"), name("c!"), name("d!")]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot gather from a column which is mutably borrowed")]
+    fn gathering_from_a_borrowed_column_panics() {
+        let mut world = World::new();
+        let entities = named(&mut world);
+        let mut query = world.query::<&mut Name>();
+
+        let _names = query.iter(&world);
+        world.gather::<Name>(&entities, &mut Vec::new());
+    }
+}
@@ -0,0 +1,183 @@
+use std::{any::TypeId, collections::HashMap, marker::PhantomData};
+
+use crate::{
+    archetype::Archetype, bundle::Bundle, disabled::Disabled, mask::ComponentMask,
+    query::QueryItem, world::World,
+};
+
+/// Registers the components of a group and returns their bits.
+type RegisterFn = fn(&mut World) -> ComponentMask;
+
+/// Archetypes of one group, kept up to date whenever an archetype is created.
+struct GroupEntry {
+    register: RegisterFn,
+    required: ComponentMask,
+    excluded: ComponentMask,
+    archetypes: Vec<usize>,
+}
+
+/// Groups of the world, indexed by the handles returned from [`World::create_group`].
+#[derive(Default)]
+pub(crate) struct Groups {
+    entries: Vec<GroupEntry>,
+    by_type: HashMap<TypeId, usize>,
+}
+
+impl Groups {
+    /// Adds the archetype at the given index to every group whose components it contains, keeping them sorted by bitmask when `ordered`.
+    pub(crate) fn track(&mut self, index: usize, archetypes: &[Archetype], ordered: bool) {
+        let bitmask = archetypes[index].bitmask();
+        for entry in &mut self.entries {
+            if bitmask.matches(&entry.required, &entry.excluded) {
+                let position = if ordered {
+                    entry
+                        .archetypes
+                        .partition_point(|other| archetypes[*other].bitmask() < bitmask)
+                } else {
+                    entry.archetypes.len()
+                };
+                entry.archetypes.insert(position, index);
+            }
+        }
+    }
+
+    /// Renumbers the archetypes after they were compacted, `indices` maps old indices to new ones or `usize::MAX` for dropped archetypes.
+    pub(crate) fn remap(&mut self, indices: &[usize]) {
+        for entry in &mut self.entries {
+            entry.archetypes.retain_mut(|index| {
+                *index = indices[*index];
+                *index != usize::MAX
+            });
+        }
+    }
+}
+
+/// Handle of a set of components whose entities are iterated together, created with [`World::create_group`].
+///
+/// A group is a query cache maintained eagerly: the world keeps the list of archetypes containing every component of the group
+/// and adds new archetypes to it as soon as they are created, so iterating skips filter matching and cache updates.
+/// It doesn't own or reorder storage like owning groups of EnTT do, entities stay in their archetypes and a group spanning
+/// several archetypes is iterated one archetype at a time, as a zip over its columns. Disabled entities are never part of a group.
+pub struct Group<B: Bundle> {
+    index: usize,
+    _marker: PhantomData<fn() -> B>,
+}
+
+impl<B: Bundle> Clone for Group<B> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<B: Bundle> Copy for Group<B> {}
+
+impl<B: Bundle> Group<B> {
+    /// Calls the closure with the item of every entity of the group. `Q` may only require components of the group,
+    /// e.g. `(&Position, &mut Velocity)` for a group of `(Position, Velocity)`, and panics otherwise or when a column is borrowed mutably elsewhere.
+    pub fn for_each<Q: QueryItem>(&self, world: &World, mut f: impl FnMut(Q::Item<'_>)) {
+        let entry = &world.groups.entries[self.index];
+        let (required, _) = Q::bitmask(world);
+        assert!(
+            entry.required.contains(&required),
+            "Query requires components outside of the group"
+        );
+
+        let archetypes = world.archetypes();
+        let tick = world.change_detection().then(|| world.change_tick());
+        for (borrowed, index) in entry.archetypes.iter().enumerate() {
+            let archetype = &archetypes[*index];
+            if !Q::borrow(archetype) {
+                for index in &entry.archetypes[..borrowed] {
+                    Q::release(&archetypes[*index]);
+                }
+                panic!("Conflicting Queries Detected");
+            }
+        }
+
+        for index in &entry.archetypes {
+            let archetype = &archetypes[*index];
+            // SAFETY: The archetype contains every component of the group and its columns were just borrowed
+            unsafe {
+                let mut state = Q::state(archetype, tick);
+                for _ in 0..archetype.count() {
+                    f(Q::fetch(&mut state));
+                }
+            }
+        }
+
+        for index in &entry.archetypes {
+            Q::release(&archetypes[*index]);
+        }
+    }
+
+    /// Number of entities in the group.
+    #[must_use]
+    pub fn len(&self, world: &World) -> usize {
+        let archetypes = world.archetypes();
+        world.groups.entries[self.index]
+            .archetypes
+            .iter()
+            .map(|index| archetypes[*index].count())
+            .sum()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self, world: &World) -> bool {
+        self.len(world) == 0
+    }
+}
+
+impl World {
+    /// Creates the group of the components in the bundle, e.g. `world.create_group::<(Position, Velocity)>()` for the hottest query,
+    /// or returns the existing one. The components are registered, the group stays valid after [`World::clear`] and [`World::gc_archetypes`].
+    pub fn create_group<B: Bundle + 'static>(&mut self) -> Group<B> {
+        if let Some(index) = self.groups.by_type.get(&TypeId::of::<B>()) {
+            return Group {
+                index: *index,
+                _marker: PhantomData,
+            };
+        }
+
+        let register: RegisterFn = |world| {
+            B::register(world);
+            B::bitmask(world)
+        };
+        let required = register(self);
+        let excluded = self.register_component::<Disabled>();
+        let mut archetypes: Vec<_> = self
+            .archetypes()
+            .iter()
+            .enumerate()
+            .filter(|(_, archetype)| archetype.bitmask().matches(&required, &excluded))
+            .map(|(index, _)| index)
+            .collect();
+        if self.deterministic {
+            archetypes.sort_unstable_by_key(|index| self.archetypes()[*index].bitmask());
+        }
+
+        let index = self.groups.entries.len();
+        self.groups.entries.push(GroupEntry {
+            register,
+            required,
+            excluded,
+            archetypes,
+        });
+        self.groups.by_type.insert(TypeId::of::<B>(), index);
+        Group {
+            index,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Registers the components of every group again after the components were cleared.
+    pub(crate) fn reregister_groups(&mut self) {
+        for index in 0..self.groups.entries.len() {
+            let required = (self.groups.entries[index].register)(self);
+            let excluded = self.register_component::<Disabled>();
+            let entry = &mut self.groups.entries[index];
+            entry.required = required;
+            entry.excluded = excluded;
+            entry.archetypes.clear();
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use std::ops::Deref;
+
+use crate::world::{Component, Entity, EntityMap, World};
+
+/// Parent of the entity, kept consistent with the [`Children`] of the parent by [`World::set_parent`] and [`World::remove_parent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Parent(Entity);
+
+impl Parent {
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> Entity {
+        self.0
+    }
+}
+
+impl Component for Parent {}
+
+/// Children of the entity in the order they were attached. The component only exists while the entity has at least one child.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Children(Vec<Entity>);
+
+impl Deref for Children {
+    type Target = [Entity];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Component for Children {}
+
+impl World {
+    /// Attaches the child to the parent, detaching it from its previous parent first.
+    /// Panics when either entity is dead or when the parent is the child itself or one of its descendants.
+    pub fn set_parent(&mut self, child: Entity, parent: Entity) {
+        assert!(
+            self.is_alive(child) && self.is_alive(parent),
+            "Both entities must be alive to set a parent"
+        );
+        assert!(
+            !self.ancestors(parent).any(|ancestor| ancestor == child) && child != parent,
+            "Setting {parent:?} as the parent of {child:?} would create a cycle"
+        );
+
+        if self.parent(child) == Some(parent) {
+            return;
+        }
+        self.remove_parent(child);
+
+        self.insert_component(child, Parent(parent));
+        if let Some(children) = self.get_component_mut::<Children>(parent) {
+            children.0.push(child);
+        } else {
+            self.insert_component(parent, Children(vec![child]));
+        }
+    }
+
+    /// Detaches the entity from its parent, it becomes a root.
+    pub fn remove_parent(&mut self, child: Entity) {
+        let Some(parent) = self.parent(child) else {
+            return;
+        };
+
+        self.remove_component::<Parent>(child);
+        self.remove_child_entry(parent, child);
+    }
+
+    #[must_use]
+    pub fn parent(&self, entity: Entity) -> Option<Entity> {
+        self.get_component::<Parent>(entity).map(Parent::get)
+    }
+
+    /// Returns the children of the entity, the slice is empty when it has none.
+    #[must_use]
+    pub fn children(&self, entity: Entity) -> &[Entity] {
+        self.get_component::<Children>(entity)
+            .map_or(&[], |children| &children.0)
+    }
+
+    /// Iterates over the parent of the entity, its parent and so on up to the root.
+    pub fn ancestors(&self, entity: Entity) -> impl Iterator<Item = Entity> + '_ {
+        std::iter::successors(self.parent(entity), |entity| self.parent(*entity))
+    }
+
+    /// Despawns the entity together with all of its descendants.
+    pub fn despawn_recursive(&mut self, entity: Entity) {
+        let mut pending = vec![entity];
+        while let Some(entity) = pending.pop() {
+            pending.extend_from_slice(self.children(entity));
+            self.despawn_entity(entity);
+        }
+    }
+
+    /// Keeps the hierarchy consistent when the entity is despawned, its children become roots.
+    pub(crate) fn unlink_hierarchy(&mut self, entity: Entity) {
+        // Most despawned entities are not part of a hierarchy, the bitmask check is cheaper than the lookups
+        if !self.has_component::<Parent>(entity) && !self.has_component::<Children>(entity) {
+            return;
+        }
+
+        if let Some(parent) = self.parent(entity) {
+            self.remove_child_entry(parent, entity);
+        }
+
+        if let Some(children) = self.get_component::<Children>(entity) {
+            for child in children.0.clone() {
+                self.remove_component::<Parent>(child);
+            }
+        }
+    }
+
+    /// Points the parent and the children of an entity moved from another world at the entities they were mapped to.
+    pub(crate) fn remap_hierarchy(&mut self, entity: Entity, map: &EntityMap) {
+        if let Some(parent) = self.get_component_mut::<Parent>(entity) {
+            parent.0 = map.get(parent.0).unwrap_or(parent.0);
+        }
+        if let Some(children) = self.get_component_mut::<Children>(entity) {
+            for child in &mut children.0 {
+                *child = map.get(*child).unwrap_or(*child);
+            }
+        }
+    }
+
+    fn remove_child_entry(&mut self, parent: Entity, child: Entity) {
+        let Some(children) = self.get_component_mut::<Children>(parent) else {
+            return;
+        };
+
+        children.0.retain(|entity| *entity != child);
+        if children.0.is_empty() {
+            self.remove_component::<Children>(parent);
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{
+    archetype::Archetype,
+    mask::ComponentMask,
+    world::{Component, ComponentId, Entity, World},
+};
+
+/// Function run by a component lifecycle hook, see [`World::on_add`], [`World::on_replace`] and [`World::on_remove`].
+pub type ComponentHook = fn(&mut World, Entity);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Hook {
+    Add,
+    Replace,
+    Remove,
+}
+
+impl Hook {
+    pub(crate) const ALL: [Hook; 3] = [Hook::Add, Hook::Replace, Hook::Remove];
+}
+
+/// Lifecycle hooks of a single component type, stored in its [`ComponentInfo`](crate::components::ComponentInfo).
+#[derive(Clone, Copy, Default)]
+pub(crate) struct ComponentHooks {
+    hooks: [Option<ComponentHook>; 3],
+}
+
+impl ComponentHooks {
+    #[inline]
+    #[must_use]
+    pub(crate) fn get(&self, hook: Hook) -> Option<ComponentHook> {
+        self.hooks[hook as usize]
+    }
+}
+
+/// Components which have a hook of each kind, so operations on other components skip the lookup.
+#[derive(Clone, Default)]
+pub(crate) struct HookMasks {
+    masks: [ComponentMask; 3],
+}
+
+impl HookMasks {
+    #[inline]
+    #[must_use]
+    pub(crate) fn get(&self, hook: Hook) -> &ComponentMask {
+        &self.masks[hook as usize]
+    }
+
+    #[inline]
+    pub(crate) fn mark(&mut self, hook: Hook, bit: &ComponentMask) {
+        self.masks[hook as usize] |= bit;
+    }
+}
+
+impl World {
+    /// Sets the hook run right after `T` is added to an entity which didn't have it, when spawning or inserting.
+    /// It replaces the previous hook of `T`. Entities moved in by [`World::append`] or [`World::restore`] don't run hooks.
+    pub fn on_add<T: Component>(&mut self, hook: ComponentHook) {
+        self.register_component::<T>();
+        self.set_hook(ComponentId::of::<T>(), Hook::Add, hook);
+    }
+
+    /// Sets the hook run right before the `T` value of an entity is overwritten by an insert, so the hook still sees the old value.
+    /// It replaces the previous hook of `T`. When the hook despawns the entity, the new value is dropped instead.
+    pub fn on_replace<T: Component>(&mut self, hook: ComponentHook) {
+        self.register_component::<T>();
+        self.set_hook(ComponentId::of::<T>(), Hook::Replace, hook);
+    }
+
+    /// Sets the hook run right before `T` is removed from an entity or the entity is despawned, so the hook still sees the value.
+    /// It replaces the previous hook of `T`. Despawns of [`World::clear`] and [`World::clear_entities`] run it too, [`World::restore`] doesn't.
+    pub fn on_remove<T: Component>(&mut self, hook: ComponentHook) {
+        self.register_component::<T>();
+        self.set_hook(ComponentId::of::<T>(), Hook::Remove, hook);
+    }
+
+    fn set_hook(&mut self, id: ComponentId, hook: Hook, function: ComponentHook) {
+        self.components.get_mut(id).unwrap().hooks.hooks[hook as usize] = Some(function);
+        self.mark_hooked(id, hook);
+    }
+
+    /// Makes operations on the registered component look for its hooks and observers of the kind.
+    pub(crate) fn mark_hooked(&mut self, id: ComponentId, hook: Hook) {
+        let bit = self.bit_of_id(&id).unwrap();
+        self.components.hooks.mark(hook, &bit);
+    }
+
+    /// Runs the hooks of the given kind of every component in the mask for the entity, then its observers,
+    /// stopping once a hook despawns it.
+    pub(crate) fn run_hooks(&mut self, entity: Entity, hook: Hook, mask: ComponentMask) {
+        let hooked = mask & self.components.hooks.get(hook);
+        if hooked.is_empty() {
+            return;
+        }
+
+        let functions: Vec<_> = hooked
+            .iter()
+            .filter_map(|bit| self.components.at(bit).hooks.get(hook))
+            .collect();
+        for function in functions {
+            if !self.is_alive(entity) {
+                return;
+            }
+            function(self, entity);
+        }
+
+        let ids: Vec<_> = hooked
+            .iter()
+            .map(|bit| self.components.at(bit).id())
+            .collect();
+        self.run_observers(entity, hook, &ids);
+    }
+
+    /// Runs the remove hooks of the components with the given ids which the entity has, before they are removed.
+    pub(crate) fn run_remove_hooks(&mut self, entity: Entity, ids: &[ComponentId]) {
+        let Some(bitmask) = self.archetype_of(entity).map(Archetype::bitmask) else {
+            return;
+        };
+        let removed = ids
+            .iter()
+            .filter_map(|id| self.bit_of_id(id))
+            .fold(ComponentMask::EMPTY, |mask, bit| mask | bit);
+        self.run_hooks(entity, Hook::Remove, removed & bitmask);
+    }
+
+    /// Runs the remove hooks of every component of the entity, before it is despawned.
+    /// A hook despawning the entity again despawns it right away without running the hooks twice.
+    pub(crate) fn run_despawn_hooks(&mut self, entity: Entity) {
+        if self.despawning.contains(&entity) {
+            return;
+        }
+        if let Some(mask) = self
+            .archetype_of(entity)
+            .map(|archetype| archetype.bitmask().clone())
+        {
+            self.despawning.push(entity);
+            self.run_hooks(entity, Hook::Remove, mask);
+            self.despawning.retain(|other| *other != entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+    use crate::world::{Component, Entity, World};
+
+    #[derive(Debug, PartialEq)]
+    struct Value(u32);
+    #[derive(Debug, PartialEq)]
+    struct Label(String);
+
+    impl Component for Value {}
+    impl Component for Label {}
+
+    #[test]
+    fn replace_hooks_see_the_old_value() {
+        static SEEN: AtomicU32 = AtomicU32::new(0);
+        static ADDED: AtomicUsize = AtomicUsize::new(0);
+
+        let mut world = World::new();
+        world.on_replace::<Value>(|world, entity| {
+            let old = world.get_component::<Value>(entity).unwrap().0;
+            SEEN.store(old, Ordering::Relaxed);
+        });
+        world.on_add::<Value>(|_, _| {
+            ADDED.fetch_add(1, Ordering::Relaxed);
+        });
+
+        let entity = world.spawn(Value(1));
+        world.insert_component(entity, Value(2));
+        assert_eq!(SEEN.load(Ordering::Relaxed), 1);
+        world.insert_bundle(entity, (Value(3), Label("bundle".into())));
+        assert_eq!(SEEN.load(Ordering::Relaxed), 2);
+        assert_eq!(world.get_component::<Value>(entity), Some(&Value(3)));
+        assert_eq!(ADDED.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn replace_hooks_despawning_the_entity_drop_the_new_value() {
+        static DESPAWNED: AtomicUsize = AtomicUsize::new(0);
+
+        fn despawn(world: &mut World, entity: Entity) {
+            DESPAWNED.fetch_add(1, Ordering::Relaxed);
+            world.despawn_entity(entity);
+        }
+
+        let mut world = World::new();
+        world.on_replace::<Label>(despawn);
+        let first = world.spawn(Label("first".into()));
+        world.insert_component(first, Label("dropped".into()));
+        assert!(!world.is_alive(first));
+
+        let second = world.spawn((Label("second".into()), Value(1)));
+        world.insert_bundle(second, (Label("dropped".into()), Value(2)));
+        assert!(!world.is_alive(second));
+        assert_eq!(DESPAWNED.load(Ordering::Relaxed), 2);
+        assert_eq!(world.query::<&Label>().iter(&world).count(), 0);
+    }
+
+    #[test]
+    fn replace_hooks_removing_the_component_turn_the_insert_into_an_add() {
+        static ADDED: AtomicUsize = AtomicUsize::new(0);
+
+        let mut world = World::new();
+        world.on_replace::<Value>(|world, entity| {
+            world.remove_component::<Value>(entity);
+        });
+        world.on_add::<Value>(|_, _| {
+            ADDED.fetch_add(1, Ordering::Relaxed);
+        });
+
+        let entity = world.spawn(Value(1));
+        world.insert_component(entity, Value(2));
+        assert_eq!(world.get_component::<Value>(entity), Some(&Value(2)));
+        assert_eq!(ADDED.load(Ordering::Relaxed), 2);
+    }
+}
@@ -1,15 +1,135 @@
+mod allocator;
+mod ambiguity;
+mod app;
+mod append;
 mod archetype;
+mod batch;
 mod blob_data;
 mod borrow;
+mod builder;
 mod bundle;
+mod change_detection;
+mod changes;
+mod checkpoint;
+mod chunks;
+mod clone;
+mod columnar;
+mod combinations;
+mod commands;
+mod compare;
+mod components;
+mod concurrent;
+#[cfg(feature = "consistency")]
+mod consistency;
+mod diff;
+mod disabled;
+mod dynamic;
+mod entity_ref;
+mod error;
+mod event;
+mod executor;
+mod extract;
+mod fixed;
+mod freeze;
+mod gather;
+mod group;
+mod hierarchy;
+mod hooks;
+mod mask;
+mod multi;
+mod name;
+mod nested;
+mod observer;
+mod one_shot;
+#[cfg(feature = "bytemuck")]
+mod pod;
+mod populate;
 mod query;
+mod query_state;
+mod quota;
+mod record;
+mod report;
+mod resource;
+mod sample;
+mod schedule;
+#[cfg(feature = "serde")]
+mod serde_entity;
+mod serialize;
+mod snapshot;
+mod spawn_at;
+mod state;
+mod stepping;
+mod system;
+mod task;
+mod time;
+mod timed;
+mod transient;
+mod variant;
+mod visit;
 mod world;
 
 pub mod prelude {
+    pub use crate::allocator::*;
+    pub use crate::ambiguity::*;
+    pub use crate::app::*;
     pub use crate::archetype::*;
+    pub use crate::batch::*;
     pub use crate::blob_data::*;
     pub use crate::borrow::*;
+    pub use crate::builder::*;
     pub use crate::bundle::*;
+    pub use crate::change_detection::*;
+    pub use crate::changes::*;
+    pub use crate::checkpoint::*;
+    pub use crate::chunks::*;
+    pub use crate::clone::*;
+    pub use crate::columnar::*;
+    pub use crate::combinations::*;
+    pub use crate::commands::*;
+    pub use crate::components::*;
+    pub use crate::concurrent::*;
+    #[cfg(feature = "consistency")]
+    pub use crate::consistency::*;
+    pub use crate::diff::*;
+    pub use crate::disabled::*;
+    pub use crate::dynamic::*;
+    pub use crate::entity_ref::*;
+    pub use crate::error::*;
+    pub use crate::event::*;
+    pub use crate::extract::*;
+    pub use crate::fixed::*;
+    pub use crate::freeze::*;
+    pub use crate::group::*;
+    pub use crate::hierarchy::*;
+    pub use crate::hooks::*;
+    pub use crate::mask::*;
+    pub use crate::multi::*;
+    pub use crate::name::*;
+    pub use crate::nested::*;
+    pub use crate::observer::*;
+    pub use crate::one_shot::*;
+    #[cfg(feature = "bytemuck")]
+    pub use crate::pod::*;
+    pub use crate::populate::*;
     pub use crate::query::*;
+    pub use crate::quota::*;
+    pub use crate::record::*;
+    pub use crate::report::*;
+    pub use crate::resource::*;
+    pub use crate::sample::*;
+    pub use crate::schedule::*;
+    #[cfg(feature = "serde")]
+    pub use crate::serde_entity::*;
+    pub use crate::serialize::*;
+    pub use crate::snapshot::*;
+    pub use crate::spawn_at::*;
+    pub use crate::state::*;
+    pub use crate::stepping::*;
+    pub use crate::system::*;
+    pub use crate::task::*;
+    pub use crate::time::*;
+    pub use crate::timed::*;
+    pub use crate::variant::*;
+    pub use crate::visit::*;
     pub use crate::world::*;
 }
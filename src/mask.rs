@@ -0,0 +1,270 @@
+use std::{
+    fmt,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign},
+};
+
+/// Number of words stored inline, masks of worlds with up to `64 * INLINE_WORDS` component types never allocate.
+const INLINE_WORDS: usize = 2;
+
+/// Set of component types, every registered component owns one bit of it. An archetype is identified by the mask of its components,
+/// and filters describe the archetypes they match with masks of required and excluded components, see [`ArchetypeFilter`](crate::query::ArchetypeFilter).
+///
+/// The mask grows with the number of registered components: the first bits are stored inline and the rest spill to the heap,
+/// so there is no limit on how many component types a world can register.
+#[derive(Clone, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+pub struct ComponentMask {
+    inline: [u64; INLINE_WORDS],
+    // Words past the inline ones, never ending with a zero word so equal masks have equal representations
+    spilled: Vec<u64>,
+}
+
+impl ComponentMask {
+    pub const EMPTY: Self = Self {
+        inline: [0; INLINE_WORDS],
+        spilled: Vec::new(),
+    };
+
+    /// Returns the mask containing only the component with the given bit index.
+    #[inline]
+    #[must_use]
+    pub fn bit(index: usize) -> Self {
+        let mut mask = Self::EMPTY;
+        *mask.word_mut(index / 64) = 1 << (index % 64);
+        mask
+    }
+
+    /// Number of words holding the bits of the mask, inline ones included.
+    #[inline]
+    fn word_count(&self) -> usize {
+        INLINE_WORDS + self.spilled.len()
+    }
+
+    /// Returns the word at the given index, zero past the stored ones.
+    #[inline]
+    fn word(&self, index: usize) -> u64 {
+        match index.checked_sub(INLINE_WORDS) {
+            None => self.inline[index],
+            Some(index) => self.spilled.get(index).copied().unwrap_or(0),
+        }
+    }
+
+    /// Returns the word at the given index, growing the mask to hold it.
+    #[inline]
+    fn word_mut(&mut self, index: usize) -> &mut u64 {
+        match index.checked_sub(INLINE_WORDS) {
+            None => &mut self.inline[index],
+            Some(index) => {
+                if self.spilled.len() <= index {
+                    self.spilled.resize(index + 1, 0);
+                }
+                &mut self.spilled[index]
+            }
+        }
+    }
+
+    /// Drops the trailing zero words left behind by an operation which cleared bits.
+    #[inline]
+    fn trim(&mut self) {
+        while self.spilled.last() == Some(&0) {
+            self.spilled.pop();
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        // Spilled words are trimmed, so any of them is non zero
+        self.spilled.is_empty() && self.inline.iter().all(|word| *word == 0)
+    }
+
+    /// Returns `true` when every component of `other` is in this mask.
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, other: &Self) -> bool {
+        other.spilled.len() <= self.spilled.len()
+            && (0..other.word_count()).all(|index| {
+                let word = other.word(index);
+                self.word(index) & word == word
+            })
+    }
+
+    /// Returns `true` when this mask and `other` have at least one component in common.
+    #[inline]
+    #[must_use]
+    pub fn intersects(&self, other: &Self) -> bool {
+        (0..self.word_count().min(other.word_count()))
+            .any(|index| self.word(index) & other.word(index) != 0)
+    }
+
+    /// Returns `true` when the mask has every required component and none of the excluded ones.
+    #[inline]
+    #[must_use]
+    pub fn matches(&self, required: &Self, excluded: &Self) -> bool {
+        self.contains(required) && !self.intersects(excluded)
+    }
+
+    /// Returns the components of this mask which are not in `other`.
+    #[inline]
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut mask = self.clone();
+        for index in 0..mask.word_count() {
+            *mask.word_mut(index) &= !other.word(index);
+        }
+        mask.trim();
+        mask
+    }
+
+    /// Returns `true` when the component with the given bit index is in the mask.
+    #[inline]
+    #[must_use]
+    pub fn has(&self, index: usize) -> bool {
+        self.word(index / 64) & (1 << (index % 64)) != 0
+    }
+
+    /// Number of components in the mask.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        (0..self.word_count())
+            .map(|index| self.word(index).count_ones() as usize)
+            .sum()
+    }
+
+    /// Iterates over the bit indices of the components in the mask, lowest first.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.word_count()).flat_map(move |index| {
+            let mut word = self.word(index);
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    index * 64 + bit
+                })
+            })
+        })
+    }
+}
+
+impl fmt::Debug for ComponentMask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl BitOr for ComponentMask {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(mut self, rhs: Self) -> Self {
+        self |= &rhs;
+        self
+    }
+}
+
+impl BitOr<&ComponentMask> for ComponentMask {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(mut self, rhs: &Self) -> Self {
+        self |= rhs;
+        self
+    }
+}
+
+impl BitOrAssign for ComponentMask {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self |= &rhs;
+    }
+}
+
+impl BitOrAssign<&ComponentMask> for ComponentMask {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: &Self) {
+        for index in 0..rhs.word_count() {
+            let word = rhs.word(index);
+            if word != 0 {
+                *self.word_mut(index) |= word;
+            }
+        }
+    }
+}
+
+impl BitAnd for ComponentMask {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(mut self, rhs: Self) -> Self {
+        self &= &rhs;
+        self
+    }
+}
+
+impl BitAnd<&ComponentMask> for ComponentMask {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(mut self, rhs: &Self) -> Self {
+        self &= rhs;
+        self
+    }
+}
+
+impl BitAndAssign for ComponentMask {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: Self) {
+        *self &= &rhs;
+    }
+}
+
+impl BitAndAssign<&ComponentMask> for ComponentMask {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: &Self) {
+        self.spilled.truncate(rhs.spilled.len());
+        for index in 0..self.word_count() {
+            *self.word_mut(index) &= rhs.word(index);
+        }
+        self.trim();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ComponentMask;
+
+    fn mask(bits: &[usize]) -> ComponentMask {
+        bits.iter().fold(ComponentMask::EMPTY, |mask, bit| {
+            mask | ComponentMask::bit(*bit)
+        })
+    }
+
+    #[test]
+    fn masks_grow_past_the_inline_words() {
+        let wide = mask(&[3, 130, 700]);
+        assert_eq!(wide.iter().collect::<Vec<_>>(), [3, 130, 700]);
+        assert_eq!(wide.len(), 3);
+        assert!(wide.has(700) && !wide.has(701) && !wide.has(10_000));
+        assert!(wide.contains(&mask(&[3, 700])));
+        assert!(!mask(&[3]).contains(&wide));
+        assert!(wide.intersects(&mask(&[700])));
+        assert!(!mask(&[3]).intersects(&mask(&[700])));
+    }
+
+    #[test]
+    fn cleared_bits_leave_equal_masks() {
+        let wide = mask(&[1, 500]);
+        assert_eq!(wide.difference(&mask(&[500])), mask(&[1]));
+        assert_eq!(wide.clone() & mask(&[1, 2]), mask(&[1]));
+        assert!(wide.difference(&wide).is_empty());
+        assert_eq!(wide.difference(&wide), ComponentMask::EMPTY);
+    }
+
+    #[test]
+    fn matches_checks_required_and_excluded() {
+        let archetype = mask(&[0, 200]);
+        assert!(archetype.matches(&mask(&[200]), &mask(&[300])));
+        assert!(!archetype.matches(&mask(&[300]), &ComponentMask::EMPTY));
+        assert!(!archetype.matches(&ComponentMask::EMPTY, &mask(&[0])));
+    }
+}
@@ -0,0 +1,93 @@
+use std::{
+    io::{self, Read, Write},
+    ops::{Deref, DerefMut},
+};
+
+use crate::{
+    serialize::{Decode, Encode},
+    world::{Component, Entity, World},
+};
+
+/// Several values of `T` attached to one entity, e.g. status effects or audio emitters.
+/// The component only exists while it holds at least one value, so `With<Multi<T>>` matches exactly the entities with some `T`.
+/// Query it with `&Multi<T>` to get the values as a slice, and change the set of values with [`World::add_multi`] and [`World::remove_multi`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Multi<T> {
+    values: Vec<T>,
+}
+
+impl<T: 'static> Component for Multi<T> {}
+
+impl<T> Deref for Multi<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.values
+    }
+}
+
+// Only the values can be changed in place, adding and removing them goes through the world so the component never stays empty
+impl<T> DerefMut for Multi<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.values
+    }
+}
+
+impl<T: Encode> Encode for Multi<T> {
+    fn encode(&self, writer: &mut dyn Write) -> io::Result<()> {
+        self.values.encode(writer)
+    }
+}
+
+impl<T: Decode> Decode for Multi<T> {
+    fn decode(reader: &mut dyn Read) -> io::Result<Self> {
+        Ok(Self {
+            values: Vec::decode(reader)?,
+        })
+    }
+}
+
+impl World {
+    /// Adds another value of `T` to the entity, inserting the [`Multi<T>`] component for the first one.
+    pub fn add_multi<T: 'static>(&mut self, entity: Entity, value: T) {
+        if let Some(multi) = self.get_component_mut::<Multi<T>>(entity) {
+            multi.values.push(value);
+            return;
+        }
+
+        self.insert_component(
+            entity,
+            Multi {
+                values: vec![value],
+            },
+        );
+    }
+
+    /// Removes every value of `T` matching the predicate and returns how many were removed.
+    /// The [`Multi<T>`] component is removed together with the last value.
+    pub fn remove_multi<T: 'static>(
+        &mut self,
+        entity: Entity,
+        mut predicate: impl FnMut(&T) -> bool,
+    ) -> usize {
+        let Some(multi) = self.get_component_mut::<Multi<T>>(entity) else {
+            return 0;
+        };
+
+        let len = multi.values.len();
+        multi.values.retain(|value| !predicate(value));
+        let removed = len - multi.values.len();
+
+        if multi.values.is_empty() {
+            self.remove_component::<Multi<T>>(entity);
+        }
+        removed
+    }
+
+    /// Returns the values of `T` attached to the entity, the slice is empty when it has none.
+    #[must_use]
+    pub fn get_multi<T: 'static>(&self, entity: Entity) -> &[T] {
+        self.get_component::<Multi<T>>(entity)
+            .map_or(&[], |multi| &multi.values)
+    }
+}
@@ -0,0 +1,134 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    io::{self, Read, Write},
+    ops::Deref,
+};
+
+use crate::{
+    mask::ComponentMask,
+    serialize::{Decode, Encode},
+    world::{Component, Entity, World},
+};
+
+/// Human-readable name of an entity, set with [`World::set_name`] or spawned with the entity, and looked up with [`World::named`].
+/// The world indexes every spawned and inserted name, including ones inserted by cloning, deserializing or replaying.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Name(String);
+
+impl Name {
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Component for Name {}
+
+impl Deref for Name {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Encode for Name {
+    fn encode(&self, writer: &mut dyn Write) -> io::Result<()> {
+        self.0.encode(writer)
+    }
+}
+
+impl Decode for Name {
+    fn decode(reader: &mut dyn Read) -> io::Result<Self> {
+        Ok(Self(String::decode(reader)?))
+    }
+}
+
+/// Entities by name, in the order they were named.
+#[derive(Default, Clone)]
+pub(crate) struct Names {
+    entities: HashMap<String, Vec<Entity>>,
+    /// Bit of [`Name`] once it is registered, so spawns can check whether they are named without a lookup.
+    pub(crate) bit: ComponentMask,
+}
+
+impl Names {
+    #[inline]
+    pub(crate) fn clear(&mut self) {
+        self.entities.clear();
+    }
+}
+
+impl World {
+    /// Names the entity, replacing its previous name. Does nothing when the entity is dead.
+    pub fn set_name(&mut self, entity: Entity, name: impl Into<String>) {
+        self.insert_component(entity, Name(name.into()));
+    }
+
+    /// Removes the name of the entity and returns it.
+    pub fn remove_name(&mut self, entity: Entity) -> Option<Name> {
+        self.remove_component::<Name>(entity)
+    }
+
+    /// Returns the entity with the given name. When several entities share it, the one named last is returned.
+    #[must_use]
+    pub fn named(&self, name: &str) -> Option<Entity> {
+        self.names.entities.get(name)?.last().copied()
+    }
+
+    #[must_use]
+    pub fn name_of(&self, entity: Entity) -> Option<&str> {
+        self.get_component::<Name>(entity).map(Name::as_str)
+    }
+
+    /// Indexes the name of a spawned entity if its archetype has one.
+    #[inline]
+    pub(crate) fn index_spawned_name(&mut self, entity: Entity, bitmask: &ComponentMask) {
+        if bitmask.intersects(&self.names.bit) {
+            self.index_name(entity);
+        }
+    }
+
+    /// Adds the current name of the entity to the index, called after a [`Name`] was inserted.
+    pub(crate) fn index_name(&mut self, entity: Entity) {
+        let Some(name) = self.get_component::<Name>(entity) else {
+            return;
+        };
+        let name = name.0.clone();
+        self.names.entities.entry(name).or_default().push(entity);
+    }
+
+    /// Removes the current name of the entity from the index, called before its [`Name`] is overwritten, removed or despawned.
+    /// It runs where replace and remove hooks do, but is not a hook itself, so users can still set their own hooks on [`Name`].
+    pub(crate) fn unindex_name(&mut self, entity: Entity) {
+        // Most entities have no name, the bitmask check is cheaper than the lookup
+        let named = self
+            .archetype_of(entity)
+            .is_some_and(|archetype| archetype.bitmask().intersects(&self.names.bit));
+        if !named {
+            return;
+        }
+
+        let name = self.get_component::<Name>(entity).unwrap().0.clone();
+        let Some(entities) = self.names.entities.get_mut(&name) else {
+            return;
+        };
+        entities.retain(|named| *named != entity);
+        if entities.is_empty() {
+            self.names.entities.remove(&name);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{
+    query::{Filter, QueryData, QueryItem, QueryIter, ReadOnlyQueryItem},
+    world::{Entity, World},
+};
+
+/// Read-only query available while another query is iterated, passed to the closure of [`World::query_nested`].
+/// Its columns stay borrowed for the whole outer iteration, so an outer query writing them panics instead of aliasing.
+pub struct NestedQuery<'w, Q: ReadOnlyQueryItem, F: Filter = ()> {
+    data: QueryData<Q, F>,
+    world: &'w World,
+}
+
+impl<'w, Q: ReadOnlyQueryItem, F: Filter> NestedQuery<'w, Q, F> {
+    fn new(mut data: QueryData<Q, F>, world: &'w World) -> Self {
+        assert!(!F::filters_rows(), "Nested queries cannot have row filters");
+        data.update_cache(world);
+        data.borrow(world.archetypes());
+        Self { data, world }
+    }
+
+    /// Iterates over every matching item, the columns are borrowed already.
+    pub fn iter(&mut self) -> QueryIter<'_, Q, F> {
+        self.data.iter_borrowed(self.world, false)
+    }
+
+    /// Fetches the item of a single entity, or `None` when the query doesn't match it.
+    pub fn get(&mut self, entity: Entity) -> Option<Q::Item<'_>> {
+        let (archetype, row) = self.data.locate(self.world, entity)?;
+        // SAFETY: The archetype matches the query and its columns are borrowed until the nested query is dropped
+        Some(unsafe { QueryData::<Q, F>::fetch_row(self.world, archetype, row) })
+    }
+}
+
+impl<Q: ReadOnlyQueryItem, F: Filter> Drop for NestedQuery<'_, Q, F> {
+    fn drop(&mut self) {
+        self.data.release(self.world.archetypes());
+    }
+}
+
+impl World {
+    /// Iterates over the outer query and calls the closure with every item and a read-only inner query over the same world,
+    /// e.g. `world.query_nested::<(&Seeker, &mut Target), &Position>(|(seeker, target), positions| ..)` for each seeker scanning all positions.
+    /// Panics when the outer query writes a component the inner query reads.
+    pub fn query_nested<O, I>(&self, mut f: impl FnMut(O::Item<'_>, &mut NestedQuery<'_, I>))
+    where
+        O: QueryItem + 'static,
+        I: ReadOnlyQueryItem + 'static,
+    {
+        // The inner columns are borrowed first, so they are released by the drop of the nested query when the outer borrow panics
+        let mut inner = NestedQuery::new(self.cached_query::<I, ()>(), self);
+        let mut outer = self.cached_query::<O, ()>();
+        for item in outer.iter(self) {
+            f(item, &mut inner);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+
+    use crate::world::{Component, Entity, World};
+
+    #[derive(Debug, PartialEq)]
+    struct Position(i32);
+    struct Seeker(i32);
+    struct Seen(usize);
+
+    impl Component for Position {}
+    impl Component for Seeker {}
+    impl Component for Seen {}
+
+    /// Spawns two seekers with different ranges and three targets, every entity has a position.
+    fn seekers() -> (World, Vec<Entity>) {
+        let mut world = World::new();
+        let seekers = vec![
+            world.spawn((Position(0), Seeker(1), Seen(0))),
+            world.spawn((Position(10), Seeker(5), Seen(0))),
+        ];
+        for position in [1, 8, 20] {
+            world.spawn(Position(position));
+        }
+        (world, seekers)
+    }
+
+    #[test]
+    fn every_outer_item_scans_the_inner_query() {
+        let (world, seekers) = seekers();
+
+        world.query_nested::<(&Position, &Seeker, &mut Seen), &Position>(
+            |(from, seeker, seen), positions| {
+                seen.0 = positions
+                    .iter()
+                    .filter(|to| to.0 != from.0 && (to.0 - from.0).abs() <= seeker.0)
+                    .count();
+            },
+        );
+        let seen: Vec<_> = seekers
+            .iter()
+            .map(|entity| world.get_component::<Seen>(*entity).unwrap().0)
+            .collect();
+        assert_eq!(seen, [1, 1]);
+    }
+
+    #[test]
+    fn inner_queries_fetch_single_entities() {
+        let (mut world, _) = seekers();
+        world.spawn(Position(3));
+
+        let mut found = Vec::new();
+        world.query_nested::<Entity, &Seeker>(|entity, seekers| {
+            found.push(seekers.get(entity).map(|seeker| seeker.0));
+        });
+        found.sort_unstable();
+        assert_eq!(found, [None, None, None, None, Some(1), Some(5)]);
+    }
+
+    #[test]
+    fn outer_writes_of_inner_columns_panic_and_release_them() {
+        let (world, _) = seekers();
+
+        let conflict = catch_unwind(AssertUnwindSafe(|| {
+            world.query_nested::<&mut Position, &Position>(|_, _| {});
+        }));
+        let message = conflict.unwrap_err();
+        assert_eq!(
+            message.downcast_ref::<&str>(),
+            Some(&"Conflicting Queries Detected")
+        );
+
+        // Both queries gave their borrows back while unwinding
+        world.query_nested::<&mut Position, &Seeker>(|position, _| position.0 += 1);
+        let mut positions = world.cached_query::<&Position, ()>();
+        let total: i32 = positions.iter(&world).map(|position| position.0).sum();
+        assert_eq!(total, 44);
+    }
+}
@@ -0,0 +1,265 @@
+use std::{collections::HashMap, mem};
+
+use crate::{
+    commands::CommandBuffer,
+    hooks::Hook,
+    one_shot::SystemId,
+    world::{Component, ComponentId, Entity, World},
+};
+
+type ObserverFn = Box<dyn FnMut(Entity, &World, &mut CommandBuffer) + Send>;
+
+/// Identifies an observer added with [`World::observe_add`] or [`World::observe_remove`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObserverId(u64);
+
+/// Conversion into an observer, implemented for closures taking the entity, the world and a command buffer,
+/// and for the [`SystemId`] of a registered system taking the entity as its input.
+pub trait IntoObserver<Marker> {
+    #[doc(hidden)]
+    fn into_observer(self) -> ObserverFn;
+}
+
+/// Marks the [`IntoObserver`] impl of closures.
+#[doc(hidden)]
+pub struct IsObserverFunction;
+
+impl<F: FnMut(Entity, &World, &mut CommandBuffer) + Send + 'static> IntoObserver<IsObserverFunction>
+    for F
+{
+    fn into_observer(self) -> ObserverFn {
+        Box::new(self)
+    }
+}
+
+impl<O: 'static> IntoObserver<()> for SystemId<Entity, O> {
+    fn into_observer(self) -> ObserverFn {
+        Box::new(move |entity, _world, commands| {
+            commands.run_system_with(self, entity);
+        })
+    }
+}
+
+struct Observer {
+    id: ObserverId,
+    function: ObserverFn,
+}
+
+/// Observers of every component and kind, stored as a resource.
+#[derive(Default)]
+struct Observers {
+    next: u64,
+    observers: HashMap<(ComponentId, usize), Vec<Observer>>,
+    /// Observers taken out while they run.
+    running: Vec<ObserverId>,
+    /// Running observers which were removed, dropped once they finish.
+    removed: Vec<ObserverId>,
+}
+
+impl World {
+    /// Calls the observer right after `T` is added to an entity which didn't have it, when spawning or inserting,
+    /// after the [hook](World::on_add) of `T`. Observers of the same component run in the order they were added.
+    ///
+    /// The observer sees the world and records changes into the command buffer, which is applied right after the observers ran.
+    /// Changes made by observers don't trigger the observers of the same component and kind again, e.g. an observer of `T` spawning
+    /// another entity with `T` doesn't run for it, while observers of other components and kinds do.
+    pub fn observe_add<T: Component, M>(&mut self, observer: impl IntoObserver<M>) -> ObserverId {
+        self.register_component::<T>();
+        self.add_observer(ComponentId::of::<T>(), Hook::Add, observer.into_observer())
+    }
+
+    /// Calls the observer right before `T` is removed from an entity or the entity is despawned, so it still sees the value,
+    /// like [`World::observe_add`].
+    pub fn observe_remove<T: Component, M>(
+        &mut self,
+        observer: impl IntoObserver<M>,
+    ) -> ObserverId {
+        self.register_component::<T>();
+        self.add_observer(
+            ComponentId::of::<T>(),
+            Hook::Remove,
+            observer.into_observer(),
+        )
+    }
+
+    /// Removes the observer, returns `false` when it was already removed.
+    pub fn remove_observer(&mut self, id: ObserverId) -> bool {
+        let Some(mut observers) = self.get_resource_mut::<Observers>() else {
+            return false;
+        };
+        if observers.running.contains(&id) {
+            if observers.removed.contains(&id) {
+                return false;
+            }
+            observers.removed.push(id);
+            return true;
+        }
+        observers.observers.values_mut().any(|list| {
+            let len = list.len();
+            list.retain(|observer| observer.id != id);
+            list.len() != len
+        })
+    }
+
+    fn add_observer(
+        &mut self,
+        component: ComponentId,
+        hook: Hook,
+        function: ObserverFn,
+    ) -> ObserverId {
+        if !self.contains_resource::<Observers>() {
+            self.insert_resource(Observers::default());
+        }
+        let mut observers = self.get_resource_mut::<Observers>().unwrap();
+        let id = ObserverId(observers.next);
+        observers.next += 1;
+        observers
+            .observers
+            .entry((component, hook as usize))
+            .or_default()
+            .push(Observer { id, function });
+        drop(observers);
+        self.mark_hooked(component, hook);
+        id
+    }
+
+    /// Runs the observers of the kind of the components for the entity, stopping once one despawns it.
+    pub(crate) fn run_observers(&mut self, entity: Entity, hook: Hook, components: &[ComponentId]) {
+        if hook == Hook::Replace || !self.contains_resource::<Observers>() {
+            return;
+        }
+        for component in components {
+            let key = (*component, hook as usize);
+            let mut taken = {
+                let mut observers = self.get_resource_mut::<Observers>().unwrap();
+                let Some(list) = observers.observers.get_mut(&key) else {
+                    continue;
+                };
+                let taken = mem::take(list);
+                observers
+                    .running
+                    .extend(taken.iter().map(|observer| observer.id));
+                taken
+            };
+
+            let mut commands = CommandBuffer::new();
+            for observer in &mut taken {
+                if !self.is_alive(entity) {
+                    break;
+                }
+                (observer.function)(entity, self, &mut commands);
+            }
+            // The list is still taken out, so the changes don't run these observers again
+            self.apply(&mut commands);
+
+            if let Some(mut observers) = self.get_resource_mut::<Observers>() {
+                let Observers {
+                    observers,
+                    running,
+                    removed,
+                    ..
+                } = &mut *observers;
+                running.retain(|id| !taken.iter().any(|observer| observer.id == *id));
+                taken.retain(|observer| !removed.contains(&observer.id));
+                removed.retain(|id| running.contains(id));
+                // Observers added while these ran come after them
+                let list = observers.entry(key).or_default();
+                let added = mem::replace(list, taken);
+                list.extend(added);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use crate::{
+        commands::CommandBuffer,
+        world::{Component, Entity, World},
+    };
+
+    struct Seed;
+    struct Sprout;
+
+    impl Component for Seed {}
+    impl Component for Sprout {}
+
+    fn counter() -> Arc<AtomicUsize> {
+        Arc::new(AtomicUsize::new(0))
+    }
+
+    #[test]
+    fn observers_do_not_observe_their_own_changes() {
+        let mut world = World::new();
+        let seeds = counter();
+        let observed = seeds.clone();
+        world.observe_add::<Seed, _>(move |_: Entity, _: &World, commands: &mut CommandBuffer| {
+            observed.fetch_add(1, Ordering::Relaxed);
+            commands.spawn(Seed);
+        });
+
+        world.spawn(Seed);
+        assert_eq!(seeds.load(Ordering::Relaxed), 1);
+        assert_eq!(world.query::<&Seed>().iter(&world).count(), 2);
+
+        // The observer is back in place for changes made outside of it
+        world.spawn(Seed);
+        assert_eq!(seeds.load(Ordering::Relaxed), 2);
+        assert_eq!(world.query::<&Seed>().iter(&world).count(), 4);
+    }
+
+    #[test]
+    fn changes_of_observers_trigger_other_observers() {
+        let mut world = World::new();
+        let sprouts = counter();
+        let observed = sprouts.clone();
+        world.observe_add::<Seed, _>(|entity: Entity, _: &World, commands: &mut CommandBuffer| {
+            commands.insert(entity, Sprout);
+        });
+        world.observe_add::<Sprout, _>(move |_: Entity, _: &World, _: &mut CommandBuffer| {
+            observed.fetch_add(1, Ordering::Relaxed);
+        });
+        let removed = counter();
+        let observed = removed.clone();
+        world.observe_remove::<Seed, _>(move |_: Entity, _: &World, _: &mut CommandBuffer| {
+            observed.fetch_add(1, Ordering::Relaxed);
+        });
+        world.observe_add::<Sprout, _>(
+            |entity: Entity, _: &World, commands: &mut CommandBuffer| {
+                commands.remove::<Seed>(entity);
+            },
+        );
+
+        let entity = world.spawn(Seed);
+        assert_eq!(sprouts.load(Ordering::Relaxed), 1);
+        assert_eq!(removed.load(Ordering::Relaxed), 1);
+        assert!(world.has_component::<Sprout>(entity));
+        assert!(!world.has_component::<Seed>(entity));
+    }
+
+    #[test]
+    fn observers_removed_while_running_are_dropped() {
+        let mut world = World::new();
+        let calls = counter();
+        let observed = calls.clone();
+        let id =
+            world.observe_add::<Seed, _>(move |_: Entity, _: &World, _: &mut CommandBuffer| {
+                observed.fetch_add(1, Ordering::Relaxed);
+            });
+        world.observe_add::<Seed, _>(move |_: Entity, _: &World, commands: &mut CommandBuffer| {
+            commands.push(move |world: &mut World| {
+                world.remove_observer(id);
+            });
+        });
+
+        world.spawn(Seed);
+        world.spawn(Seed);
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        assert!(!world.remove_observer(id));
+    }
+}
@@ -0,0 +1,272 @@
+use std::{
+    any::{Any, type_name},
+    collections::HashMap,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+use crate::{
+    commands::CommandBuffer,
+    system::{SystemAccess, SystemParam, SystemParamItem},
+    world::World,
+};
+
+/// Input of a one-shot system, taken as its first parameter and passed to [`World::run_system_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct In<T>(pub T);
+
+/// Identifies a system registered with [`World::register_system`] taking `I` and returning `O`.
+pub struct SystemId<I = (), O = ()> {
+    id: u64,
+    _marker: PhantomData<fn(I) -> O>,
+}
+
+impl<I, O> Clone for SystemId<I, O> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I, O> Copy for SystemId<I, O> {}
+
+impl<I, O> PartialEq for SystemId<I, O> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<I, O> Eq for SystemId<I, O> {}
+
+impl<I, O> Hash for SystemId<I, O> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<I, O> fmt::Debug for SystemId<I, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SystemId").field(&self.id).finish()
+    }
+}
+
+/// Returned by [`World::run_system`] when the system can't run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunSystemError {
+    /// The system was never registered or was unregistered.
+    NotRegistered,
+    /// The system is already running, e.g. it tried to run itself.
+    Running,
+}
+
+impl fmt::Display for RunSystemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunSystemError::NotRegistered => write!(f, "system is not registered"),
+            RunSystemError::Running => write!(f, "system is already running"),
+        }
+    }
+}
+
+impl std::error::Error for RunSystemError {}
+
+/// Functions which can be registered as one-shot systems, taking an optional [`In`] followed by up to 8 [`SystemParam`]s
+/// and returning any value. The marker is the signature of the function.
+pub trait OneShotFunction<Marker>: Send + 'static {
+    type Input: 'static;
+    type Output: 'static;
+    type Param: SystemParam;
+
+    fn run(
+        &mut self,
+        input: Self::Input,
+        param: SystemParamItem<'_, '_, Self::Param>,
+    ) -> Self::Output;
+}
+
+/// A registered function with the state of its parameters.
+trait OneShot<I, O>: Send {
+    fn run(&mut self, input: I, world: &mut World) -> O;
+}
+
+struct OneShotSystem<Marker, F: OneShotFunction<Marker>> {
+    func: F,
+    state: <F::Param as SystemParam>::State,
+    _marker: PhantomData<fn() -> Marker>,
+}
+
+impl<Marker: 'static, F: OneShotFunction<Marker>> OneShot<F::Input, F::Output>
+    for OneShotSystem<Marker, F>
+{
+    fn run(&mut self, input: F::Input, world: &mut World) -> F::Output {
+        let output = self
+            .func
+            .run(input, F::Param::fetch(&mut self.state, world));
+        F::Param::apply(&mut self.state, world);
+        output
+    }
+}
+
+/// The registered systems, `None` while a system is taken out to run.
+#[derive(Default)]
+struct SystemRegistry {
+    next: u64,
+    systems: HashMap<u64, Option<Box<dyn Any + Send>>>,
+}
+
+impl World {
+    /// Registers the function as a system which runs on demand with [`World::run_system`], outside of any schedule.
+    /// The state of its parameters, like [`Local`](crate::system::Local) values, is kept between the runs.
+    pub fn register_system<M: 'static, F: OneShotFunction<M>>(
+        &mut self,
+        func: F,
+    ) -> SystemId<F::Input, F::Output> {
+        let state = F::Param::init(self, &mut SystemAccess::default());
+        let system: Box<dyn OneShot<F::Input, F::Output>> = Box::new(OneShotSystem {
+            func,
+            state,
+            _marker: PhantomData,
+        });
+
+        if !self.contains_resource::<SystemRegistry>() {
+            self.insert_resource(SystemRegistry::default());
+        }
+        let mut registry = self.get_resource_mut::<SystemRegistry>().unwrap();
+        let id = registry.next;
+        registry.next += 1;
+        registry.systems.insert(id, Some(Box::new(system)));
+        SystemId {
+            id,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Removes the system, returns `false` when it wasn't registered. A running system is dropped once it finishes.
+    pub fn unregister_system<I, O>(&mut self, id: SystemId<I, O>) -> bool {
+        self.get_resource_mut::<SystemRegistry>()
+            .is_some_and(|mut registry| registry.systems.remove(&id.id).is_some())
+    }
+
+    /// Runs the system with exclusive access to the world and applies its deferred changes right away, returning its output.
+    pub fn run_system<O: 'static>(&mut self, id: SystemId<(), O>) -> Result<O, RunSystemError> {
+        self.run_system_with(id, ())
+    }
+
+    /// Runs the system like [`World::run_system`], passing the input as its [`In`] parameter.
+    pub fn run_system_with<I: 'static, O: 'static>(
+        &mut self,
+        id: SystemId<I, O>,
+        input: I,
+    ) -> Result<O, RunSystemError> {
+        let boxed = {
+            let mut registry = self
+                .get_resource_mut::<SystemRegistry>()
+                .ok_or(RunSystemError::NotRegistered)?;
+            let slot = registry
+                .systems
+                .get_mut(&id.id)
+                .ok_or(RunSystemError::NotRegistered)?;
+            slot.take().ok_or(RunSystemError::Running)?
+        };
+        let mut system = boxed
+            .downcast::<Box<dyn OneShot<I, O>>>()
+            .unwrap_or_else(|_| panic!("System {id:?} doesn't take {}", type_name::<I>()));
+
+        let output = system.run(input, self);
+
+        // The system may have been unregistered while it ran
+        if let Some(mut registry) = self.get_resource_mut::<SystemRegistry>()
+            && let Some(slot) = registry.systems.get_mut(&id.id)
+        {
+            *slot = Some(system);
+        }
+        Ok(output)
+    }
+}
+
+impl CommandBuffer {
+    /// Runs the registered system when the buffer is applied, like [`World::run_system`]. Errors are ignored.
+    pub fn run_system<O: 'static>(&mut self, id: SystemId<(), O>) -> &mut Self {
+        self.push(move |world| {
+            let _ = world.run_system(id);
+        })
+    }
+
+    /// Runs the registered system with the input when the buffer is applied, like [`World::run_system_with`]. Errors are ignored.
+    pub fn run_system_with<I: Send + 'static, O: 'static>(
+        &mut self,
+        id: SystemId<I, O>,
+        input: I,
+    ) -> &mut Self {
+        self.push(move |world| {
+            let _ = world.run_system_with(id, input);
+        })
+    }
+}
+
+macro_rules! impl_one_shot_function {
+    ($($name:ident),*) => {
+        impl<Func, Out, $($name: SystemParam),*> OneShotFunction<fn($($name,)*) -> Out> for Func
+        where
+            Func: Send + 'static,
+            Out: 'static,
+            for<'a> &'a mut Func: FnMut($($name),*) -> Out + FnMut($(SystemParamItem<'_, '_, $name>),*) -> Out,
+        {
+            type Input = ();
+            type Output = Out;
+            type Param = ($($name,)*);
+
+            #[inline]
+            fn run(&mut self, _input: (), param: SystemParamItem<'_, '_, Self::Param>) -> Out {
+                // Calling through a generic function lets the compiler pick the second `FnMut` bound
+                #[allow(clippy::too_many_arguments, non_snake_case)]
+                fn call<Out, $($name),*>(mut func: impl FnMut($($name),*) -> Out, $($name: $name),*) -> Out {
+                    func($($name),*)
+                }
+
+                #[allow(non_snake_case)]
+                let ($($name,)*) = param;
+                call(self, $($name),*)
+            }
+        }
+
+        impl<Func, Input, Out, $($name: SystemParam),*> OneShotFunction<fn(In<Input>, $($name,)*) -> Out> for Func
+        where
+            Func: Send + 'static,
+            Input: 'static,
+            Out: 'static,
+            for<'a> &'a mut Func: FnMut(In<Input>, $($name),*) -> Out
+                + FnMut(In<Input>, $(SystemParamItem<'_, '_, $name>),*) -> Out,
+        {
+            type Input = Input;
+            type Output = Out;
+            type Param = ($($name,)*);
+
+            #[inline]
+            fn run(&mut self, input: Input, param: SystemParamItem<'_, '_, Self::Param>) -> Out {
+                #[allow(clippy::too_many_arguments, non_snake_case)]
+                fn call<Input, Out, $($name),*>(
+                    mut func: impl FnMut(In<Input>, $($name),*) -> Out,
+                    input: In<Input>,
+                    $($name: $name),*
+                ) -> Out {
+                    func(input, $($name),*)
+                }
+
+                #[allow(non_snake_case)]
+                let ($($name,)*) = param;
+                call(self, In(input), $($name),*)
+            }
+        }
+    };
+}
+
+impl_one_shot_function!();
+impl_one_shot_function!(A);
+impl_one_shot_function!(A, B);
+impl_one_shot_function!(A, B, C);
+impl_one_shot_function!(A, B, C, D);
+impl_one_shot_function!(A, B, C, D, E);
+impl_one_shot_function!(A, B, C, D, E, F);
+impl_one_shot_function!(A, B, C, D, E, F, G);
+impl_one_shot_function!(A, B, C, D, E, F, G, H);
@@ -0,0 +1,203 @@
+use std::{ops::Deref, sync::Arc};
+
+use bytemuck::Pod;
+
+use crate::{
+    archetype::Archetype,
+    batch::{Borrows, Shared},
+    query::{Filter, QueryData},
+    world::{Component, ComponentId, World},
+};
+
+impl<T: Component + Pod, F: Filter> QueryData<&T, F> {
+    /// Returns the raw bytes of the `T` column of every matched archetype which is not empty, without copying them.
+    /// Each slice holds the components in the same order as [`QueryData::iter`] yields them, so it can be uploaded as an instance buffer directly.
+    /// Panics with row filters like [`Changed`](crate::query::Changed), which cannot be applied to whole columns.
+    pub fn as_byte_slices<'a>(&'a mut self, world: &'a World) -> ByteSlices<'a, T, F> {
+        assert!(
+            !F::filters_rows(),
+            "Cannot view whole columns through a query with row filters"
+        );
+        self.update_cache(world);
+        self.borrow(world.archetypes());
+
+        ByteSlices {
+            borrows: Arc::new(Borrows {
+                data: self,
+                archetypes: world.archetypes(),
+            }),
+            data: self,
+            archetypes: world.archetypes(),
+            cursor: 0,
+        }
+    }
+}
+
+/// Iterator over the column bytes of a query, the columns stay borrowed until this iterator and every slice it handed out are dropped.
+pub struct ByteSlices<'a, T: Component + Pod, F: Filter> {
+    borrows: Arc<Borrows<'a, &'a T, F>>,
+    data: &'a QueryData<&'a T, F>,
+    archetypes: &'a [Archetype],
+    cursor: usize,
+}
+
+/// Bytes of one column handed out by [`ByteSlices`], keeping the column borrowed until it is dropped.
+pub struct ColumnBytes<'a> {
+    bytes: &'a [u8],
+    _borrows: Arc<dyn Shared + 'a>,
+}
+
+// SAFETY: The bytes are plain data, and dropping the last slice on another thread only releases the atomic borrow flags of the columns
+unsafe impl Send for ColumnBytes<'_> {}
+
+impl Deref for ColumnBytes<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.bytes
+    }
+}
+
+impl<'a, T: Component + Pod, F: Filter> Iterator for ByteSlices<'a, T, F> {
+    type Item = ColumnBytes<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let matching = self.data.matching();
+
+        while let Some(index) = matching.get(self.cursor) {
+            self.cursor += 1;
+
+            let archetype = &self.archetypes[*index];
+            let count = archetype.count();
+            if count == 0 {
+                continue;
+            }
+
+            let column = archetype.column(&ComponentId::of::<T>()).unwrap();
+            // Pod types have no padding, so every byte of the column is initialized
+            let bytes = unsafe {
+                std::slice::from_raw_parts(
+                    column.as_ptr::<T>().cast::<u8>(),
+                    count * std::mem::size_of::<T>(),
+                ) // SAFETY: The column stores `count` values of T and stays borrowed while the slice is alive
+            };
+            return Some(ColumnBytes {
+                bytes,
+                _borrows: self.borrows.clone(),
+            });
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        panic::{AssertUnwindSafe, catch_unwind},
+        thread,
+    };
+
+    use bytemuck::{Pod, Zeroable};
+
+    use crate::{
+        query::{Changed, QueryData},
+        world::{Component, World},
+    };
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[repr(C)]
+    struct Instance {
+        offset: [f32; 2],
+        color: u32,
+    }
+    struct Hidden;
+
+    // SAFETY: The fields are plain data without padding
+    unsafe impl Zeroable for Instance {}
+    unsafe impl Pod for Instance {}
+    impl Component for Instance {}
+    impl Component for Hidden {}
+
+    fn instance(color: u32) -> Instance {
+        Instance {
+            offset: [color as f32, -(color as f32)],
+            color,
+        }
+    }
+
+    fn instances() -> World {
+        let mut world = World::new();
+        world.spawn(instance(0));
+        world.spawn((instance(1), Hidden));
+        world.spawn(instance(2));
+        let empty = world.spawn((instance(3), Hidden));
+        world.despawn_entity(empty);
+        world
+    }
+
+    #[test]
+    fn byte_slices_hold_every_non_empty_column_in_iteration_order() {
+        let world = instances();
+        let mut query = QueryData::<&Instance, ()>::new(&world);
+        let iterated: Vec<_> = query.iter(&world).copied().collect();
+
+        let slices: Vec<_> = query.as_byte_slices(&world).collect();
+        assert_eq!(slices.len(), 2);
+        let exported: Vec<Instance> = slices
+            .iter()
+            .flat_map(|bytes| bytemuck::cast_slice::<u8, Instance>(bytes).to_vec())
+            .collect();
+        assert_eq!(exported, iterated);
+        assert_eq!(slices[0].len(), 2 * std::mem::size_of::<Instance>());
+    }
+
+    #[test]
+    fn byte_slices_keep_the_columns_borrowed() {
+        let world = instances();
+        let mut query = QueryData::<&Instance, ()>::new(&world);
+        let mut writer = QueryData::<&mut Instance, ()>::new(&world);
+
+        let slices: Vec<_> = query.as_byte_slices(&world).collect();
+        // The iterator is gone, but the slices still point into the columns
+        let conflict = catch_unwind(AssertUnwindSafe(|| writer.iter(&world).count()));
+        assert!(conflict.is_err());
+        assert_eq!(slices.len(), 2);
+
+        drop(slices);
+        assert_eq!(writer.iter(&world).count(), 3);
+    }
+
+    #[test]
+    fn byte_slices_are_uploaded_from_other_threads() {
+        let world = instances();
+        let mut query = QueryData::<&Instance, ()>::new(&world);
+
+        let lengths: Vec<_> = thread::scope(|scope| {
+            let uploads: Vec<_> = query
+                .as_byte_slices(&world)
+                .map(|bytes| scope.spawn(move || bytes.len()))
+                .collect();
+            uploads
+                .into_iter()
+                .map(|upload| upload.join().unwrap())
+                .collect()
+        });
+        let size = std::mem::size_of::<Instance>();
+        assert_eq!(lengths, [2 * size, size]);
+        assert_eq!(
+            QueryData::<&mut Instance, ()>::new(&world)
+                .iter(&world)
+                .count(),
+            3
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot view whole columns through a query with row filters")]
+    fn byte_slices_reject_row_filters() {
+        let world = instances();
+        let mut query = QueryData::<&Instance, Changed<Instance>>::new(&world);
+        let _ = query.as_byte_slices(&world);
+    }
+}
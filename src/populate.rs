@@ -0,0 +1,325 @@
+use std::{
+    marker::PhantomData,
+    ops::Range,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{
+    bundle::Bundle,
+    hooks::Hook,
+    world::{Entity, EntityMeta, Location, World, WorldId},
+};
+
+/// Entity range and archetype capacity reserved by [`World::reserve_population`], filled by [`PopulationChunk`]s and stored by [`Population::commit`].
+/// Dropping it without committing spawns nothing and leaks the values written so far.
+pub struct Population<'w, B: Bundle> {
+    world: &'w mut World,
+    archetype: usize,
+    first_index: usize,
+    len: usize,
+    columns: Vec<*mut u8>,
+    filled: AtomicUsize,
+    chunked: bool,
+    _marker: PhantomData<fn(B)>,
+}
+
+/// Disjoint range of the rows of a [`Population`], which can be filled on another thread.
+pub struct PopulationChunk<'a, B: Bundle> {
+    columns: &'a [*mut u8],
+    range: Range<usize>,
+    first_index: usize,
+    world: WorldId,
+    filled: &'a AtomicUsize,
+    _marker: PhantomData<fn(B)>,
+}
+
+// SAFETY: Every chunk writes only its own rows, and the written values are moved to the thread committing the population
+unsafe impl<B: Bundle + Send> Send for PopulationChunk<'_, B> {}
+
+impl<B: Bundle> Population<'_, B> {
+    /// Number of reserved entities.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the entity which is spawned for the row at the given position, so components filled in can reference other reserved entities.
+    #[inline]
+    #[must_use]
+    pub fn entity(&self, position: usize) -> Entity {
+        assert!(position < self.len, "Position is out of the reserved range");
+        Entity::new(self.first_index + position, 0, self.world.id())
+    }
+
+    /// Splits the reserved rows into chunks of at most `size` rows, each of which can be moved to its own thread.
+    /// Panics when called more than once, because the rows can only be written once.
+    pub fn chunks(&mut self, size: usize) -> Vec<PopulationChunk<'_, B>> {
+        assert!(!self.chunked, "Population was split into chunks already");
+        assert!(size > 0, "Chunk size must be greater than zero");
+        self.chunked = true;
+
+        (0..self.len)
+            .step_by(size)
+            .map(|start| PopulationChunk {
+                columns: &self.columns,
+                range: start..(start + size).min(self.len),
+                first_index: self.first_index,
+                world: self.world.id(),
+                filled: &self.filled,
+                _marker: PhantomData,
+            })
+            .collect()
+    }
+
+    /// Stores the filled rows in the world, spawning every reserved entity.
+    /// Panics when some chunk was not filled, the values written so far are leaked.
+    pub fn commit(self) {
+        assert_eq!(
+            self.filled.load(Ordering::Acquire),
+            self.len,
+            "Every chunk must be filled before committing the population"
+        );
+
+        let world = self.world;
+        let id = world.id();
+        let tick = world.change_tick();
+        let archetype = &mut world.archetypes_mut()[self.archetype];
+        let first_row = archetype.count();
+
+        for id in B::component_ids() {
+            unsafe {
+                // SAFETY: Every reserved slot was written by exactly one chunk
+                archetype
+                    .column_mut(&id)
+                    .unwrap()
+                    .assume_pushed(self.len, tick);
+            }
+        }
+
+        let entities = self.first_index..self.first_index + self.len;
+        for index in entities.clone() {
+            archetype.insert_row(Entity::new(index, 0, id));
+        }
+
+        world
+            .entities
+            .metas
+            .extend((0..self.len).map(|position| EntityMeta {
+                generation: 0,
+                free: false,
+                location: Location {
+                    archetype: self.archetype,
+                    row: first_row + position,
+                },
+            }));
+
+        let bitmask = world.archetypes()[self.archetype].bitmask().clone();
+        for index in entities.clone() {
+            world.index_spawned_name(Entity::new(index, 0, id), &bitmask);
+        }
+
+        if world.is_recording() {
+            for index in entities.clone() {
+                world.log_spawn(Entity::new(index, 0, id));
+            }
+        }
+        for index in entities {
+            world.run_hooks(Entity::new(index, 0, id), Hook::Add, bitmask.clone());
+        }
+    }
+}
+
+impl<B: Bundle> PopulationChunk<'_, B> {
+    /// Positions of the rows owned by this chunk within the population.
+    #[inline]
+    #[must_use]
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Returns the entity which is spawned for the row at the given position of the population.
+    #[inline]
+    #[must_use]
+    pub fn entity(&self, position: usize) -> Entity {
+        Entity::new(self.first_index + position, 0, self.world)
+    }
+
+    /// Writes the bundle returned by the closure for every position of the chunk straight into the reserved columns.
+    pub fn fill(self, mut f: impl FnMut(usize) -> B) {
+        for position in self.range.clone() {
+            let bundle = f(position);
+            unsafe {
+                bundle.write(self.columns, position); // SAFETY: The position is reserved and belongs only to this chunk
+            }
+        }
+        self.filled.fetch_add(self.range.len(), Ordering::Release);
+    }
+}
+
+impl World {
+    /// Reserves `n` entities with fresh ids and room for them in the archetype of `B`, so the rows can be filled from several threads,
+    /// each owning a disjoint [`PopulationChunk`], before a single-threaded [`Population::commit`]. Meant for initializing millions of entities.
+    /// Panics when the entity or archetype quota would be exceeded.
+    pub fn reserve_population<B: Bundle>(&mut self, n: usize) -> Population<'_, B> {
+        B::register(self);
+        let archetype = self.archetype_index(&B::bitmask(self));
+        if let Err(error) = self.check_quota_for(Some(archetype), n) {
+            panic!("{error}");
+        }
+
+        let storage = &mut self.archetypes_mut()[archetype];
+        B::init_columns(storage);
+        let columns = if n == 0 {
+            Vec::new()
+        } else {
+            storage.reserve(n);
+            B::component_ids()
+                .iter()
+                .map(|id| unsafe {
+                    storage.column(id).unwrap().spare_ptr() // SAFETY: The column was just reserved
+                })
+                .collect()
+        };
+
+        Population {
+            first_index: self.entities.metas.len(),
+            world: self,
+            archetype,
+            len: n,
+            columns,
+            filled: AtomicUsize::new(0),
+            chunked: false,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        thread,
+    };
+
+    use crate::world::{Component, Entity, World};
+
+    #[derive(Debug, PartialEq)]
+    struct Value(usize);
+    #[derive(Debug, PartialEq)]
+    struct Link(Entity);
+    #[derive(Debug, PartialEq)]
+    struct Marker;
+
+    impl Component for Value {}
+    impl Component for Link {}
+    impl Component for Marker {}
+
+    #[test]
+    fn chunks_filled_on_threads_are_committed() {
+        let mut world = World::new();
+        let existing = world.spawn((Value(usize::MAX), Marker));
+
+        let mut population = world.reserve_population::<(Value, Marker)>(1000);
+        let first = population.entity(0);
+        let chunks = population.chunks(64);
+        assert_eq!(chunks.len(), 16);
+        thread::scope(|scope| {
+            for chunk in chunks {
+                scope.spawn(move || chunk.fill(|position| (Value(position), Marker)));
+            }
+        });
+        population.commit();
+
+        assert_eq!(
+            world.get_component::<Value>(existing),
+            Some(&Value(usize::MAX))
+        );
+        for position in 0..1000 {
+            let entity = Entity::new(first.index + position, 0, world.id());
+            assert!(world.is_alive(entity));
+            assert_eq!(world.get_component::<Value>(entity), Some(&Value(position)));
+            assert!(world.has_component::<Marker>(entity));
+        }
+        assert_eq!(world.query::<&Value>().iter(&world).count(), 1001);
+
+        // Later spawns don't reuse the committed slots
+        let next = world.spawn(Value(0));
+        assert_eq!(next.index, first.index + 1000);
+    }
+
+    #[test]
+    fn rows_can_reference_other_reserved_entities() {
+        let mut world = World::new();
+        let mut population = world.reserve_population::<Link>(3);
+        let entities: Vec<_> = (0..3).map(|position| population.entity(position)).collect();
+        for chunk in population.chunks(2) {
+            chunk.fill(|position| Link(entities[(position + 1) % 3]));
+        }
+        population.commit();
+
+        for (position, entity) in entities.iter().enumerate() {
+            assert_eq!(
+                world.get_component::<Link>(*entity),
+                Some(&Link(entities[(position + 1) % 3]))
+            );
+        }
+    }
+
+    #[test]
+    fn committing_runs_add_hooks() {
+        static ADDED: AtomicUsize = AtomicUsize::new(0);
+
+        let mut world = World::new();
+        world.on_add::<Value>(|_, _| {
+            ADDED.fetch_add(1, Ordering::Relaxed);
+        });
+        let mut population = world.reserve_population::<Value>(5);
+        for chunk in population.chunks(5) {
+            chunk.fill(Value);
+        }
+        population.commit();
+        assert_eq!(ADDED.load(Ordering::Relaxed), 5);
+    }
+
+    #[test]
+    fn empty_populations_commit_nothing() {
+        let mut world = World::new();
+        let population = world.reserve_population::<Value>(0);
+        assert!(population.is_empty());
+        population.commit();
+        assert_eq!(world.iter_entities().count(), 0);
+    }
+
+    #[test]
+    fn dropped_populations_spawn_nothing() {
+        let mut world = World::new();
+        let mut population = world.reserve_population::<Value>(4);
+        for chunk in population.chunks(4) {
+            chunk.fill(Value);
+        }
+        drop(population);
+
+        assert_eq!(world.iter_entities().count(), 0);
+        assert_eq!(world.query::<&Value>().iter(&world).count(), 0);
+        let entity = world.spawn(Value(7));
+        assert_eq!(world.get_component::<Value>(entity), Some(&Value(7)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Every chunk must be filled before committing the population")]
+    fn committing_unfilled_rows_panics() {
+        let mut world = World::new();
+        let mut population = world.reserve_population::<Value>(4);
+        let mut chunks = population.chunks(2);
+        chunks.pop().unwrap().fill(Value);
+        drop(chunks);
+        population.commit();
+    }
+}
@@ -1,27 +1,258 @@
 use crate::{
     archetype::Archetype,
-    world::{Component, Entity, World},
+    blob_data::BlobData,
+    disabled::Disabled,
+    mask::ComponentMask,
+    world::{Component, ComponentId, Entity, World},
+};
+use std::{
+    cell::Cell,
+    collections::{BTreeSet, HashMap},
+    iter::Take,
+    marker::PhantomData,
+    ops::Range,
+    sync::{
+        Arc, Mutex, PoisonError, RwLock, RwLockReadGuard,
+        atomic::{AtomicUsize, Ordering},
+    },
 };
-use std::{any::TypeId, marker::PhantomData};
 
 pub trait QueryItem: Filter {
     type Item<'a>;
-    type State;
+    /// Cloned to fetch several rows of the same archetype at once, see [`QueryData::iter_combinations`].
+    type State: Clone;
 
     fn borrow(archetype: &Archetype) -> bool;
     fn release(archetype: &Archetype);
 
-    unsafe fn state(archetype: &Archetype) -> Self::State;
+    /// Adds the components read and written by the item, e.g. to check that two queries can be used at the same time.
+    fn access(access: &mut Access);
+
+    /// Creates the fetch state for the first row of an archetype, mutable items mark fetched rows as changed at `tick`.
+    /// The tick is `None` when change detection is disabled, see [`WorldBuilder::change_detection`](crate::builder::WorldBuilder::change_detection).
+    ///
+    /// # Safety
+    /// Caller must ensure that the archetype contains every column accessed by this item and that it is borrowed.
+    unsafe fn state(archetype: &Archetype, tick: Option<u64>) -> Self::State;
+
+    /// # Safety
+    /// Caller must ensure that the state still points to a row within the archetype it was created for.
     unsafe fn fetch<'a>(state: &mut Self::State) -> Self::Item<'a>;
+
+    /// Advances the state by the given number of rows without fetching them.
+    ///
+    /// # Safety
+    /// Caller must ensure that the state stays within the archetype it was created for.
+    unsafe fn skip(state: &mut Self::State, rows: usize);
 }
 
+/// Query items which only read, so the same row can be fetched several times at once, e.g. by [`QueryData::iter_combinations`].
+///
+/// # Safety
+/// Fetched items must not give mutable access to the columns.
+pub unsafe trait ReadOnlyQueryItem: QueryItem {}
+
+unsafe impl<T: Component> ReadOnlyQueryItem for &T {}
+unsafe impl<T: Component> ReadOnlyQueryItem for Option<&T> {}
+unsafe impl ReadOnlyQueryItem for Entity {}
+
 pub trait Filter {
-    fn bitmask(world: &World) -> (u64, u64); // (required, excluded)
+    fn bitmask(world: &World) -> (ComponentMask, ComponentMask); // (required, excluded)
+
+    /// Whether entities with the [`Disabled`](crate::disabled::Disabled) marker are matched too, see [`IncludeDisabled`](crate::disabled::IncludeDisabled).
+    #[inline(always)]
+    fn includes_disabled() -> bool {
+        false
+    }
+
+    /// Whether the filter checks the ticks of every row on top of the archetype masks, like [`Added`] and [`Changed`].
+    #[inline(always)]
+    fn filters_rows() -> bool {
+        false
+    }
+
+    /// Appends the tick arrays of the archetype checked by the filter, a row matches when every checked tick is newer than the previous iteration.
+    #[inline(always)]
+    fn tick_checks(_archetype: &Archetype, _checks: &mut Vec<TickCheck>) {}
+
+    /// Appends groups of alternatives an archetype has to match on top of [`Filter::bitmask`], one of every group, like [`Or`] does.
+    #[inline(always)]
+    fn alternatives(_world: &World, _groups: &mut Vec<Vec<ArchetypeFilter>>) {}
+
+    /// Adds the components whose change ticks are read by the filter, which mutable items of other queries write, like [`Changed`] does.
+    #[inline(always)]
+    fn filter_access(_access: &mut Access) {}
+}
+
+/// Components read and written by a query, used to check that two queries can be used at the same time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Access {
+    reads: BTreeSet<ComponentId>,
+    writes: BTreeSet<ComponentId>,
+}
+
+impl Access {
+    /// Returns the access of the query item `Q`.
+    #[must_use]
+    pub fn of<Q: QueryItem>() -> Self {
+        let mut access = Self::default();
+        Q::access(&mut access);
+        access
+    }
+
+    pub fn add_read(&mut self, id: ComponentId) {
+        self.reads.insert(id);
+    }
+
+    pub fn add_write(&mut self, id: ComponentId) {
+        self.writes.insert(id);
+    }
+
+    /// Adds every component accessed by the other access.
+    pub fn extend(&mut self, other: &Access) {
+        self.reads.extend(other.reads.iter().copied());
+        self.writes.extend(other.writes.iter().copied());
+    }
+
+    /// Components which are only read.
+    pub fn reads(&self) -> impl Iterator<Item = ComponentId> + '_ {
+        self.reads.difference(&self.writes).copied()
+    }
+
+    pub fn writes(&self) -> impl Iterator<Item = ComponentId> + '_ {
+        self.writes.iter().copied()
+    }
+
+    fn accesses(&self, id: &ComponentId) -> bool {
+        self.reads.contains(id) || self.writes.contains(id)
+    }
+
+    /// Returns `true` when neither access writes a component the other one accesses.
+    #[must_use]
+    pub fn is_compatible(&self, other: &Access) -> bool {
+        !self.writes.iter().any(|id| other.accesses(id))
+            && !other.writes.iter().any(|id| self.accesses(id))
+    }
+
+    /// Components written by one access and accessed by the other, in order.
+    #[must_use]
+    pub fn conflicts(&self, other: &Access) -> BTreeSet<ComponentId> {
+        let ours = self.writes.iter().filter(|id| other.accesses(id));
+        let theirs = other.writes.iter().filter(|id| self.accesses(id));
+        ours.chain(theirs).copied().collect()
+    }
+
+    /// Returns `true` when the other access covers this one: it accesses every component accessed here, and writes the written ones.
+    #[must_use]
+    pub fn is_subset(&self, other: &Access) -> bool {
+        self.reads.iter().all(|id| other.accesses(id)) && self.writes.is_subset(&other.writes)
+    }
+}
+
+/// Archetypes matched by a filter: the ones with every required component, none of the excluded ones,
+/// and matching one alternative of every group.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ArchetypeFilter {
+    required: ComponentMask,
+    excluded: ComponentMask,
+    groups: Vec<Vec<ArchetypeFilter>>,
+}
+
+impl ArchetypeFilter {
+    /// Returns the predicate of the filter `F` in the world.
+    #[must_use]
+    pub fn of<F: Filter>(world: &World) -> Self {
+        let (required, excluded) = F::bitmask(world);
+        let mut groups = Vec::new();
+        F::alternatives(world, &mut groups);
+        Self {
+            required,
+            excluded,
+            groups,
+        }
+    }
+
+    /// Returns `true` when archetypes with the given components match.
+    #[must_use]
+    pub fn matches(&self, mask: &ComponentMask) -> bool {
+        mask.matches(&self.required, &self.excluded)
+            && self
+                .groups
+                .iter()
+                .all(|group| group.iter().any(|filter| filter.matches(mask)))
+    }
+
+    /// Components every matched archetype has.
+    #[inline]
+    #[must_use]
+    pub fn required(&self) -> &ComponentMask {
+        &self.required
+    }
+
+    /// Components no matched archetype has.
+    #[inline]
+    #[must_use]
+    pub fn excluded(&self) -> &ComponentMask {
+        &self.excluded
+    }
+
+    /// Returns alternatives matching exactly the archetypes this filter doesn't match, one of which has to match.
+    #[must_use]
+    pub fn negated(&self) -> Vec<ArchetypeFilter> {
+        // Not (a and b and (c or d)) is (not a) or (not b) or ((not c) and (not d))
+        let without = self.required.iter().map(|bit| ArchetypeFilter {
+            excluded: ComponentMask::bit(bit),
+            ..ArchetypeFilter::default()
+        });
+        let with = self.excluded.iter().map(|bit| ArchetypeFilter {
+            required: ComponentMask::bit(bit),
+            ..ArchetypeFilter::default()
+        });
+        let groups = self.groups.iter().map(|group| ArchetypeFilter {
+            groups: group.iter().map(ArchetypeFilter::negated).collect(),
+            ..ArchetypeFilter::default()
+        });
+        without.chain(with).chain(groups).collect()
+    }
+
+    /// Returns `true` when some archetype can only match by having one of the components, e.g. through one alternative of [`Or`].
+    fn may_require(&self, mask: &ComponentMask) -> bool {
+        self.required.intersects(mask)
+            || self
+                .groups
+                .iter()
+                .flatten()
+                .any(|filter| filter.may_require(mask))
+    }
+}
+
+/// Tick array of a column checked row by row by [`Added`] and [`Changed`].
+#[derive(Clone, Copy)]
+pub struct TickCheck(*const u64);
+
+impl TickCheck {
+    /// # Safety
+    /// Caller must ensure that the row is within the archetype the check was created for.
+    #[inline(always)]
+    unsafe fn passes(self, row: usize, since: u64) -> bool {
+        unsafe { *self.0.add(row) > since }
+    }
+}
+
+/// Returns `true` when the row passes every check.
+///
+/// # Safety
+/// Caller must ensure that the row is within the archetype the checks were created for.
+#[inline(always)]
+unsafe fn passes_checks(checks: &[TickCheck], row: usize, since: u64) -> bool {
+    checks
+        .iter()
+        .all(|check| unsafe { check.passes(row, since) })
 }
 
 impl Filter for () {
-    fn bitmask(_world: &World) -> (u64, u64) {
-        (0, 0)
+    fn bitmask(_world: &World) -> (ComponentMask, ComponentMask) {
+        (ComponentMask::EMPTY, ComponentMask::EMPTY)
     }
 }
 
@@ -29,19 +260,23 @@ impl<T: Component> QueryItem for &T {
     type Item<'a> = &'a T;
     type State = *const T;
 
+    fn access(access: &mut Access) {
+        access.add_read(ComponentId::of::<T>());
+    }
+
     #[inline(always)]
     fn borrow(archetype: &Archetype) -> bool {
-        archetype.column(&TypeId::of::<T>()).unwrap().borrow()
+        archetype.column(&ComponentId::of::<T>()).unwrap().borrow()
     }
 
     #[inline(always)]
     fn release(archetype: &Archetype) {
-        archetype.column(&TypeId::of::<T>()).unwrap().release();
+        archetype.column(&ComponentId::of::<T>()).unwrap().release();
     }
 
     #[inline(always)]
-    unsafe fn state(archetype: &Archetype) -> Self::State {
-        unsafe { archetype.column(&TypeId::of::<T>()).unwrap().as_ptr() }
+    unsafe fn state(archetype: &Archetype, _tick: Option<u64>) -> Self::State {
+        unsafe { archetype.column(&ComponentId::of::<T>()).unwrap().as_ptr() }
     }
 
     #[inline(always)]
@@ -52,48 +287,182 @@ impl<T: Component> QueryItem for &T {
             &*current
         }
     }
+
+    #[inline(always)]
+    unsafe fn skip(state: &mut Self::State, rows: usize) {
+        unsafe {
+            *state = state.add(rows);
+        }
+    }
 }
 
 impl<T: Component> Filter for &T {
     #[inline(always)]
-    fn bitmask(world: &World) -> (u64, u64) {
-        (world.bit_of::<T>().unwrap(), 0)
+    fn bitmask(world: &World) -> (ComponentMask, ComponentMask) {
+        (world.bit_of::<T>().unwrap(), ComponentMask::EMPTY)
     }
 }
 
 impl<T: Component> QueryItem for &mut T {
     type Item<'a> = &'a mut T;
-    type State = *mut T;
+    type State = (*mut T, *const Cell<u64>, Option<u64>);
+
+    fn access(access: &mut Access) {
+        access.add_write(ComponentId::of::<T>());
+    }
 
     #[inline(always)]
     fn borrow(archetype: &Archetype) -> bool {
-        archetype.column(&TypeId::of::<T>()).unwrap().borrow_mut()
+        archetype
+            .column(&ComponentId::of::<T>())
+            .unwrap()
+            .borrow_mut()
     }
 
     #[inline(always)]
     fn release(archetype: &Archetype) {
-        archetype.column(&TypeId::of::<T>()).unwrap().release_mut();
+        archetype
+            .column(&ComponentId::of::<T>())
+            .unwrap()
+            .release_mut();
     }
 
     #[inline(always)]
-    unsafe fn state(archetype: &Archetype) -> Self::State {
-        unsafe { archetype.column(&TypeId::of::<T>()).unwrap().as_mut_ptr() }
+    unsafe fn state(archetype: &Archetype, tick: Option<u64>) -> Self::State {
+        let column = archetype.column(&ComponentId::of::<T>()).unwrap();
+        unsafe { (column.as_mut_ptr(), column.ticks_ptr(), tick) }
     }
 
     #[inline(always)]
     unsafe fn fetch<'a>(state: &mut Self::State) -> Self::Item<'a> {
+        let (data, ticks, tick) = state;
         unsafe {
-            let current = *state;
-            *state = state.add(1);
+            let current = *data;
+            if let Some(tick) = *tick {
+                (**ticks).set(tick);
+            }
+            *data = data.add(1);
+            *ticks = ticks.add(1);
             &mut *current
         }
     }
+
+    #[inline(always)]
+    unsafe fn skip(state: &mut Self::State, rows: usize) {
+        let (data, ticks, _) = state;
+        unsafe {
+            *data = data.add(rows);
+            *ticks = ticks.add(rows);
+        }
+    }
 }
 
 impl<T: Component> Filter for &mut T {
     #[inline(always)]
-    fn bitmask(world: &World) -> (u64, u64) {
-        (world.bit_of::<T>().unwrap(), 0)
+    fn bitmask(world: &World) -> (ComponentMask, ComponentMask) {
+        (world.bit_of::<T>().unwrap(), ComponentMask::EMPTY)
+    }
+}
+
+/// Matches every entity, yielding `None` for the ones without `T`.
+impl<T: Component> QueryItem for Option<&T> {
+    type Item<'a> = Option<&'a T>;
+    type State = Option<*const T>;
+
+    fn access(access: &mut Access) {
+        <&T>::access(access);
+    }
+
+    #[inline(always)]
+    fn borrow(archetype: &Archetype) -> bool {
+        archetype
+            .column(&ComponentId::of::<T>())
+            .is_none_or(BlobData::borrow)
+    }
+
+    #[inline(always)]
+    fn release(archetype: &Archetype) {
+        if let Some(column) = archetype.column(&ComponentId::of::<T>()) {
+            column.release();
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn state(archetype: &Archetype, tick: Option<u64>) -> Self::State {
+        archetype
+            .column(&ComponentId::of::<T>())
+            .map(|_| unsafe { <&T>::state(archetype, tick) })
+    }
+
+    #[inline(always)]
+    unsafe fn fetch<'a>(state: &mut Self::State) -> Self::Item<'a> {
+        state.as_mut().map(|state| unsafe { <&T>::fetch(state) })
+    }
+
+    #[inline(always)]
+    unsafe fn skip(state: &mut Self::State, rows: usize) {
+        if let Some(state) = state {
+            unsafe { <&T>::skip(state, rows) }
+        }
+    }
+}
+
+impl<T: Component> Filter for Option<&T> {
+    #[inline(always)]
+    fn bitmask(_world: &World) -> (ComponentMask, ComponentMask) {
+        (ComponentMask::EMPTY, ComponentMask::EMPTY)
+    }
+}
+
+/// Matches every entity, yielding `None` for the ones without `T`.
+impl<T: Component> QueryItem for Option<&mut T> {
+    type Item<'a> = Option<&'a mut T>;
+    type State = Option<<&'static mut T as QueryItem>::State>;
+
+    fn access(access: &mut Access) {
+        <&mut T>::access(access);
+    }
+
+    #[inline(always)]
+    fn borrow(archetype: &Archetype) -> bool {
+        archetype
+            .column(&ComponentId::of::<T>())
+            .is_none_or(BlobData::borrow_mut)
+    }
+
+    #[inline(always)]
+    fn release(archetype: &Archetype) {
+        if let Some(column) = archetype.column(&ComponentId::of::<T>()) {
+            column.release_mut();
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn state(archetype: &Archetype, tick: Option<u64>) -> Self::State {
+        archetype
+            .column(&ComponentId::of::<T>())
+            .map(|_| unsafe { <&mut T>::state(archetype, tick) })
+    }
+
+    #[inline(always)]
+    unsafe fn fetch<'a>(state: &mut Self::State) -> Self::Item<'a> {
+        state
+            .as_mut()
+            .map(|state| unsafe { <&mut T>::fetch(state) })
+    }
+
+    #[inline(always)]
+    unsafe fn skip(state: &mut Self::State, rows: usize) {
+        if let Some(state) = state {
+            unsafe { <&mut T>::skip(state, rows) }
+        }
+    }
+}
+
+impl<T: Component> Filter for Option<&mut T> {
+    #[inline(always)]
+    fn bitmask(_world: &World) -> (ComponentMask, ComponentMask) {
+        (ComponentMask::EMPTY, ComponentMask::EMPTY)
     }
 }
 
@@ -106,8 +475,10 @@ impl QueryItem for Entity {
     }
     fn release(_archetype: &Archetype) {}
 
+    fn access(_access: &mut Access) {}
+
     #[inline(always)]
-    unsafe fn state(archetype: &Archetype) -> Self::State {
+    unsafe fn state(archetype: &Archetype, _tick: Option<u64>) -> Self::State {
         archetype.entities().as_ptr()
     }
 
@@ -119,12 +490,19 @@ impl QueryItem for Entity {
             current
         }
     }
+
+    #[inline(always)]
+    unsafe fn skip(state: &mut Self::State, rows: usize) {
+        unsafe {
+            *state = state.add(rows);
+        }
+    }
 }
 
 impl Filter for Entity {
     #[inline(always)]
-    fn bitmask(_world: &World) -> (u64, u64) {
-        (0, 0)
+    fn bitmask(_world: &World) -> (ComponentMask, ComponentMask) {
+        (ComponentMask::EMPTY, ComponentMask::EMPTY)
     }
 }
 
@@ -133,15 +511,165 @@ pub struct Without<T>(PhantomData<T>);
 
 impl<T: Component> Filter for With<T> {
     #[inline(always)]
-    fn bitmask(world: &World) -> (u64, u64) {
-        (world.bit_of::<T>().unwrap_or(0), 0)
+    fn bitmask(world: &World) -> (ComponentMask, ComponentMask) {
+        (
+            world.bit_of::<T>().unwrap_or_default(),
+            ComponentMask::EMPTY,
+        )
+    }
+
+    #[inline(always)]
+    fn alternatives(world: &World, groups: &mut Vec<Vec<ArchetypeFilter>>) {
+        // No archetype has a component which is not registered, an empty group matches none
+        if world.bit_of::<T>().is_none() {
+            groups.push(Vec::new());
+        }
     }
 }
 
 impl<T: Component> Filter for Without<T> {
     #[inline(always)]
-    fn bitmask(world: &World) -> (u64, u64) {
-        (0, world.bit_of::<T>().unwrap_or(0))
+    fn bitmask(world: &World) -> (ComponentMask, ComponentMask) {
+        (
+            ComponentMask::EMPTY,
+            world.bit_of::<T>().unwrap_or_default(),
+        )
+    }
+}
+
+/// Matches entities whose `T` component was added since the previous iteration of the query, e.g. to initialize new entities once.
+/// The first iteration matches every entity with `T`.
+pub struct Added<T>(PhantomData<T>);
+
+/// Matches entities whose `T` component was added or mutably accessed since the previous iteration of the query.
+/// The first iteration matches every entity with `T`.
+pub struct Changed<T>(PhantomData<T>);
+
+impl<T: Component> Filter for Added<T> {
+    #[inline(always)]
+    fn bitmask(world: &World) -> (ComponentMask, ComponentMask) {
+        With::<T>::bitmask(world)
+    }
+
+    #[inline(always)]
+    fn alternatives(world: &World, groups: &mut Vec<Vec<ArchetypeFilter>>) {
+        With::<T>::alternatives(world, groups);
+    }
+
+    #[inline(always)]
+    fn filters_rows() -> bool {
+        true
+    }
+
+    #[inline(always)]
+    fn tick_checks(archetype: &Archetype, checks: &mut Vec<TickCheck>) {
+        let column = archetype.column(&ComponentId::of::<T>()).unwrap();
+        checks.push(TickCheck(column.added_ptr()));
+    }
+}
+
+impl<T: Component> Filter for Changed<T> {
+    #[inline(always)]
+    fn bitmask(world: &World) -> (ComponentMask, ComponentMask) {
+        With::<T>::bitmask(world)
+    }
+
+    #[inline(always)]
+    fn alternatives(world: &World, groups: &mut Vec<Vec<ArchetypeFilter>>) {
+        With::<T>::alternatives(world, groups);
+    }
+
+    #[inline(always)]
+    fn filters_rows() -> bool {
+        true
+    }
+
+    #[inline(always)]
+    fn tick_checks(archetype: &Archetype, checks: &mut Vec<TickCheck>) {
+        let column = archetype.column(&ComponentId::of::<T>()).unwrap();
+        // Cell<u64> has the same layout as u64
+        checks.push(TickCheck(column.ticks_ptr().cast()));
+    }
+
+    #[inline(always)]
+    fn filter_access(access: &mut Access) {
+        access.add_read(ComponentId::of::<T>());
+    }
+}
+
+/// Indices of the archetypes matching one archetype filter, shared by every query reducing to it.
+pub(crate) struct MatchList {
+    epoch: u64,
+    filter: ArchetypeFilter,
+    archetypes: RwLock<Vec<usize>>,
+    high_water_mark: AtomicUsize,
+}
+
+impl MatchList {
+    /// Adds the matching archetypes created since the last update, sorted by bitmask when `ordered` and by creation otherwise.
+    fn update(&self, archetypes: &[Archetype], ordered: bool) {
+        if self.high_water_mark.load(Ordering::Acquire) == archetypes.len() {
+            return;
+        }
+
+        // Another thread may have caught the list up while this one waited for the lock
+        let mut matching = self
+            .archetypes
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        for (index, archetype) in archetypes
+            .iter()
+            .enumerate()
+            .skip(self.high_water_mark.load(Ordering::Acquire))
+        {
+            if !self.filter.matches(archetype.bitmask()) {
+                continue;
+            }
+            let position = if ordered {
+                matching.partition_point(|other| archetypes[*other].bitmask() < archetype.bitmask())
+            } else {
+                matching.len()
+            };
+            matching.insert(position, index);
+        }
+
+        self.high_water_mark
+            .store(archetypes.len(), Ordering::Release);
+    }
+}
+
+/// Match lists of the world by archetype filter.
+#[derive(Default)]
+pub(crate) struct MatchLists {
+    lists: Mutex<HashMap<ArchetypeFilter, Arc<MatchList>>>,
+    /// Bumped whenever archetypes are dropped, so lists created before are no longer used.
+    epoch: u64,
+}
+
+impl MatchLists {
+    /// Forgets every list, queries holding one of them fetch a new list on their next update.
+    pub(crate) fn invalidate(&mut self) {
+        self.lists
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+        self.epoch += 1;
+    }
+
+    fn get(&self, filter: ArchetypeFilter) -> Arc<MatchList> {
+        self.lists
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(filter.clone())
+            .or_insert_with(|| {
+                Arc::new(MatchList {
+                    epoch: self.epoch,
+                    filter,
+                    archetypes: RwLock::new(Vec::new()),
+                    high_water_mark: AtomicUsize::new(0),
+                })
+            })
+            .clone()
     }
 }
 
@@ -150,47 +678,84 @@ where
     Q: QueryItem,
     F: Filter,
 {
-    matching: Vec<usize>,
-    high_water_mark: usize,
-    _marker: PhantomData<(Q, F)>,
+    list: Arc<MatchList>,
+    /// Number of archetypes when the filter was last computed, the shared list can be ahead of it.
+    seen: usize,
+    /// Change tick of the previous iteration, [`Added`] and [`Changed`] match rows with newer ticks.
+    last_run: u64,
+    /// Keeps the archetype filter of the query it was created from, see [`QueryData::transmute_lens`].
+    lens: bool,
+    _marker: PhantomData<fn() -> (Q, F)>,
 }
 
 impl<Q: QueryItem, F: Filter> QueryData<Q, F> {
     pub fn new(world: &World) -> Self {
-        let mut q = Self {
-            matching: Vec::new(),
-            high_water_mark: 0,
+        let q = Self {
+            list: world.match_lists.get(Self::archetype_filter(world)),
+            seen: world.archetypes().len(),
+            last_run: 0,
+            lens: false,
             _marker: PhantomData,
         };
-        q.update_cache(world);
+        q.list.update(world.archetypes(), world.deterministic);
         q
     }
 
-    pub fn update_cache(&mut self, world: &World) {
-        let archetypes = world.archetypes();
-
-        if self.high_water_mark == archetypes.len() {
-            return;
+    /// Returns a query sharing the match list of this one, which starts without a previous iteration.
+    pub(crate) fn fork(&self) -> Self {
+        Self {
+            list: self.list.clone(),
+            seen: self.seen,
+            last_run: 0,
+            lens: self.lens,
+            _marker: PhantomData,
         }
+    }
 
-        let (required_q, excluded_q) = Q::bitmask(world);
-        let (required_f, excluded_f) = F::bitmask(world);
+    /// Returns the archetypes matched by the items and the filter of the query.
+    pub(crate) fn archetype_filter(world: &World) -> ArchetypeFilter {
+        let mut filter = ArchetypeFilter::of::<(Q, F)>(world);
 
-        let required = required_q | required_f;
-        let excluded = excluded_q | excluded_f;
+        // Disabled entities are skipped unless the query opts in or asks for the marker itself, also within an alternative
+        let disabled = world.bit_of::<Disabled>().unwrap_or_default();
+        if !Q::includes_disabled() && !F::includes_disabled() && !filter.may_require(&disabled) {
+            filter.excluded |= disabled;
+        }
+        filter
+    }
 
-        for (index, archetype) in archetypes.iter().enumerate().skip(self.high_water_mark) {
-            let mask = archetype.bitmask();
-            if (mask & required) == required && (mask & excluded) == 0 {
-                self.matching.push(index);
-            }
+    /// Indices of the archetypes matched by the query, valid after [`QueryData::update_cache`].
+    #[inline]
+    pub(crate) fn matching(&self) -> RwLockReadGuard<'_, Vec<usize>> {
+        self.list
+            .archetypes
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Catches up with the archetypes created since the last update. Queries with the same archetype filter share the work.
+    pub fn update_cache(&mut self, world: &World) {
+        let archetypes = world.archetypes();
+        let stale = self.list.epoch != world.match_lists.epoch;
+        if !stale && self.seen == archetypes.len() {
+            return;
         }
 
-        self.high_water_mark = archetypes.len();
+        // Components registered since the last update can change the filter, even when another query already caught the list up
+        let filter = if self.lens {
+            self.list.filter.clone()
+        } else {
+            Self::archetype_filter(world)
+        };
+        if stale || filter != self.list.filter {
+            self.list = world.match_lists.get(filter);
+        }
+        self.list.update(archetypes, world.deterministic);
+        self.seen = archetypes.len();
     }
 
-    fn borrow(&self, archetypes: &[Archetype]) {
-        for matching in self.matching.iter() {
+    pub(crate) fn borrow(&self, archetypes: &[Archetype]) {
+        for matching in self.matching().iter() {
             let archetype = &archetypes[*matching];
             if !Q::borrow(archetype) {
                 panic!("Conflicting Queries Detected");
@@ -198,8 +763,8 @@ impl<Q: QueryItem, F: Filter> QueryData<Q, F> {
         }
     }
 
-    fn release(&self, archetypes: &[Archetype]) {
-        for matching in self.matching.iter() {
+    pub(crate) fn release(&self, archetypes: &[Archetype]) {
+        for matching in self.matching().iter() {
             let archetype = &archetypes[*matching];
             Q::release(archetype);
         }
@@ -208,25 +773,189 @@ impl<Q: QueryItem, F: Filter> QueryData<Q, F> {
     pub fn iter<'a>(&'a mut self, world: &'a World) -> QueryIter<'a, Q, F> {
         self.update_cache(world);
         self.borrow(world.archetypes());
+        self.iter_borrowed(world, true)
+    }
+
+    /// Iterates like [`QueryData::iter`] without acquiring and releasing the borrow flags of the columns,
+    /// which saves the atomic operations of every matching archetype in hot loops.
+    ///
+    /// # Safety
+    /// Caller must ensure that no other query, iterator or reference accesses the columns written by this query,
+    /// and that no one writes the columns it reads, until the iterator and its items are dropped.
+    pub unsafe fn iter_unchecked<'a>(&'a mut self, world: &'a World) -> QueryIter<'a, Q, F> {
+        self.update_cache(world);
+        self.iter_borrowed(world, false)
+    }
+
+    /// Creates an iterator over the cached archetypes, `borrowed` tells whether their columns were borrowed for it and are released on drop.
+    pub(crate) fn iter_borrowed<'a>(
+        &'a mut self,
+        world: &'a World,
+        borrowed: bool,
+    ) -> QueryIter<'a, Q, F> {
+        let (tick, since) = self.advance(world);
 
         QueryIter {
             data: self,
             archetypes: world.archetypes(),
-            matching: &self.matching,
+            matching: self.matching(),
+            borrowed,
             state: None,
+            tick,
+            since,
+            checks: Vec::new(),
             cursor: 0,
             row: 0,
             current_len: 0,
             _marker: PhantomData,
         }
     }
+
+    /// Returns the tick marking rows fetched mutably, `None` when change detection is disabled, and the tick of the previous iteration.
+    /// Queries with row filters advance the change tick, so changes made after the iteration are newer than its tick.
+    pub(crate) fn advance(&mut self, world: &World) -> (Option<u64>, u64) {
+        let since = self.last_run;
+        if F::filters_rows() {
+            assert!(
+                world.change_detection(),
+                "Cannot filter by change ticks when change detection is disabled"
+            );
+            self.last_run = world.increment_change_tick();
+            return (Some(self.last_run), since);
+        }
+        (world.change_detection().then(|| world.change_tick()), since)
+    }
+
+    /// Returns the tick of the previous iteration, [`Added`] and [`Changed`] match rows with newer ticks.
+    #[inline]
+    #[must_use]
+    pub fn last_run(&self) -> u64 {
+        self.last_run
+    }
+
+    /// Reuses the archetypes matched by this query for a narrower view `L`, e.g. `&A` for a `QueryData<(&mut A, &B)>`,
+    /// without matching them again. The lens keeps matching the archetypes of this query and its change tick.
+    /// Panics when `L` accesses a component this query doesn't, writes one this query only reads, or requires one this query doesn't require.
+    #[must_use]
+    pub fn transmute_lens<L: QueryItem>(&mut self, world: &World) -> QueryData<L, F> {
+        assert!(
+            Access::of::<L>().is_subset(&Access::of::<Q>()),
+            "Lens accesses components the query doesn't"
+        );
+        self.update_cache(world);
+        let (required, _) = L::bitmask(world);
+        assert!(
+            self.list.filter.required().contains(&required),
+            "Lens requires components the query doesn't"
+        );
+
+        QueryData {
+            list: self.list.clone(),
+            seen: self.seen,
+            last_run: self.last_run,
+            lens: true,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<Q: QueryItem, F: Filter> QueryData<Q, F> {
+    /// Returns every matching entity in iteration order, copied straight from the archetype rows without fetching any item.
+    #[must_use]
+    pub fn collect_entities(&mut self, world: &World) -> Vec<Entity> {
+        let mut entities = Vec::new();
+        self.collect_into(world, &mut entities);
+        entities
+    }
+
+    /// Appends every matching entity to the vector, like [`QueryData::collect_entities`] but reusing its allocation.
+    pub fn collect_into(&mut self, world: &World, entities: &mut Vec<Entity>) {
+        self.update_cache(world);
+        let (_, since) = self.advance(world);
+        let archetypes = world.archetypes();
+        let matching = self.matching();
+
+        if F::filters_rows() {
+            let mut checks = Vec::new();
+            for index in matching.iter() {
+                let archetype = &archetypes[*index];
+                checks.clear();
+                F::tick_checks(archetype, &mut checks);
+                entities.extend(archetype.entities().iter().enumerate().filter_map(
+                    |(row, entity)| {
+                        // SAFETY: The row is within the archetype the checks were created for
+                        unsafe { passes_checks(&checks, row, since) }.then_some(*entity)
+                    },
+                ));
+            }
+            return;
+        }
+
+        entities.reserve(
+            matching
+                .iter()
+                .map(|index| archetypes[*index].count())
+                .sum(),
+        );
+        for index in matching.iter() {
+            entities.extend_from_slice(archetypes[*index].entities());
+        }
+    }
+}
+
+impl<'a, Q: QueryItem, F: Filter> QueryData<Q, F> {
+    /// Iterates over the matching items within the range of positions, in the same order as [`QueryData::iter`].
+    /// The archetype counts are used to jump directly to the first row, so skipped items are never fetched.
+    /// With row filters like [`Changed`] the positions count matching rows only, so the skipped rows are checked one by one.
+    pub fn iter_range(
+        &'a mut self,
+        world: &'a World,
+        range: Range<usize>,
+    ) -> Take<QueryIter<'a, Q, F>> {
+        let len = range.end.saturating_sub(range.start);
+        let mut iter = self.iter(world);
+
+        if F::filters_rows() {
+            if range.start > 0 {
+                iter.nth(range.start - 1);
+            }
+            return iter.take(len);
+        }
+
+        let mut offset = range.start;
+        while iter.cursor < iter.matching.len() {
+            let archetype = &iter.archetypes[iter.matching[iter.cursor]];
+            let count = archetype.count();
+            if offset < count {
+                unsafe {
+                    let mut state = Q::state(archetype, iter.tick);
+                    Q::skip(&mut state, offset);
+                    iter.state = Some(state);
+                }
+                iter.current_len = count;
+                iter.row = offset;
+                break;
+            }
+
+            offset -= count;
+            iter.cursor += 1;
+        }
+
+        iter.take(len)
+    }
 }
 
 pub struct QueryIter<'a, Q: QueryItem, F: Filter> {
     data: &'a QueryData<Q, F>,
     archetypes: &'a [Archetype],
-    matching: &'a [usize],
+    matching: RwLockReadGuard<'a, Vec<usize>>,
+    /// Whether the columns are released on drop, `false` for [`QueryData::iter_unchecked`].
+    borrowed: bool,
     state: Option<Q::State>,
+    tick: Option<u64>,
+    since: u64,
+    /// Tick checks of the current archetype, only used by row filters.
+    checks: Vec<TickCheck>,
     cursor: usize,
     row: usize,
     current_len: usize,
@@ -240,9 +969,14 @@ impl<'a, Q: QueryItem, F: Filter> Iterator for QueryIter<'a, Q, F> {
     fn next(&mut self) -> Option<Self::Item> {
         loop {
             if self.row < self.current_len {
+                let row = self.row;
                 self.row += 1;
                 unsafe {
                     let state = self.state.as_mut().unwrap_unchecked();
+                    if F::filters_rows() && !passes_checks(&self.checks, row, self.since) {
+                        Q::skip(state, 1);
+                        continue;
+                    }
                     return Some(Q::fetch(state));
                 }
             }
@@ -261,8 +995,12 @@ impl<'a, Q: QueryItem, F: Filter> Iterator for QueryIter<'a, Q, F> {
             let len = archetype.count();
 
             if len > 0 {
+                if F::filters_rows() {
+                    self.checks.clear();
+                    F::tick_checks(archetype, &mut self.checks);
+                }
                 unsafe {
-                    self.state = Some(Q::state(archetype));
+                    self.state = Some(Q::state(archetype, self.tick));
                     self.current_len = len;
                     self.row = 0;
                 }
@@ -273,20 +1011,42 @@ impl<'a, Q: QueryItem, F: Filter> Iterator for QueryIter<'a, Q, F> {
     }
 
     #[inline(always)]
-    fn for_each<Func>(self, mut f: Func)
+    fn for_each<Func>(mut self, mut f: Func)
     where
         Func: FnMut(Self::Item),
     {
-        for matching in self.matching {
+        // Finish the archetype which is partially iterated already
+        if let Some(state) = self.state.as_mut() {
+            for row in self.row..self.current_len {
+                unsafe {
+                    if F::filters_rows() && !passes_checks(&self.checks, row, self.since) {
+                        Q::skip(state, 1);
+                        continue;
+                    }
+                    f(Q::fetch(state));
+                }
+            }
+            self.cursor += 1;
+        }
+
+        for matching in &self.matching[self.cursor.min(self.matching.len())..] {
             let archetype = &self.archetypes[*matching];
             let count = archetype.count();
             if count == 0 {
                 continue;
             }
 
+            if F::filters_rows() {
+                self.checks.clear();
+                F::tick_checks(archetype, &mut self.checks);
+            }
             unsafe {
-                let mut state = Q::state(archetype);
-                for _ in 0..count {
+                let mut state = Q::state(archetype, self.tick);
+                for row in 0..count {
+                    if F::filters_rows() && !passes_checks(&self.checks, row, self.since) {
+                        Q::skip(&mut state, 1);
+                        continue;
+                    }
                     f(Q::fetch(&mut state));
                 }
             }
@@ -295,6 +1055,182 @@ impl<'a, Q: QueryItem, F: Filter> Iterator for QueryIter<'a, Q, F> {
 }
 
 impl<Q: QueryItem, F: Filter> Drop for QueryIter<'_, Q, F> {
+    fn drop(&mut self) {
+        if self.borrowed {
+            self.data.release(self.archetypes);
+        }
+    }
+}
+
+impl<Q: QueryItem, F: Filter> QueryData<Q, F> {
+    /// Fetches the item of a single entity, e.g. the target of another entity, or `None` when the query doesn't match it.
+    /// The columns of its archetype stay borrowed until the returned guard is dropped, like during iteration.
+    /// Row filters like [`Changed`] compare against the previous iteration without advancing it.
+    pub fn get<'a>(&mut self, world: &'a World, entity: Entity) -> Option<QueryGuard<'a, Q>> {
+        let (archetype, row) = self.locate(world, entity)?;
+        if !Q::borrow(archetype) {
+            panic!("Conflicting Queries Detected");
+        }
+
+        Some(QueryGuard {
+            world,
+            archetype,
+            row,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Fetches the item of a single entity like [`QueryData::get`], the exclusive borrow of the world makes the column borrows unnecessary.
+    pub fn get_mut<'a>(&mut self, world: &'a mut World, entity: Entity) -> Option<Q::Item<'a>> {
+        let world = &*world;
+        let (archetype, row) = self.locate(world, entity)?;
+        // SAFETY: The archetype matches the query and can't be borrowed while the world is borrowed exclusively
+        Some(unsafe { Self::fetch_row(world, archetype, row) })
+    }
+
+    /// Returns the archetype and row of the entity when the query matches it.
+    pub(crate) fn locate<'a>(
+        &mut self,
+        world: &'a World,
+        entity: Entity,
+    ) -> Option<(&'a Archetype, usize)> {
+        if !world.is_alive(entity) {
+            return None;
+        }
+        self.update_cache(world);
+
+        let location = world.entities.metas[entity.index].location;
+        let archetype = world.archetypes().get(location.archetype)?;
+        if !self.list.filter.matches(archetype.bitmask()) {
+            return None;
+        }
+
+        if F::filters_rows() {
+            let mut checks = Vec::new();
+            F::tick_checks(archetype, &mut checks);
+            // SAFETY: The row is within the archetype the checks were created for
+            if !unsafe { passes_checks(&checks, location.row, self.last_run) } {
+                return None;
+            }
+        }
+        Some((archetype, location.row))
+    }
+
+    /// # Safety
+    /// Caller must ensure that the archetype matches the query, that its columns are borrowed and that the row is within it.
+    pub(crate) unsafe fn fetch_row<'a>(
+        world: &World,
+        archetype: &Archetype,
+        row: usize,
+    ) -> Q::Item<'a> {
+        let tick = world.change_detection().then(|| world.change_tick());
+        unsafe {
+            let mut state = Q::state(archetype, tick);
+            Q::skip(&mut state, row);
+            Q::fetch(&mut state)
+        }
+    }
+}
+
+/// Item of a single entity returned by [`QueryData::get`], keeps the columns of its archetype borrowed until it is dropped.
+/// The item is fetched from the guard and borrows it, so no reference into the columns outlives their borrows.
+pub struct QueryGuard<'a, Q: QueryItem> {
+    world: &'a World,
+    archetype: &'a Archetype,
+    row: usize,
+    _marker: PhantomData<fn() -> Q>,
+}
+
+impl<Q: QueryItem> QueryGuard<'_, Q> {
+    /// Returns the item of a read-only query, several of them may be alive at once.
+    #[inline]
+    pub fn get(&self) -> Q::Item<'_>
+    where
+        Q: ReadOnlyQueryItem,
+    {
+        // SAFETY: The columns stay borrowed while the guard lives, and read-only items never alias a mutable one
+        unsafe { QueryData::<Q>::fetch_row(self.world, self.archetype, self.row) }
+    }
+
+    /// Returns the item, the exclusive borrow of the guard keeps mutable references in it unique.
+    #[inline]
+    pub fn get_mut(&mut self) -> Q::Item<'_> {
+        // SAFETY: The columns stay borrowed while the guard lives, and the item can't outlive the borrow of the guard
+        unsafe { QueryData::<Q>::fetch_row(self.world, self.archetype, self.row) }
+    }
+}
+
+impl<Q: QueryItem> Drop for QueryGuard<'_, Q> {
+    fn drop(&mut self) {
+        Q::release(self.archetype);
+    }
+}
+
+impl<'a, Q: QueryItem, F: Filter> QueryData<Q, F> {
+    /// Iterates over the matching items ordered by entity index and generation, regardless of the archetype layout,
+    /// e.g. for deterministic replays or network messages. The rows are sorted up front, so it costs `O(n log n)` before the first item.
+    pub fn iter_sorted_by_entity(&'a mut self, world: &'a World) -> SortedIter<'a, Q, F> {
+        self.update_cache(world);
+        self.borrow(world.archetypes());
+        let (tick, since) = self.advance(world);
+
+        let archetypes = world.archetypes();
+        let mut rows = Vec::new();
+        let mut checks = Vec::new();
+        for index in self.matching().iter() {
+            let archetype = &archetypes[*index];
+            checks.clear();
+            F::tick_checks(archetype, &mut checks);
+            rows.extend(
+                archetype
+                    .entities()
+                    .iter()
+                    .enumerate()
+                    // SAFETY: The row is within the archetype the checks were created for
+                    .filter(|(row, _)| unsafe { passes_checks(&checks, *row, since) })
+                    .map(|(row, entity)| (*entity, *index, row)),
+            );
+        }
+        rows.sort_unstable_by_key(|(entity, _, _)| (entity.index, entity.generation));
+
+        SortedIter {
+            data: self,
+            archetypes,
+            rows: rows.into_iter(),
+            tick,
+        }
+    }
+}
+
+/// Iterator of [`QueryData::iter_sorted_by_entity`].
+pub struct SortedIter<'a, Q: QueryItem, F: Filter> {
+    data: &'a QueryData<Q, F>,
+    archetypes: &'a [Archetype],
+    rows: std::vec::IntoIter<(Entity, usize, usize)>,
+    tick: Option<u64>,
+}
+
+impl<'a, Q: QueryItem, F: Filter> Iterator for SortedIter<'a, Q, F> {
+    type Item = Q::Item<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, archetype, row) = self.rows.next()?;
+        unsafe {
+            // SAFETY: The archetype matches the query and is borrowed, every row is fetched once because entities are unique
+            let mut state = Q::state(&self.archetypes[archetype], self.tick);
+            Q::skip(&mut state, row);
+            Some(Q::fetch(&mut state))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rows.size_hint()
+    }
+}
+
+impl<Q: QueryItem, F: Filter> ExactSizeIterator for SortedIter<'_, Q, F> {}
+
+impl<Q: QueryItem, F: Filter> Drop for SortedIter<'_, Q, F> {
     fn drop(&mut self) {
         self.data.release(self.archetypes);
     }
@@ -314,9 +1250,13 @@ macro_rules! impl_query_tuple {
                 $($name::release(archetype));*
             }
 
+            fn access(access: &mut Access) {
+                $($name::access(access));*
+            }
+
             #[inline(always)]
-            unsafe fn state(archetype: &Archetype) -> Self::State {
-                unsafe { ($($name::state(archetype),)*) }
+            unsafe fn state(archetype: &Archetype, tick: Option<u64>) -> Self::State {
+                unsafe { ($($name::state(archetype, tick),)*) }
             }
 
             #[inline(always)]
@@ -325,13 +1265,22 @@ macro_rules! impl_query_tuple {
                 let ($($name,)*) = ptr;
                 unsafe { ($($name::fetch($name),)*) }
             }
+
+            #[inline(always)]
+            unsafe fn skip(ptr: &mut Self::State, rows: usize) {
+                #[allow(non_snake_case)]
+                let ($($name,)*) = ptr;
+                unsafe { $($name::skip($name, rows));* }
+            }
         }
 
+        unsafe impl<$($name: ReadOnlyQueryItem),*> ReadOnlyQueryItem for ($($name,)*) {}
+
         impl<$($name: Filter),*> Filter for ($($name,)*) {
             #[inline(always)]
-            fn bitmask(world: &World) -> (u64, u64) {
-                let mut required = 0;
-                let mut excluded = 0;
+            fn bitmask(world: &World) -> (ComponentMask, ComponentMask) {
+                let mut required = ComponentMask::EMPTY;
+                let mut excluded = ComponentMask::EMPTY;
                 $(
                     let (r, e) = $name::bitmask(world);
                     required |= r;
@@ -339,6 +1288,31 @@ macro_rules! impl_query_tuple {
                 )*
                 (required, excluded)
             }
+
+            #[inline(always)]
+            fn includes_disabled() -> bool {
+                $($name::includes_disabled())||*
+            }
+
+            #[inline(always)]
+            fn filters_rows() -> bool {
+                $($name::filters_rows())||*
+            }
+
+            #[inline(always)]
+            fn tick_checks(archetype: &Archetype, checks: &mut Vec<TickCheck>) {
+                $($name::tick_checks(archetype, checks));*
+            }
+
+            #[inline(always)]
+            fn alternatives(world: &World, groups: &mut Vec<Vec<ArchetypeFilter>>) {
+                $($name::alternatives(world, groups));*
+            }
+
+            #[inline(always)]
+            fn filter_access(access: &mut Access) {
+                $($name::filter_access(access));*
+            }
         }
     };
 }
@@ -358,3 +1332,346 @@ impl_query_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M);
 impl_query_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N);
 impl_query_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O);
 impl_query_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P);
+
+/// Matches entities matching any of the filters in the tuple, e.g. `Or<(With<Player>, With<Enemy>)>`.
+/// The alternatives can be any filters, like conjunctions `Or<(With<A>, (With<B>, Without<C>))>`, another `Or` or [`Not`].
+/// Row filters like [`Changed`] can't be combined this way.
+pub struct Or<T>(PhantomData<T>);
+
+macro_rules! impl_or_tuple {
+    ($($name:ident),*) => {
+        impl<$($name: Filter),*> Filter for Or<($($name,)*)> {
+            #[inline(always)]
+            fn bitmask(_world: &World) -> (ComponentMask, ComponentMask) {
+                (ComponentMask::EMPTY, ComponentMask::EMPTY)
+            }
+
+            #[inline(always)]
+            fn includes_disabled() -> bool {
+                $($name::includes_disabled())||*
+            }
+
+            fn alternatives(world: &World, groups: &mut Vec<Vec<ArchetypeFilter>>) {
+                assert!(
+                    !($($name::filters_rows())||*),
+                    "Row filters cannot be combined with Or"
+                );
+                groups.push(vec![$(ArchetypeFilter::of::<$name>(world)),*]);
+            }
+        }
+    };
+}
+
+impl_or_tuple!(A, B);
+impl_or_tuple!(A, B, C);
+impl_or_tuple!(A, B, C, D);
+impl_or_tuple!(A, B, C, D, E);
+impl_or_tuple!(A, B, C, D, E, F);
+impl_or_tuple!(A, B, C, D, E, F, G);
+impl_or_tuple!(A, B, C, D, E, F, G, H);
+
+/// Matches entities not matched by the filter, e.g. `Not<Or<(With<Player>, With<Enemy>)>>`.
+/// Filters nest freely, every combination is evaluated per archetype. Row filters like [`Changed`] can't be negated.
+pub struct Not<T>(PhantomData<T>);
+
+impl<T: Filter> Filter for Not<T> {
+    #[inline(always)]
+    fn bitmask(_world: &World) -> (ComponentMask, ComponentMask) {
+        (ComponentMask::EMPTY, ComponentMask::EMPTY)
+    }
+
+    fn alternatives(world: &World, groups: &mut Vec<Vec<ArchetypeFilter>>) {
+        assert!(!T::filters_rows(), "Row filters cannot be negated");
+        groups.push(ArchetypeFilter::of::<T>(world).negated());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{Changed, Not, Or, QueryData, With, Without};
+    use crate::world::{Component, Entity, World};
+
+    #[derive(Debug, PartialEq)]
+    struct Position(u32);
+    #[derive(Debug, PartialEq)]
+    struct Velocity(u32);
+    struct Frozen;
+
+    impl Component for Position {}
+    impl Component for Velocity {}
+    impl Component for Frozen {}
+
+    /// Spawns archetypes of 2, 3 and 1 entities, with positions counting up in spawn order.
+    fn layered() -> World {
+        let mut world = World::new();
+        world.spawn(Position(0));
+        world.spawn((Position(1), Velocity(10)));
+        world.spawn(Position(2));
+        world.spawn((Position(3), Velocity(30)));
+        world.spawn((Position(4), Frozen));
+        world.spawn((Position(5), Velocity(50)));
+        world
+    }
+
+    fn positions<F: super::Filter>(world: &World) -> Vec<u32> {
+        let mut query = QueryData::<&Position, F>::new(world);
+        let mut positions: Vec<_> = query.iter(world).map(|position| position.0).collect();
+        positions.sort_unstable();
+        positions
+    }
+
+    #[test]
+    fn query_guards_hand_out_items_borrowing_them() {
+        let mut world = World::new();
+        let entity = world.spawn(Position(1));
+        let mut writer = world.query::<&mut Position>();
+        let mut reader = world.query::<&Position>();
+
+        {
+            let mut guard = writer.get(&world, entity).unwrap();
+            guard.get_mut().0 += 1;
+            guard.get_mut().0 += 1;
+        }
+        let guard = reader.get(&world, entity).unwrap();
+        assert_eq!((guard.get(), guard.get()), (&Position(3), &Position(3)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Conflicting Queries Detected")]
+    fn query_guards_keep_the_columns_borrowed() {
+        let mut world = World::new();
+        let entity = world.spawn(Position(1));
+        let mut writer = world.query::<&mut Position>();
+        let mut reader = world.query::<&Position>();
+
+        let _guard = writer.get(&world, entity).unwrap();
+        reader.get(&world, entity);
+    }
+
+    #[test]
+    fn dropped_query_guards_release_the_columns() {
+        let mut world = World::new();
+        let entity = world.spawn(Position(1));
+        let mut writer = world.query::<&mut Position>();
+        let mut reader = world.query::<&Position>();
+
+        drop(writer.get(&world, entity).unwrap());
+        assert_eq!(reader.iter(&world).count(), 1);
+        drop(reader.get(&world, entity).unwrap());
+        writer.get(&world, entity).unwrap().get_mut().0 = 5;
+        assert_eq!(world.get_component::<Position>(entity), Some(&Position(5)));
+    }
+
+    #[test]
+    fn optional_items_are_none_without_the_column() {
+        let world = layered();
+        let mut query = QueryData::<(&Position, Option<&Velocity>)>::new(&world);
+        let mut items: Vec<_> = query
+            .iter(&world)
+            .map(|(position, velocity)| (position.0, velocity.map(|velocity| velocity.0)))
+            .collect();
+        items.sort_unstable();
+        assert_eq!(
+            items,
+            [
+                (0, None),
+                (1, Some(10)),
+                (2, None),
+                (3, Some(30)),
+                (4, None),
+                (5, Some(50))
+            ]
+        );
+    }
+
+    #[test]
+    fn optional_mutable_items_write_where_the_column_exists() {
+        let mut world = layered();
+        let mut query = QueryData::<Option<&mut Velocity>>::new(&world);
+        let mut missing = 0;
+        for velocity in query.iter(&world) {
+            match velocity {
+                Some(velocity) => velocity.0 += 1,
+                None => missing += 1,
+            }
+        }
+        assert_eq!(missing, 3);
+
+        let mut query = world.query::<&Velocity>();
+        let mut velocities: Vec<_> = query.iter(&world).map(|velocity| velocity.0).collect();
+        velocities.sort_unstable();
+        assert_eq!(velocities, [11, 31, 51]);
+    }
+
+    #[test]
+    fn filters_combine_per_archetype() {
+        let world = layered();
+        assert_eq!(positions::<With<Velocity>>(&world), [1, 3, 5]);
+        assert_eq!(positions::<Without<Velocity>>(&world), [0, 2, 4]);
+        assert_eq!(
+            positions::<Or<(With<Velocity>, With<Frozen>)>>(&world),
+            [1, 3, 4, 5]
+        );
+        assert_eq!(
+            positions::<Or<(Without<Velocity>, (With<Velocity>, Without<Frozen>))>>(&world),
+            [0, 1, 2, 3, 4, 5]
+        );
+        assert_eq!(
+            positions::<Not<Or<(With<Velocity>, With<Frozen>)>>>(&world),
+            [0, 2]
+        );
+    }
+
+    #[test]
+    fn ranges_match_skipping_and_taking_items() {
+        let world = layered();
+        let mut query = QueryData::<(&Position, Option<&Velocity>)>::new(&world);
+        let all: Vec<_> = query
+            .iter(&world)
+            .map(|(position, velocity)| (position.0, velocity.map(|velocity| velocity.0)))
+            .collect();
+        for start in 0..=all.len() + 1 {
+            for end in start..=all.len() + 2 {
+                let range: Vec<_> = query
+                    .iter_range(&world, start..end)
+                    .map(|(position, velocity)| (position.0, velocity.map(|velocity| velocity.0)))
+                    .collect();
+                let expected: Vec<_> = all.iter().copied().skip(start).take(end - start).collect();
+                assert_eq!(range, expected, "range {start}..{end}");
+            }
+        }
+    }
+
+    #[test]
+    fn ranges_of_row_filters_count_matching_rows() {
+        let mut world = layered();
+        let entities = world.query::<Entity>().collect_entities(&world);
+        let mut query = QueryData::<&Position, Changed<Position>>::new(&world);
+        assert_eq!(query.iter(&world).count(), 6);
+
+        for entity in [entities[1], entities[3], entities[5]] {
+            world.get_component_mut::<Position>(entity).unwrap().0 += 100;
+        }
+        let changed: Vec<_> = query
+            .iter_range(&world, 1..3)
+            .map(|position| position.0)
+            .collect();
+        assert_eq!(changed.len(), 2);
+        assert!(changed.iter().all(|position| *position >= 100));
+        // The range iterated the query, older changes are not matched again
+        assert_eq!(query.iter(&world).count(), 0);
+    }
+
+    #[test]
+    fn unchecked_iteration_leaves_the_borrow_flags_alone() {
+        let mut world = layered();
+        let mut query = world.query::<&mut Position>();
+        let mut checked = world.query::<&mut Position>();
+
+        // SAFETY: No other query accesses the positions while the iterator lives
+        for position in unsafe { query.iter_unchecked(&world) } {
+            position.0 *= 2;
+        }
+        let mut positions: Vec<_> = checked.iter(&world).map(|position| position.0).collect();
+        positions.sort_unstable();
+        assert_eq!(positions, [0, 2, 4, 6, 8, 10]);
+    }
+
+    #[test]
+    fn sorted_iteration_follows_the_entities() {
+        let mut world = layered();
+        let mut query = world.query::<&Position>();
+        let unsorted: Vec<_> = query.iter(&world).map(|position| position.0).collect();
+        assert_ne!(unsorted, [0, 1, 2, 3, 4, 5]);
+
+        let sorted: Vec<_> = query
+            .iter_sorted_by_entity(&world)
+            .map(|position| position.0)
+            .collect();
+        assert_eq!(sorted, [0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn lenses_keep_the_archetypes_of_the_query() {
+        let mut world = layered();
+        let mut query = QueryData::<(&mut Position, &Velocity)>::new(&world);
+        let mut lens = query.transmute_lens::<&Position>(&world);
+        let mut positions: Vec<_> = lens.iter(&world).map(|position| position.0).collect();
+        positions.sort_unstable();
+        assert_eq!(positions, [1, 3, 5]);
+
+        // Archetypes created later are matched by the filter of the query, not by the one of the lens
+        world.spawn((Position(6), Velocity(60), Frozen));
+        world.spawn(Position(7));
+        assert_eq!(lens.iter(&world).count(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Lens accesses components the query doesn't")]
+    fn lenses_cannot_write_what_the_query_reads() {
+        let world = layered();
+        let mut query = QueryData::<(&mut Position, &Velocity)>::new(&world);
+        let _lens = query.transmute_lens::<&mut Velocity>(&world);
+    }
+
+    #[test]
+    #[should_panic(expected = "Lens requires components the query doesn't")]
+    fn lenses_cannot_require_optional_components() {
+        let world = layered();
+        let mut query = QueryData::<(&Position, Option<&Velocity>)>::new(&world);
+        let _lens = query.transmute_lens::<&Velocity>(&world);
+    }
+
+    #[test]
+    fn collected_entities_match_the_iteration() {
+        let world = layered();
+        let mut query = QueryData::<Entity, With<Velocity>>::new(&world);
+        let iterated: Vec<_> = query.iter(&world).collect();
+        assert_eq!(query.collect_entities(&world), iterated);
+
+        let mut entities = vec![Entity::new(99, 0, world.id())];
+        query.collect_into(&world, &mut entities);
+        assert_eq!(entities.len(), 4);
+        assert_eq!(entities[1..], iterated);
+    }
+
+    #[test]
+    fn collected_entities_of_row_filters_are_filtered() {
+        let mut world = layered();
+        let entities = world.query::<Entity>().collect_entities(&world);
+        let mut query = QueryData::<Entity, Changed<Position>>::new(&world);
+        assert_eq!(query.collect_entities(&world).len(), 6);
+
+        world.get_component_mut::<Position>(entities[2]).unwrap().0 = 20;
+        assert_eq!(query.collect_entities(&world), [entities[2]]);
+    }
+
+    #[test]
+    fn queries_share_match_lists_and_catch_up() {
+        let mut world = layered();
+        let mut first = world.query::<&Velocity>();
+        let mut second = QueryData::<&Velocity, ()>::new(&world);
+        assert!(Arc::ptr_eq(&first.list, &second.list));
+
+        world.spawn((Velocity(70), Frozen));
+        assert_eq!(first.iter(&world).count(), 4);
+        assert_eq!(second.iter(&world).count(), 4);
+        assert_eq!(first.matching().len(), 2);
+    }
+
+    #[test]
+    fn queries_rematch_after_archetypes_are_dropped() {
+        let mut world = layered();
+        let mut query = world.query::<&Velocity>();
+        assert_eq!(query.iter(&world).count(), 3);
+
+        world.clear_entities();
+        assert!(world.gc_archetypes() > 0);
+        world.spawn((Velocity(1), Frozen));
+        world.spawn(Position(1));
+        let velocities: Vec<_> = query.iter(&world).map(|velocity| velocity.0).collect();
+        assert_eq!(velocities, [1]);
+    }
+}
@@ -0,0 +1,65 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+};
+
+use crate::{
+    query::{Filter, QueryData, QueryItem},
+    world::World,
+};
+
+/// Query states owned by the world, keyed by the type of the query items and the filter.
+#[derive(Default)]
+pub(crate) struct QueryStates {
+    states: Mutex<HashMap<TypeId, Box<dyn Any + Send>>>,
+}
+
+impl World {
+    /// Returns a query sharing the matched archetypes of the state cached for its type, which is created on first use.
+    pub(crate) fn cached_query<Q: QueryItem + 'static, F: Filter + 'static>(
+        &self,
+    ) -> QueryData<Q, F> {
+        let mut states = self
+            .query_states
+            .states
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let state = states
+            .entry(TypeId::of::<(Q, F)>())
+            .or_insert_with(|| Box::new(QueryData::<Q, F>::new(self)))
+            .downcast_mut::<QueryData<Q, F>>()
+            .unwrap();
+        state.update_cache(self);
+        state.fork()
+    }
+
+    /// Runs the closure with the query state owned by the world, which keeps its cache and the tick of its previous iteration between calls,
+    /// e.g. `world.with_query(|query: &mut QueryData<&Health, Changed<Health>>, world| ..)` only visits the entities changed since the last call.
+    /// A nested call for the same query type gets a state of its own.
+    pub fn with_query<Q, F, R>(&self, f: impl FnOnce(&mut QueryData<Q, F>, &World) -> R) -> R
+    where
+        Q: QueryItem + 'static,
+        F: Filter + 'static,
+    {
+        let key = TypeId::of::<(Q, F)>();
+        let taken = self
+            .query_states
+            .states
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&key);
+        let mut state = match taken {
+            Some(state) => *state.downcast::<QueryData<Q, F>>().unwrap(),
+            None => QueryData::new(self),
+        };
+
+        let result = f(&mut state, self);
+        self.query_states
+            .states
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(key, Box::new(state));
+        result
+    }
+}
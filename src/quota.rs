@@ -0,0 +1,107 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    archetype::ArchetypeId,
+    bundle::Bundle,
+    world::{Entity, World},
+};
+
+/// Returned by [`World::try_spawn`] when a spawn would exceed a configured limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaError {
+    /// The world already has the maximum number of live entities.
+    Entities { limit: usize },
+    /// The archetype already stores the maximum number of entities.
+    Archetype { id: ArchetypeId, limit: usize },
+}
+
+impl fmt::Display for QuotaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuotaError::Entities { limit } => {
+                write!(f, "entity quota of {limit} live entities is exhausted")
+            }
+            QuotaError::Archetype { id, limit } => write!(
+                f,
+                "quota of {limit} entities in archetype {} is exhausted",
+                id.index()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for QuotaError {}
+
+/// Limits checked before every spawn.
+#[derive(Default, Clone)]
+pub(crate) struct Quotas {
+    max_entities: Option<usize>,
+    pub(crate) archetypes: HashMap<usize, usize>,
+}
+
+impl World {
+    /// Limits the number of live entities, `None` removes the limit. Entities which are alive already are kept.
+    pub fn set_entity_limit(&mut self, limit: Option<usize>) {
+        self.quotas.max_entities = limit;
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn entity_limit(&self) -> Option<usize> {
+        self.quotas.max_entities
+    }
+
+    /// Limits the number of entities spawned into the archetype, `None` removes the limit.
+    /// Only spawns are checked, entities moved into the archetype by inserting or removing components are not.
+    pub fn set_archetype_limit(&mut self, id: ArchetypeId, limit: Option<usize>) {
+        match limit {
+            Some(limit) => self.quotas.archetypes.insert(id.index(), limit),
+            None => self.quotas.archetypes.remove(&id.index()),
+        };
+    }
+
+    /// Spawns an entity like [`World::spawn`], but reports an error instead of panicking when a quota is exhausted.
+    pub fn try_spawn<B: Bundle>(&mut self, bundle: B) -> Result<Entity, QuotaError> {
+        B::register(self);
+        let index = self.archetype_index(&B::bitmask(self));
+        self.check_quota(Some(index))?;
+        Ok(self.spawn_in_archetype(bundle, index))
+    }
+
+    /// Spawns an entity like [`World::spawn_empty`], but reports an error instead of panicking when the entity quota is exhausted.
+    pub fn try_spawn_empty(&mut self) -> Result<Entity, QuotaError> {
+        self.check_quota(None)?;
+        Ok(self.spawn_empty())
+    }
+
+    /// Checks whether one more entity can be spawned, optionally into the archetype with the given index.
+    #[inline]
+    pub(crate) fn check_quota(&self, archetype: Option<usize>) -> Result<(), QuotaError> {
+        self.check_quota_for(archetype, 1)
+    }
+
+    /// Checks whether `additional` entities can be spawned, optionally into the archetype with the given index.
+    pub(crate) fn check_quota_for(
+        &self,
+        archetype: Option<usize>,
+        additional: usize,
+    ) -> Result<(), QuotaError> {
+        if let Some(limit) = self.quotas.max_entities
+            && self.entities.len() + additional > limit
+        {
+            return Err(QuotaError::Entities { limit });
+        }
+
+        if let Some(index) = archetype
+            && let Some(limit) = self.quotas.archetypes.get(&index)
+            && self.archetypes()[index].count() + additional > *limit
+        {
+            return Err(QuotaError::Archetype {
+                id: ArchetypeId(index),
+                limit: *limit,
+            });
+        }
+
+        Ok(())
+    }
+}
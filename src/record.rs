@@ -0,0 +1,308 @@
+use std::{
+    collections::HashMap,
+    io::{self, Read},
+};
+
+use crate::{
+    serialize::{Decode, Encode, SerializeFns, invalid_data, read_bytes, write_bytes},
+    world::{ComponentId, Entity, World},
+};
+
+const RECORD_MAGIC: &[u8; 4] = b"BECR";
+const RECORD_VERSION: u32 = 1;
+
+// Every operation starts with one of these tags
+const OP_COMPONENT: u8 = 0; // name, assigns the next component index
+const OP_SPAWN: u8 = 1; // entity
+const OP_DESPAWN: u8 = 2; // entity
+const OP_INSERT: u8 = 3; // entity, component index, payload
+const OP_REMOVE: u8 = 4; // entity, component index
+const OP_FRAME: u8 = 5; // end of a frame, written by `World::flush`
+
+/// Operations recorded since [`World::start_recording`].
+pub(crate) struct Recording {
+    stream: Vec<u8>,
+    values: bool,
+    since: u64,
+    types: HashMap<ComponentId, usize>,
+}
+
+impl Recording {
+    fn put(&mut self, value: &(impl Encode + ?Sized)) {
+        value
+            .encode(&mut self.stream)
+            .expect("Writing into a Vec cannot fail");
+    }
+
+    /// Returns the index of the component in the stream, defining it on the first use.
+    fn type_index(&mut self, id: ComponentId, fns: &SerializeFns) -> usize {
+        if let Some(index) = self.types.get(&id) {
+            return *index;
+        }
+
+        let index = self.types.len();
+        self.types.insert(id, index);
+        self.stream.push(OP_COMPONENT);
+        self.put(fns.name);
+        index
+    }
+}
+
+impl World {
+    /// Starts logging every structural operation (spawns, despawns, inserted and removed components) into a compact stream,
+    /// which can be applied to a fresh world with [`World::replay`] or a [`Replayer`]. The stream starts with the current state of the world.
+    /// When `record_values` is `true`, [`World::flush`] also logs the values of components changed in place, e.g. through queries.
+    ///
+    /// Only components registered with [`World::register_serializable`] are logged, spawns and despawns are logged for every entity.
+    /// Undo and redo of checkpoints are not logged. Starting again discards the previous recording.
+    pub fn start_recording(&mut self, record_values: bool) {
+        let mut recording = Recording {
+            stream: RECORD_MAGIC.to_vec(),
+            values: record_values,
+            since: 0,
+            types: HashMap::new(),
+        };
+        recording.put(&RECORD_VERSION);
+        self.recording = Some(recording);
+
+        let alive: Vec<_> = self.entities.alive().collect();
+        for entity in alive {
+            self.log_spawn(entity);
+        }
+
+        // Everything changed until now is part of the recorded state already
+        let since = self.increment_change_tick();
+        if let Some(recording) = &mut self.recording {
+            recording.since = since;
+        }
+    }
+
+    /// Stops the recording and returns the recorded stream, or `None` when nothing was recorded.
+    pub fn stop_recording(&mut self) -> Option<Vec<u8>> {
+        self.recording.take().map(|recording| recording.stream)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Applies a whole stream recorded by [`World::start_recording`], flushing the world at the end of every recorded frame.
+    /// Meant for a fresh world with the same serializable components registered, entities keep the ids they had when they were recorded.
+    pub fn replay(&mut self, reader: impl Read) -> io::Result<()> {
+        let mut replayer = Replayer::new(reader)?;
+        while replayer.next_frame(self)? {}
+        Ok(())
+    }
+
+    /// Logs the spawn of the entity together with its serializable components.
+    pub(crate) fn log_spawn(&mut self, entity: Entity) {
+        let Some(recording) = &mut self.recording else {
+            return;
+        };
+        recording.stream.push(OP_SPAWN);
+        recording.put(&entity);
+
+        let ids: Vec<_> = self
+            .archetype_of(entity)
+            .filter(|_| !self.is_empty(entity))
+            .into_iter()
+            .flat_map(|archetype| archetype.columns().map(|(id, _)| *id))
+            .collect();
+        for id in ids {
+            self.log_insert(entity, id);
+        }
+    }
+
+    /// Logs the current value of the component if it is serializable.
+    pub(crate) fn log_insert(&mut self, entity: Entity, id: ComponentId) {
+        if self.recording.is_none() {
+            return;
+        }
+        let Some(fns) = self.serializers.get(&id).copied() else {
+            return;
+        };
+        let Some(archetype) = self.archetype_of(entity) else {
+            return;
+        };
+        let row = self.entities.metas[entity.index].location.row;
+        let Some(bytes) = archetype.get_bytes(id, row) else {
+            return;
+        };
+
+        let mut payload = Vec::new();
+        unsafe {
+            (fns.encode)(bytes, &mut payload) // SAFETY: The bytes come from the column of the component
+                .expect("Failed to serialize a recorded component");
+        }
+
+        let recording = self.recording.as_mut().unwrap();
+        let index = recording.type_index(id, &fns);
+        recording.stream.push(OP_INSERT);
+        recording.put(&entity);
+        recording.put(&index);
+        write_bytes(&mut recording.stream, &payload).expect("Writing into a Vec cannot fail");
+    }
+
+    /// Logs the removal of the component if it is serializable.
+    pub(crate) fn log_remove(&mut self, entity: Entity, id: ComponentId) {
+        let Some(recording) = &mut self.recording else {
+            return;
+        };
+        let Some(fns) = self.serializers.get(&id) else {
+            return;
+        };
+
+        let index = recording.type_index(id, fns);
+        recording.stream.push(OP_REMOVE);
+        recording.put(&entity);
+        recording.put(&index);
+    }
+
+    pub(crate) fn log_despawn(&mut self, entity: Entity) {
+        let Some(recording) = &mut self.recording else {
+            return;
+        };
+        recording.stream.push(OP_DESPAWN);
+        recording.put(&entity);
+    }
+
+    /// Logs the values changed in place during the frame when they are recorded, then ends the frame.
+    pub(crate) fn log_frame(&mut self) {
+        let Some(recording) = &self.recording else {
+            return;
+        };
+
+        if recording.values {
+            let since = recording.since;
+            let mut changed = Vec::new();
+            for archetype in self.archetypes() {
+                for (id, column) in archetype.columns() {
+                    if self.serializers.get(id).is_none() {
+                        continue;
+                    }
+                    for (row, entity) in archetype.entities().iter().enumerate() {
+                        if column.tick(row) > since {
+                            changed.push((*entity, *id));
+                        }
+                    }
+                }
+            }
+
+            for (entity, id) in changed {
+                self.log_insert(entity, id);
+            }
+        }
+
+        let change_tick = self.change_tick();
+        let recording = self.recording.as_mut().unwrap();
+        recording.stream.push(OP_FRAME);
+        recording.since = change_tick;
+    }
+}
+
+/// Applies a stream recorded by [`World::start_recording`] frame by frame, e.g. to step through a bug reproduction.
+pub struct Replayer<R: Read> {
+    reader: R,
+    types: Vec<SerializeFns>,
+}
+
+impl<R: Read> Replayer<R> {
+    /// Reads the header of the stream.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != RECORD_MAGIC {
+            return Err(invalid_data("not a recorded stream"));
+        }
+
+        let version = u32::decode(&mut reader)?;
+        if version != RECORD_VERSION {
+            return Err(invalid_data(format!(
+                "unsupported record version {version}, expected {RECORD_VERSION}"
+            )));
+        }
+
+        Ok(Self {
+            reader,
+            types: Vec::new(),
+        })
+    }
+
+    /// Applies the operations of the next frame and flushes the world.
+    /// Returns `false` when the stream ended, operations recorded after the last flush are applied without flushing.
+    pub fn next_frame(&mut self, world: &mut World) -> io::Result<bool> {
+        loop {
+            let mut tag = [0];
+            match self.reader.read_exact(&mut tag) {
+                Ok(()) => {}
+                Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(false),
+                Err(error) => return Err(error),
+            }
+
+            let reader = &mut self.reader as &mut dyn Read;
+            match tag[0] {
+                OP_COMPONENT => {
+                    let name = String::decode(reader)?;
+                    let Some(fns) = world.serializers.by_name(&name) else {
+                        return Err(invalid_data(format!(
+                            "component `{name}` is not registered as serializable"
+                        )));
+                    };
+                    self.types.push(*fns);
+                }
+                OP_SPAWN => {
+                    let entity = Entity::decode(reader)?;
+                    Self::ensure_alive(world, entity)?;
+                }
+                OP_DESPAWN => {
+                    let entity = Entity::decode(reader)?;
+                    world.despawn_entity(entity);
+                }
+                OP_INSERT => {
+                    let entity = Entity::decode(reader)?;
+                    let index = usize::decode(reader)?;
+                    let fns = component(&self.types, index)?;
+                    let payload = read_bytes(reader)?;
+
+                    Self::ensure_alive(world, entity)?;
+                    let mut bytes = payload.as_slice();
+                    (fns.decode)(world, entity, &mut bytes)?;
+                    if !bytes.is_empty() {
+                        return Err(invalid_data("component payload was not fully read"));
+                    }
+                }
+                OP_REMOVE => {
+                    let entity = Entity::decode(reader)?;
+                    let index = usize::decode(reader)?;
+                    let fns = component(&self.types, index)?;
+                    (fns.remove)(world, entity);
+                }
+                OP_FRAME => {
+                    world.flush();
+                    return Ok(true);
+                }
+                tag => return Err(invalid_data(format!("unknown operation {tag}"))),
+            }
+        }
+    }
+
+    /// Allocates the recorded id if the entity does not exist yet.
+    fn ensure_alive(world: &mut World, entity: Entity) -> io::Result<()> {
+        if world.is_alive(entity) || world.entities.alloc_at(entity) {
+            Ok(())
+        } else {
+            Err(invalid_data(format!(
+                "slot of the recorded {entity:?} is taken by another entity"
+            )))
+        }
+    }
+}
+
+fn component(types: &[SerializeFns], index: usize) -> io::Result<SerializeFns> {
+    types
+        .get(index)
+        .copied()
+        .ok_or_else(|| invalid_data("component index out of range"))
+}
@@ -0,0 +1,147 @@
+use std::{cmp::Reverse, time::Duration};
+
+use crate::schedule::Schedule;
+
+/// Wall-clock durations of the runs of one system, deferred changes applied after the run aren't included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SystemTiming {
+    name: &'static str,
+    runs: u64,
+    total: Duration,
+    last: Duration,
+    max: Duration,
+}
+
+impl SystemTiming {
+    pub(crate) fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            runs: 0,
+            total: Duration::ZERO,
+            last: Duration::ZERO,
+            max: Duration::ZERO,
+        }
+    }
+
+    pub(crate) fn record(&mut self, duration: Duration) {
+        self.runs += 1;
+        self.total += duration;
+        self.last = duration;
+        self.max = self.max.max(duration);
+    }
+
+    pub(crate) fn reset(&mut self) {
+        *self = Self::new(self.name);
+    }
+
+    /// Name of the system, see [`System::name`](crate::system::System::name).
+    #[inline]
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Number of recorded runs.
+    #[inline]
+    #[must_use]
+    pub fn runs(&self) -> u64 {
+        self.runs
+    }
+
+    /// Duration of every recorded run together.
+    #[inline]
+    #[must_use]
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
+    /// Duration of the latest run, zero before the first one.
+    #[inline]
+    #[must_use]
+    pub fn last(&self) -> Duration {
+        self.last
+    }
+
+    /// Duration of the slowest run.
+    #[inline]
+    #[must_use]
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// Average duration of a run, zero before the first one.
+    #[must_use]
+    pub fn mean(&self) -> Duration {
+        match self.runs {
+            0 => Duration::ZERO,
+            runs => self.total.div_f64(runs as f64),
+        }
+    }
+}
+
+/// Timings of the systems of a [`Schedule`] in the order they run, returned by [`Schedule::report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleReport {
+    systems: Vec<SystemTiming>,
+}
+
+impl ScheduleReport {
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &SystemTiming> {
+        self.systems.iter()
+    }
+
+    /// Timing of the first system with the name.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&SystemTiming> {
+        self.systems.iter().find(|timing| timing.name == name)
+    }
+
+    /// Systems sorted from the most to the least total time spent running them.
+    #[must_use]
+    pub fn hot_spots(&self) -> Vec<&SystemTiming> {
+        let mut systems: Vec<_> = self.systems.iter().collect();
+        systems.sort_by_key(|timing| Reverse(timing.total));
+        systems
+    }
+
+    /// Time spent running every system together. Systems of parallel runs overlap, so it may exceed the wall-clock time of the runs.
+    #[must_use]
+    pub fn total(&self) -> Duration {
+        self.systems.iter().map(|timing| timing.total).sum()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.systems.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.systems.is_empty()
+    }
+}
+
+impl Schedule {
+    /// Collects the timings recorded for every system since it was added or since [`Schedule::reset_timings`],
+    /// in the order the systems run, valid after [`Schedule::initialize`].
+    #[must_use]
+    pub fn report(&self) -> ScheduleReport {
+        ScheduleReport {
+            systems: self
+                .order
+                .iter()
+                .map(|index| self.systems[*index].timing)
+                .collect(),
+        }
+    }
+
+    /// Clears the timings of every system.
+    pub fn reset_timings(&mut self) {
+        for config in &mut self.systems {
+            config.timing.reset();
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use std::{
+    any::{Any, TypeId},
+    cell::UnsafeCell,
+    collections::HashMap,
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{borrow::AtomicBorrow, world::World};
+
+/// A resource value with its borrow state, the value is an `UnsafeCell<R>` so it can be handed out mutably through `&World`.
+struct ResourceCell {
+    value: Box<dyn Any>,
+    borrow: AtomicBorrow,
+}
+
+impl ResourceCell {
+    fn get<R: 'static>(&self) -> *mut R {
+        self.value.downcast_ref::<UnsafeCell<R>>().unwrap().get()
+    }
+}
+
+/// Singleton values of the world keyed by their type, see [`World::insert_resource`].
+#[derive(Default)]
+pub(crate) struct Resources {
+    cells: HashMap<TypeId, ResourceCell>,
+}
+
+/// Shared borrow of a resource returned by [`World::get_resource`], releases the borrow when dropped.
+pub struct Res<'a, R> {
+    value: &'a R,
+    borrow: &'a AtomicBorrow,
+}
+
+impl<R> Deref for Res<'_, R> {
+    type Target = R;
+
+    #[inline]
+    fn deref(&self) -> &R {
+        self.value
+    }
+}
+
+impl<R> Drop for Res<'_, R> {
+    fn drop(&mut self) {
+        self.borrow.release();
+    }
+}
+
+impl<R: fmt::Debug> fmt::Debug for Res<'_, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+/// Unique borrow of a resource returned by [`World::get_resource_mut`], releases the borrow when dropped.
+pub struct ResMut<'a, R> {
+    value: &'a mut R,
+    borrow: &'a AtomicBorrow,
+}
+
+impl<R> Deref for ResMut<'_, R> {
+    type Target = R;
+
+    #[inline]
+    fn deref(&self) -> &R {
+        self.value
+    }
+}
+
+impl<R> DerefMut for ResMut<'_, R> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut R {
+        self.value
+    }
+}
+
+impl<R> Drop for ResMut<'_, R> {
+    fn drop(&mut self) {
+        self.borrow.release_mut();
+    }
+}
+
+impl<R: fmt::Debug> fmt::Debug for ResMut<'_, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl World {
+    /// Stores a singleton value of type `R` next to the entities, e.g. the input state or asset handles, replacing the previous one.
+    pub fn insert_resource<R: 'static>(&mut self, value: R) {
+        self.resources.cells.insert(
+            TypeId::of::<R>(),
+            ResourceCell {
+                value: Box::new(UnsafeCell::new(value)),
+                borrow: AtomicBorrow::new(),
+            },
+        );
+    }
+
+    /// Borrows the resource of type `R`. Panics when it is borrowed mutably already.
+    #[must_use]
+    pub fn get_resource<R: 'static>(&self) -> Option<Res<'_, R>> {
+        let cell = self.resources.cells.get(&TypeId::of::<R>())?;
+        if !cell.borrow.borrow() {
+            panic!(
+                "Conflicting resource borrow of {}",
+                std::any::type_name::<R>()
+            );
+        }
+
+        Some(Res {
+            // SAFETY: The shared borrow excludes unique borrows until the guard is dropped
+            value: unsafe { &*cell.get::<R>() },
+            borrow: &cell.borrow,
+        })
+    }
+
+    /// Borrows the resource of type `R` mutably, several resources can be borrowed at once this way. Panics when it is borrowed already.
+    #[must_use]
+    pub fn get_resource_mut<R: 'static>(&self) -> Option<ResMut<'_, R>> {
+        let cell = self.resources.cells.get(&TypeId::of::<R>())?;
+        if !cell.borrow.borrow_mut() {
+            panic!(
+                "Conflicting resource borrow of {}",
+                std::any::type_name::<R>()
+            );
+        }
+
+        Some(ResMut {
+            // SAFETY: The unique borrow excludes every other borrow until the guard is dropped
+            value: unsafe { &mut *cell.get::<R>() },
+            borrow: &cell.borrow,
+        })
+    }
+
+    /// Removes the resource of type `R` and returns it.
+    pub fn remove_resource<R: 'static>(&mut self) -> Option<R> {
+        let cell = self.resources.cells.remove(&TypeId::of::<R>())?;
+        let value = cell.value.downcast::<UnsafeCell<R>>().unwrap();
+        Some(value.into_inner())
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn contains_resource<R: 'static>(&self) -> bool {
+        self.resources.cells.contains_key(&TypeId::of::<R>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::world::World;
+
+    #[derive(Debug, PartialEq)]
+    struct Score(u32);
+    #[derive(Debug, PartialEq)]
+    struct Settings(&'static str);
+
+    #[test]
+    fn resources_are_replaced_and_removed() {
+        let mut world = World::new();
+        assert!(world.get_resource::<Score>().is_none());
+        world.insert_resource(Score(1));
+        world.insert_resource(Score(2));
+        assert!(world.contains_resource::<Score>());
+        assert_eq!(*world.get_resource::<Score>().unwrap(), Score(2));
+
+        world.get_resource_mut::<Score>().unwrap().0 += 1;
+        assert_eq!(world.remove_resource::<Score>(), Some(Score(3)));
+        assert_eq!(world.remove_resource::<Score>(), None);
+        assert!(!world.contains_resource::<Score>());
+    }
+
+    #[test]
+    fn shared_and_disjoint_borrows_coexist() {
+        let mut world = World::new();
+        world.insert_resource(Score(1));
+        world.insert_resource(Settings("fast"));
+
+        let first = world.get_resource::<Score>().unwrap();
+        let second = world.get_resource::<Score>().unwrap();
+        let mut settings = world.get_resource_mut::<Settings>().unwrap();
+        settings.0 = "slow";
+        assert_eq!((first.0, second.0, settings.0), (1, 1, "slow"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Conflicting resource borrow")]
+    fn resources_cannot_be_written_while_read() {
+        let mut world = World::new();
+        world.insert_resource(Score(1));
+        let _score = world.get_resource::<Score>().unwrap();
+        let _ = world.get_resource_mut::<Score>();
+    }
+
+    #[test]
+    #[should_panic(expected = "Conflicting resource borrow")]
+    fn resources_cannot_be_read_while_written() {
+        let mut world = World::new();
+        world.insert_resource(Score(1));
+        let _score = world.get_resource_mut::<Score>().unwrap();
+        let _ = world.get_resource::<Score>();
+    }
+
+    #[test]
+    fn dropped_guards_release_the_borrow() {
+        let mut world = World::new();
+        world.insert_resource(Score(1));
+        drop(world.get_resource::<Score>().unwrap());
+        drop(world.get_resource_mut::<Score>().unwrap());
+        assert_eq!(world.get_resource_mut::<Score>().unwrap().0, 1);
+    }
+
+    #[test]
+    fn resources_are_dropped_with_the_world() {
+        let shared = Rc::new(());
+        let mut world = World::new();
+        world.insert_resource(shared.clone());
+        // Replacing drops the previous value
+        world.insert_resource(shared.clone());
+        assert_eq!(Rc::strong_count(&shared), 2);
+        drop(world);
+        assert_eq!(Rc::strong_count(&shared), 1);
+    }
+}
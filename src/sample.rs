@@ -0,0 +1,68 @@
+use std::collections::HashSet;
+
+use crate::{
+    query::{Filter, QueryData, QueryItem},
+    world::{Entity, World},
+};
+
+/// Source of uniformly distributed random numbers used by [`QueryData::sample`].
+/// Implemented for closures, so any generator can be used with `|| rng.next_u64()`.
+pub trait RandomSource {
+    fn next_u64(&mut self) -> u64;
+
+    /// Returns a uniformly distributed number in `0..bound`, `bound` must not be zero.
+    fn below(&mut self, bound: u64) -> u64 {
+        debug_assert!(bound > 0, "Bound must be greater than zero");
+        ((u128::from(self.next_u64()) * u128::from(bound)) >> 64) as u64
+    }
+}
+
+impl<F: FnMut() -> u64> RandomSource for F {
+    fn next_u64(&mut self) -> u64 {
+        self()
+    }
+}
+
+impl<Q: QueryItem, F: Filter> QueryData<Q, F> {
+    /// Picks `n` distinct matching entities uniformly at random, or every matching entity when there are not more than `n`.
+    /// Rows are located through the archetype counts, so the full result set is never materialized.
+    /// The entities are returned in iteration order. Panics with row filters like [`Changed`](crate::query::Changed).
+    pub fn sample(&mut self, world: &World, rng: &mut impl RandomSource, n: usize) -> Vec<Entity> {
+        assert!(!F::filters_rows(), "Cannot sample a query with row filters");
+        self.update_cache(world);
+        let archetypes = world.archetypes();
+        let matching = self.matching();
+
+        // Running totals of the matching archetype counts, used to map a global index to its archetype
+        let mut ends = Vec::with_capacity(matching.len());
+        let mut total = 0;
+        for index in matching.iter() {
+            total += archetypes[*index].count();
+            ends.push(total);
+        }
+
+        let mut picked: Vec<usize> = if n >= total {
+            (0..total).collect()
+        } else {
+            // Floyd's algorithm, every subset of size n is equally likely
+            let mut picked = HashSet::with_capacity(n);
+            for bound in total - n..total {
+                let candidate = rng.below(bound as u64 + 1) as usize;
+                if !picked.insert(candidate) {
+                    picked.insert(bound);
+                }
+            }
+            picked.into_iter().collect()
+        };
+        picked.sort_unstable();
+
+        picked
+            .into_iter()
+            .map(|global| {
+                let position = ends.partition_point(|end| *end <= global);
+                let start = if position == 0 { 0 } else { ends[position - 1] };
+                archetypes[matching[position]].entities()[global - start]
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,729 @@
+use std::{
+    any::{TypeId, type_name},
+    collections::BTreeSet,
+    sync::LazyLock,
+    time::Instant,
+};
+
+use crate::{
+    error::{ErrorHandler, OnError, SystemError, SystemResult},
+    report::SystemTiming,
+    stepping::Stepping,
+    system::{IntoSystem, System, SystemAccess},
+    world::World,
+};
+
+/// Identifies the systems created from the same function or system type, used by ordering constraints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct SystemLabel(TypeId);
+
+impl SystemLabel {
+    pub(crate) fn of<M, S: IntoSystem<M>>() -> Self {
+        Self(TypeId::of::<S::System>())
+    }
+}
+
+/// A system with its ordering constraints, added to a [`Schedule`] with [`Schedule::add_system`].
+pub struct SystemConfig {
+    pub(crate) system: Box<dyn System>,
+    pub(crate) label: SystemLabel,
+    before: Vec<SystemLabel>,
+    after: Vec<SystemLabel>,
+    conditions: Vec<Condition>,
+    pub(crate) timing: SystemTiming,
+}
+
+/// Decides whether a system runs, see [`IntoSystemConfig::run_if`].
+type Condition = Box<dyn Fn(&World) -> bool + Send>;
+
+impl SystemConfig {
+    /// Returns `true` when every condition of the system holds.
+    pub(crate) fn should_run(&self, world: &World) -> bool {
+        self.conditions.iter().all(|condition| condition(world))
+    }
+
+    /// Runs the system, recording how long it took.
+    pub(crate) fn run(&mut self, world: &World) -> Result<(), SystemError> {
+        let start = Instant::now();
+        let result = self.system.run(world);
+        self.timing.record(start.elapsed());
+        result.map_err(|error| SystemError::new(self.system.name(), error))
+    }
+}
+
+/// Conversion into a [`SystemConfig`], implemented for everything which converts into a system.
+pub trait IntoSystemConfig<Marker>: Sized {
+    fn into_config(self) -> SystemConfig;
+
+    /// Runs this system before every system created from `other` in the same schedule.
+    fn before<M, S: IntoSystem<M>>(self, _other: S) -> SystemConfig {
+        let mut config = self.into_config();
+        config.before.push(SystemLabel::of::<M, S>());
+        config
+    }
+
+    /// Runs this system after every system created from `other` in the same schedule.
+    fn after<M, S: IntoSystem<M>>(self, _other: S) -> SystemConfig {
+        let mut config = self.into_config();
+        config.after.push(SystemLabel::of::<M, S>());
+        config
+    }
+
+    /// Runs this system only when the condition holds, e.g. `movement.run_if(in_state(Screen::Game))`.
+    /// Conditions are evaluated at the start of every [`Schedule::run`], before any system of the run.
+    fn run_if(self, condition: impl Fn(&World) -> bool + Send + 'static) -> SystemConfig {
+        let mut config = self.into_config();
+        config.conditions.push(Box::new(condition));
+        config
+    }
+}
+
+impl<M, S: IntoSystem<M>> IntoSystemConfig<M> for S {
+    fn into_config(self) -> SystemConfig {
+        let system = self.into_system();
+        let timing = SystemTiming::new(system.name());
+        SystemConfig {
+            system: Box::new(system),
+            label: SystemLabel::of::<M, S>(),
+            before: Vec::new(),
+            after: Vec::new(),
+            conditions: Vec::new(),
+            timing,
+        }
+    }
+}
+
+/// Marks the [`IntoSystemConfig`] impl of configs.
+#[doc(hidden)]
+pub struct IsSystemConfig;
+
+impl IntoSystemConfig<IsSystemConfig> for SystemConfig {
+    #[inline]
+    fn into_config(self) -> SystemConfig {
+        self
+    }
+}
+
+/// Systems run against a world in an order derived from their constraints.
+///
+/// Systems run after every system they are constrained to follow with [`IntoSystemConfig::after`] or [`IntoSystemConfig::before`].
+/// Two unconstrained systems whose access conflicts, e.g. one writing a component the other reads, run in the order they were added
+/// and are reported by [`Schedule::ambiguities`],
+/// while systems with compatible access have no order between them. Constraints naming systems which aren't in the schedule are ignored.
+#[derive(Default)]
+pub struct Schedule {
+    pub(crate) systems: Vec<SystemConfig>,
+    /// Indices of the systems every system has to run after, valid when the schedule isn't dirty.
+    pub(crate) dependencies: Vec<Vec<usize>>,
+    pub(crate) order: Vec<usize>,
+    executor: Executor,
+    pub(crate) stepping: Option<Stepping>,
+    /// Pairs of conflicting systems ordered only by the order they were added, earlier system first.
+    pub(crate) ambiguities: Vec<(usize, usize)>,
+    pub(crate) error_handler: ErrorHandler,
+    deny_ambiguities: bool,
+    dirty: bool,
+}
+
+/// Conversion into several [`SystemConfig`]s, implemented for configs and for tuples of up to 8 of them.
+pub trait IntoSystemConfigs<Marker> {
+    fn into_configs(self) -> Vec<SystemConfig>;
+}
+
+impl<M, C: IntoSystemConfig<M>> IntoSystemConfigs<M> for C {
+    #[inline]
+    fn into_configs(self) -> Vec<SystemConfig> {
+        vec![self.into_config()]
+    }
+}
+
+/// Marks the [`IntoSystemConfigs`] impls of tuples.
+#[doc(hidden)]
+pub struct IsSystemTuple;
+
+macro_rules! impl_configs_tuple {
+    ($($name:ident $marker:ident),*) => {
+        impl<$($name: IntoSystemConfigs<$marker>, $marker),*> IntoSystemConfigs<(IsSystemTuple, ($($marker,)*))> for ($($name,)*) {
+            fn into_configs(self) -> Vec<SystemConfig> {
+                #[allow(non_snake_case)]
+                let ($($name,)*) = self;
+                let mut configs = Vec::new();
+                $(configs.extend($name.into_configs());)*
+                configs
+            }
+        }
+    };
+}
+
+impl_configs_tuple!(A MA);
+impl_configs_tuple!(A MA, B MB);
+impl_configs_tuple!(A MA, B MB, C MC);
+impl_configs_tuple!(A MA, B MB, C MC, D MD);
+impl_configs_tuple!(A MA, B MB, C MC, D MD, E ME);
+impl_configs_tuple!(A MA, B MB, C MC, D MD, E ME, F MF);
+impl_configs_tuple!(A MA, B MB, C MC, D MD, E ME, F MF, G MG);
+impl_configs_tuple!(A MA, B MB, C MC, D MD, E ME, F MF, G MG, H MH);
+
+/// Sync point of a [`Schedule`], applying the deferred changes of the systems ordered before it,
+/// e.g. `schedule.add_system(spawn_enemies).add_system(ApplyDeferred).add_system(target_enemies)`.
+/// It conflicts with every system, so systems which aren't ordered through constraints run before or after it in the order they were added.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApplyDeferred;
+
+static EXCLUSIVE: LazyLock<SystemAccess> = LazyLock::new(|| {
+    let mut access = SystemAccess::default();
+    access.set_exclusive();
+    access
+});
+
+// SAFETY: The system doesn't access anything, its access is exclusive so it only orders the other systems
+unsafe impl System for ApplyDeferred {
+    #[inline]
+    fn name(&self) -> &'static str {
+        type_name::<Self>()
+    }
+
+    #[inline]
+    fn access(&self) -> &SystemAccess {
+        &EXCLUSIVE
+    }
+
+    #[inline]
+    fn initialize(&mut self, _world: &mut World) {}
+
+    #[inline]
+    fn run(&mut self, _world: &World) -> SystemResult {
+        Ok(())
+    }
+}
+
+/// How a [`Schedule`] runs its systems, set with [`Schedule::set_executor`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Executor {
+    /// One system after another on the calling thread.
+    #[default]
+    Sequential,
+    /// Systems without an order between them run at the same time on the given number of threads, the calling one included,
+    /// or on as many threads as [`std::thread::available_parallelism`] reports when it is zero.
+    Parallel(usize),
+}
+
+impl Schedule {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a system, e.g. `schedule.add_system(movement.after(input))`.
+    pub fn add_system<M>(&mut self, system: impl IntoSystemConfig<M>) -> &mut Self {
+        self.systems.push(system.into_config());
+        self.dirty = true;
+        self
+    }
+
+    /// Adds several systems in order, e.g. `schedule.add_systems((input, movement.after(input)))`.
+    pub fn add_systems<M>(&mut self, systems: impl IntoSystemConfigs<M>) -> &mut Self {
+        self.systems.extend(systems.into_configs());
+        self.dirty = true;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.systems.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.systems.is_empty()
+    }
+
+    /// Initializes the systems added since the previous call and computes their order.
+    /// Panics when the constraints form a cycle, or when ambiguities are denied and there are some.
+    pub fn initialize(&mut self, world: &mut World) {
+        if !self.dirty {
+            return;
+        }
+        for config in &mut self.systems {
+            config.system.initialize(world);
+        }
+        self.build();
+        if self.deny_ambiguities && !self.ambiguities.is_empty() {
+            let ambiguities: Vec<String> = self
+                .ambiguities(world)
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            panic!("Schedule has ambiguities:\n{}", ambiguities.join("\n"));
+        }
+        if let Some(stepping) = &mut self.stepping {
+            stepping.cursor = 0;
+        }
+        self.dirty = false;
+    }
+
+    /// Chooses how the systems are run, see [`Executor`].
+    pub fn set_executor(&mut self, executor: Executor) -> &mut Self {
+        self.executor = executor;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn executor(&self) -> Executor {
+        self.executor
+    }
+
+    /// Makes [`Schedule::initialize`] panic listing the [ambiguities](Schedule::ambiguities) of the schedule,
+    /// e.g. in tests which keep the order of the systems explicit.
+    pub fn deny_ambiguities(&mut self, deny: bool) -> &mut Self {
+        self.deny_ambiguities = deny;
+        self.dirty = true;
+        self
+    }
+
+    /// Runs every system once in order, initializing the schedule first when systems were added,
+    /// then applies their deferred changes like [`Commands`](crate::system::Commands) in the same order.
+    /// Systems whose [run conditions](IntoSystemConfig::run_if) don't hold are passed over.
+    /// [`ApplyDeferred`] sync points apply the deferred changes of the systems before them, so the systems after them see the changes.
+    /// Other [exclusive](SystemAccess::set_exclusive) systems are sync points too, they run alone on the calling thread
+    /// once the changes of the systems before them are applied, and their own deferred changes are applied right after them.
+    /// While stepping is enabled only the systems requested through [`Stepping`] run, one after another on the calling thread.
+    /// Errors of fallible systems go to the [error handler](Schedule::set_error_handler), which may abort the run.
+    pub fn run(&mut self, world: &mut World) {
+        self.initialize(world);
+        if self.stepping.is_some() {
+            self.run_stepping(world);
+            return;
+        }
+        let enabled: Vec<bool> = self
+            .systems
+            .iter()
+            .map(|config| config.should_run(world))
+            .collect();
+
+        // Every system runs either before or after each sync point, since sync points conflict with everything
+        let sync_points: Vec<bool> = (0..self.systems.len())
+            .map(|index| self.is_sync_point(index))
+            .collect();
+        let order = self.order.clone();
+        for segment in order.split_inclusive(|index| sync_points[*index]) {
+            let (sync_point, segment) = match segment.split_last() {
+                Some((last, rest)) if sync_points[*last] => (Some(*last), rest),
+                _ => (None, segment),
+            };
+            let aborted = match self.executor {
+                Executor::Sequential => segment
+                    .iter()
+                    .filter(|index| enabled[**index])
+                    .any(|index| self.run_system(*index, world) == OnError::Abort),
+                Executor::Parallel(threads) => self.run_parallel(world, threads, segment, &enabled),
+            };
+            for index in segment.iter().filter(|index| enabled[**index]) {
+                self.systems[*index].system.apply_deferred(world);
+            }
+            if aborted {
+                return;
+            }
+            if let Some(index) = sync_point.filter(|index| enabled[*index])
+                && self.run_sync_point(index, world) == OnError::Abort
+            {
+                return;
+            }
+        }
+    }
+
+    /// Runs a sync point with nothing running next to it, then applies its deferred changes.
+    pub(crate) fn run_sync_point(&mut self, index: usize, world: &mut World) -> OnError {
+        let on_error = self.run_system(index, world);
+        self.systems[index].system.apply_deferred(world);
+        on_error
+    }
+
+    /// Runs a system on the calling thread, passing its error to the error handler.
+    pub(crate) fn run_system(&mut self, index: usize, world: &World) -> OnError {
+        match self.systems[index].run(world) {
+            Ok(()) => OnError::Continue,
+            Err(error) => (self.error_handler.0)(error),
+        }
+    }
+
+    /// Sets the function called with the errors returned by fallible systems, e.g. [`log_error`](crate::error::log_error).
+    /// The default handler is [`panic_on_error`](crate::error::panic_on_error).
+    pub fn set_error_handler(
+        &mut self,
+        handler: impl FnMut(SystemError) -> OnError + Send + 'static,
+    ) -> &mut Self {
+        self.error_handler = ErrorHandler(Box::new(handler));
+        self
+    }
+
+    /// Returns `true` when the system is an [`ApplyDeferred`] sync point, or another system conflicting with every system.
+    pub(crate) fn is_sync_point(&self, index: usize) -> bool {
+        self.systems[index].system.access().is_exclusive()
+    }
+
+    /// Names of the systems in the order they run, valid after [`Schedule::initialize`].
+    pub fn order(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.order
+            .iter()
+            .map(|index| self.systems[*index].system.name())
+    }
+
+    /// Collects the explicit constraints and the implicit ones between conflicting systems, then sorts the systems topologically.
+    fn build(&mut self) {
+        let count = self.systems.len();
+        let mut edges = vec![BTreeSet::new(); count];
+        for (index, config) in self.systems.iter().enumerate() {
+            for (other, target) in self.systems.iter().enumerate() {
+                if config.before.contains(&target.label) {
+                    edges[other].insert(index);
+                }
+                if config.after.contains(&target.label) {
+                    edges[index].insert(other);
+                }
+            }
+        }
+        edges.iter_mut().enumerate().for_each(|(index, edges)| {
+            edges.remove(&index);
+        });
+
+        // `reaches[a][b]` is set when `b` has to run after `a`, kept transitive while implicit edges are added
+        let mut reaches = vec![vec![false; count]; count];
+        for (index, dependencies) in edges.iter().enumerate() {
+            for dependency in dependencies {
+                reaches[*dependency][index] = true;
+            }
+        }
+        for middle in 0..count {
+            let through = reaches[middle].clone();
+            for row in reaches.iter_mut().filter(|row| row[middle]) {
+                row.iter_mut()
+                    .zip(&through)
+                    .for_each(|(reached, through)| *reached |= through);
+            }
+        }
+        if let Some(index) = (0..count).find(|index| reaches[*index][*index]) {
+            panic!(
+                "System {} is part of a cycle of ordering constraints",
+                self.systems[index].system.name()
+            );
+        }
+
+        // Sync points are ordered first, so conflicting systems on different sides of one aren't ambiguous
+        self.ambiguities.clear();
+        for sync_pass in [true, false] {
+            for later in 0..count {
+                for earlier in 0..later {
+                    let sync = self.is_sync_point(earlier) || self.is_sync_point(later);
+                    let compatible = self.systems[earlier]
+                        .system
+                        .access()
+                        .is_compatible(self.systems[later].system.access());
+                    if sync != sync_pass
+                        || compatible
+                        || reaches[earlier][later]
+                        || reaches[later][earlier]
+                    {
+                        continue;
+                    }
+                    edges[later].insert(earlier);
+                    if !sync {
+                        self.ambiguities.push((earlier, later));
+                    }
+                    let before: Vec<_> = (0..count)
+                        .filter(|from| *from == earlier || reaches[*from][earlier])
+                        .collect();
+                    let after: Vec<_> = (0..count)
+                        .filter(|to| *to == later || reaches[later][*to])
+                        .collect();
+                    for from in &before {
+                        for to in &after {
+                            reaches[*from][*to] = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        // Kahn's algorithm, picking the earliest added system among the ready ones
+        let mut remaining: Vec<usize> = edges.iter().map(BTreeSet::len).collect();
+        let mut ready: BTreeSet<usize> =
+            (0..count).filter(|index| remaining[*index] == 0).collect();
+        self.order.clear();
+        while let Some(index) = ready.pop_first() {
+            self.order.push(index);
+            for (other, dependencies) in edges.iter().enumerate() {
+                if dependencies.contains(&index) {
+                    remaining[other] -= 1;
+                    if remaining[other] == 0 {
+                        ready.insert(other);
+                    }
+                }
+            }
+        }
+        self.dependencies = edges
+            .into_iter()
+            .map(|dependencies| dependencies.into_iter().collect())
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::{ApplyDeferred, Executor, IntoSystemConfig, Schedule};
+    use crate::{
+        error::SystemResult,
+        resource::Res,
+        system::{Commands, Query, System, SystemAccess},
+        world::{Component, World},
+    };
+
+    struct Plain;
+
+    impl Component for Plain {}
+
+    /// Names of the systems which ran, with the number of entities each of them saw.
+    #[derive(Default)]
+    struct Log(Mutex<Vec<(&'static str, usize)>>);
+
+    impl Log {
+        fn push(&self, name: &'static str, seen: usize) {
+            self.0.lock().unwrap().push((name, seen));
+        }
+
+        fn take(&self) -> Vec<(&'static str, usize)> {
+            std::mem::take(&mut self.0.lock().unwrap())
+        }
+    }
+
+    fn spawn_plain(mut commands: Commands, mut query: Query<&Plain>, log: Res<Log>) {
+        log.push("spawn", query.iter().count());
+        commands.spawn(Plain);
+    }
+
+    fn count_plain(mut query: Query<&Plain>, log: Res<Log>) {
+        log.push("count", query.iter().count());
+    }
+
+    /// Exclusive system spawning an entity as its deferred change.
+    #[derive(Default)]
+    struct Exclusive {
+        access: SystemAccess,
+    }
+
+    // SAFETY: The access is exclusive, so nothing runs next to the system
+    unsafe impl System for Exclusive {
+        fn name(&self) -> &'static str {
+            "exclusive"
+        }
+
+        fn access(&self) -> &SystemAccess {
+            &self.access
+        }
+
+        fn initialize(&mut self, _world: &mut World) {
+            self.access.set_exclusive();
+        }
+
+        fn run(&mut self, world: &World) -> SystemResult {
+            world
+                .get_resource::<Log>()
+                .unwrap()
+                .push("exclusive", world.entity_count());
+            Ok(())
+        }
+
+        fn apply_deferred(&mut self, world: &mut World) {
+            world.spawn(Plain);
+        }
+    }
+
+    #[test]
+    fn exclusive_systems_run_between_the_systems_they_separate() {
+        for executor in [Executor::Sequential, Executor::Parallel(2)] {
+            let mut world = World::new();
+            world.register_thread_safe::<Plain>();
+            world.insert_resource(Log::default());
+            let mut schedule = Schedule::new();
+            schedule
+                .set_executor(executor)
+                .add_system(spawn_plain)
+                .add_system(Exclusive::default())
+                .add_system(count_plain);
+
+            schedule.run(&mut world);
+            // The exclusive system sees the spawn deferred before it, and its own spawn is applied before the next system
+            assert_eq!(
+                world.get_resource::<Log>().unwrap().take(),
+                [("spawn", 0), ("exclusive", 1), ("count", 2)]
+            );
+        }
+    }
+
+    #[test]
+    fn stepping_runs_exclusive_systems() {
+        let mut world = World::new();
+        world.register_component::<Plain>();
+        world.insert_resource(Log::default());
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system(spawn_plain)
+            .add_system(Exclusive::default())
+            .add_system(count_plain);
+        schedule.enable_stepping().continue_frame();
+
+        schedule.run(&mut world);
+        assert_eq!(
+            world.get_resource::<Log>().unwrap().take(),
+            [("spawn", 0), ("exclusive", 1), ("count", 2)]
+        );
+    }
+
+    fn first(log: Res<Log>) {
+        log.push("first", 0);
+    }
+
+    fn middle(log: Res<Log>) {
+        log.push("middle", 0);
+    }
+
+    fn last(log: Res<Log>) {
+        log.push("last", 0);
+    }
+
+    fn absent() {}
+
+    fn grow_plain(mut query: Query<&mut Plain>, log: Res<Log>) {
+        log.push("grow", query.iter().count());
+    }
+
+    fn world_with_log() -> World {
+        let mut world = World::new();
+        world.register_thread_safe::<Plain>();
+        world.insert_resource(Log::default());
+        world
+    }
+
+    /// Names of the systems in the order they run, without their module path.
+    fn order(schedule: &Schedule) -> Vec<&'static str> {
+        schedule
+            .order()
+            .map(|name| name.rsplit("::").next().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn constraints_order_the_systems() {
+        let mut world = world_with_log();
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system(last.after(middle))
+            .add_system(middle.after(first))
+            .add_system(first.before(last));
+
+        schedule.initialize(&mut world);
+        assert_eq!(order(&schedule), ["first", "middle", "last"]);
+        for executor in [Executor::Sequential, Executor::Parallel(3)] {
+            schedule.set_executor(executor).run(&mut world);
+            let log = world.get_resource::<Log>().unwrap().take();
+            assert_eq!(log, [("first", 0), ("middle", 0), ("last", 0)]);
+        }
+    }
+
+    #[test]
+    fn conflicting_systems_run_in_the_order_they_were_added() {
+        let mut world = world_with_log();
+        world.spawn(Plain);
+
+        let mut added = Schedule::new();
+        added.add_systems((count_plain, grow_plain));
+        added.initialize(&mut world);
+        assert_eq!(order(&added), ["count_plain", "grow_plain"]);
+
+        // Constraints win over the order of addition
+        let mut constrained = Schedule::new();
+        constrained.add_systems((count_plain, grow_plain.before(count_plain)));
+        constrained.initialize(&mut world);
+        assert_eq!(order(&constrained), ["grow_plain", "count_plain"]);
+    }
+
+    #[test]
+    fn constraints_naming_missing_systems_are_ignored() {
+        let mut world = world_with_log();
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system(first.after(absent))
+            .add_system(last.before(absent));
+
+        schedule.run(&mut world);
+        let mut log = world.get_resource::<Log>().unwrap().take();
+        log.sort_unstable();
+        assert_eq!(log, [("first", 0), ("last", 0)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "is part of a cycle of ordering constraints")]
+    fn cyclic_constraints_panic() {
+        let mut world = world_with_log();
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system(first.after(last))
+            .add_system(middle.after(first))
+            .add_system(last.after(middle));
+        schedule.initialize(&mut world);
+    }
+
+    #[test]
+    fn systems_added_later_are_ordered_on_the_next_run() {
+        let mut world = world_with_log();
+        let mut schedule = Schedule::new();
+        schedule.add_system(last);
+        schedule.run(&mut world);
+        world.get_resource::<Log>().unwrap().take();
+
+        schedule.add_system(first.before(last));
+        schedule.run(&mut world);
+        assert_eq!(schedule.len(), 2);
+        assert_eq!(
+            world.get_resource::<Log>().unwrap().take(),
+            [("first", 0), ("last", 0)]
+        );
+    }
+
+    #[test]
+    fn sync_points_apply_the_changes_of_the_systems_before_them() {
+        for executor in [Executor::Sequential, Executor::Parallel(2)] {
+            let mut world = world_with_log();
+            let mut schedule = Schedule::new();
+            schedule
+                .set_executor(executor)
+                .add_systems((spawn_plain, ApplyDeferred, count_plain));
+
+            schedule.run(&mut world);
+            assert_eq!(
+                world.get_resource::<Log>().unwrap().take(),
+                [("spawn", 0), ("count", 1)]
+            );
+        }
+    }
+
+    #[test]
+    fn run_conditions_pass_systems_over() {
+        let mut world = world_with_log();
+        let mut schedule = Schedule::new();
+        schedule
+            .add_system(first.run_if(|world| world.entity_count() > 0))
+            .add_system(last.run_if(|world| world.entity_count() == 0));
+
+        schedule.run(&mut world);
+        assert_eq!(world.get_resource::<Log>().unwrap().take(), [("last", 0)]);
+        world.spawn(Plain);
+        schedule.run(&mut world);
+        assert_eq!(world.get_resource::<Log>().unwrap().take(), [("first", 0)]);
+    }
+}
@@ -0,0 +1,152 @@
+use std::cell::Cell;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::world::{Entity, EntityMapper};
+
+thread_local! {
+    static MAPPER: Cell<Option<*mut dyn EntityMapper>> = const { Cell::new(None) };
+}
+
+/// Restores the previously active mapper, also when the closure panics.
+struct Restore(Option<*mut dyn EntityMapper>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        MAPPER.with(|mapper| mapper.set(self.0));
+    }
+}
+
+/// Runs the closure with the mapper active on this thread, so every [`Entity`] deserialized by it is translated by the mapper.
+/// Components referencing other entities then round-trip through scenes and snapshots, e.g. `with_entity_mapper(&mut map, || serde_json::from_str(text))`.
+/// Calls can be nested, the innermost mapper is used.
+pub fn with_entity_mapper<R>(mapper: &mut dyn EntityMapper, f: impl FnOnce() -> R) -> R {
+    let mapper: *mut (dyn EntityMapper + '_) = mapper;
+    // SAFETY: The pointer is only used until the closure returns, the guard removes it from the thread local before the borrow ends
+    let mapper: *mut (dyn EntityMapper + 'static) = unsafe { std::mem::transmute(mapper) };
+
+    let _restore = Restore(MAPPER.with(|active| active.replace(Some(mapper))));
+    f()
+}
+
+/// Serialized as the bits of [`Entity::to_bits`].
+impl Serialize for Entity {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.to_bits())
+    }
+}
+
+/// Translated by the mapper of [`with_entity_mapper`] when one is active.
+impl<'de> Deserialize<'de> for Entity {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let entity = Entity::from_bits(u64::deserialize(deserializer)?);
+
+        // The mapper is taken out while it runs, so an entity deserialized by the mapper itself is not mapped again
+        let Some(mapper) = MAPPER.with(Cell::take) else {
+            return Ok(entity);
+        };
+        let _restore = Restore(Some(mapper));
+        Ok(unsafe {
+            (*mapper).map_entity(entity) // SAFETY: The mapper outlives the closure of `with_entity_mapper` which is running
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+
+    use serde::{
+        Deserialize,
+        de::{IntoDeserializer, value::Error},
+    };
+
+    use super::with_entity_mapper;
+    use crate::world::{Component, Entity, World};
+
+    struct Marker;
+
+    impl Component for Marker {}
+
+    fn entities() -> (Entity, Entity, Entity) {
+        let mut world = World::new();
+        (
+            world.spawn(Marker),
+            world.spawn(Marker),
+            world.spawn(Marker),
+        )
+    }
+
+    fn decode(entity: Entity) -> Entity {
+        Entity::deserialize(IntoDeserializer::<Error>::into_deserializer(
+            entity.to_bits(),
+        ))
+        .unwrap()
+    }
+
+    fn decode_all(entities: &[Entity]) -> Vec<Entity> {
+        let bits: Vec<_> = entities.iter().map(|entity| entity.to_bits()).collect();
+        Vec::<Entity>::deserialize(IntoDeserializer::<Error>::into_deserializer(bits)).unwrap()
+    }
+
+    #[test]
+    fn entities_pass_through_without_a_mapper() {
+        let (a, b, _) = entities();
+        assert_eq!(decode_all(&[a, b]), [a, b]);
+    }
+
+    #[test]
+    fn active_mappers_translate_every_entity() {
+        let (a, b, c) = entities();
+        let mut mapper = |entity: Entity| if entity == a { c } else { entity };
+
+        let decoded = with_entity_mapper(&mut mapper, || decode_all(&[a, b, a]));
+        assert_eq!(decoded, [c, b, c]);
+        // The mapper is only active inside the closure
+        assert_eq!(decode(a), a);
+    }
+
+    #[test]
+    fn nested_mappers_use_the_innermost_one() {
+        let (a, b, c) = entities();
+        let mut outer = |_: Entity| b;
+        let mut inner = |_: Entity| c;
+
+        let decoded = with_entity_mapper(&mut outer, || {
+            let nested = with_entity_mapper(&mut inner, || decode(a));
+            (nested, decode(a))
+        });
+        assert_eq!(decoded, (c, b));
+    }
+
+    #[test]
+    fn mappers_are_restored_when_the_closure_panics() {
+        let (a, b, c) = entities();
+        let mut outer = |_: Entity| b;
+        let mut failing = |_: Entity| c;
+
+        let decoded = with_entity_mapper(&mut outer, || {
+            let failed = catch_unwind(AssertUnwindSafe(|| {
+                with_entity_mapper(&mut failing, || panic!("decoding failed"))
+            }));
+            assert!(failed.is_err());
+            decode(a)
+        });
+        assert_eq!(decoded, b);
+        assert_eq!(decode(a), a);
+    }
+
+    #[test]
+    fn entities_decoded_by_the_mapper_are_not_mapped_again() {
+        let (a, b, c) = entities();
+        let mut calls = 0;
+        let mut mapper = |entity: Entity| {
+            calls += 1;
+            // Mappers may deserialize entities themselves, e.g. to look them up in a table
+            if entity == a { decode(b) } else { c }
+        };
+
+        assert_eq!(with_entity_mapper(&mut mapper, || decode(a)), b);
+        assert_eq!(calls, 1);
+    }
+}
@@ -0,0 +1,756 @@
+use std::{
+    collections::HashMap,
+    io::{self, Read, Write},
+};
+
+use crate::{
+    archetype::Archetype,
+    blob_data::ColumnGuard,
+    query::Filter,
+    world::{Component, ComponentId, Entity, EntityMap, World, WorldId},
+};
+
+/// Types that can be written into a byte stream by [`World::serialize`].
+/// All numbers are written in little endian and lengths are written as `u64`, so the output does not depend on the platform.
+pub trait Encode {
+    fn encode(&self, writer: &mut dyn Write) -> io::Result<()>;
+}
+
+/// Types that can be read back from a byte stream written by [`Encode`].
+pub trait Decode: Sized {
+    fn decode(reader: &mut dyn Read) -> io::Result<Self>;
+}
+
+macro_rules! impl_codec_for_number {
+    ($($T:ty),*) => {
+        $(
+            impl Encode for $T {
+                #[inline]
+                fn encode(&self, writer: &mut dyn Write) -> io::Result<()> {
+                    writer.write_all(&self.to_le_bytes())
+                }
+            }
+
+            impl Decode for $T {
+                #[inline]
+                fn decode(reader: &mut dyn Read) -> io::Result<Self> {
+                    let mut bytes = [0; std::mem::size_of::<$T>()];
+                    reader.read_exact(&mut bytes)?;
+                    Ok(<$T>::from_le_bytes(bytes))
+                }
+            }
+        )*
+    };
+}
+
+impl_codec_for_number!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl Encode for usize {
+    fn encode(&self, writer: &mut dyn Write) -> io::Result<()> {
+        (*self as u64).encode(writer)
+    }
+}
+
+impl Decode for usize {
+    fn decode(reader: &mut dyn Read) -> io::Result<Self> {
+        usize::try_from(u64::decode(reader)?)
+            .map_err(|_| invalid_data("value does not fit into usize"))
+    }
+}
+
+impl Encode for isize {
+    fn encode(&self, writer: &mut dyn Write) -> io::Result<()> {
+        (*self as i64).encode(writer)
+    }
+}
+
+impl Decode for isize {
+    fn decode(reader: &mut dyn Read) -> io::Result<Self> {
+        isize::try_from(i64::decode(reader)?)
+            .map_err(|_| invalid_data("value does not fit into isize"))
+    }
+}
+
+impl Encode for bool {
+    fn encode(&self, writer: &mut dyn Write) -> io::Result<()> {
+        (*self as u8).encode(writer)
+    }
+}
+
+impl Decode for bool {
+    fn decode(reader: &mut dyn Read) -> io::Result<Self> {
+        match u8::decode(reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(invalid_data("invalid bool")),
+        }
+    }
+}
+
+impl Encode for char {
+    fn encode(&self, writer: &mut dyn Write) -> io::Result<()> {
+        (*self as u32).encode(writer)
+    }
+}
+
+impl Decode for char {
+    fn decode(reader: &mut dyn Read) -> io::Result<Self> {
+        char::from_u32(u32::decode(reader)?).ok_or_else(|| invalid_data("invalid char"))
+    }
+}
+
+impl Encode for () {
+    fn encode(&self, _writer: &mut dyn Write) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Decode for () {
+    fn decode(_reader: &mut dyn Read) -> io::Result<Self> {
+        Ok(())
+    }
+}
+
+impl Encode for str {
+    fn encode(&self, writer: &mut dyn Write) -> io::Result<()> {
+        write_bytes(writer, self.as_bytes())
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, writer: &mut dyn Write) -> io::Result<()> {
+        self.as_str().encode(writer)
+    }
+}
+
+impl Decode for String {
+    fn decode(reader: &mut dyn Read) -> io::Result<Self> {
+        let bytes = read_bytes(reader)?;
+        String::from_utf8(bytes).map_err(|_| invalid_data("invalid utf-8 string"))
+    }
+}
+
+impl<T: Encode> Encode for [T] {
+    fn encode(&self, writer: &mut dyn Write) -> io::Result<()> {
+        self.len().encode(writer)?;
+        for item in self {
+            item.encode(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, writer: &mut dyn Write) -> io::Result<()> {
+        self.as_slice().encode(writer)
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(reader: &mut dyn Read) -> io::Result<Self> {
+        let len = usize::decode(reader)?;
+
+        // Do not trust the length for the allocation, a corrupted stream would abort the process
+        let mut items = Vec::with_capacity(len.min(1024));
+        for _ in 0..len {
+            items.push(T::decode(reader)?);
+        }
+        Ok(items)
+    }
+}
+
+impl<T: Encode, const N: usize> Encode for [T; N] {
+    fn encode(&self, writer: &mut dyn Write) -> io::Result<()> {
+        for item in self {
+            item.encode(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Decode, const N: usize> Decode for [T; N] {
+    fn decode(reader: &mut dyn Read) -> io::Result<Self> {
+        let mut items = Vec::with_capacity(N);
+        for _ in 0..N {
+            items.push(T::decode(reader)?);
+        }
+        // The vector has exactly N items
+        Ok(items.try_into().ok().unwrap())
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, writer: &mut dyn Write) -> io::Result<()> {
+        match self {
+            Some(value) => {
+                true.encode(writer)?;
+                value.encode(writer)
+            }
+            None => false.encode(writer),
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode(reader: &mut dyn Read) -> io::Result<Self> {
+        if bool::decode(reader)? {
+            Ok(Some(T::decode(reader)?))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl<T: Encode + ?Sized> Encode for Box<T> {
+    fn encode(&self, writer: &mut dyn Write) -> io::Result<()> {
+        (**self).encode(writer)
+    }
+}
+
+impl<T: Decode> Decode for Box<T> {
+    fn decode(reader: &mut dyn Read) -> io::Result<Self> {
+        Ok(Box::new(T::decode(reader)?))
+    }
+}
+
+impl Encode for Entity {
+    fn encode(&self, writer: &mut dyn Write) -> io::Result<()> {
+        self.index.encode(writer)?;
+        self.generation.encode(writer)
+    }
+}
+
+impl Decode for Entity {
+    fn decode(reader: &mut dyn Read) -> io::Result<Self> {
+        Ok(Entity::new(
+            usize::decode(reader)?,
+            usize::decode(reader)?,
+            WorldId::ANY,
+        ))
+    }
+}
+
+macro_rules! impl_codec_for_tuple {
+    ($($T:ident, $N:tt),+) => {
+        impl<$($T: Encode),+> Encode for ($($T,)+) {
+            fn encode(&self, writer: &mut dyn Write) -> io::Result<()> {
+                $(
+                    self.$N.encode(writer)?;
+                )+
+                Ok(())
+            }
+        }
+
+        impl<$($T: Decode),+> Decode for ($($T,)+) {
+            fn decode(reader: &mut dyn Read) -> io::Result<Self> {
+                Ok(($($T::decode(reader)?,)+))
+            }
+        }
+    };
+}
+
+impl_codec_for_tuple!(T0, 0);
+impl_codec_for_tuple!(T0, 0, T1, 1);
+impl_codec_for_tuple!(T0, 0, T1, 1, T2, 2);
+impl_codec_for_tuple!(T0, 0, T1, 1, T2, 2, T3, 3);
+impl_codec_for_tuple!(T0, 0, T1, 1, T2, 2, T3, 3, T4, 4);
+impl_codec_for_tuple!(T0, 0, T1, 1, T2, 2, T3, 3, T4, 4, T5, 5);
+
+/// Type-erased functions used to write and read a registered component.
+#[derive(Clone, Copy)]
+pub(crate) struct SerializeFns {
+    pub(crate) name: &'static str,
+    pub(crate) encode: unsafe fn(*const u8, &mut dyn Write) -> io::Result<()>,
+    pub(crate) decode: fn(&mut World, Entity, &mut dyn Read) -> io::Result<()>,
+    pub(crate) remove: fn(&mut World, Entity),
+}
+
+impl SerializeFns {
+    fn of<T: Component + Encode + Decode>() -> Self {
+        unsafe fn encode<T: Encode>(ptr: *const u8, writer: &mut dyn Write) -> io::Result<()> {
+            unsafe { (*ptr.cast::<T>()).encode(writer) }
+        }
+
+        fn decode<T: Component + Decode>(
+            world: &mut World,
+            entity: Entity,
+            reader: &mut dyn Read,
+        ) -> io::Result<()> {
+            let component = T::decode(reader)?;
+            world.insert_component(entity, component);
+            Ok(())
+        }
+
+        fn remove<T: Component>(world: &mut World, entity: Entity) {
+            world.remove_component::<T>(entity);
+        }
+
+        Self {
+            name: std::any::type_name::<T>(),
+            encode: encode::<T>,
+            decode: decode::<T>,
+            remove: remove::<T>,
+        }
+    }
+}
+
+/// Components which can be written by [`World::serialize`], keyed both by type and by the name stored in the stream.
+#[derive(Default, Clone)]
+pub(crate) struct Serializers {
+    by_type: HashMap<ComponentId, SerializeFns>,
+    by_name: HashMap<&'static str, ComponentId>,
+}
+
+impl Serializers {
+    pub(crate) fn register<T: Component + Encode + Decode>(&mut self) {
+        let fns = SerializeFns::of::<T>();
+        self.by_type.insert(ComponentId::of::<T>(), fns);
+        self.by_name.insert(fns.name, ComponentId::of::<T>());
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn get(&self, id: &ComponentId) -> Option<&SerializeFns> {
+        self.by_type.get(id)
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn by_name(&self, name: &str) -> Option<&SerializeFns> {
+        self.by_type.get(self.by_name.get(name)?)
+    }
+}
+
+/// Serializable column of an archetype, borrowed until it is dropped.
+pub(crate) struct SerializedColumn<'a> {
+    pub(crate) id: ComponentId,
+    pub(crate) fns: SerializeFns,
+    pub(crate) column: ColumnGuard<'a>,
+}
+
+impl SerializedColumn<'_> {
+    /// Replaces the contents of the buffer with the encoded value in the row.
+    pub(crate) fn encode(&self, row: usize, payload: &mut Vec<u8>) -> io::Result<()> {
+        assert!(row < self.column.len(), "Row is out of bounds");
+        payload.clear();
+        unsafe {
+            (self.fns.encode)(self.column.get_bytes(row), payload) // SAFETY: The row is within bounds and the function belongs to the column's type
+        }
+    }
+}
+
+impl World {
+    /// Borrows every serializable column of the archetype, sorted by component id.
+    /// Panics naming the action when a column is mutably borrowed, the columns borrowed before are released again.
+    pub(crate) fn serializable_columns<'a>(
+        &self,
+        archetype: &'a Archetype,
+        action: &str,
+    ) -> Vec<SerializedColumn<'a>> {
+        let mut columns: Vec<_> = archetype
+            .columns()
+            .filter_map(|(id, column)| Some((*id, *self.serializers.get(id)?, column)))
+            .collect();
+        columns.sort_unstable_by_key(|(id, _, _)| *id);
+        columns
+            .into_iter()
+            .map(|(id, fns, column)| SerializedColumn {
+                id,
+                fns,
+                column: ColumnGuard::new(column, action),
+            })
+            .collect()
+    }
+}
+
+/// Writes every row of an archetype, each column together with its position in the type table.
+fn write_rows(
+    writer: &mut dyn Write,
+    entities: &[Entity],
+    columns: &[(usize, SerializedColumn)],
+    payload: &mut Vec<u8>,
+) -> io::Result<()> {
+    for (row, entity) in entities.iter().enumerate() {
+        entity.encode(writer)?;
+        columns.len().encode(writer)?;
+
+        for (index, column) in columns {
+            column.encode(row, payload)?;
+            index.encode(writer)?;
+            write_bytes(writer, payload)?;
+        }
+    }
+    Ok(())
+}
+
+/// Magic bytes at the start of every serialized world.
+pub(crate) const MAGIC: &[u8; 4] = b"BECS";
+
+/// Version of the stream format, bumped whenever the layout changes.
+pub(crate) const FORMAT_VERSION: u32 = 1;
+
+/// Writes a length prefixed byte buffer.
+pub(crate) fn write_bytes(writer: &mut dyn Write, bytes: &[u8]) -> io::Result<()> {
+    bytes.len().encode(writer)?;
+    writer.write_all(bytes)
+}
+
+/// Reads a length prefixed byte buffer written by [`write_bytes`].
+pub(crate) fn read_bytes(reader: &mut dyn Read) -> io::Result<Vec<u8>> {
+    let len = usize::decode(reader)?;
+    let mut bytes = Vec::new();
+
+    // `take` stops at the declared length, so a corrupted length can not make us allocate more than the stream holds
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(bytes)
+}
+
+#[must_use]
+pub(crate) fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+impl World {
+    /// Registers a [`Component`] so it is written by [`World::serialize`] and can be read back by [`World::deserialize`].
+    /// Components which are not registered are skipped when serializing.
+    pub fn register_serializable<T: Component + Encode + Decode>(&mut self) {
+        self.register_component::<T>();
+        self.serializers.register::<T>();
+    }
+
+    /// Writes every entity with at least one component registered with [`World::register_serializable`] into the writer, only those components are written.
+    pub fn serialize(&self, writer: impl Write) -> io::Result<()> {
+        self.serialize_filtered::<()>(writer)
+    }
+
+    /// Writes only the entities matching the filter, e.g. `world.serialize_filtered::<With<Persistent>>(writer)`.
+    /// Only components registered with [`World::register_serializable`] are written, and only their types are listed in the stream,
+    /// so it can be read by a world registering just those. Entities without such components are skipped.
+    /// Panics with row filters like [`Changed`](crate::query::Changed).
+    pub fn serialize_filtered<F: Filter>(&self, mut writer: impl Write) -> io::Result<()> {
+        assert!(
+            !F::filters_rows(),
+            "Cannot serialize through a filter with row filters"
+        );
+        let writer = &mut writer as &mut dyn Write;
+
+        // The stream refers to components by their position in this table, which lists only the types being written
+        let mut types = HashMap::new();
+        let mut names = Vec::new();
+        let mut archetypes = Vec::new();
+        for archetype in self.matching_archetypes::<F>() {
+            if archetype.count() == 0 {
+                continue;
+            }
+            let columns: Vec<_> = self
+                .serializable_columns(archetype, "serialize")
+                .into_iter()
+                .map(|column| {
+                    let index = *types.entry(column.id).or_insert_with(|| {
+                        names.push(column.fns.name);
+                        names.len() - 1
+                    });
+                    (index, column)
+                })
+                .collect();
+            if !columns.is_empty() {
+                archetypes.push((archetype, columns));
+            }
+        }
+
+        writer.write_all(MAGIC)?;
+        FORMAT_VERSION.encode(writer)?;
+        names.len().encode(writer)?;
+        for name in &names {
+            name.encode(writer)?;
+        }
+
+        archetypes
+            .iter()
+            .map(|(archetype, _)| archetype.count())
+            .sum::<usize>()
+            .encode(writer)?;
+
+        let mut payload = Vec::new();
+        for (archetype, columns) in &archetypes {
+            write_rows(writer, archetype.entities(), columns, &mut payload)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads entities written by [`World::serialize`] or [`World::serialize_filtered`] and spawns them into this world.
+    /// Loaded entities get new ids, the returned map translates the ids stored in the stream into them.
+    /// Every component in the stream must be registered with [`World::register_serializable`]. On error no entities are spawned.
+    pub fn deserialize(&mut self, mut reader: impl Read) -> io::Result<EntityMap> {
+        let reader = &mut reader as &mut dyn Read;
+        let mut map = EntityMap::default();
+
+        let result = self.deserialize_inner(reader, &mut map);
+        if result.is_err() {
+            for (_, entity) in map.iter() {
+                self.despawn_entity(entity);
+            }
+        }
+        result.map(|_| map)
+    }
+
+    fn deserialize_inner(&mut self, reader: &mut dyn Read, map: &mut EntityMap) -> io::Result<()> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a serialized world"));
+        }
+
+        let version = u32::decode(reader)?;
+        if version != FORMAT_VERSION {
+            return Err(invalid_data(format!(
+                "unsupported format version {version}, expected {FORMAT_VERSION}"
+            )));
+        }
+
+        // Resolve the whole type table up front so an unknown component fails before anything is spawned
+        let type_count = usize::decode(reader)?;
+        let mut types = Vec::new();
+        for _ in 0..type_count {
+            let name = String::decode(reader)?;
+            let Some(fns) = self.serializers.by_name(&name) else {
+                return Err(invalid_data(format!(
+                    "component `{name}` is not registered as serializable"
+                )));
+            };
+            types.push(fns.decode);
+        }
+
+        let entity_count = usize::decode(reader)?;
+        for _ in 0..entity_count {
+            let saved = Entity::decode(reader)?;
+            let entity = self.spawn_empty();
+            map.insert(saved, entity);
+
+            let component_count = usize::decode(reader)?;
+            for _ in 0..component_count {
+                let index = usize::decode(reader)?;
+                let decode = types
+                    .get(index)
+                    .ok_or_else(|| invalid_data("component index out of range"))?;
+
+                let payload = read_bytes(reader)?;
+                let mut bytes = payload.as_slice();
+                decode(self, entity, &mut bytes)?;
+                if !bytes.is_empty() {
+                    return Err(invalid_data("component payload was not fully read"));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{self, Read, Write},
+        panic::{self, AssertUnwindSafe},
+    };
+
+    use super::{Decode, Encode};
+    use crate::{
+        query::{Changed, With},
+        world::{Component, Entity, World},
+    };
+
+    #[derive(Debug, PartialEq)]
+    struct Valid(u32);
+    struct Failing;
+    #[derive(Debug, PartialEq)]
+    struct Label(String);
+    struct Persistent;
+    struct Scratch;
+
+    impl Component for Valid {}
+    impl Component for Failing {}
+    impl Component for Label {}
+    impl Component for Persistent {}
+    impl Component for Scratch {}
+
+    impl Encode for Label {
+        fn encode(&self, writer: &mut dyn Write) -> io::Result<()> {
+            self.0.encode(writer)
+        }
+    }
+
+    impl Decode for Label {
+        fn decode(reader: &mut dyn Read) -> io::Result<Self> {
+            String::decode(reader).map(Label)
+        }
+    }
+
+    fn label(value: &str) -> Label {
+        Label(value.to_string())
+    }
+
+    /// Returns a world registering the serializable components, with a persistent entity, a scratch one, and one without any of them.
+    fn saved() -> (World, [Entity; 3]) {
+        let mut world = World::new();
+        world.register_serializable::<Valid>();
+        world.register_serializable::<Label>();
+        let persistent = world.spawn((Valid(1), label("kept"), Persistent));
+        let scratch = world.spawn((Valid(2), Scratch));
+        let plain = world.spawn(Persistent);
+        (world, [persistent, scratch, plain])
+    }
+
+    fn loading_world() -> World {
+        let mut world = World::new();
+        world.register_serializable::<Valid>();
+        world.register_serializable::<Label>();
+        world
+    }
+
+    impl Encode for Valid {
+        fn encode(&self, writer: &mut dyn Write) -> io::Result<()> {
+            self.0.encode(writer)
+        }
+    }
+
+    impl Decode for Valid {
+        fn decode(reader: &mut dyn Read) -> io::Result<Self> {
+            u32::decode(reader).map(Valid)
+        }
+    }
+
+    impl Encode for Failing {
+        fn encode(&self, _: &mut dyn Write) -> io::Result<()> {
+            Err(io::ErrorKind::Other.into())
+        }
+    }
+
+    impl Decode for Failing {
+        fn decode(_: &mut dyn Read) -> io::Result<Self> {
+            Ok(Failing)
+        }
+    }
+
+    #[test]
+    fn columns_are_released_when_encoding_panics() {
+        let mut world = World::new();
+        world.register_serializable::<Valid>();
+        world.register_serializable::<Failing>();
+        world.spawn((Valid(1), Failing));
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| world.push_checkpoint()));
+        assert!(result.is_err());
+
+        // Both columns can be borrowed mutably again
+        assert_eq!(world.query::<&mut Valid>().iter(&world).count(), 1);
+        assert_eq!(world.query::<&mut Failing>().iter(&world).count(), 1);
+    }
+
+    #[test]
+    fn columns_are_released_when_serializing_fails() {
+        let mut world = World::new();
+        world.register_serializable::<Valid>();
+        world.register_serializable::<Failing>();
+        world.spawn((Valid(1), Failing));
+
+        assert!(world.serialize(&mut Vec::new()).is_err());
+        assert_eq!(world.query::<&mut Valid>().iter(&world).count(), 1);
+        assert_eq!(world.query::<&mut Failing>().iter(&world).count(), 1);
+    }
+
+    #[test]
+    fn worlds_round_trip_their_serializable_components() {
+        let (world, [persistent, scratch, plain]) = saved();
+        let mut bytes = Vec::new();
+        world.serialize(&mut bytes).unwrap();
+
+        let mut loaded = loading_world();
+        let map = loaded.deserialize(bytes.as_slice()).unwrap();
+        // The entity without serializable components is skipped
+        assert_eq!(map.len(), 2);
+        assert!(map.get(plain).is_none());
+
+        let persistent = map.get(persistent).unwrap();
+        assert_eq!(loaded.get_component::<Valid>(persistent), Some(&Valid(1)));
+        assert_eq!(
+            loaded.get_component::<Label>(persistent),
+            Some(&label("kept"))
+        );
+        assert!(!loaded.has_component::<Persistent>(persistent));
+        let scratch = map.get(scratch).unwrap();
+        assert_eq!(loaded.get_component::<Valid>(scratch), Some(&Valid(2)));
+        assert!(!loaded.has_component::<Label>(scratch));
+    }
+
+    #[test]
+    fn filtered_worlds_write_only_the_matching_entities() {
+        let (mut world, [persistent, ..]) = saved();
+        let empty = world.spawn((Valid(3), Persistent, Scratch));
+        world.despawn_entity(empty);
+        let mut bytes = Vec::new();
+        world
+            .serialize_filtered::<With<Persistent>>(&mut bytes)
+            .unwrap();
+
+        let mut loaded = loading_world();
+        let map = loaded.deserialize(bytes.as_slice()).unwrap();
+        assert_eq!(
+            map.iter().map(|(saved, _)| saved).collect::<Vec<_>>(),
+            [persistent]
+        );
+        assert_eq!(loaded.entity_count(), 1);
+    }
+
+    #[test]
+    fn type_tables_list_only_the_written_types() {
+        let (world, _) = saved();
+        let mut bytes = Vec::new();
+        world
+            .serialize_filtered::<With<Scratch>>(&mut bytes)
+            .unwrap();
+
+        // The scratch entity has no label, so a world registering only values can load it
+        let mut loaded = World::new();
+        loaded.register_serializable::<Valid>();
+        let map = loaded.deserialize(bytes.as_slice()).unwrap();
+        assert_eq!(map.len(), 1);
+
+        bytes.clear();
+        world.serialize(&mut bytes).unwrap();
+        let error = loaded.deserialize(bytes.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(
+            error
+                .to_string()
+                .contains("is not registered as serializable")
+        );
+        // The failed load spawned nothing
+        assert_eq!(loaded.entity_count(), 1);
+    }
+
+    #[test]
+    fn streams_of_other_data_are_rejected() {
+        let mut loaded = loading_world();
+        let error = loaded.deserialize(&b"nope and more"[..]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        let (world, _) = saved();
+        let mut bytes = Vec::new();
+        world.serialize(&mut bytes).unwrap();
+        bytes.truncate(bytes.len() - 1);
+        assert!(loaded.deserialize(bytes.as_slice()).is_err());
+        assert_eq!(loaded.entity_count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot serialize through a filter with row filters")]
+    fn row_filters_are_rejected() {
+        let (world, _) = saved();
+        let _ = world.serialize_filtered::<Changed<Valid>>(&mut Vec::new());
+    }
+}
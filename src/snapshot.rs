@@ -0,0 +1,308 @@
+use std::sync::PoisonError;
+
+use crate::{
+    archetype::Archetype,
+    clone::{CloneError, CloneFns},
+    mask::ComponentMask,
+    world::{Component, ComponentId, Entities, Entity, Location, World},
+};
+
+/// Immutable copy of the entities and components of a [`World`], created by [`World::snapshot`] and returned to with [`World::restore`].
+pub struct WorldSnapshot {
+    pub(crate) archetypes: Vec<Archetype>,
+    pub(crate) entities: Entities,
+    change_tick: u64,
+}
+
+impl WorldSnapshot {
+    #[inline]
+    #[must_use]
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.entities.contains(entity)
+    }
+
+    /// Returns the `T` component of the entity at the time of the snapshot.
+    #[must_use]
+    pub fn get<T: Component>(&self, entity: Entity) -> Option<&T> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+
+        let location = self.entities.metas[entity.index].location;
+        self.archetypes.get(location.archetype)?.get(location.row)
+    }
+
+    #[must_use]
+    pub fn has<T: Component>(&self, entity: Entity) -> bool {
+        self.get::<T>(entity).is_some()
+    }
+
+    /// Iterates over every entity with the `T` component, archetype by archetype.
+    pub fn iter<T: Component>(&self) -> impl Iterator<Item = (Entity, &T)> {
+        self.archetypes
+            .iter()
+            .filter(|archetype| archetype.column(&ComponentId::of::<T>()).is_some())
+            .flat_map(|archetype| {
+                archetype
+                    .entities()
+                    .iter()
+                    .enumerate()
+                    .map(|(row, entity)| (*entity, archetype.get(row).unwrap()))
+            })
+    }
+
+    /// Iterates over every entity which was alive at the time of the snapshot.
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entities.alive()
+    }
+
+    /// Number of entities which were alive at the time of the snapshot.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Change tick of the world at the time of the snapshot.
+    #[inline]
+    #[must_use]
+    pub fn change_tick(&self) -> u64 {
+        self.change_tick
+    }
+}
+
+impl World {
+    /// Copies every entity and component together with the entity allocator, so [`World::restore`] can roll the world back to this point,
+    /// e.g. to resimulate predicted frames or to undo an editor operation. Timers, transient values and settings are not part of the snapshot.
+    ///
+    /// It also advances the change tick, so every later write is marked with a tick newer than [`WorldSnapshot::change_tick`].
+    ///
+    /// Fails without copying anything when the world contains components which are not registered with [`World::register_cloneable`].
+    pub fn snapshot(&mut self) -> Result<WorldSnapshot, CloneError> {
+        self.check_cloneable(self.archetypes())?;
+        let snapshot = self.snapshot_unchecked();
+        self.increment_change_tick();
+        Ok(snapshot)
+    }
+
+    /// Copies the world into a snapshot, every component must have a clone function.
+    pub(crate) fn snapshot_unchecked(&self) -> WorldSnapshot {
+        WorldSnapshot {
+            archetypes: self.clone_archetypes(),
+            entities: self.entities.clone(),
+            change_tick: self.change_tick(),
+        }
+    }
+
+    /// Replaces every entity and component with clones of the ones in the snapshot and restores the entity allocator,
+    /// so handles which were alive at the time of the snapshot are valid again and later ones are not.
+    /// Restored components keep their change ticks from the snapshot, the change tick of the world keeps counting.
+    ///
+    /// The snapshot may come from another world, e.g. a [`World::clone_world`] copy, as long as every component in it is registered here with a clone function.
+    /// Panics when that is not the case, before anything was changed.
+    pub fn restore(&mut self, snapshot: &WorldSnapshot) {
+        let plans: Vec<_> = snapshot
+            .archetypes
+            .iter()
+            .map(|archetype| {
+                let mut bitmask = ComponentMask::EMPTY;
+                let fns: Vec<(ComponentId, CloneFns)> = archetype
+                    .columns()
+                    .map(|(id, _)| {
+                        let info = self
+                            .component_info(id)
+                            .filter(|info| info.clone.is_some())
+                            .unwrap_or_else(|| {
+                                panic!("Cannot restore a component which is not registered as cloneable")
+                            });
+                        bitmask |= self.bit_of_id(id).unwrap();
+                        (*id, info.clone.unwrap())
+                    })
+                    .collect();
+                (bitmask, fns)
+            })
+            .collect();
+
+        for entity in self.entities.alive().collect::<Vec<_>>() {
+            self.record_despawned(entity);
+            self.log_despawn(entity);
+        }
+        for archetype in self.archetypes_mut() {
+            archetype.clear();
+        }
+        self.names.clear();
+        self.deferred_despawns
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+
+        let id = self.id();
+        let mut indices = vec![usize::MAX; snapshot.archetypes.len()];
+        for ((bitmask, fns), (source, index)) in plans
+            .into_iter()
+            .zip(snapshot.archetypes.iter().zip(&mut indices))
+        {
+            // Archetypes which were empty at the time of the snapshot are not needed
+            if source.count() == 0 {
+                continue;
+            }
+
+            *index = self.archetype_index(&bitmask);
+            let target = &mut self.archetypes_mut()[*index];
+            for (type_id, fns) in fns {
+                let column = source.column(&type_id).unwrap();
+                target.with(type_id, *column.type_info());
+                (fns.clone_column)(column, target.column_mut(&type_id).unwrap());
+            }
+            for entity in source.entities() {
+                target.insert_row(Entity::new(entity.index, entity.generation, id));
+            }
+        }
+
+        self.entities.metas.clone_from(&snapshot.entities.metas);
+        self.entities.free.clone_from(&snapshot.entities.free);
+        self.entities.retired = snapshot.entities.retired;
+        for meta in &mut self.entities.metas {
+            if meta.location != Location::EMPTY {
+                meta.location.archetype = indices[meta.location.archetype];
+            }
+        }
+
+        for entity in self.entities.alive().collect::<Vec<_>>() {
+            if let Some(bitmask) = self
+                .archetype_of(entity)
+                .map(|archetype| archetype.bitmask().clone())
+            {
+                self.index_spawned_name(entity, &bitmask);
+            }
+            self.log_spawn(entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{self, AssertUnwindSafe};
+
+    use crate::world::{Component, World};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Health(u32);
+    #[derive(Debug, Clone, PartialEq)]
+    struct Label(String);
+    #[derive(Debug, PartialEq)]
+    struct Opaque;
+
+    impl Component for Health {}
+    impl Component for Label {}
+    impl Component for Opaque {}
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.register_cloneable::<Health>();
+        world.register_cloneable::<Label>();
+        world
+    }
+
+    #[test]
+    fn restore_rolls_components_and_entities_back() {
+        let mut world = world();
+        let kept = world.spawn((Health(10), Label("kept".into())));
+        let despawned = world.spawn(Health(3));
+        let snapshot = world.snapshot().unwrap();
+
+        world.get_component_mut::<Health>(kept).unwrap().0 = 1;
+        world.remove_component::<Label>(kept);
+        world.despawn_entity(despawned);
+        let spawned = world.spawn(Label("new".into()));
+
+        world.restore(&snapshot);
+        assert_eq!(world.get_component::<Health>(kept), Some(&Health(10)));
+        assert_eq!(
+            world.get_component::<Label>(kept),
+            Some(&Label("kept".into()))
+        );
+        assert!(world.is_alive(despawned));
+        assert_eq!(world.get_component::<Health>(despawned), Some(&Health(3)));
+        if spawned != despawned {
+            assert!(!world.is_alive(spawned));
+        }
+        assert_eq!(world.iter_entities().count(), 2);
+        assert_eq!(world.query::<&Label>().iter(&world).count(), 1);
+    }
+
+    #[test]
+    fn restore_keeps_the_snapshot_reusable() {
+        let mut world = world();
+        let entity = world.spawn(Label("start".into()));
+        let snapshot = world.snapshot().unwrap();
+
+        for round in 0..3 {
+            world.get_component_mut::<Label>(entity).unwrap().0 = format!("round {round}");
+            world.spawn(Health(round));
+            world.restore(&snapshot);
+            assert_eq!(
+                world.get_component::<Label>(entity),
+                Some(&Label("start".into()))
+            );
+            assert_eq!(world.iter_entities().count(), 1);
+        }
+        assert_eq!(snapshot.get::<Label>(entity), Some(&Label("start".into())));
+    }
+
+    #[test]
+    fn restore_keeps_change_ticks_of_the_snapshot() {
+        let mut world = world();
+        let entity = world.spawn(Health(1));
+        let ticks = world.component_ticks::<Health>(entity).unwrap();
+        let snapshot = world.snapshot().unwrap();
+
+        world.get_component_mut::<Health>(entity).unwrap().0 = 2;
+        assert_ne!(world.component_ticks::<Health>(entity), Some(ticks));
+        world.restore(&snapshot);
+        assert_eq!(world.component_ticks::<Health>(entity), Some(ticks));
+    }
+
+    #[test]
+    fn snapshots_can_be_restored_into_a_clone() {
+        let mut world = world();
+        let entity = world.spawn((Health(5), Label("shared".into())));
+        let snapshot = world.snapshot().unwrap();
+
+        let mut copy = world.clone_world().unwrap();
+        copy.despawn_entity(entity);
+        copy.restore(&snapshot);
+        assert_eq!(
+            copy.get_component::<Label>(entity),
+            Some(&Label("shared".into()))
+        );
+    }
+
+    #[test]
+    fn restore_of_unknown_components_panics_before_changing_anything() {
+        let mut world = world();
+        let entity = world.spawn(Health(1));
+        let snapshot = world.snapshot().unwrap();
+
+        let mut other = World::new();
+        let existing = other.spawn(Opaque);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| other.restore(&snapshot)));
+        assert!(result.is_err());
+        assert!(other.is_alive(existing));
+        assert!(other.has_component::<Opaque>(existing));
+        assert_eq!(world.get_component::<Health>(entity), Some(&Health(1)));
+    }
+
+    #[test]
+    fn snapshot_fails_on_components_which_are_not_cloneable() {
+        let mut world = world();
+        world.spawn((Health(1), Opaque));
+        assert!(world.snapshot().is_err());
+    }
+}
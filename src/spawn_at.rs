@@ -0,0 +1,73 @@
+use std::fmt;
+
+use crate::{
+    bundle::Bundle,
+    quota::QuotaError,
+    world::{Entity, MAX_GENERATION, World},
+};
+
+/// Returned by [`World::spawn_at`] when the given id can't become alive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpawnAtError {
+    /// The slot of the id is used by the alive entity.
+    Occupied(Entity),
+    /// The slot of the id is retired or the generation is above [`MAX_GENERATION`].
+    Retired,
+    /// Spawning would exceed a quota.
+    Quota(QuotaError),
+}
+
+impl fmt::Display for SpawnAtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpawnAtError::Occupied(entity) => write!(f, "slot is occupied by {entity:?}"),
+            SpawnAtError::Retired => write!(f, "slot is retired"),
+            SpawnAtError::Quota(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SpawnAtError {}
+
+impl From<QuotaError> for SpawnAtError {
+    fn from(error: QuotaError) -> Self {
+        SpawnAtError::Quota(error)
+    }
+}
+
+impl World {
+    /// Spawns an entity with exactly the given index and generation, e.g. on a client mirroring the ids assigned by a server.
+    /// The slots are extended when the index is past the last one, skipped slots become free.
+    /// Returns the entity, which belongs to this world even when the given id was decoded with [`Entity::from_bits`].
+    pub fn spawn_at<B: Bundle>(
+        &mut self,
+        entity: Entity,
+        bundle: B,
+    ) -> Result<Entity, SpawnAtError> {
+        if let Some(occupant) = self.entity_in_slot(entity.index) {
+            return Err(SpawnAtError::Occupied(occupant));
+        }
+        if entity.generation > MAX_GENERATION {
+            return Err(SpawnAtError::Retired);
+        }
+
+        B::register(self);
+        let index = self.archetype_index(&B::bitmask(self));
+        self.check_quota(Some(index))?;
+        if !self.entities.alloc_at(entity) {
+            return Err(SpawnAtError::Retired);
+        }
+
+        let entity = Entity::new(entity.index, entity.generation, self.id());
+        self.put_in_archetype(entity, bundle, index);
+        Ok(entity)
+    }
+
+    /// Returns the alive entity stored in the slot with the given index.
+    fn entity_in_slot(&self, index: usize) -> Option<Entity> {
+        let meta = self.entities.metas.get(index)?;
+        let entity = Entity::new(index, meta.generation, self.id());
+        // Free slots have the generation of their next entity, the liveness check rejects them
+        self.is_alive(entity).then_some(entity)
+    }
+}
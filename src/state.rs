@@ -0,0 +1,180 @@
+use std::{collections::HashMap, hash::Hash};
+
+use crate::{
+    schedule::{IntoSystemConfig, Schedule},
+    world::World,
+};
+
+/// Values usable as states, implemented for every `Eq + Hash + Clone` type which can be shared between threads.
+pub trait StateValue: Eq + Hash + Clone + Send + Sync + 'static {}
+
+impl<S: Eq + Hash + Clone + Send + Sync + 'static> StateValue for S {}
+
+/// The current state of type `S`, stored as a resource by [`World::insert_state`].
+/// Changes requested with [`States::set`] take effect in [`World::apply_state_transition`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct States<S> {
+    current: S,
+    next: Option<S>,
+    /// Whether the schedule entering the current state already ran.
+    entered: bool,
+}
+
+impl<S: StateValue> States<S> {
+    #[inline]
+    #[must_use]
+    pub fn current(&self) -> &S {
+        &self.current
+    }
+
+    /// The state requested by [`States::set`] which the next transition switches to.
+    #[inline]
+    #[must_use]
+    pub fn next(&self) -> Option<&S> {
+        self.next.as_ref()
+    }
+
+    /// Requests a switch to the state, replacing an earlier request. Requesting the current state does nothing.
+    pub fn set(&mut self, next: S) {
+        self.next = Some(next);
+    }
+}
+
+/// Schedule run by [`World::apply_state_transition`] when the state is entered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OnEnter<S>(pub S);
+
+/// Schedule run by [`World::apply_state_transition`] when the state is left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OnExit<S>(pub S);
+
+/// The [`OnEnter`] and [`OnExit`] schedules of states of type `S`, stored as a resource by [`World::insert_state`].
+pub struct StateSchedules<S> {
+    enter: HashMap<S, Schedule>,
+    exit: HashMap<S, Schedule>,
+}
+
+impl<S> Default for StateSchedules<S> {
+    fn default() -> Self {
+        Self {
+            enter: HashMap::new(),
+            exit: HashMap::new(),
+        }
+    }
+}
+
+/// Labels of the schedules of [`StateSchedules`], implemented by [`OnEnter`] and [`OnExit`].
+pub trait StateSchedule {
+    type State: StateValue;
+
+    #[doc(hidden)]
+    fn schedule(self, schedules: &mut StateSchedules<Self::State>) -> &mut Schedule;
+}
+
+impl<S: StateValue> StateSchedule for OnEnter<S> {
+    type State = S;
+
+    fn schedule(self, schedules: &mut StateSchedules<S>) -> &mut Schedule {
+        schedules.enter.entry(self.0).or_default()
+    }
+}
+
+impl<S: StateValue> StateSchedule for OnExit<S> {
+    type State = S;
+
+    fn schedule(self, schedules: &mut StateSchedules<S>) -> &mut Schedule {
+        schedules.exit.entry(self.0).or_default()
+    }
+}
+
+/// Run condition holding while the current state of its type is `state`, e.g. `movement.run_if(in_state(Screen::Game))`.
+pub fn in_state<S: StateValue>(state: S) -> impl Fn(&World) -> bool + Send + Sync + 'static {
+    move |world| {
+        world
+            .get_resource::<States<S>>()
+            .is_some_and(|states| states.current == state)
+    }
+}
+
+impl World {
+    /// Inserts the [`States`] resource starting in the initial state, whose [`OnEnter`] schedule runs on the next transition.
+    /// Schedules added earlier are kept.
+    pub fn insert_state<S: StateValue>(&mut self, initial: S) {
+        self.insert_resource(States {
+            current: initial,
+            next: None,
+            entered: false,
+        });
+        if !self.contains_resource::<StateSchedules<S>>() {
+            self.insert_resource(StateSchedules::<S>::default());
+        }
+    }
+
+    /// Returns a clone of the current state of the type, `None` when it wasn't inserted.
+    #[must_use]
+    pub fn state<S: StateValue>(&self) -> Option<S> {
+        self.get_resource::<States<S>>()
+            .map(|states| states.current.clone())
+    }
+
+    /// Requests a switch to the state like [`States::set`]. Panics when the state type wasn't inserted.
+    pub fn set_state<S: StateValue>(&mut self, next: S) {
+        self.get_resource_mut::<States<S>>()
+            .expect("State wasn't inserted with World::insert_state")
+            .set(next);
+    }
+
+    /// Adds a system to the [`OnEnter`] or [`OnExit`] schedule of a state, e.g. `world.add_state_system(OnEnter(Screen::Menu), spawn_menu)`.
+    pub fn add_state_system<L: StateSchedule, M>(
+        &mut self,
+        label: L,
+        system: impl IntoSystemConfig<M>,
+    ) {
+        if !self.contains_resource::<StateSchedules<L::State>>() {
+            self.insert_resource(StateSchedules::<L::State>::default());
+        }
+        let mut schedules = self.get_resource_mut::<StateSchedules<L::State>>().unwrap();
+        label.schedule(&mut schedules).add_system(system);
+    }
+
+    /// Switches to the requested state of the type, running the [`OnExit`] schedule of the current state,
+    /// then the [`OnEnter`] schedule of the new one. The first transition runs the [`OnEnter`] schedule of the initial state.
+    /// Returns `true` when a schedule ran or the state changed. Switches requested by the schedules are left for the next transition.
+    pub fn apply_state_transition<S: StateValue>(&mut self) -> bool {
+        let (exit, enter) = {
+            let Some(mut states) = self.get_resource_mut::<States<S>>() else {
+                return false;
+            };
+            if !states.entered {
+                states.entered = true;
+                if let Some(next) = states.next.take() {
+                    states.current = next;
+                }
+                (None, states.current.clone())
+            } else {
+                match states.next.take() {
+                    Some(next) if next != states.current => (Some(states.current.clone()), next),
+                    _ => return false,
+                }
+            }
+        };
+
+        if let Some(exit) = exit {
+            self.run_state_schedule(OnExit(exit));
+            if let Some(mut states) = self.get_resource_mut::<States<S>>() {
+                states.current = enter.clone();
+            }
+        }
+        self.run_state_schedule(OnEnter(enter));
+        true
+    }
+
+    /// Runs the schedule of a state if it has one. The schedules are taken out of the world while it runs.
+    fn run_state_schedule<L: StateSchedule>(&mut self, label: L) {
+        let Some(mut schedules) = self.remove_resource::<StateSchedules<L::State>>() else {
+            return;
+        };
+        label.schedule(&mut schedules).run(self);
+        self.insert_resource(schedules);
+    }
+}
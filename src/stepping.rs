@@ -0,0 +1,180 @@
+use std::mem;
+
+use crate::{
+    error::OnError,
+    schedule::{Schedule, SystemLabel},
+    system::IntoSystem,
+    world::World,
+};
+
+/// What the next run of a stepped schedule does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Action {
+    /// Nothing runs, the schedule is paused.
+    #[default]
+    Wait,
+    /// The next system runs.
+    Step,
+    /// The systems left in the frame run.
+    Continue,
+}
+
+/// Debugging controller of a [`Schedule`], enabled with [`Schedule::enable_stepping`].
+///
+/// While stepping is enabled the schedule is paused and [`Schedule::run`] only runs what was requested since the previous run,
+/// a single system with [`Stepping::step_system`] or the rest of the frame with [`Stepping::continue_frame`].
+/// A frame is one pass through the systems in order, skipped systems are passed over without running.
+#[derive(Debug, Default)]
+pub struct Stepping {
+    /// Position in the order of the system the frame continues with.
+    pub(crate) cursor: usize,
+    action: Action,
+    skipped: Vec<SystemLabel>,
+}
+
+impl Stepping {
+    /// Runs the next system which isn't skipped on the next [`Schedule::run`], starting a new frame after the last one.
+    pub fn step_system(&mut self) -> &mut Self {
+        self.action = Action::Step;
+        self
+    }
+
+    /// Runs the systems left in the current frame on the next [`Schedule::run`], then pauses at the start of the next frame.
+    pub fn continue_frame(&mut self) -> &mut Self {
+        self.action = Action::Continue;
+        self
+    }
+
+    /// Drops the requested step, the next [`Schedule::run`] runs nothing.
+    pub fn pause(&mut self) -> &mut Self {
+        self.action = Action::Wait;
+        self
+    }
+
+    /// Passes over every system created from `system` until it is unskipped.
+    pub fn skip_system<M, S: IntoSystem<M>>(&mut self, _system: S) -> &mut Self {
+        let label = SystemLabel::of::<M, S>();
+        if !self.skipped.contains(&label) {
+            self.skipped.push(label);
+        }
+        self
+    }
+
+    /// Runs the systems created from `system` again.
+    pub fn unskip_system<M, S: IntoSystem<M>>(&mut self, _system: S) -> &mut Self {
+        let label = SystemLabel::of::<M, S>();
+        self.skipped.retain(|skipped| *skipped != label);
+        self
+    }
+
+    /// Returns `true` when the current frame hasn't run any system yet.
+    #[inline]
+    #[must_use]
+    pub fn is_frame_start(&self) -> bool {
+        self.cursor == 0
+    }
+}
+
+impl Schedule {
+    /// Pauses the schedule and returns its stepping controller, see [`Stepping`]. The controller is kept when stepping already was enabled.
+    pub fn enable_stepping(&mut self) -> &mut Stepping {
+        self.stepping.get_or_insert_with(Stepping::default)
+    }
+
+    /// Removes the stepping controller, the next [`Schedule::run`] runs every system again. A partly stepped frame is abandoned.
+    pub fn disable_stepping(&mut self) {
+        self.stepping = None;
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn stepping(&self) -> Option<&Stepping> {
+        self.stepping.as_ref()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn stepping_mut(&mut self) -> Option<&mut Stepping> {
+        self.stepping.as_mut()
+    }
+
+    /// Name of the system the next step runs, `None` when stepping isn't enabled or every system is skipped.
+    /// Valid after [`Schedule::initialize`].
+    #[must_use]
+    pub fn next_system(&self) -> Option<&'static str> {
+        let position = self.next_position()?;
+        Some(self.systems[self.order[position]].system.name())
+    }
+
+    /// Position in the order of the next system which isn't skipped, wrapping around to the next frame.
+    fn next_position(&self) -> Option<usize> {
+        let stepping = self.stepping.as_ref()?;
+        let cursor = stepping.cursor.min(self.order.len());
+        (cursor..self.order.len())
+            .chain(0..cursor)
+            .find(|position| !self.is_skipped(stepping, *position))
+    }
+
+    fn is_skipped(&self, stepping: &Stepping, position: usize) -> bool {
+        let label = self.systems[self.order[position]].label;
+        stepping.skipped.contains(&label)
+    }
+
+    /// Applies the deferred changes of the systems in the order they ran.
+    fn apply_pending(&mut self, world: &mut World, ran: &mut Vec<usize>) {
+        for index in ran.drain(..) {
+            self.systems[index].system.apply_deferred(world);
+        }
+    }
+
+    /// Runs what the stepping controller requested, applying the deferred changes of the systems which ran at sync points and at the end.
+    pub(crate) fn run_stepping(&mut self, world: &mut World) {
+        let Some(stepping) = &mut self.stepping else {
+            return;
+        };
+        let action = mem::take(&mut stepping.action);
+        let cursor = stepping.cursor;
+
+        let selected: Vec<usize> = match action {
+            Action::Wait => Vec::new(),
+            Action::Step => self.next_position().into_iter().collect(),
+            Action::Continue => {
+                let stepping = self.stepping.as_ref().unwrap();
+                (cursor.min(self.order.len())..self.order.len())
+                    .filter(|position| !self.is_skipped(stepping, *position))
+                    .collect()
+            }
+        };
+        // Systems whose conditions don't hold count as stepped over
+        let ran: Vec<usize> = selected
+            .iter()
+            .copied()
+            .filter(|position| self.systems[self.order[*position]].should_run(world))
+            .collect();
+        let mut pending = Vec::new();
+        for position in ran {
+            let index = self.order[position];
+            let on_error = if self.is_sync_point(index) {
+                self.apply_pending(world, &mut pending);
+                self.run_sync_point(index, world)
+            } else {
+                pending.push(index);
+                self.run_system(index, world)
+            };
+            if on_error == OnError::Abort {
+                break;
+            }
+        }
+        self.apply_pending(world, &mut pending);
+
+        let stepping = self.stepping.as_mut().unwrap();
+        stepping.cursor = match action {
+            Action::Wait => cursor,
+            Action::Step => selected.first().map_or(cursor, |position| position + 1),
+            Action::Continue => 0,
+        };
+        if stepping.cursor >= self.order.len() {
+            stepping.cursor = 0;
+        }
+    }
+}
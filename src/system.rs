@@ -0,0 +1,771 @@
+use std::{
+    any::type_name,
+    collections::BTreeMap,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+pub use becs_macros::SystemParam;
+
+use crate::{
+    chunks::{ChunkItem, ChunkIter},
+    commands::CommandBuffer,
+    error::{BoxedError, SystemResult},
+    query::{Access, Filter, QueryData, QueryGuard, QueryItem, QueryIter},
+    resource::{Res, ResMut},
+    world::{ComponentId, Entity, World},
+};
+
+/// Components and resources accessed by a system, used to find the systems which can run at the same time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SystemAccess {
+    components: Access,
+    resources: Access,
+    /// Type names of the resources, by the [`ComponentId`] of their type.
+    resource_names: BTreeMap<ComponentId, &'static str>,
+    exclusive: bool,
+    main_thread: bool,
+}
+
+impl SystemAccess {
+    #[inline]
+    #[must_use]
+    pub fn components(&self) -> &Access {
+        &self.components
+    }
+
+    /// Resources of the system, keyed by the [`ComponentId`] of their type.
+    #[inline]
+    #[must_use]
+    pub fn resources(&self) -> &Access {
+        &self.resources
+    }
+
+    /// Adds the components accessed by a query. Queries of the same system may overlap, their columns are borrowed while iterated.
+    pub fn add_components(&mut self, access: &Access) {
+        self.components.extend(access);
+    }
+
+    /// Adds a resource which is read. Panics when another parameter of the system writes it, since both are borrowed for the whole run.
+    pub fn add_resource_read<R: 'static>(&mut self) {
+        let mut access = Access::default();
+        access.add_read(ComponentId::of::<R>());
+        self.add_resources::<R>(&access);
+    }
+
+    /// Adds a resource which is written. Panics when another parameter of the system accesses it.
+    pub fn add_resource_write<R: 'static>(&mut self) {
+        let mut access = Access::default();
+        access.add_write(ComponentId::of::<R>());
+        self.add_resources::<R>(&access);
+    }
+
+    fn add_resources<R: 'static>(&mut self, access: &Access) {
+        assert!(
+            self.resources.is_compatible(access),
+            "Resource {} is borrowed by several parameters of one system and written by one of them",
+            type_name::<R>()
+        );
+        self.resources.extend(access);
+        self.resource_names
+            .insert(ComponentId::of::<R>(), type_name::<R>());
+    }
+
+    /// Type name of a resource of the system.
+    #[must_use]
+    pub fn resource_name(&self, id: ComponentId) -> Option<&'static str> {
+        self.resource_names.get(&id).copied()
+    }
+
+    /// Marks the system as accessing the whole world, so it conflicts with every other system, like [`ApplyDeferred`](crate::schedule::ApplyDeferred).
+    /// Schedules run it alone as a sync point, after applying the deferred changes of the systems before it.
+    pub fn set_exclusive(&mut self) {
+        self.exclusive = true;
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_exclusive(&self) -> bool {
+        self.exclusive
+    }
+
+    /// Marks the system as accessing data which can't leave the thread calling [`Schedule::run`](crate::schedule::Schedule::run),
+    /// like a [`NonSend`] resource, so it always runs on that thread.
+    pub fn set_main_thread(&mut self) {
+        self.main_thread = true;
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_main_thread(&self) -> bool {
+        self.main_thread
+    }
+
+    /// Adds everything accessed by the other access.
+    pub fn extend(&mut self, other: &SystemAccess) {
+        self.components.extend(&other.components);
+        self.resources.extend(&other.resources);
+        self.resource_names.extend(&other.resource_names);
+        self.exclusive |= other.exclusive;
+        self.main_thread |= other.main_thread;
+    }
+
+    /// Returns `true` when neither access is exclusive or writes a component or resource the other one accesses.
+    #[must_use]
+    pub fn is_compatible(&self, other: &SystemAccess) -> bool {
+        !self.exclusive
+            && !other.exclusive
+            && self.components.is_compatible(&other.components)
+            && self.resources.is_compatible(&other.resources)
+    }
+}
+
+/// Parameter of a function system fetched from the world on every run, like [`Query`], [`Res`] and [`ResMut`].
+/// Tuples of parameters are parameters too, and structs of parameters with `#[derive(SystemParam)]`.
+///
+/// # Safety
+/// The access added by [`SystemParam::init`] must cover everything the fetched item touches, since systems with compatible access
+/// run at the same time on different threads. Components are only touched through their borrow flags.
+pub unsafe trait SystemParam {
+    /// State kept by the system between runs, created once when the system is initialized.
+    type State: Send + 'static;
+    /// The parameter handed to the system, borrowing the world for `'w` and the state for `'s`.
+    type Item<'w, 's>;
+
+    /// Creates the state and adds everything the parameter accesses.
+    fn init(world: &mut World, access: &mut SystemAccess) -> Self::State;
+
+    /// Fetches the parameter for one run. Panics when its data is missing or borrowed in a conflicting way.
+    fn fetch<'w, 's>(state: &'s mut Self::State, world: &'w World) -> Self::Item<'w, 's>;
+
+    /// Applies the changes deferred by the previous runs, like the commands recorded with [`Commands`].
+    #[inline]
+    fn apply(_state: &mut Self::State, _world: &mut World) {}
+}
+
+/// The item of the parameter `P`, as received by a system.
+pub type SystemParamItem<'w, 's, P> = <P as SystemParam>::Item<'w, 's>;
+
+/// Query parameter of a system, keeping its cache and the tick of its previous run between runs of the system,
+/// e.g. `fn movement(mut query: Query<(&mut Position, &Velocity)>)`.
+pub struct Query<'w, 's, Q: QueryItem, F: Filter = ()> {
+    data: &'s mut QueryData<Q, F>,
+    world: &'w World,
+}
+
+impl<'w, Q: QueryItem, F: Filter> Query<'w, '_, Q, F> {
+    /// Iterates over every matching item, like [`QueryData::iter`].
+    pub fn iter(&mut self) -> QueryIter<'_, Q, F> {
+        self.data.iter(self.world)
+    }
+
+    /// Fetches the item of a single entity, like [`QueryData::get`].
+    pub fn get(&mut self, entity: Entity) -> Option<QueryGuard<'_, Q>> {
+        self.data.get(self.world, entity)
+    }
+
+    /// Iterates over the matching archetypes as slices, like [`QueryData::iter_chunks`].
+    pub fn iter_chunks(&mut self) -> ChunkIter<'_, Q>
+    where
+        Q: ChunkItem,
+    {
+        self.data.iter_chunks(self.world)
+    }
+
+    /// The state of the query, e.g. for the tick of its previous iteration.
+    #[inline]
+    pub fn data(&mut self) -> &mut QueryData<Q, F> {
+        self.data
+    }
+}
+
+// SAFETY: The items and the change ticks read by the filter are added, and the columns are borrowed while iterated
+unsafe impl<Q: QueryItem + 'static, F: Filter + 'static> SystemParam for Query<'_, '_, Q, F> {
+    type State = QueryData<Q, F>;
+    type Item<'w, 's> = Query<'w, 's, Q, F>;
+
+    fn init(world: &mut World, access: &mut SystemAccess) -> Self::State {
+        let mut components = Access::of::<Q>();
+        F::filter_access(&mut components);
+        access.add_components(&components);
+        QueryData::new(world)
+    }
+
+    fn fetch<'w, 's>(state: &'s mut Self::State, world: &'w World) -> Self::Item<'w, 's> {
+        Query { data: state, world }
+    }
+}
+
+// SAFETY: The resource is added and it can be shared with other threads
+unsafe impl<R: Send + Sync + 'static> SystemParam for Res<'_, R> {
+    type State = ();
+    type Item<'w, 's> = Res<'w, R>;
+
+    fn init(_world: &mut World, access: &mut SystemAccess) -> Self::State {
+        access.add_resource_read::<R>();
+    }
+
+    fn fetch<'w, 's>(_state: &'s mut Self::State, world: &'w World) -> Self::Item<'w, 's> {
+        world
+            .get_resource::<R>()
+            .unwrap_or_else(|| panic!("Resource {} is missing", type_name::<R>()))
+    }
+}
+
+// SAFETY: The resource is added and it can be shared with other threads
+unsafe impl<R: Send + Sync + 'static> SystemParam for ResMut<'_, R> {
+    type State = ();
+    type Item<'w, 's> = ResMut<'w, R>;
+
+    fn init(_world: &mut World, access: &mut SystemAccess) -> Self::State {
+        access.add_resource_write::<R>();
+    }
+
+    fn fetch<'w, 's>(_state: &'s mut Self::State, world: &'w World) -> Self::Item<'w, 's> {
+        world
+            .get_resource_mut::<R>()
+            .unwrap_or_else(|| panic!("Resource {} is missing", type_name::<R>()))
+    }
+}
+
+// SAFETY: Like `Res`
+unsafe impl<R: Send + Sync + 'static> SystemParam for Option<Res<'_, R>> {
+    type State = ();
+    type Item<'w, 's> = Option<Res<'w, R>>;
+
+    fn init(_world: &mut World, access: &mut SystemAccess) -> Self::State {
+        access.add_resource_read::<R>();
+    }
+
+    fn fetch<'w, 's>(_state: &'s mut Self::State, world: &'w World) -> Self::Item<'w, 's> {
+        world.get_resource::<R>()
+    }
+}
+
+// SAFETY: Like `ResMut`
+unsafe impl<R: Send + Sync + 'static> SystemParam for Option<ResMut<'_, R>> {
+    type State = ();
+    type Item<'w, 's> = Option<ResMut<'w, R>>;
+
+    fn init(_world: &mut World, access: &mut SystemAccess) -> Self::State {
+        access.add_resource_write::<R>();
+    }
+
+    fn fetch<'w, 's>(_state: &'s mut Self::State, world: &'w World) -> Self::Item<'w, 's> {
+        world.get_resource_mut::<R>()
+    }
+}
+
+/// Shared access to a resource which can't be sent to or shared with other threads, e.g. a window or graphics context.
+/// Systems with it always run on the thread calling [`Schedule::run`](crate::schedule::Schedule::run), dereferences to the resource.
+pub struct NonSend<'w, R>(Res<'w, R>);
+
+impl<R> Deref for NonSend<'_, R> {
+    type Target = R;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Exclusive access to a resource which can't be sent to or shared with other threads, see [`NonSend`].
+pub struct NonSendMut<'w, R>(ResMut<'w, R>);
+
+impl<R> Deref for NonSendMut<'_, R> {
+    type Target = R;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<R> DerefMut for NonSendMut<'_, R> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+// SAFETY: The resource is added, and the system only runs on the thread calling `Schedule::run`
+unsafe impl<R: 'static> SystemParam for NonSend<'_, R> {
+    type State = ();
+    type Item<'w, 's> = NonSend<'w, R>;
+
+    fn init(_world: &mut World, access: &mut SystemAccess) -> Self::State {
+        access.add_resource_read::<R>();
+        access.set_main_thread();
+    }
+
+    fn fetch<'w, 's>(_state: &'s mut Self::State, world: &'w World) -> Self::Item<'w, 's> {
+        NonSend(
+            world
+                .get_resource::<R>()
+                .unwrap_or_else(|| panic!("Resource {} is missing", type_name::<R>())),
+        )
+    }
+}
+
+// SAFETY: Like `NonSend`
+unsafe impl<R: 'static> SystemParam for NonSendMut<'_, R> {
+    type State = ();
+    type Item<'w, 's> = NonSendMut<'w, R>;
+
+    fn init(_world: &mut World, access: &mut SystemAccess) -> Self::State {
+        access.add_resource_write::<R>();
+        access.set_main_thread();
+    }
+
+    fn fetch<'w, 's>(_state: &'s mut Self::State, world: &'w World) -> Self::Item<'w, 's> {
+        NonSendMut(
+            world
+                .get_resource_mut::<R>()
+                .unwrap_or_else(|| panic!("Resource {} is missing", type_name::<R>())),
+        )
+    }
+}
+
+/// Command buffer of a system, e.g. `fn cleanup(mut query: Query<(Entity, &Health)>, mut commands: Commands)`.
+/// The recorded commands are applied at the next [`ApplyDeferred`](crate::schedule::ApplyDeferred) or after the schedule ran every system,
+/// dereferences to [`CommandBuffer`].
+pub struct Commands<'s>(&'s mut CommandBuffer);
+
+impl Deref for Commands<'_> {
+    type Target = CommandBuffer;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl DerefMut for Commands<'_> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.0
+    }
+}
+
+// SAFETY: The buffer belongs to the system, the world is only touched when the commands are applied
+unsafe impl SystemParam for Commands<'_> {
+    type State = CommandBuffer;
+    type Item<'w, 's> = Commands<'s>;
+
+    fn init(_world: &mut World, _access: &mut SystemAccess) -> Self::State {
+        CommandBuffer::new()
+    }
+
+    fn fetch<'w, 's>(state: &'s mut Self::State, _world: &'w World) -> Self::Item<'w, 's> {
+        Commands(state)
+    }
+
+    fn apply(state: &mut Self::State, world: &mut World) {
+        world.apply(state);
+    }
+}
+
+/// State owned by one system and kept between its runs, e.g. `fn spawner(mut timer: Local<f32>)`.
+/// It starts as the default value, and every system using it gets its own, even systems created from the same function.
+pub struct Local<'s, T>(&'s mut T);
+
+impl<T> Deref for Local<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl<T> DerefMut for Local<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.0
+    }
+}
+
+// SAFETY: The value belongs to the system, the world is not touched
+unsafe impl<T: Default + Send + 'static> SystemParam for Local<'_, T> {
+    type State = T;
+    type Item<'w, 's> = Local<'s, T>;
+
+    fn init(_world: &mut World, _access: &mut SystemAccess) -> Self::State {
+        T::default()
+    }
+
+    fn fetch<'w, 's>(state: &'s mut Self::State, _world: &'w World) -> Self::Item<'w, 's> {
+        Local(state)
+    }
+}
+
+macro_rules! impl_param_tuple {
+    ($($name:ident),*) => {
+        // SAFETY: Every parameter adds its own access
+        unsafe impl<$($name: SystemParam),*> SystemParam for ($($name,)*) {
+            type State = ($($name::State,)*);
+            type Item<'w, 's> = ($($name::Item<'w, 's>,)*);
+
+            #[allow(unused_variables, clippy::unused_unit)]
+            fn init(world: &mut World, access: &mut SystemAccess) -> Self::State {
+                ($($name::init(world, access),)*)
+            }
+
+            #[allow(unused_variables, clippy::unused_unit)]
+            fn fetch<'w, 's>(state: &'s mut Self::State, world: &'w World) -> Self::Item<'w, 's> {
+                #[allow(non_snake_case)]
+                let ($($name,)*) = state;
+                ($($name::fetch($name, world),)*)
+            }
+
+            #[allow(unused_variables)]
+            fn apply(state: &mut Self::State, world: &mut World) {
+                #[allow(non_snake_case)]
+                let ($($name,)*) = state;
+                $($name::apply($name, world);)*
+            }
+        }
+    };
+}
+
+impl_param_tuple!();
+impl_param_tuple!(A);
+impl_param_tuple!(A, B);
+impl_param_tuple!(A, B, C);
+impl_param_tuple!(A, B, C, D);
+impl_param_tuple!(A, B, C, D, E);
+impl_param_tuple!(A, B, C, D, E, F);
+impl_param_tuple!(A, B, C, D, E, F, G);
+impl_param_tuple!(A, B, C, D, E, F, G, H);
+
+/// A unit of logic run against the world, usually a function whose parameters implement [`SystemParam`], see [`IntoSystem`].
+///
+/// # Safety
+/// [`System::access`] must cover everything [`System::run`] touches, since systems with compatible access run at the same time on different threads.
+pub unsafe trait System: Send + 'static {
+    fn name(&self) -> &'static str;
+
+    /// Everything the system accesses, valid after [`System::initialize`].
+    fn access(&self) -> &SystemAccess;
+
+    /// Creates the state of the system, repeated calls keep the existing state.
+    fn initialize(&mut self, world: &mut World);
+
+    /// Runs the system once, returning the error of a fallible system. Panics when it wasn't initialized.
+    fn run(&mut self, world: &World) -> SystemResult;
+
+    /// Applies the changes deferred by the previous runs, like the commands recorded with [`Commands`].
+    #[inline]
+    fn apply_deferred(&mut self, _world: &mut World) {}
+}
+
+/// Conversion into a [`System`], implemented for systems and for functions of up to 8 [`SystemParam`]s returning a [`SystemOutput`].
+/// The marker tells the impls apart.
+pub trait IntoSystem<Marker> {
+    type System: System;
+
+    fn into_system(self) -> Self::System;
+}
+
+impl<S: System> IntoSystem<()> for S {
+    type System = S;
+
+    #[inline]
+    fn into_system(self) -> Self::System {
+        self
+    }
+}
+
+/// Values returned by function systems, `()` for systems which can't fail and `Result<(), E>` for fallible ones.
+pub trait SystemOutput {
+    fn into_result(self) -> SystemResult;
+}
+
+impl SystemOutput for () {
+    #[inline]
+    fn into_result(self) -> SystemResult {
+        Ok(())
+    }
+}
+
+impl<E: Into<BoxedError>> SystemOutput for Result<(), E> {
+    #[inline]
+    fn into_result(self) -> SystemResult {
+        self.map_err(Into::into)
+    }
+}
+
+/// Functions which can be called with the items of their parameters, the marker is the signature of the function.
+pub trait SystemParamFunction<Marker>: Send + 'static {
+    type Param: SystemParam;
+
+    fn run(&mut self, param: SystemParamItem<'_, '_, Self::Param>) -> SystemResult;
+}
+
+/// Marks the [`IntoSystem`] impl of functions.
+#[doc(hidden)]
+pub struct IsFunctionSystem;
+
+impl<Marker: 'static, F: SystemParamFunction<Marker>> IntoSystem<(IsFunctionSystem, Marker)> for F {
+    type System = FunctionSystem<Marker, F>;
+
+    fn into_system(self) -> Self::System {
+        FunctionSystem {
+            func: self,
+            state: None,
+            access: SystemAccess::default(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// System calling a function with its parameters, created by [`IntoSystem::into_system`].
+pub struct FunctionSystem<Marker, F: SystemParamFunction<Marker>> {
+    func: F,
+    state: Option<<F::Param as SystemParam>::State>,
+    access: SystemAccess,
+    _marker: PhantomData<fn() -> Marker>,
+}
+
+// SAFETY: The function only receives its parameters, which add everything they access
+unsafe impl<Marker: 'static, F: SystemParamFunction<Marker>> System for FunctionSystem<Marker, F> {
+    #[inline]
+    fn name(&self) -> &'static str {
+        type_name::<F>()
+    }
+
+    #[inline]
+    fn access(&self) -> &SystemAccess {
+        &self.access
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        if self.state.is_none() {
+            self.state = Some(F::Param::init(world, &mut self.access));
+        }
+    }
+
+    fn run(&mut self, world: &World) -> SystemResult {
+        let Some(state) = &mut self.state else {
+            panic!("System {} was not initialized", type_name::<F>());
+        };
+        self.func.run(F::Param::fetch(state, world))
+    }
+
+    fn apply_deferred(&mut self, world: &mut World) {
+        if let Some(state) = &mut self.state {
+            F::Param::apply(state, world);
+        }
+    }
+}
+
+macro_rules! impl_system_function {
+    ($($name:ident),*) => {
+        impl<Func, Out: SystemOutput, $($name: SystemParam),*> SystemParamFunction<fn($($name,)*) -> Out> for Func
+        where
+            Func: Send + 'static,
+            for<'a> &'a mut Func: FnMut($($name),*) -> Out + FnMut($(SystemParamItem<'_, '_, $name>),*) -> Out,
+        {
+            type Param = ($($name,)*);
+
+            #[inline]
+            fn run(&mut self, param: SystemParamItem<'_, '_, Self::Param>) -> SystemResult {
+                // Calling through a generic function lets the compiler pick the second `FnMut` bound
+                #[allow(clippy::too_many_arguments, non_snake_case)]
+                fn call<Out, $($name),*>(mut func: impl FnMut($($name),*) -> Out, $($name: $name),*) -> Out {
+                    func($($name),*)
+                }
+
+                #[allow(non_snake_case)]
+                let ($($name,)*) = param;
+                call(self, $($name),*).into_result()
+            }
+        }
+    };
+}
+
+impl_system_function!();
+impl_system_function!(A);
+impl_system_function!(A, B);
+impl_system_function!(A, B, C);
+impl_system_function!(A, B, C, D);
+impl_system_function!(A, B, C, D, E);
+impl_system_function!(A, B, C, D, E, F);
+impl_system_function!(A, B, C, D, E, F, G);
+impl_system_function!(A, B, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        query::{Changed, With},
+        resource::{Res, ResMut},
+        system::{Commands, IntoSystem, Local, NonSend, Query, System, SystemAccess},
+        world::{Component, ComponentId, World},
+    };
+
+    struct Position(u32);
+    struct Velocity;
+    struct Frozen;
+
+    impl Component for Position {}
+    impl Component for Velocity {}
+    impl Component for Frozen {}
+
+    #[derive(Default)]
+    struct Score(u32);
+    #[derive(Default)]
+    struct Settings;
+
+    fn initialized<M>(world: &mut World, system: impl IntoSystem<M>) -> impl System {
+        let mut system = system.into_system();
+        system.initialize(world);
+        system
+    }
+
+    fn access_of<M>(system: impl IntoSystem<M>) -> SystemAccess {
+        let mut world = World::new();
+        world.register_component::<Position>();
+        world.register_component::<Velocity>();
+        world.register_component::<Frozen>();
+        initialized(&mut world, system).access().clone()
+    }
+
+    fn movement(_query: Query<(&mut Position, &Velocity)>, _settings: Res<Settings>) {}
+    fn read_velocity(_query: Query<&Velocity, With<Frozen>>) {}
+    fn read_position(_query: Query<&Position>) {}
+    fn changed_velocity(_query: Query<&Frozen, Changed<Velocity>>) {}
+    fn write_settings(_settings: ResMut<Settings>) {}
+    fn read_settings(_settings: Res<Settings>, _again: Option<Res<Settings>>) {}
+    fn window(_window: NonSend<Settings>) {}
+
+    #[test]
+    fn function_systems_collect_the_access_of_their_parameters() {
+        let access = access_of(movement);
+        assert_eq!(
+            access.components().writes().collect::<Vec<_>>(),
+            [ComponentId::of::<Position>()]
+        );
+        assert_eq!(
+            access.components().reads().collect::<Vec<_>>(),
+            [ComponentId::of::<Velocity>()]
+        );
+        assert_eq!(
+            access.resources().reads().collect::<Vec<_>>(),
+            [ComponentId::of::<Settings>()]
+        );
+        assert_eq!(
+            access.resource_name(ComponentId::of::<Settings>()),
+            Some(std::any::type_name::<Settings>())
+        );
+        assert!(!access.is_exclusive() && !access.is_main_thread());
+
+        // Row filters read the change ticks of their component
+        let access = access_of(changed_velocity);
+        assert!(
+            access
+                .components()
+                .reads()
+                .any(|id| id == ComponentId::of::<Velocity>())
+        );
+        assert!(access_of(window).is_main_thread());
+    }
+
+    #[test]
+    fn accesses_conflict_when_one_writes_what_the_other_accesses() {
+        let movement = access_of(movement);
+        assert!(movement.is_compatible(&access_of(read_velocity)));
+        assert!(movement.is_compatible(&access_of(read_settings)));
+        assert!(!movement.is_compatible(&access_of(read_position)));
+        assert!(!movement.is_compatible(&access_of(write_settings)));
+        // Compatibility is symmetric
+        assert!(!access_of(read_position).is_compatible(&movement));
+
+        let mut exclusive = SystemAccess::default();
+        assert!(exclusive.is_compatible(&SystemAccess::default()));
+        exclusive.set_exclusive();
+        assert!(!exclusive.is_compatible(&SystemAccess::default()));
+        assert!(!SystemAccess::default().is_compatible(&exclusive));
+    }
+
+    fn read_and_write_settings(_settings: Res<Settings>, _mut_settings: ResMut<Settings>) {}
+
+    #[test]
+    #[should_panic(
+        expected = "is borrowed by several parameters of one system and written by one of them"
+    )]
+    fn systems_cannot_read_and_write_a_resource() {
+        access_of(read_and_write_settings);
+    }
+
+    fn count(mut query: Query<&Position>, mut score: ResMut<Score>, mut runs: Local<u32>) {
+        *runs += 1;
+        score.0 = query.iter().map(|position| position.0).sum::<u32>() * 100 + *runs;
+    }
+
+    #[test]
+    fn parameters_are_fetched_on_every_run() {
+        let mut world = World::new();
+        world.insert_resource(Score::default());
+        world.spawn(Position(1));
+        let mut system = initialized(&mut world, count);
+        let mut other = initialized(&mut world, count);
+
+        system.run(&world).unwrap();
+        assert_eq!(world.get_resource::<Score>().unwrap().0, 101);
+        world.spawn(Position(2));
+        system.run(&world).unwrap();
+        assert_eq!(world.get_resource::<Score>().unwrap().0, 302);
+        // Every system has its own locals
+        other.run(&world).unwrap();
+        assert_eq!(world.get_resource::<Score>().unwrap().0, 301);
+    }
+
+    fn spawn(mut commands: Commands) {
+        commands.spawn(Position(1));
+    }
+
+    #[test]
+    fn commands_wait_for_the_deferred_changes() {
+        let mut world = World::new();
+        let mut system = initialized(&mut world, spawn);
+        system.run(&world).unwrap();
+        assert_eq!(world.entity_count(), 0);
+        system.apply_deferred(&mut world);
+        assert_eq!(world.entity_count(), 1);
+        // Applied commands are not applied again
+        system.apply_deferred(&mut world);
+        assert_eq!(world.entity_count(), 1);
+    }
+
+    fn optional(settings: Option<Res<Settings>>, mut score: ResMut<Score>) {
+        score.0 = u32::from(settings.is_some());
+    }
+
+    #[test]
+    fn optional_resources_may_be_missing() {
+        let mut world = World::new();
+        world.insert_resource(Score(5));
+        let mut system = initialized(&mut world, optional);
+        system.run(&world).unwrap();
+        assert_eq!(world.get_resource::<Score>().unwrap().0, 0);
+
+        world.insert_resource(Settings);
+        system.run(&world).unwrap();
+        assert_eq!(world.get_resource::<Score>().unwrap().0, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "is missing")]
+    fn required_resources_panic_when_missing() {
+        let mut world = World::new();
+        let mut system = initialized(&mut world, write_settings);
+        system.run(&world).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "was not initialized")]
+    fn systems_must_be_initialized() {
+        let world = World::new();
+        write_settings.into_system().run(&world).unwrap();
+    }
+}
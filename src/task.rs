@@ -0,0 +1,220 @@
+use std::{
+    any::Any,
+    fmt, mem,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        Arc, Condvar, Mutex, MutexGuard, PoisonError,
+        mpsc::{self, Sender},
+    },
+    thread::{self, JoinHandle},
+};
+
+use crate::{
+    commands::CommandBuffer,
+    world::{Component, Entity, World},
+};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Worker threads running long jobs outside of the schedule, e.g. pathfinding or decoding assets.
+/// Usually stored as a resource, systems spawn jobs with `Res<TaskPool>` and keep the returned [`Task`] as a component or resource.
+/// Dropping the pool waits for the spawned jobs to finish.
+pub struct TaskPool {
+    sender: Option<Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl Default for TaskPool {
+    /// A pool with as many threads as [`std::thread::available_parallelism`] reports.
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl TaskPool {
+    /// Creates a pool with the number of threads, or with as many as [`std::thread::available_parallelism`] reports when it is zero.
+    #[must_use]
+    pub fn new(threads: usize) -> Self {
+        let threads = match threads {
+            0 => thread::available_parallelism().map_or(1, usize::from),
+            threads => threads,
+        };
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let workers = (0..threads)
+            .map(|_| {
+                let receiver = receiver.clone();
+                thread::spawn(move || {
+                    loop {
+                        let job = receiver
+                            .lock()
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .recv();
+                        match job {
+                            Ok(job) => job(),
+                            // The pool was dropped
+                            Err(_) => return,
+                        }
+                    }
+                })
+            })
+            .collect();
+        Self {
+            sender: Some(sender),
+            workers,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn threads(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Runs the job on one of the threads, jobs start in the order they were spawned.
+    pub fn spawn<T: Send + 'static>(&self, job: impl FnOnce() -> T + Send + 'static) -> Task<T> {
+        let shared = Arc::new(Shared {
+            progress: Mutex::new(Progress::Running),
+            finished: Condvar::new(),
+        });
+        let task = Task {
+            shared: shared.clone(),
+        };
+        let job: Job = Box::new(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(job));
+            *shared.progress() = Progress::Finished(result);
+            shared.finished.notify_all();
+        });
+        // The workers only stop once the sender is dropped
+        self.sender.as_ref().unwrap().send(job).unwrap();
+        task
+    }
+
+    /// Spawns a job recording commands, which [`World::apply_tasks`] applies once it finished.
+    pub fn spawn_commands(
+        &self,
+        job: impl FnOnce(&mut CommandBuffer) + Send + 'static,
+    ) -> Task<CommandBuffer> {
+        self.spawn(move || {
+            let mut commands = CommandBuffer::new();
+            job(&mut commands);
+            commands
+        })
+    }
+}
+
+impl Drop for TaskPool {
+    fn drop(&mut self) {
+        self.sender = None;
+        for worker in self.workers.drain(..) {
+            // Jobs catch their panics, so workers don't panic
+            let _ = worker.join();
+        }
+    }
+}
+
+impl fmt::Debug for TaskPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskPool")
+            .field("threads", &self.workers.len())
+            .finish()
+    }
+}
+
+enum Progress<T> {
+    Running,
+    /// The result of the job or its panic.
+    Finished(Result<T, Box<dyn Any + Send>>),
+    Taken,
+}
+
+struct Shared<T> {
+    progress: Mutex<Progress<T>>,
+    finished: Condvar,
+}
+
+impl<T> Shared<T> {
+    fn progress(&self) -> MutexGuard<'_, Progress<T>> {
+        self.progress.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Handle of a job spawned on a [`TaskPool`], which can be stored as a component and polled every frame.
+/// Dropping the handle doesn't stop the job, its result is dropped.
+pub struct Task<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: 'static> Component for Task<T> {}
+
+impl<T> Task<T> {
+    /// Returns `true` when the job finished and its result wasn't taken yet.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        matches!(*self.shared.progress(), Progress::Finished(_))
+    }
+
+    /// Takes the result of the job once it finished, later polls return `None`.
+    /// Panics with the panic of the job when it panicked.
+    pub fn poll(&mut self) -> Option<T> {
+        let mut progress = self.shared.progress();
+        if !matches!(*progress, Progress::Finished(_)) {
+            return None;
+        }
+        let Progress::Finished(result) = mem::replace(&mut *progress, Progress::Taken) else {
+            unreachable!();
+        };
+        drop(progress);
+        Some(result.unwrap_or_else(|payload| panic::resume_unwind(payload)))
+    }
+
+    /// Waits for the job to finish and returns its result. Panics with the panic of the job when it panicked,
+    /// or when the result was already taken by [`Task::poll`].
+    pub fn block(self) -> T {
+        let mut progress = self.shared.progress();
+        loop {
+            match mem::replace(&mut *progress, Progress::Taken) {
+                Progress::Running => *progress = Progress::Running,
+                Progress::Finished(result) => {
+                    drop(progress);
+                    return result.unwrap_or_else(|payload| panic::resume_unwind(payload));
+                }
+                Progress::Taken => panic!("Result of the task was already taken"),
+            }
+            progress = self
+                .shared
+                .finished
+                .wait(progress)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+}
+
+impl<T> fmt::Debug for Task<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Task")
+            .field("finished", &self.is_finished())
+            .finish()
+    }
+}
+
+impl World {
+    /// Applies the commands of every finished [`Task<CommandBuffer>`] component and removes the finished tasks from their entities.
+    /// Returns the number of applied tasks.
+    pub fn apply_tasks(&mut self) -> usize {
+        self.register_component::<Task<CommandBuffer>>();
+        let mut query = self.query::<(Entity, &mut Task<CommandBuffer>)>();
+        let finished: Vec<(Entity, CommandBuffer)> = query
+            .iter(self)
+            .filter_map(|(entity, task)| Some((entity, task.poll()?)))
+            .collect();
+        drop(query);
+
+        let count = finished.len();
+        for (entity, mut commands) in finished {
+            self.remove_component::<Task<CommandBuffer>>(entity);
+            self.apply(&mut commands);
+        }
+        count
+    }
+}
@@ -0,0 +1,109 @@
+use crate::world::World;
+
+/// Canonical clock of a [`World`], stored as a resource and advanced by [`World::update_time`].
+/// Systems read it with `Res<Time>`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Time {
+    delta: f32,
+    elapsed: f64,
+    tick: u64,
+}
+
+impl Time {
+    /// Seconds passed between the last two updates.
+    #[inline]
+    #[must_use]
+    pub fn delta(&self) -> f32 {
+        self.delta
+    }
+
+    /// Seconds summed from every update.
+    #[inline]
+    #[must_use]
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+
+    /// Number of updates so far.
+    #[inline]
+    #[must_use]
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Advances the clock by `delta` seconds and one tick, negative deltas are treated as zero.
+    pub fn advance(&mut self, delta: f32) {
+        self.delta = delta.max(0.0);
+        self.elapsed += f64::from(self.delta);
+        self.tick += 1;
+    }
+}
+
+impl World {
+    /// Returns a copy of the [`Time`] resource, or a clock which never advanced when the resource was removed.
+    #[inline]
+    #[must_use]
+    pub fn time(&self) -> Time {
+        self.get_resource::<Time>()
+            .map_or_else(Time::default, |time| *time)
+    }
+
+    /// Advances the world clock by `delta` seconds and one tick, inserting the [`Time`] resource again when it was removed,
+    /// then removes the timed components whose lifetime has passed.
+    pub fn update_time(&mut self, delta: f32) {
+        if !self.contains_resource::<Time>() {
+            self.insert_resource(Time::default());
+        }
+        self.get_resource_mut::<Time>().unwrap().advance(delta);
+        self.expire_timed();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Time;
+    use crate::{
+        resource::{Res, ResMut},
+        schedule::Schedule,
+        world::World,
+    };
+
+    #[derive(Default)]
+    struct Seen(Vec<(f32, u64)>);
+
+    fn read_time(time: Res<Time>, mut seen: ResMut<Seen>) {
+        seen.0.push((time.delta(), time.tick()));
+    }
+
+    #[test]
+    fn systems_read_the_world_clock() {
+        let mut world = World::new();
+        world.insert_resource(Seen::default());
+        let mut schedule = Schedule::new();
+        schedule.add_system(read_time);
+
+        schedule.run(&mut world);
+        world.update_time(0.5);
+        schedule.run(&mut world);
+        world.update_time(0.25);
+        schedule.run(&mut world);
+
+        assert_eq!(
+            world.get_resource::<Seen>().unwrap().0,
+            [(0.0, 0), (0.5, 1), (0.25, 2)]
+        );
+        assert_eq!(world.time().elapsed(), 0.75);
+    }
+
+    #[test]
+    fn updates_insert_a_removed_clock_again() {
+        let mut world = World::new();
+        world.update_time(1.0);
+        assert_eq!(world.remove_resource::<Time>().unwrap().tick(), 1);
+        assert_eq!(world.time(), Time::default());
+
+        world.update_time(2.0);
+        assert_eq!(world.time().tick(), 1);
+        assert_eq!(world.time().delta(), 2.0);
+    }
+}
@@ -0,0 +1,180 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap},
+};
+
+use crate::{
+    time::Time,
+    world::{Component, ComponentId, Entity, World},
+};
+
+/// How long a component inserted with [`World::insert_timed`] lives.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Lifetime {
+    /// Number of [`World::update_time`] calls.
+    Ticks(u64),
+    /// Seconds of the world [`Time`].
+    ///
+    /// Components inserted during a step of [`World::run_fixed`] are measured by the fixed clock instead.
+    Seconds(f32),
+}
+
+impl From<f32> for Lifetime {
+    fn from(seconds: f32) -> Self {
+        Lifetime::Seconds(seconds)
+    }
+}
+
+/// A pending removal, ordered by its deadline.
+#[derive(Clone)]
+struct Timer {
+    deadline: u64,
+    id: u64,
+    entity: Entity,
+    component: ComponentId,
+    remove: fn(&mut World, Entity),
+}
+
+impl PartialEq for Timer {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Timer {}
+
+impl PartialOrd for Timer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Timer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.deadline, self.id).cmp(&(other.deadline, other.id))
+    }
+}
+
+/// Deadlines of the timed components of a [`World`].
+#[derive(Default, Clone)]
+pub(crate) struct Timers {
+    next_id: u64,
+    world: Deadlines,
+    fixed: Deadlines,
+    active: HashMap<(Entity, ComponentId), u64>,
+}
+
+/// Deadlines measured by one clock, the world one or the one of fixed timestep schedules.
+#[derive(Default, Clone)]
+struct Deadlines {
+    by_ticks: BinaryHeap<Reverse<Timer>>,
+    // Deadlines in seconds are stored as the bits of a positive f64, which sort the same way as the values
+    by_seconds: BinaryHeap<Reverse<Timer>>,
+}
+
+impl Timers {
+    /// Forgets the timer of the component, so it is not removed when the old deadline passes.
+    #[inline]
+    pub(crate) fn cancel(&mut self, entity: Entity, component: ComponentId) {
+        if !self.active.is_empty() {
+            self.active.remove(&(entity, component));
+        }
+    }
+
+    fn schedule<T: Component>(
+        &mut self,
+        time: &Time,
+        fixed: bool,
+        entity: Entity,
+        lifetime: Lifetime,
+    ) {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.active.insert((entity, ComponentId::of::<T>()), id);
+
+        let deadlines = if fixed {
+            &mut self.fixed
+        } else {
+            &mut self.world
+        };
+        let (heap, deadline) = match lifetime {
+            Lifetime::Ticks(ticks) => (&mut deadlines.by_ticks, time.tick().saturating_add(ticks)),
+            Lifetime::Seconds(seconds) => (
+                &mut deadlines.by_seconds,
+                (time.elapsed() + f64::from(seconds.max(0.0))).to_bits(),
+            ),
+        };
+        heap.push(Reverse(Timer {
+            deadline,
+            id,
+            entity,
+            component: ComponentId::of::<T>(),
+            remove: remove::<T>,
+        }));
+    }
+
+    /// Pops every expired timer of the clock which is still active, the heap entries of cancelled timers are dropped on the way.
+    fn expired(&mut self, time: &Time, fixed: bool) -> Vec<Timer> {
+        let mut expired = Vec::new();
+        let seconds = time.elapsed().to_bits();
+
+        let deadlines = if fixed {
+            &mut self.fixed
+        } else {
+            &mut self.world
+        };
+        for (heap, now) in [
+            (&mut deadlines.by_ticks, time.tick()),
+            (&mut deadlines.by_seconds, seconds),
+        ] {
+            while heap.peek().is_some_and(|timer| timer.0.deadline <= now) {
+                expired.push(heap.pop().unwrap().0);
+            }
+        }
+
+        expired.retain(|timer| {
+            let key = (timer.entity, timer.component);
+            if self.active.get(&key) == Some(&timer.id) {
+                self.active.remove(&key);
+                true
+            } else {
+                false
+            }
+        });
+        expired
+    }
+}
+
+fn remove<T: Component>(world: &mut World, entity: Entity) {
+    world.remove_component::<T>(entity);
+}
+
+impl World {
+    /// Inserts a component which is removed automatically once its lifetime passes, e.g. `insert_timed(entity, Burning, 3.0)`.
+    /// Inserting the component again restarts its lifetime, inserting it with [`World::insert_component`] makes it permanent.
+    pub fn insert_timed<T: Component>(
+        &mut self,
+        entity: Entity,
+        component: T,
+        lifetime: impl Into<Lifetime>,
+    ) {
+        if !self.is_alive(entity) {
+            return;
+        }
+
+        self.insert_component(entity, component);
+        let time = self.time();
+        self.timers
+            .schedule::<T>(&time, self.fixed_step, entity, lifetime.into());
+    }
+
+    /// Removes every timed component whose lifetime measured by the current clock has passed, grouped by type.
+    pub(crate) fn expire_timed(&mut self) {
+        let time = self.time();
+        let mut expired = self.timers.expired(&time, self.fixed_step);
+        expired.sort_by_key(|timer| timer.component);
+        for timer in expired {
+            (timer.remove)(self, timer.entity);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+};
+
+use crate::world::{Entity, World};
+
+/// Values of a single transient type for the current frame.
+struct Arena<T> {
+    entities: Vec<Entity>,
+    values: Vec<T>,
+    index: HashMap<Entity, usize>,
+}
+
+trait AnyArena {
+    fn reset(&mut self);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Copy + 'static> AnyArena for Arena<T> {
+    fn reset(&mut self) {
+        // `T: Copy` has no drop glue, so the values are forgotten without touching them and the capacity is kept for the next frame
+        self.entities.clear();
+        self.values.clear();
+        self.index.clear();
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Per-frame storage of transient components, kept outside of the archetypes so inserting them never moves an entity.
+#[derive(Default)]
+pub(crate) struct Transients {
+    arenas: HashMap<TypeId, Box<dyn AnyArena>>,
+}
+
+impl Transients {
+    fn arena<T: Copy + 'static>(&self) -> Option<&Arena<T>> {
+        self.arenas.get(&TypeId::of::<T>())?.as_any().downcast_ref()
+    }
+
+    fn arena_mut<T: Copy + 'static>(&mut self) -> &mut Arena<T> {
+        self.arenas
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| {
+                Box::new(Arena::<T> {
+                    entities: Vec::new(),
+                    values: Vec::new(),
+                    index: HashMap::new(),
+                })
+            })
+            .as_any_mut()
+            .downcast_mut()
+            .unwrap()
+    }
+
+    /// Drops every transient value in bulk, keeping the allocations.
+    pub(crate) fn reset(&mut self) {
+        for arena in self.arenas.values_mut() {
+            arena.reset();
+        }
+    }
+}
+
+impl World {
+    /// Attaches a transient value to the entity until the next [`World::flush`], replacing the previous one of the same type.
+    /// Transient values are not stored in archetypes, so they are not visible to queries, but attaching them is cheap and never moves the entity.
+    /// Meant for data recomputed every frame, like contact points or intents.
+    pub fn insert_transient<T: Copy + 'static>(&mut self, entity: Entity, value: T) {
+        if !self.is_alive(entity) {
+            return;
+        }
+
+        let arena = self.transients.arena_mut::<T>();
+        if let Some(index) = arena.index.get(&entity) {
+            arena.values[*index] = value;
+            return;
+        }
+
+        arena.index.insert(entity, arena.values.len());
+        arena.entities.push(entity);
+        arena.values.push(value);
+    }
+
+    #[must_use]
+    pub fn get_transient<T: Copy + 'static>(&self, entity: Entity) -> Option<&T> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+
+        let arena = self.transients.arena::<T>()?;
+        arena.index.get(&entity).map(|index| &arena.values[*index])
+    }
+
+    #[must_use]
+    pub fn get_transient_mut<T: Copy + 'static>(&mut self, entity: Entity) -> Option<&mut T> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+
+        let arena = self.transients.arena_mut::<T>();
+        let index = *arena.index.get(&entity)?;
+        Some(&mut arena.values[index])
+    }
+
+    /// Iterates over the transient values of type `T` of alive entities, in the order they were inserted this frame.
+    pub fn iter_transient<T: Copy + 'static>(&self) -> impl Iterator<Item = (Entity, &T)> {
+        self.transients
+            .arena::<T>()
+            .into_iter()
+            .flat_map(|arena| arena.entities.iter().copied().zip(&arena.values))
+            .filter(|(entity, _)| self.is_alive(*entity))
+    }
+
+    /// Drops every transient value, [`World::flush`] calls this at the end of each frame.
+    pub fn clear_transients(&mut self) {
+        self.transients.reset();
+    }
+}
@@ -0,0 +1,122 @@
+use std::marker::PhantomData;
+
+use crate::{
+    mask::ComponentMask,
+    world::{Component, Entity, World},
+};
+
+/// Maximum number of variants of a [`VariantComponent`].
+pub const MAX_VARIANTS: usize = 16;
+
+/// An enum component whose variant is part of the archetype key, so queries can filter by it with [`Variant`].
+/// Set it with [`World::insert_variant`] to keep the variant marker of the entity up to date.
+pub trait VariantComponent: Component {
+    /// Index of the current variant, must be lower than [`MAX_VARIANTS`].
+    fn variant(&self) -> usize;
+}
+
+/// Marker component of the `N`-th variant of `T`, e.g. `With<Variant<State, 1>>` only matches entities whose `State` is in the variant with index 1.
+/// Aliasing it, like `type Attacking = Variant<State, 1>`, keeps queries readable.
+pub struct Variant<T, const N: usize>(PhantomData<fn() -> T>);
+
+impl<T: 'static, const N: usize> Component for Variant<T, N> {}
+
+fn insert_marker<T: 'static, const N: usize>(world: &mut World, entity: Entity) {
+    world.insert_component(entity, Variant::<T, N>(PhantomData));
+}
+
+fn remove_marker<T: 'static, const N: usize>(world: &mut World, entity: Entity) {
+    world.remove_component::<Variant<T, N>>(entity);
+}
+
+type MarkerFn = fn(&mut World, Entity);
+
+macro_rules! marker_fns {
+    ($index:expr, $($n:literal),*) => {
+        match $index {
+            $($n => (insert_marker::<T, $n> as MarkerFn, remove_marker::<T, $n> as MarkerFn),)*
+            index => panic!("Variant index {index} is out of range, at most {MAX_VARIANTS} variants are supported"),
+        }
+    };
+}
+
+/// Returns the functions inserting and removing the marker of the variant with the given index.
+fn markers<T: 'static>(index: usize) -> (MarkerFn, MarkerFn) {
+    marker_fns!(index, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15)
+}
+
+impl World {
+    /// Inserts the enum component together with the marker of its variant, replacing the marker of the previous variant.
+    /// Changing the variant moves the entity into another archetype, so iterating over one variant never branches per row.
+    pub fn insert_variant<T: VariantComponent>(&mut self, entity: Entity, component: T) {
+        if !self.is_alive(entity) {
+            return;
+        }
+
+        let variant = component.variant();
+        let previous = self.get_component::<T>(entity).map(T::variant);
+        let (insert, _) = markers::<T>(variant);
+
+        self.insert_component(entity, component);
+        if previous != Some(variant) {
+            if let Some(previous) = previous {
+                (markers::<T>(previous).1)(self, entity);
+            }
+            insert(self, entity);
+        }
+    }
+
+    /// Removes the enum component together with the marker of its variant.
+    pub fn remove_variant<T: VariantComponent>(&mut self, entity: Entity) {
+        let Some(variant) = self.get_component::<T>(entity).map(T::variant) else {
+            return;
+        };
+
+        self.remove_component::<T>(entity);
+        (markers::<T>(variant).1)(self, entity);
+    }
+
+    /// Fixes the variant markers of every `T` whose variant was changed in place, e.g. through a query or [`World::get_component_mut`].
+    pub fn sync_variants<T: VariantComponent>(&mut self) {
+        if self.bit_of::<T>().is_none() {
+            return;
+        }
+
+        let mut stale = Vec::new();
+        for archetype in self.matching_archetypes::<&T>() {
+            // Every entity of an archetype has the same marker
+            let mask = archetype.bitmask();
+            let marked = (0..MAX_VARIANTS).find(|index| {
+                self.variant_bit::<T>(*index)
+                    .is_some_and(|bit| mask.intersects(&bit))
+            });
+
+            for (row, entity) in archetype.entities().iter().enumerate() {
+                let variant = archetype.get::<T>(row).unwrap().variant();
+                if marked != Some(variant) {
+                    stale.push((*entity, marked, variant));
+                }
+            }
+        }
+
+        for (entity, marked, variant) in stale {
+            if let Some(marked) = marked {
+                (markers::<T>(marked).1)(self, entity);
+            }
+            (markers::<T>(variant).0)(self, entity);
+        }
+    }
+
+    /// Returns the bit of the marker of the variant with the given index, if it was registered already.
+    fn variant_bit<T: 'static>(&self, index: usize) -> Option<ComponentMask> {
+        macro_rules! bit {
+            ($($n:literal),*) => {
+                match index {
+                    $($n => self.bit_of::<Variant<T, $n>>(),)*
+                    _ => None,
+                }
+            };
+        }
+        bit!(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15)
+    }
+}
@@ -0,0 +1,260 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::{
+    archetype::Archetype,
+    blob_data::BlobData,
+    mask::ComponentMask,
+    query::{Filter, QueryData},
+    world::{Component, ComponentId, Entity, World},
+};
+
+/// Archetype visited by [`World::for_each_matching_archetype`], exposing its entities and the raw slices of its columns.
+/// Every row index is the same across the entities and all the columns.
+pub struct ArchetypeView<'a> {
+    archetype: &'a Archetype,
+    tick: Option<u64>,
+}
+
+impl<'a> ArchetypeView<'a> {
+    /// Entities stored in the archetype, in row order.
+    #[inline]
+    #[must_use]
+    pub fn entities(&self) -> &'a [Entity] {
+        self.archetype.entities()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.archetype.count()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn bitmask(&self) -> &ComponentMask {
+        self.archetype.bitmask()
+    }
+
+    /// Returns whether the archetype stores the component.
+    #[inline]
+    #[must_use]
+    pub fn has<T: Component>(&self) -> bool {
+        self.archetype.column(&ComponentId::of::<T>()).is_some()
+    }
+
+    /// Borrows the column of the component as a slice, or `None` when the archetype doesn't store it.
+    /// Panics when the column is borrowed mutably, e.g. by [`ArchetypeView::column_mut`].
+    pub fn column<T: Component>(&self) -> Option<ColumnRef<'a, T>> {
+        let column = self.archetype.column(&ComponentId::of::<T>())?;
+        assert!(column.borrow(), "Column is already borrowed mutably");
+        // SAFETY: The column stores `T` and holds a row for every entity, the borrow is released when the guard is dropped
+        let slice = unsafe { std::slice::from_raw_parts(column.as_ptr(), self.len()) };
+        Some(ColumnRef { column, slice })
+    }
+
+    /// Borrows the column of the component as a mutable slice, or `None` when the archetype doesn't store it.
+    /// Every row is marked as changed. Panics when the column is borrowed elsewhere.
+    pub fn column_mut<T: Component>(&self) -> Option<ColumnMut<'a, T>> {
+        let column = self.archetype.column(&ComponentId::of::<T>())?;
+        assert!(column.borrow_mut(), "Column is already borrowed");
+        if let Some(tick) = self.tick {
+            (0..self.len()).for_each(|row| column.set_tick(row, tick));
+        }
+        // SAFETY: The column stores `T` and is borrowed uniquely until the guard is dropped
+        let slice = unsafe { std::slice::from_raw_parts_mut(column.as_mut_ptr(), self.len()) };
+        Some(ColumnMut { column, slice })
+    }
+}
+
+/// Shared borrow of a column, returned by [`ArchetypeView::column`].
+pub struct ColumnRef<'a, T> {
+    column: &'a BlobData,
+    slice: &'a [T],
+}
+
+impl<T> Deref for ColumnRef<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.slice
+    }
+}
+
+impl<T> Drop for ColumnRef<'_, T> {
+    fn drop(&mut self) {
+        self.column.release();
+    }
+}
+
+/// Unique borrow of a column, returned by [`ArchetypeView::column_mut`].
+pub struct ColumnMut<'a, T> {
+    column: &'a BlobData,
+    slice: &'a mut [T],
+}
+
+impl<T> Deref for ColumnMut<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.slice
+    }
+}
+
+impl<T> DerefMut for ColumnMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.slice
+    }
+}
+
+impl<T> Drop for ColumnMut<'_, T> {
+    fn drop(&mut self) {
+        self.column.release_mut();
+    }
+}
+
+impl World {
+    /// Calls the closure with a view of every non-empty archetype matching the filter, e.g.
+    /// `world.for_each_matching_archetype::<(With<Position>, With<Velocity>)>(|view| ..)` to run a SIMD kernel over whole columns
+    /// or upload them to the GPU. Disabled entities are skipped like in queries. Panics with row filters like [`Changed`](crate::query::Changed).
+    pub fn for_each_matching_archetype<F: Filter>(&self, mut f: impl FnMut(ArchetypeView<'_>)) {
+        assert!(
+            !F::filters_rows(),
+            "Cannot visit the archetypes of a filter with row filters"
+        );
+        let filter = QueryData::<Entity, F>::archetype_filter(self);
+        let tick = self.change_detection().then(|| self.change_tick());
+        self.ordered_archetypes()
+            .filter(|archetype| archetype.count() > 0 && filter.matches(archetype.bitmask()))
+            .for_each(|archetype| f(ArchetypeView { archetype, tick }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        query::{Changed, QueryData, With, Without},
+        world::{Component, Entity, World},
+    };
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Position(u32);
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Velocity(u32);
+    struct Frozen;
+
+    impl Component for Position {}
+    impl Component for Velocity {}
+    impl Component for Frozen {}
+
+    fn moving() -> (World, Vec<Entity>) {
+        let mut world = World::new();
+        let entities = (0..3)
+            .map(|index| world.spawn((Position(index), Velocity(index * 10))))
+            .collect();
+        world.spawn((Position(9), Frozen));
+        (world, entities)
+    }
+
+    #[test]
+    fn views_align_entities_and_columns() {
+        let (world, entities) = moving();
+
+        let mut visited = 0;
+        world.for_each_matching_archetype::<With<Velocity>>(|view| {
+            visited += 1;
+            assert_eq!(view.len(), 3);
+            assert!(view.has::<Position>() && !view.has::<Frozen>());
+            assert_eq!(view.entities(), entities);
+            let positions = view.column::<Position>().unwrap();
+            let velocities = view.column::<Velocity>().unwrap();
+            for row in 0..view.len() {
+                assert_eq!(velocities[row].0, positions[row].0 * 10);
+            }
+            assert!(view.column::<Frozen>().is_none());
+        });
+        assert_eq!(visited, 1);
+    }
+
+    #[test]
+    fn mutable_columns_write_and_mark_every_row() {
+        let (mut world, _) = moving();
+        let mut changed = QueryData::<&Position, Changed<Position>>::new(&world);
+        assert_eq!(changed.iter(&world).count(), 4);
+
+        world.for_each_matching_archetype::<Without<Frozen>>(|view| {
+            let velocities = view.column::<Velocity>().unwrap();
+            let mut positions = view.column_mut::<Position>().unwrap();
+            for (position, velocity) in positions.iter_mut().zip(velocities.iter()) {
+                position.0 += velocity.0;
+            }
+        });
+        let mut positions: Vec<_> = changed.iter(&world).map(|position| position.0).collect();
+        positions.sort_unstable();
+        assert_eq!(positions, [0, 11, 22]);
+        assert_eq!(world.query::<&Position>().iter(&world).count(), 4);
+    }
+
+    #[test]
+    fn empty_and_disabled_archetypes_are_skipped() {
+        let (mut world, entities) = moving();
+        let lone = world.spawn(Velocity(1));
+        world.despawn_entity(lone);
+        for entity in entities {
+            world.disable(entity);
+        }
+
+        let mut visited = Vec::new();
+        world.for_each_matching_archetype::<()>(|view| visited.push(view.len()));
+        assert_eq!(visited, [1]);
+    }
+
+    #[test]
+    fn released_columns_are_borrowed_again() {
+        let (world, _) = moving();
+
+        world.for_each_matching_archetype::<With<Velocity>>(|view| {
+            drop(view.column_mut::<Position>().unwrap());
+            let first = view.column::<Position>().unwrap();
+            let second = view.column::<Position>().unwrap();
+            assert_eq!(first[..], second[..]);
+            drop((first, second));
+            view.column_mut::<Position>().unwrap()[0] = Position(5);
+        });
+        let mut query = QueryData::<&Position, With<Velocity>>::new(&world);
+        assert!(query.iter(&world).any(|position| *position == Position(5)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Column is already borrowed mutably")]
+    fn shared_columns_conflict_with_mutable_ones() {
+        let (world, _) = moving();
+        world.for_each_matching_archetype::<With<Velocity>>(|view| {
+            let _positions = view.column_mut::<Position>();
+            let _ = view.column::<Position>();
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Column is already borrowed")]
+    fn mutable_columns_conflict_with_queries() {
+        let (world, _) = moving();
+        let mut query = QueryData::<&Position, ()>::new(&world);
+        let _positions = query.iter(&world);
+        world.for_each_matching_archetype::<With<Velocity>>(|view| {
+            let _ = view.column_mut::<Position>();
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot visit the archetypes of a filter with row filters")]
+    fn row_filters_are_rejected() {
+        let (world, _) = moving();
+        world.for_each_matching_archetype::<Changed<Position>>(|_| {});
+    }
+}
@@ -1,46 +1,138 @@
-use std::{any::TypeId, collections::HashMap};
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    sync::{
+        Mutex, MutexGuard, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+};
 
 use crate::{
-    archetype::Archetype,
-    blob_data::TypeInfo,
+    allocator::{EntityAllocator, RecyclingAllocator, Slot},
+    archetype::{Archetype, ArchetypeCallback, ArchetypeHandle, ArchetypeId, ArchetypeInfo},
+    blob_data::{ComponentTicks, TypeInfo},
     bundle::Bundle,
-    query::{Filter, QueryData, QueryItem},
+    changes::ChangeLog,
+    checkpoint::Checkpoints,
+    components::{ComponentInfo, Components},
+    group::Groups,
+    hooks::Hook,
+    mask::ComponentMask,
+    name::{Name, Names},
+    query::{ArchetypeFilter, Filter, MatchLists, QueryData, QueryItem},
+    query_state::QueryStates,
+    quota::Quotas,
+    record::Recording,
+    resource::Resources,
+    serialize::Serializers,
+    time::Time,
+    timed::Timers,
+    transient::Transients,
 };
 
 pub struct World {
-    bitmap: HashMap<TypeId, u64>,
-    archetype_map: HashMap<u64, usize>,
+    pub(crate) archetype_map: HashMap<ComponentMask, usize>,
     archetypes: Vec<Archetype>,
-    entities: Entities,
-    next_bitmask: u8,
+    pub(crate) entities: Entities,
+    pub(crate) allocator: Box<dyn EntityAllocator>,
+    pub(crate) components: Components,
+    archetype_callbacks: Vec<ArchetypeCallback>,
+    change_tick: AtomicU64,
+    /// Whether mutable query items write change ticks, see [`WorldBuilder::change_detection`](crate::builder::WorldBuilder::change_detection).
+    pub(crate) change_detection: bool,
+    /// Whether queries visit archetypes by bitmask instead of creation order, see [`WorldBuilder::deterministic_order`](crate::builder::WorldBuilder::deterministic_order).
+    pub(crate) deterministic: bool,
+    pub(crate) deferred_despawns: Mutex<Vec<Entity>>,
+    /// Entities whose remove hooks are running before their despawn.
+    pub(crate) despawning: Vec<Entity>,
+    pub(crate) extracted_tick: u64,
+    pub(crate) serializers: Serializers,
+    pub(crate) checkpoints: Checkpoints,
+    pub(crate) change_log: ChangeLog,
+    pub(crate) transients: Transients,
+    pub(crate) timers: Timers,
+    /// Set while [`World::run_fixed`] runs a step, the [`Time`] resource is the fixed clock then.
+    pub(crate) fixed_step: bool,
+    pub(crate) recording: Option<Recording>,
+    pub(crate) quotas: Quotas,
+    pub(crate) resources: Resources,
+    pub(crate) match_lists: MatchLists,
+    pub(crate) names: Names,
+    pub(crate) groups: Groups,
+    pub(crate) query_states: QueryStates,
+    compact_threshold: usize,
+    /// Identifies the current numbering of the archetypes, replaced whenever archetypes are dropped, see [`next_archetype_epoch`].
+    archetype_epoch: u64,
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl World {
     #[must_use]
     pub fn new() -> Self {
-        Self {
-            bitmap: HashMap::new(),
+        let mut world = Self {
             archetype_map: HashMap::new(),
             archetypes: Vec::new(),
             entities: Entities::new(),
-            next_bitmask: 0,
-        }
+            allocator: Box::new(RecyclingAllocator),
+            components: Components::default(),
+            archetype_callbacks: Vec::new(),
+            change_tick: AtomicU64::new(1),
+            change_detection: true,
+            deterministic: false,
+            deferred_despawns: Mutex::new(Vec::new()),
+            despawning: Vec::new(),
+            extracted_tick: 0,
+            serializers: Serializers::default(),
+            checkpoints: Checkpoints::default(),
+            change_log: ChangeLog::default(),
+            transients: Transients::default(),
+            timers: Timers::default(),
+            fixed_step: false,
+            recording: None,
+            quotas: Quotas::default(),
+            resources: Resources::default(),
+            match_lists: MatchLists::default(),
+            names: Names::default(),
+            groups: Groups::default(),
+            query_states: QueryStates::default(),
+            compact_threshold: 0,
+            archetype_epoch: next_archetype_epoch(),
+        };
+        world.insert_resource(Time::default());
+        world
     }
 
     /// Registers a [`Component`] type by giving it a unique bit which is returned.
     /// It does nothing when the type is already registered.
     /// Usually you should not use this function directly, because the components are registered automatically when you [`World::spawn`] an entity with a bundle.
-    pub fn register_component<T: Component>(&mut self) -> u64 {
-        if let Some(bit) = self.bitmap.get(&TypeId::of::<T>()) {
-            return *bit;
+    pub fn register_component<T: Component>(&mut self) -> ComponentMask {
+        self.register_info(ComponentInfo::new(
+            ComponentId::of::<T>(),
+            std::any::type_name::<T>(),
+            TypeInfo::of::<T>(),
+        ))
+    }
+
+    /// Untyped version of [`World::register_component`], the entry is only stored when the type is not registered yet.
+    pub(crate) fn register_info(&mut self, info: ComponentInfo) -> ComponentMask {
+        let named = info.id == ComponentId::of::<Name>();
+        let bit = self.components.register(info);
+        if named {
+            self.names.bit = bit.clone();
         }
-        let bit = 1_u64 << self.next_bitmask;
-        self.bitmap.insert(TypeId::of::<T>(), bit);
-        self.next_bitmask += 1;
         bit
     }
 
     /// Spawns an [`Entity`] with the given components without an archetypal move. Registers components when needed, use [`World::spawn_no_register`] if you don't want to.
+    /// Panics when an entity or archetype quota is exhausted, use [`World::try_spawn`] to handle it.
     pub fn spawn<B: Bundle>(&mut self, bundle: B) -> Entity {
         B::register(self);
 
@@ -54,56 +146,287 @@ impl World {
         self.spawn_inner(bundle, bitmask)
     }
 
+    /// Spawns `n` entities with the bundles returned by the closure for each index, e.g. to place entities on a grid.
+    /// The columns are reserved for the whole batch up front and every bundle is written straight into them, so nothing is collected first.
+    /// Panics when a quota is exhausted, entities spawned until then are kept.
+    pub fn spawn_batch_with<B: Bundle>(
+        &mut self,
+        n: usize,
+        mut f: impl FnMut(usize) -> B,
+    ) -> Vec<Entity> {
+        let archetype_idx = self.reserve_batch::<B>(n);
+        (0..n)
+            .map(|index| self.spawn_in_archetype(f(index), archetype_idx))
+            .collect()
+    }
+
+    /// Spawns an entity for every bundle of the iterator. The archetype is resolved once and the columns are reserved up front from the size hint.
+    /// Panics when a quota is exhausted, entities spawned until then are kept.
+    pub fn spawn_batch<B: Bundle, I: IntoIterator<Item = B>>(&mut self, bundles: I) -> Vec<Entity> {
+        let bundles = bundles.into_iter();
+        let archetype_idx = self.reserve_batch::<B>(bundles.size_hint().0);
+        bundles
+            .map(|bundle| self.spawn_in_archetype(bundle, archetype_idx))
+            .collect()
+    }
+
+    /// Resolves the archetype of a batch of `B` and reserves room for `additional` entities, returning the archetype index.
+    fn reserve_batch<B: Bundle>(&mut self, additional: usize) -> usize {
+        B::register(self);
+        let archetype_idx = self.archetype_index(&B::bitmask(self));
+
+        let archetype = &mut self.archetypes[archetype_idx];
+        B::init_columns(archetype);
+        archetype.reserve(additional);
+        let reused = self.entities.free.len();
+        self.entities
+            .metas
+            .reserve(additional.saturating_sub(reused));
+
+        archetype_idx
+    }
+
     /// Inner method for spawning so there can be alternative spawn methods.
-    fn spawn_inner(&mut self, bundle: impl Bundle, bitmask: u64) -> Entity {
-        let entity = self.entities.create();
-        let archetype_idx = if let Some(archetype_idx) = self.archetype_map.get(&bitmask) {
-            *archetype_idx
-        } else {
-            self.archetypes.push(Archetype::new(bitmask));
-            self.archetype_map
-                .insert(bitmask, self.archetypes.len() - 1);
-            self.archetypes.len() - 1
-        };
+    fn spawn_inner(&mut self, bundle: impl Bundle, bitmask: ComponentMask) -> Entity {
+        let archetype_idx = self.archetype_index(&bitmask);
+        self.spawn_in_archetype(bundle, archetype_idx)
+    }
 
+    /// Spawns the bundle into an already resolved archetype, which must have exactly the bundle's components.
+    /// Panics when a quota is exhausted.
+    pub(crate) fn spawn_in_archetype(
+        &mut self,
+        bundle: impl Bundle,
+        archetype_idx: usize,
+    ) -> Entity {
+        if let Err(error) = self.check_quota(Some(archetype_idx)) {
+            panic!("{error}");
+        }
+        let entity = self.alloc_entity();
+        self.put_in_archetype(entity, bundle, archetype_idx);
+        entity
+    }
+
+    /// Stores the bundle of an allocated entity without components in an already resolved archetype.
+    pub(crate) fn put_in_archetype(
+        &mut self,
+        entity: Entity,
+        bundle: impl Bundle,
+        archetype_idx: usize,
+    ) {
         let archetype = &mut self.archetypes[archetype_idx];
         let row = archetype.count();
 
-        bundle.put(entity, archetype);
+        let bitmask = archetype.bitmask().clone();
+        bundle.put(entity, archetype, self.change_tick.load(Ordering::Relaxed));
 
         self.entities.metas[entity.index].location = Location {
             archetype: archetype_idx,
             row,
         };
+        self.index_spawned_name(entity, &bitmask);
+        self.log_spawn(entity);
+        self.run_hooks(entity, Hook::Add, bitmask);
+    }
 
-        entity
+    /// Returns the index of the archetype with the given bitmask, creating it when it doesn't exist yet.
+    pub(crate) fn archetype_index(&mut self, bitmask: &ComponentMask) -> usize {
+        if let Some(index) = self.archetype_map.get(bitmask) {
+            return *index;
+        }
+
+        let index = self.archetypes.len();
+        let mut archetype = Archetype::new(bitmask.clone());
+        archetype.set_compact_until(self.compact_threshold);
+        for info in self
+            .components
+            .iter()
+            .filter(|info| info.column_capacity > 0 && bitmask.contains(&info.bit))
+        {
+            archetype.with(info.id, info.type_info);
+            archetype
+                .column_mut(&info.id)
+                .unwrap()
+                .reserve(info.column_capacity);
+        }
+        self.archetypes.push(archetype);
+        self.archetype_map.insert(bitmask.clone(), index);
+        self.groups
+            .track(index, &self.archetypes, self.deterministic);
+
+        if !self.archetype_callbacks.is_empty() {
+            let info = ArchetypeInfo {
+                id: ArchetypeId(index),
+                components: self
+                    .components
+                    .iter()
+                    .filter(|info| bitmask.contains(&info.bit))
+                    .map(|info| (info.id, info.name))
+                    .collect(),
+                bitmask: bitmask.clone(),
+            };
+            for callback in &mut self.archetype_callbacks {
+                callback(&info);
+            }
+        }
+
+        index
+    }
+
+    /// Creates the archetype of the bundle `B` with all of its columns up front and returns its id, so spawning `B` never has to create it.
+    /// Preregistering the known archetypes in a fixed order at startup gives them the same ids on every run.
+    pub fn preregister_archetype<B: Bundle>(&mut self) -> ArchetypeId {
+        B::register(self);
+        let index = self.archetype_index(&B::bitmask(self));
+        B::init_columns(&mut self.archetypes[index]);
+        ArchetypeId(index)
+    }
+
+    /// Reserves room for `additional` more archetypes and their lookup.
+    pub(crate) fn reserve_archetypes(&mut self, additional: usize) {
+        self.archetypes.reserve(additional);
+        self.archetype_map.reserve(additional);
+    }
+
+    /// Makes archetypes created afterwards store their rows compactly until they hold `threshold` entities, `None` turns it off.
+    /// Compact columns grow by a single row instead of doubling, so worlds with many rarely populated component combinations
+    /// don't keep unused capacity for each of them. Once an archetype passes the threshold it is promoted to amortized growth for good.
+    /// Only the memory layout changes: every combination still gets its own archetype, which queries match and visit like any other,
+    /// and [`World::gc_archetypes`] is what drops archetypes which emptied out.
+    pub fn set_compact_threshold(&mut self, threshold: Option<usize>) {
+        self.compact_threshold = threshold.unwrap_or(0);
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn compact_threshold(&self) -> Option<usize> {
+        Some(self.compact_threshold).filter(|threshold| *threshold != 0)
+    }
+
+    /// Returns `true` when the archetype still stores its rows compactly, see [`World::set_compact_threshold`].
+    #[must_use]
+    pub fn is_archetype_compact(&self, id: ArchetypeId) -> bool {
+        self.archetypes.get(id.0).is_some_and(Archetype::is_compact)
+    }
+
+    /// Resolves the archetype of the bundle `B` once, creating it when needed, so [`World::spawn_in`] can skip the bitmask computation and the archetype lookup.
+    pub fn archetype_handle<B: Bundle>(&mut self) -> ArchetypeHandle<B> {
+        let id = self.preregister_archetype::<B>();
+        ArchetypeHandle {
+            id,
+            world: self.id(),
+            epoch: self.archetype_epoch,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Spawns an [`Entity`] directly into the archetype resolved by [`World::archetype_handle`]. Use it on hot paths spawning many identical entities.
+    /// Panics when the handle was created by another world, or before [`World::gc_archetypes`] dropped archetypes or [`World::clear`] ran.
+    pub fn spawn_in<B: Bundle>(&mut self, handle: ArchetypeHandle<B>, bundle: B) -> Entity {
+        assert!(
+            handle.world == self.id() && handle.epoch == self.archetype_epoch,
+            "Archetype handle does not belong to this world or is stale"
+        );
+        self.spawn_in_archetype(bundle, handle.id.0)
+    }
+
+    /// Returns the id of the archetype storing the entity, or `None` when it is dead or has no components.
+    #[must_use]
+    pub fn archetype_id_of(&self, entity: Entity) -> Option<ArchetypeId> {
+        if !self.is_alive(entity) || self.is_empty(entity) {
+            return None;
+        }
+        Some(ArchetypeId(
+            self.entities.metas[entity.index].location.archetype,
+        ))
+    }
+
+    /// Registers a callback invoked whenever a new archetype is created, so per-archetype data can be initialized eagerly.
+    /// The callback can't access the world, because it is called in the middle of the operation which created the archetype.
+    pub fn on_archetype_created(&mut self, callback: impl FnMut(&ArchetypeInfo) + 'static) {
+        self.archetype_callbacks.push(Box::new(callback));
     }
 
     /// Spawn an entity with no components. Location in the entity's meta is equal to [`Location::EMPTY`]. It is possible to check if the entity has a component using [`World::is_empty`].
     pub fn spawn_empty(&mut self) -> Entity {
-        self.entities.create()
+        if let Err(error) = self.check_quota(None) {
+            panic!("{error}");
+        }
+        let entity = self.alloc_entity();
+        self.log_spawn(entity);
+        entity
     }
 
     /// Inserts a component into an entity. Does archetypal move if necessary (e.g. when the entity already has another components).
     /// Inserting already existing component will overwrite it. ZST are also supported
     pub fn insert_component<T: Component>(&mut self, entity: Entity, component: T) {
-        // TODO: Improve performance, add safety checks and comments, do not use blob data API directly
         if !self.is_alive(entity) {
             return;
         }
 
-        let typeid = TypeId::of::<T>();
-        let bit = if let Some(bit) = self.bitmap.get(&typeid) {
-            *bit
-        } else {
-            self.register_component::<T>();
-            self.bitmap[&typeid]
-        };
+        // Replace hooks still see the old value, and may despawn the entity or remove the component
+        if self.has_component::<T>(entity) {
+            self.run_hooks(entity, Hook::Replace, self.bit_of::<T>().unwrap());
+            if !self.is_alive(entity) {
+                return;
+            }
+        }
+
+        let named = ComponentId::of::<T>() == ComponentId::of::<Name>();
+        if named {
+            self.unindex_name(entity);
+        }
+        let replaced = self.has_component::<T>(entity);
+        self.insert_component_inner(entity, component);
+        if named {
+            self.index_name(entity);
+        }
+        self.log_insert(entity, ComponentId::of::<T>());
+
+        if !replaced {
+            self.run_hooks(entity, Hook::Add, self.bit_of::<T>().unwrap());
+        }
+    }
+
+    fn insert_component_inner<T: Component>(&mut self, entity: Entity, component: T) {
+        if !self.is_alive(entity) {
+            return;
+        }
+
+        let bit = self.register_component::<T>();
+        let mut component = std::mem::ManuallyDrop::new(component);
+        unsafe {
+            // SAFETY: The bytes come from a value of `T`, which is forgotten so the column takes it over
+            self.insert_bytes(
+                entity,
+                ComponentId::of::<T>(),
+                &bit,
+                TypeInfo::of::<T>(),
+                (&mut *component as *mut T).cast(),
+            );
+        }
+    }
+
+    /// Untyped version of [`World::insert_component`] for the registered component with the given id and bit.
+    ///
+    /// # Safety
+    /// Caller must ensure that the entity is alive and `bytes` points to a valid value described by `info`, which is moved into the world.
+    pub(crate) unsafe fn insert_bytes(
+        &mut self,
+        entity: Entity,
+        typeid: ComponentId,
+        bit: &ComponentMask,
+        info: TypeInfo,
+        bytes: *mut u8,
+    ) {
+        // TODO: Improve performance, add safety checks and comments, do not use blob data API directly
+        self.timers.cancel(entity, typeid);
+
         let source_archetype = self.archetype_of(entity);
 
         // Check if the entity already has the component
         if let Some(source_arch) = source_archetype
-            && source_arch.bitmask() & bit == bit
+            && source_arch.bitmask().contains(bit)
         {
             let meta = &self.entities.metas[entity.index];
             // Get the mutable reference to the source archetype
@@ -117,35 +440,21 @@ impl World {
 
                 // Drop the old component
                 column.type_info().call_drop(ptr);
-                std::ptr::copy_nonoverlapping(
-                    &component as *const T as *const u8,
-                    ptr,
-                    column.type_info().size,
-                );
+                std::ptr::copy_nonoverlapping(bytes, ptr, column.type_info().size);
             }
-            std::mem::forget(component);
+            column.set_tick(meta.location.row, self.change_tick.load(Ordering::Relaxed));
 
             return;
         }
 
         let target_bitmask = if let Some(source_archetype) = source_archetype {
-            source_archetype.bitmask() | bit
+            source_archetype.bitmask().clone() | bit
         } else {
-            bit
+            bit.clone()
         };
 
         // Try to find existing archetype with needed bitmask, otherwise create a new one
-        let target_archetype_index =
-            if let Some(archetype_index) = self.archetype_map.get(&target_bitmask) {
-                *archetype_index
-            } else {
-                // Insert new archetype and return its index
-                let archetype = Archetype::new(target_bitmask);
-                self.archetypes.push(archetype);
-                self.archetype_map
-                    .insert(target_bitmask, self.archetypes.len() - 1);
-                self.archetypes.len() - 1
-            };
+        let target_archetype_index = self.archetype_index(&target_bitmask);
 
         // We need to handle empty entities differently, because they don't have an source archetype yet
         if self.is_empty(entity) {
@@ -153,8 +462,14 @@ impl World {
             let row = target_archetype.count();
 
             // Add the new component to the target archetype
-            target_archetype.with(typeid, TypeInfo::of::<T>());
-            target_archetype.insert(component);
+            target_archetype.with(typeid, info);
+            unsafe {
+                target_archetype.insert_bytes(
+                    typeid,
+                    bytes,
+                    ComponentTicks::new(self.change_tick.load(Ordering::Relaxed)),
+                ); // SAFETY: The caller guarantees the bytes match the column
+            }
 
             // Insert the new entity into the target archetype
             target_archetype.insert_row(entity);
@@ -177,16 +492,24 @@ impl World {
         // Move other entity's components to the new archetype
         let moved = source_archetype.move_to(
             self.entities.metas[entity.index].location.row,
-            |bytes, typeid, typeinfo| {
+            |bytes, typeid, typeinfo, tick| {
                 target_archetype.with(typeid, *typeinfo);
-                target_archetype.insert_bytes(typeid, bytes);
+                unsafe {
+                    target_archetype.insert_bytes(typeid, bytes, tick); // SAFETY: The bytes were just moved out of the column with the same type
+                }
             },
         );
         let row = target_archetype.count();
 
         // Insert the new component into new archetype
-        target_archetype.with(typeid, TypeInfo::of::<T>());
-        target_archetype.insert(component);
+        target_archetype.with(typeid, info);
+        unsafe {
+            target_archetype.insert_bytes(
+                typeid,
+                bytes,
+                ComponentTicks::new(self.change_tick.load(Ordering::Relaxed)),
+            ); // SAFETY: The caller guarantees the bytes match the column
+        }
 
         // Insert the old entity into new archetype
         target_archetype.insert_row(entity);
@@ -209,10 +532,140 @@ impl World {
         };
     }
 
+    /// Inserts every component of the bundle with a single archetypal move, instead of one move per component like repeated [`World::insert_component`] calls.
+    /// Components the entity already has are overwritten.
+    pub fn insert_bundle<B: Bundle>(&mut self, entity: Entity, bundle: B) {
+        if !self.is_alive(entity) {
+            return;
+        }
+
+        B::register(self);
+        let bundle_bitmask = B::bitmask(self);
+        // Replace hooks still see the old values, and may despawn the entity or change its components
+        let replaced = self
+            .archetype_of(entity)
+            .map_or(ComponentMask::EMPTY, |archetype| {
+                bundle_bitmask.clone() & archetype.bitmask()
+            });
+        self.run_hooks(entity, Hook::Replace, replaced);
+        if !self.is_alive(entity) {
+            return;
+        }
+
+        let ids = B::component_ids();
+        for id in &ids {
+            self.timers.cancel(entity, *id);
+        }
+        let named = ids.contains(&ComponentId::of::<Name>());
+        if named {
+            self.unindex_name(entity);
+        }
+
+        let location = self.entities.metas[entity.index].location;
+        let source_bitmask = self
+            .archetype_of(entity)
+            .map_or(ComponentMask::EMPTY, |archetype| {
+                archetype.bitmask().clone()
+            });
+        let target_bitmask = source_bitmask.clone() | &bundle_bitmask;
+        let target_archetype_index = self.archetype_index(&target_bitmask);
+
+        if self.is_empty(entity) {
+            let target_archetype = &mut self.archetypes[target_archetype_index];
+            let row = target_archetype.count();
+            bundle.put(
+                entity,
+                target_archetype,
+                self.change_tick.load(Ordering::Relaxed),
+            );
+
+            self.entities.metas[entity.index].location = Location {
+                archetype: target_archetype_index,
+                row,
+            };
+        } else if source_bitmask == target_bitmask {
+            // The entity has every component already, so they are overwritten in place
+            let archetype = &mut self.archetypes[location.archetype];
+            let slots: Vec<_> = ids
+                .iter()
+                .map(|id| {
+                    let column = archetype.column_mut(id).unwrap();
+                    column.set_tick(location.row, self.change_tick.load(Ordering::Relaxed));
+                    unsafe {
+                        // SAFETY: The row belongs to the entity, its old value is dropped so the slot can be written again
+                        let slot = column.get_bytes(location.row);
+                        column.type_info().call_drop(slot);
+                        slot
+                    }
+                })
+                .collect();
+
+            unsafe {
+                bundle.write(&slots, 0); // SAFETY: The slots are in the order of the component ids and were dropped above
+            }
+        } else {
+            let (source_archetype, target_archetype) = index2(
+                &mut self.archetypes,
+                location.archetype,
+                target_archetype_index,
+            );
+
+            // Components of the bundle replace the old values but keep their added ticks, the others are moved over
+            let mut replaced = Vec::new();
+            let moved = source_archetype.move_to(location.row, |bytes, typeid, typeinfo, tick| {
+                if ids.contains(&typeid) {
+                    unsafe {
+                        typeinfo.call_drop(bytes);
+                    }
+                    replaced.push((typeid, tick.added));
+                    return;
+                }
+                target_archetype.with(typeid, *typeinfo);
+                unsafe {
+                    target_archetype.insert_bytes(typeid, bytes, tick); // SAFETY: The bytes were just moved out of the column with the same type
+                }
+            });
+            let row = target_archetype.count();
+            bundle.put(
+                entity,
+                target_archetype,
+                self.change_tick.load(Ordering::Relaxed),
+            );
+            for (typeid, added) in replaced {
+                target_archetype
+                    .column_mut(&typeid)
+                    .unwrap()
+                    .set_added(row, added);
+            }
+
+            if let Some(moved) = moved {
+                self.entities.metas[moved.index].location = location;
+            }
+            self.entities.metas[entity.index].location = Location {
+                archetype: target_archetype_index,
+                row,
+            };
+        }
+
+        if named {
+            self.index_name(entity);
+        }
+        for id in ids {
+            self.log_insert(entity, id);
+        }
+
+        self.run_hooks(
+            entity,
+            Hook::Add,
+            bundle_bitmask.difference(&source_bitmask),
+        );
+    }
+
     /// Checks if the entity has the component of type `T`.
     #[must_use]
     pub fn has_component<T: Component>(&self, entity: Entity) -> bool {
-        let Some(source_archetype) = self.archetype_of(entity) else {
+        let Some(source_archetype) = self.archetype_of(entity).filter(|_| self.is_alive(entity))
+        else {
             return false;
         };
 
@@ -220,92 +673,135 @@ impl World {
             return false;
         };
 
-        source_archetype.bitmask() & bit != 0
+        source_archetype.bitmask().intersects(&bit)
     }
 
-    /// Removes the component of type `T` from the entity. Does archetypal move if necessary.
-    pub fn remove_component<T: Component>(&mut self, entity: Entity) {
-        // TODO: Improve performance, add safety checks and comments, do not use blob data API directly
-        if !self.is_alive(entity) || self.is_empty(entity) {
-            return;
-        }
+    /// Removes the component of type `T` from the entity and returns it. Does archetypal move if necessary.
+    /// Returns `None` when the entity is dead or has no such component.
+    pub fn remove_component<T: Component>(&mut self, entity: Entity) -> Option<T> {
+        self.take_component(entity)
+    }
 
-        let removed_typeid = TypeId::of::<T>();
+    /// Moves the component of type `T` out of its column and returns it, transferring the ownership back out of the world.
+    /// Returns `None` when the entity is dead or has no such component.
+    pub fn take_component<T: Component>(&mut self, entity: Entity) -> Option<T> {
+        self.take_bundle::<T>(entity)
+    }
 
-        let Some(bit) = self.bitmap.get(&removed_typeid) else {
-            return;
-        };
+    /// Removes every component of the bundle the entity has with a single archetypal move, components it doesn't have are ignored.
+    /// Returns `false` when nothing was removed.
+    pub fn remove_bundle<B: Bundle>(&mut self, entity: Entity) -> bool {
+        let ids = B::component_ids();
+        self.run_remove_hooks(entity, &ids);
+        self.remove_ids(entity, &ids, |bytes, _, info| unsafe {
+            info.call_drop(bytes); // SAFETY: The bytes were just moved out of the column
+        })
+    }
 
-        let Some(source_archetype) = self.archetype_of(entity) else {
-            return;
+    /// Removes the components of the bundle with a single archetypal move and returns them.
+    /// Returns `None` without removing anything when the entity is dead or lacks any of them.
+    pub fn take_bundle<B: Bundle>(&mut self, entity: Entity) -> Option<B> {
+        let ids = B::component_ids();
+        let has_all = |world: &World| {
+            world
+                .archetype_of(entity)
+                .filter(|_| world.is_alive(entity))
+                .is_some_and(|archetype| ids.iter().all(|id| archetype.column(id).is_some()))
         };
-
-        // Check if the entity doesn't have the component
-        if source_archetype.bitmask() & bit != *bit {
-            return;
+        if !has_all(self) {
+            return None;
+        }
+        self.run_remove_hooks(entity, &ids);
+        // A hook may have removed some of the components already
+        if !has_all(self) {
+            return None;
         }
 
-        let combined_bitmask = source_archetype.bitmask() & !bit;
+        let mut values = vec![std::ptr::null(); ids.len()];
+        self.remove_ids(entity, &ids, |bytes, id, _| {
+            let position = ids.iter().position(|other| *other == id).unwrap();
+            values[position] = bytes.cast_const();
+        });
 
-        // If it is the last component in the entity, remove the component and set the entity's location to EMPTY
-        if combined_bitmask == 0 {
-            let meta = &mut self.entities.metas[entity.index];
-            let source_archetype = &mut self.archetypes[meta.location.archetype];
-            source_archetype.swap_remove(meta.location.row);
+        // SAFETY: Popped values stay in the spare capacity of their columns until something is pushed into them again
+        Some(unsafe { B::read(&values) })
+    }
 
-            meta.location = Location::EMPTY;
-            return;
+    /// Moves the entity into the archetype without the given components, passing each removed value to `take` which must drop or move it.
+    /// Returns `false` when the entity has none of them.
+    pub(crate) fn remove_ids(
+        &mut self,
+        entity: Entity,
+        ids: &[ComponentId],
+        mut take: impl FnMut(*mut u8, ComponentId, &TypeInfo),
+    ) -> bool {
+        if !self.is_alive(entity) || self.is_empty(entity) {
+            return false;
         }
 
-        let target_archetype_index = if let Some(index) = self.archetype_map.get(&combined_bitmask)
-        {
-            *index
-        } else {
-            let new_archetype = Archetype::new(combined_bitmask);
-            self.archetypes.push(new_archetype);
-            self.archetype_map
-                .insert(combined_bitmask, self.archetypes.len() - 1);
-            self.archetypes.len() - 1
-        };
+        let location = self.entities.metas[entity.index].location;
+        let source_bitmask = self.archetypes[location.archetype].bitmask().clone();
+        let removed_bitmask = ids
+            .iter()
+            .filter_map(|id| self.bit_of_id(id))
+            .fold(ComponentMask::EMPTY, |mask, bit| mask | bit)
+            & &source_bitmask;
+        if removed_bitmask.is_empty() {
+            return false;
+        }
+
+        if ids.contains(&ComponentId::of::<Name>()) {
+            self.unindex_name(entity);
+        }
+        for id in ids {
+            if self.archetypes[location.archetype].column(id).is_some() {
+                self.record_removed(entity, *id);
+                self.log_remove(entity, *id);
+            }
+        }
+
+        // Only removed components are left, so the entity becomes empty
+        let target_bitmask = source_bitmask.difference(&removed_bitmask);
+        if target_bitmask.is_empty() {
+            let moved = self.archetypes[location.archetype]
+                .move_to(location.row, |bytes, typeid, typeinfo, _| {
+                    take(bytes, typeid, typeinfo)
+                });
+            if let Some(moved) = moved {
+                self.entities.metas[moved.index].location = location;
+            }
+
+            self.entities.metas[entity.index].location = Location::EMPTY;
+            return true;
+        }
 
+        let target_archetype_index = self.archetype_index(&target_bitmask);
         let (source_archetype, target_archetype) = index2(
             &mut self.archetypes,
-            self.entities.metas[entity.index].location.archetype,
+            location.archetype,
             target_archetype_index,
         );
 
-        // Move remaining components from source archetype to target archetype and drop the removed one
-        let moved = source_archetype.move_to(
-            self.entities.metas[entity.index].location.row,
-            |bytes, typeid, typeinfo| {
-                if typeid == removed_typeid {
-                    // We are removing the component, so we need to drop it
-                    unsafe {
-                        typeinfo.call_drop(bytes);
-                    }
-                    return;
-                }
-                target_archetype.with(typeid, *typeinfo);
-                target_archetype.insert_bytes(typeid, bytes);
-            },
-        );
-
+        let moved = source_archetype.move_to(location.row, |bytes, typeid, typeinfo, tick| {
+            if ids.contains(&typeid) {
+                take(bytes, typeid, typeinfo);
+                return;
+            }
+            target_archetype.with(typeid, *typeinfo);
+            unsafe {
+                target_archetype.insert_bytes(typeid, bytes, tick); // SAFETY: The bytes were just moved out of the column with the same type
+            }
+        });
         target_archetype.insert_row(entity);
 
-        // If some entity has moved into this entity's previous location, we need to update it
         if let Some(moved) = moved {
-            let meta = &self.entities.metas[entity.index];
-            self.entities.metas[moved.index].location = Location {
-                archetype: meta.location.archetype,
-                row: meta.location.row,
-            };
+            self.entities.metas[moved.index].location = location;
         }
-
-        // Update the entity's location to the new archetype and row
         self.entities.metas[entity.index].location = Location {
             archetype: target_archetype_index,
             row: target_archetype.count() - 1,
         };
+        true
     }
 
     /// Returns an immutable reference to the `T` component in the given entity.
@@ -320,6 +816,19 @@ impl World {
         archetype.get(meta.location.row)
     }
 
+    /// Returns the ticks at which the `T` component was added to the entity and last changed.
+    #[must_use]
+    pub fn component_ticks<T: Component>(&self, entity: Entity) -> Option<ComponentTicks> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+
+        let meta = &self.entities.metas[entity.index];
+        let archetype = self.archetypes.get(meta.location.archetype)?;
+        let column = archetype.column(&ComponentId::of::<T>())?;
+        Some(column.ticks(meta.location.row))
+    }
+
     /// Returns a mutable reference to the `T` component in the given entity.
     #[must_use]
     pub fn get_component_mut<T: Component>(&mut self, entity: Entity) -> Option<&mut T> {
@@ -329,67 +838,335 @@ impl World {
 
         let meta = &mut self.entities.metas[entity.index];
         let archetype = self.archetypes.get_mut(meta.location.archetype)?;
-        archetype.get_mut(meta.location.row)
+        archetype.get_mut(meta.location.row, self.change_tick.load(Ordering::Relaxed))
     }
 
-    /// Despawns the given entity.
-    pub fn despawn_entity(&mut self, entity: Entity) {
-        let Some(meta) = self.entities.metas.get(entity.index) else {
-            return;
-        };
+    /// Returns mutable references to the `T` components of several entities at once, e.g. to let an attacker damage its target.
+    /// Returns `None` when any entity is dead, has no such component or is given more than once.
+    #[must_use]
+    pub fn get_many_mut<T: Component, const N: usize>(
+        &mut self,
+        entities: [Entity; N],
+    ) -> Option<[&mut T; N]> {
+        let typeid = ComponentId::of::<T>();
+        let mut slots = [(std::ptr::null_mut(), Location::EMPTY); N];
+        for (index, entity) in entities.iter().enumerate() {
+            if !self.is_alive(*entity) || entities[..index].contains(entity) {
+                return None;
+            }
+
+            let location = self.entities.metas[entity.index].location;
+            let bytes = self
+                .archetypes
+                .get(location.archetype)?
+                .get_bytes(typeid, location.row)?;
+            slots[index] = (bytes.cast::<T>(), location);
+        }
 
-        let location = meta.location;
+        // Nothing is marked as changed unless every component was found
+        for (_, location) in &slots {
+            let column = self.archetypes[location.archetype].column(&typeid).unwrap();
+            column.set_tick(location.row, self.change_tick.load(Ordering::Relaxed));
+        }
+
+        // SAFETY: The entities are distinct, so the references point to different values, and the world is borrowed mutably
+        Some(slots.map(|(ptr, _)| unsafe { &mut *ptr }))
+    }
 
+    /// Despawns the given entity. Returns `false` when it was dead already, e.g. because the handle is stale.
+    pub fn despawn_entity(&mut self, entity: Entity) -> bool {
         if !self.is_alive(entity) {
-            return;
+            return false;
+        }
+        self.run_despawn_hooks(entity);
+        // The hook may have despawned the entity itself
+        if !self.is_alive(entity) {
+            return true;
         }
+        self.unlink_hierarchy(entity);
+        self.unindex_name(entity);
+        self.record_despawned(entity);
+        self.log_despawn(entity);
 
-        let Some(archetype) = self.archetypes.get_mut(meta.location.archetype) else {
-            return;
-        };
-        if let Some(moved) = archetype.swap_remove(meta.location.row) {
+        let location = self.entities.metas[entity.index].location;
+
+        // Empty entities are not stored in any archetype
+        if let Some(archetype) = self.archetypes.get_mut(location.archetype)
+            && let Some(moved) = archetype.swap_remove(location.row)
+        {
             let moved_meta = &mut self.entities.metas[moved.index];
             moved_meta.location = location;
         }
 
-        let meta = &mut self.entities.metas[entity.index];
+        self.entities.release(entity.index);
+        true
+    }
 
-        meta.generation += 1;
-        meta.location = Location::EMPTY;
+    /// Despawns every entity matching the filter `F` and returns how many were despawned, e.g. `world.despawn_where::<With<Expired>>()`.
+    /// Like queries, disabled entities are only matched when the filter asks for them.
+    /// Rows are removed archetype by archetype, archetypes left without other entities are cleared at once.
+    /// Panics with row filters like [`Changed`](crate::query::Changed), use a query to collect those entities instead.
+    pub fn despawn_where<F: Filter>(&mut self) -> usize {
+        assert!(
+            !F::filters_rows(),
+            "Cannot despawn through a filter with row filters"
+        );
+        let filter = QueryData::<Entity, F>::archetype_filter(self);
+        let mut targets: Vec<Entity> = self
+            .archetypes
+            .iter()
+            .filter(|archetype| filter.matches(archetype.bitmask()))
+            .flat_map(|archetype| archetype.entities().iter().copied())
+            .collect();
+
+        for &entity in &targets {
+            self.run_despawn_hooks(entity);
+        }
+        targets.retain(|entity| self.is_alive(*entity));
+        for &entity in &targets {
+            self.unlink_hierarchy(entity);
+            self.unindex_name(entity);
+            self.record_despawned(entity);
+            self.log_despawn(entity);
+        }
 
-        self.entities.free.push(entity.index);
-    }
+        // Unlinking the hierarchy may have moved some of the entities, so they are grouped by their current location
+        let mut rows: HashMap<usize, Vec<usize>> = HashMap::new();
+        for entity in &targets {
+            let location = self.entities.metas[entity.index].location;
+            if location.archetype < self.archetypes.len() {
+                rows.entry(location.archetype)
+                    .or_default()
+                    .push(location.row);
+            }
+        }
 
-    /// Creates a query data which can be later used to iterate over entities. Store the returned query data so the cache might be used to optimize future queries.
-    #[inline]
-    #[must_use]
-    pub fn query<Q: QueryItem>(&mut self) -> QueryData<Q> {
-        QueryData::new(self)
-    }
+        for (index, mut rows) in rows {
+            let archetype = &mut self.archetypes[index];
+            if rows.len() == archetype.count() {
+                archetype.clear();
+                continue;
+            }
 
-    /// Creates a filtered query data which can be later used to iterate over entities. Store the returned query data so the cache might be used to optimize future queries.
-    #[inline]
-    #[must_use]
-    pub fn query_filtered<Q: QueryItem, F: Filter>(&mut self) -> QueryData<Q, F> {
-        QueryData::new(self)
+            // Removing from the back only ever moves rows which are kept
+            rows.sort_unstable_by(|a, b| b.cmp(a));
+            for row in rows {
+                if let Some(moved) = archetype.swap_remove(row) {
+                    self.entities.metas[moved.index].location = Location {
+                        archetype: index,
+                        row,
+                    };
+                }
+            }
+        }
+
+        for entity in &targets {
+            self.entities.release(entity.index);
+        }
+        targets.len()
     }
 
-    #[inline]
-    #[must_use]
+    /// Despawns every entity and drops all component data, but keeps the registered components and the archetypes with their columns,
+    /// so spawning the next level or test run reuses the storage and existing queries stay valid. Handles of the despawned entities stay invalid.
+    /// Entities are not unlinked from pending timers one by one, every timer and transient value is dropped at once.
+    pub fn clear_entities(&mut self) {
+        for entity in self.entities.alive().collect::<Vec<_>>() {
+            self.run_despawn_hooks(entity);
+        }
+
+        let alive: Vec<_> = self.entities.alive().collect();
+        for entity in alive {
+            self.record_despawned(entity);
+            self.log_despawn(entity);
+        }
+
+        for archetype in &mut self.archetypes {
+            archetype.clear();
+        }
+        self.entities.clear();
+        self.names.clear();
+        self.deferred_despawns
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+        self.timers = Timers::default();
+        self.clear_transients();
+    }
+
+    /// Despawns every entity like [`World::clear_entities`] and also drops the archetypes and the registered components,
+    /// including their clone, comparison and sharing functions. Other settings like callbacks, subscriptions and quotas are kept.
+    /// Component hooks and observers are kept as well and apply again once their components are registered again,
+    /// and queries created before keep working then too.
+    pub fn clear(&mut self) {
+        self.clear_entities();
+
+        self.archetype_map.clear();
+        self.archetypes.clear();
+        self.archetype_epoch = next_archetype_epoch();
+        self.components.clear();
+        self.names = Names::default();
+        self.quotas.archetypes.clear();
+        self.match_lists.invalidate();
+        self.reregister_groups();
+    }
+
+    /// Drops the archetypes without entities, so queries stop scanning them, and returns how many were dropped.
+    /// Archetypes with a limit set by [`World::set_archetype_limit`] are kept. The remaining archetypes are renumbered,
+    /// so [`ArchetypeId`]s and [`ArchetypeHandle`]s obtained before become stale, existing queries keep working.
+    pub fn gc_archetypes(&mut self) -> usize {
+        let mut indices = Vec::with_capacity(self.archetypes.len());
+        let mut kept = 0;
+        for (index, archetype) in self.archetypes.iter().enumerate() {
+            if archetype.count() > 0 || self.quotas.archetypes.contains_key(&index) {
+                indices.push(kept);
+                kept += 1;
+            } else {
+                indices.push(usize::MAX);
+            }
+        }
+        let removed = self.archetypes.len() - kept;
+        if removed == 0 {
+            return 0;
+        }
+
+        self.archetype_epoch = next_archetype_epoch();
+        let mut index = 0;
+        self.archetypes.retain(|_| {
+            index += 1;
+            indices[index - 1] != usize::MAX
+        });
+        self.archetype_map = self
+            .archetypes
+            .iter()
+            .enumerate()
+            .map(|(index, archetype)| (archetype.bitmask().clone(), index))
+            .collect();
+
+        for meta in &mut self.entities.metas {
+            if meta.location != Location::EMPTY {
+                meta.location.archetype = indices[meta.location.archetype];
+            }
+        }
+        self.quotas.archetypes = self
+            .quotas
+            .archetypes
+            .drain()
+            .map(|(index, limit)| (indices[index], limit))
+            .collect();
+        self.groups.remap(&indices);
+        self.match_lists.invalidate();
+        removed
+    }
+
+    /// Marks the entity to be despawned by the next [`World::flush`], until then it stays alive and visible to queries.
+    /// It only needs a shared reference, so it can be called while iterating over a query.
+    pub fn despawn_deferred(&self, entity: Entity) {
+        self.deferred_despawns().push(entity);
+    }
+
+    /// Returns `true` when the entity was passed to [`World::despawn_deferred`] and the despawn was not applied yet.
+    #[must_use]
+    pub fn is_despawn_pending(&self, entity: Entity) -> bool {
+        self.deferred_despawns().contains(&entity)
+    }
+
+    fn deferred_despawns(&self) -> MutexGuard<'_, Vec<Entity>> {
+        self.deferred_despawns
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Despawns every entity passed to [`World::despawn_deferred`] since the last call.
+    pub(crate) fn apply_deferred_despawns(&mut self) {
+        let pending = std::mem::take(
+            self.deferred_despawns
+                .get_mut()
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        for entity in pending {
+            self.despawn_entity(entity);
+        }
+    }
+
+    /// Creates a query data which can be later used to iterate over entities. Store the returned query data so the cache might be used to optimize future queries.
+    /// The world keeps a state of every query type, so even a query created every frame starts from the matched archetypes, see [`World::with_query`].
+    #[inline]
+    #[must_use]
+    pub fn query<Q: QueryItem + 'static>(&mut self) -> QueryData<Q> {
+        self.cached_query()
+    }
+
+    /// Creates a filtered query data which can be later used to iterate over entities. Store the returned query data so the cache might be used to optimize future queries.
+    #[inline]
+    #[must_use]
+    pub fn query_filtered<Q: QueryItem + 'static, F: Filter + 'static>(
+        &mut self,
+    ) -> QueryData<Q, F> {
+        self.cached_query()
+    }
+
+    #[inline]
+    #[must_use]
     pub fn is_empty(&self, entity: Entity) -> bool {
         self.entities
             .metas
             .get(entity.index)
-            .map_or(true, |meta| meta.location == Location::EMPTY)
+            .is_none_or(|meta| meta.location == Location::EMPTY)
     }
 
     #[inline]
     #[must_use]
     pub fn is_alive(&self, entity: Entity) -> bool {
-        self.entities
-            .metas
-            .get(entity.index)
-            .map_or(false, |meta| meta.generation == entity.generation)
+        self.entities.contains(entity)
+    }
+
+    /// Number of entity slots which used up their generations and are never reused, see [`MAX_GENERATION`].
+    #[inline]
+    #[must_use]
+    pub fn retired_slots(&self) -> usize {
+        self.entities.retired
+    }
+
+    /// Returns the unique id of the world. Clones made by [`World::clone_world`] and mirrors filled by [`World::extract_into`] share it,
+    /// because the handles of this world are valid in them.
+    #[inline]
+    #[must_use]
+    pub fn id(&self) -> WorldId {
+        self.entities.world
+    }
+
+    /// Returns the number of alive entities, including the ones without components.
+    #[inline]
+    #[must_use]
+    pub fn entity_count(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Iterates over every alive entity by index, including the ones without components.
+    pub fn iter_entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entities.alive()
+    }
+
+    /// Iterates over every alive entity together with its location, which is `None` for entities without components.
+    pub fn iter_entity_locations(&self) -> impl Iterator<Item = (Entity, Option<Location>)> + '_ {
+        self.entities.alive().map(|entity| {
+            let location = self.entities.metas[entity.index].location;
+            (entity, (location != Location::EMPTY).then_some(location))
+        })
+    }
+
+    /// Returns the current change tick. Inserted and mutably accessed components are marked with it, and it advances whenever changes are consumed
+    /// (e.g. by [`World::extract_into`] or by iterating a query with [`Added`](crate::query::Added) or [`Changed`](crate::query::Changed)).
+    #[inline]
+    #[must_use]
+    pub fn change_tick(&self) -> u64 {
+        self.change_tick.load(Ordering::Relaxed)
+    }
+
+    /// Advances the change tick and returns the previous one, every change made before this call is marked with a tick lower or equal to it.
+    #[inline]
+    pub(crate) fn increment_change_tick(&self) -> u64 {
+        self.change_tick.fetch_add(1, Ordering::Relaxed)
     }
 
     #[inline]
@@ -398,6 +1175,28 @@ impl World {
         &self.archetypes
     }
 
+    #[inline]
+    #[must_use]
+    pub(crate) fn archetypes_mut(&mut self) -> &mut Vec<Archetype> {
+        &mut self.archetypes
+    }
+
+    /// Returns the archetypes matching the filter.
+    pub(crate) fn matching_archetypes<F: Filter>(&self) -> impl Iterator<Item = &Archetype> {
+        let filter = ArchetypeFilter::of::<F>(self);
+        self.ordered_archetypes()
+            .filter(move |archetype| filter.matches(archetype.bitmask()))
+    }
+
+    /// Returns the archetypes in the order queries visit them, by bitmask when the order is deterministic and by creation otherwise.
+    pub(crate) fn ordered_archetypes(&self) -> impl Iterator<Item = &Archetype> {
+        let mut archetypes: Vec<_> = self.archetypes.iter().collect();
+        if self.deterministic {
+            archetypes.sort_unstable_by_key(|archetype| archetype.bitmask());
+        }
+        archetypes.into_iter()
+    }
+
     #[inline]
     #[must_use]
     pub(crate) fn archetype_of(&self, entity: Entity) -> Option<&Archetype> {
@@ -407,21 +1206,222 @@ impl World {
 
     #[inline]
     #[must_use]
-    pub(crate) fn bit_of<T: 'static>(&self) -> Option<u64> {
-        self.bitmap.get(&TypeId::of::<T>()).copied()
+    pub(crate) fn bit_of<T: 'static>(&self) -> Option<ComponentMask> {
+        self.bit_of_id(&ComponentId::of::<T>())
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn bit_of_id(&self, id: &ComponentId) -> Option<ComponentMask> {
+        self.components.get(*id).map(|info| info.bit.clone())
+    }
+
+    #[must_use]
+    pub(crate) fn component_info(&self, id: &ComponentId) -> Option<&ComponentInfo> {
+        self.components.get(*id)
+    }
+
+    #[must_use]
+    pub(crate) fn component_info_mut(&mut self, id: &ComponentId) -> Option<&mut ComponentInfo> {
+        self.components.get_mut(*id)
+    }
+
+    /// Creates a world with the same components, entities and registries as this one, storing the given copies of its archetypes.
+    pub(crate) fn clone_structure(&self, archetypes: Vec<Archetype>) -> World {
+        let mut world = World {
+            archetype_map: self.archetype_map.clone(),
+            archetypes,
+            entities: self.entities.clone(),
+            components: self.components.clone(),
+            change_tick: AtomicU64::new(self.change_tick.load(Ordering::Relaxed)),
+            change_detection: self.change_detection,
+            deterministic: self.deterministic,
+            deferred_despawns: Mutex::new(self.deferred_despawns().clone()),
+            extracted_tick: self.extracted_tick,
+            serializers: self.serializers.clone(),
+            timers: self.timers.clone(),
+            quotas: self.quotas.clone(),
+            names: self.names.clone(),
+            compact_threshold: self.compact_threshold,
+            // The archetypes are copied in order, so handles of this world stay valid in the clone
+            archetype_epoch: self.archetype_epoch,
+            ..World::new()
+        };
+        // Timed components expire by the clock of the world they are in
+        world.insert_resource(self.time());
+        world
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Unique id of a [`World`]. In debug builds every entity carries the id of the world which spawned it,
+/// so using it with another world finds no entity instead of reading whatever lives at the same index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WorldId(u64);
+
+impl WorldId {
+    /// Id of entities which were unpacked or decoded instead of spawned, they are accepted by every world.
+    pub(crate) const ANY: WorldId = WorldId(0);
+
+    fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+        WorldId(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Returns a number no archetype numbering of any world had before, so clones copying the archetypes in order can share it
+/// without a renumbering in one of them validating handles of the other.
+fn next_archetype_epoch() -> u64 {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    NEXT.fetch_add(1, Ordering::Relaxed)
+}
+
+#[derive(Clone, Copy)]
 pub struct Entity {
-    index: usize,
-    generation: usize,
+    pub(crate) index: usize,
+    pub(crate) generation: usize,
+    #[cfg(debug_assertions)]
+    world: WorldId,
+}
+
+// The world is only a debug tag, so entities unpacked from bits are equal to the spawned ones
+impl PartialEq for Entity {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.generation == other.generation
+    }
+}
+
+impl Eq for Entity {}
+
+impl Hash for Entity {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+impl fmt::Debug for Entity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Entity")
+            .field("index", &self.index)
+            .field("generation", &self.generation)
+            .finish()
+    }
+}
+
+impl Entity {
+    #[inline]
+    pub(crate) fn new(index: usize, generation: usize, world: WorldId) -> Self {
+        #[cfg(not(debug_assertions))]
+        let _ = world;
+        Self {
+            index,
+            generation,
+            #[cfg(debug_assertions)]
+            world,
+        }
+    }
+
+    /// Returns `false` when the entity was spawned by another world, which is only known in debug builds.
+    #[inline(always)]
+    pub(crate) fn belongs_to(self, world: WorldId) -> bool {
+        #[cfg(debug_assertions)]
+        return self.world == world || self.world == WorldId::ANY;
+        #[cfg(not(debug_assertions))]
+        {
+            let _ = world;
+            true
+        }
+    }
+
+    /// Packs the entity into 64 bits, the index in the lower and the generation in the upper half.
+    /// Panics when the index or the generation doesn't fit into 32 bits.
+    #[must_use]
+    pub fn to_bits(self) -> u64 {
+        let index = u32::try_from(self.index).expect("Entity index does not fit into 32 bits");
+        let generation =
+            u32::try_from(self.generation).expect("Entity generation does not fit into 32 bits");
+        (u64::from(generation) << 32) | u64::from(index)
+    }
+
+    /// Unpacks an entity packed by [`Entity::to_bits`].
+    #[must_use]
+    pub fn from_bits(bits: u64) -> Self {
+        Self::new(
+            (bits & 0xFFFF_FFFF) as usize,
+            (bits >> 32) as usize,
+            WorldId::ANY,
+        )
+    }
+}
+
+/// Translates entities of another world or of a serialized stream into entities of this world.
+pub trait EntityMapper {
+    fn map_entity(&mut self, entity: Entity) -> Entity;
+}
+
+impl<F: FnMut(Entity) -> Entity> EntityMapper for F {
+    fn map_entity(&mut self, entity: Entity) -> Entity {
+        self(entity)
+    }
+}
+
+/// Maps entities of another world or of a serialized stream to the entities they became in this world.
+#[derive(Debug, Default, Clone)]
+pub struct EntityMap {
+    map: HashMap<Entity, Entity>,
+}
+
+impl EntityMap {
+    /// Returns the entity the given one was mapped to.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, entity: Entity) -> Option<Entity> {
+        self.map.get(&entity).copied()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Iterates over `(source, mapped)` pairs in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, Entity)> + '_ {
+        self.map.iter().map(|(source, mapped)| (*source, *mapped))
+    }
+
+    #[inline]
+    pub(crate) fn insert(&mut self, source: Entity, mapped: Entity) {
+        self.map.insert(source, mapped);
+    }
+}
+
+/// Entities which were not mapped are kept as they are.
+impl EntityMapper for EntityMap {
+    fn map_entity(&mut self, entity: Entity) -> Entity {
+        self.get(entity).unwrap_or(entity)
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Entities {
-    metas: Vec<EntityMeta>,
-    free: Vec<usize>,
+    pub(crate) metas: Vec<EntityMeta>,
+    pub(crate) free: Vec<usize>,
+    /// Number of slots whose generation reached [`RETIRED`], they are neither alive nor free.
+    pub(crate) retired: usize,
+    pub(crate) world: WorldId,
+}
+
+impl Default for Entities {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Entities {
@@ -429,38 +1429,177 @@ impl Entities {
         Self {
             metas: Vec::new(),
             free: Vec::new(),
+            retired: 0,
+            world: WorldId::next(),
         }
     }
 
+    /// Allocates an entity, reusing the most recently freed slot like the [`RecyclingAllocator`].
     pub fn create(&mut self) -> Entity {
-        if !self.free.is_empty() {
-            let slot = self.free.pop().unwrap();
-            let meta = &mut self.metas[slot];
+        self.create_with(&mut RecyclingAllocator)
+    }
+
+    /// Allocates an entity in the slot picked by the allocator. Panics when the slot is not free or new.
+    pub(crate) fn create_with(&mut self, allocator: &mut dyn EntityAllocator) -> Entity {
+        let slot = match allocator.allocate(&self.free, self.metas.len()) {
+            Slot::Free(position) => {
+                assert!(
+                    position < self.free.len(),
+                    "Entity allocator picked a slot which is not free"
+                );
+                self.free.swap_remove(position)
+            }
+            Slot::New(index) => {
+                assert!(
+                    index >= self.metas.len(),
+                    "Entity allocator picked a slot which exists already"
+                );
+                // Skipped slots become free so they can still be allocated later
+                self.free.extend(self.metas.len()..index);
+                self.metas.resize(index + 1, EntityMeta::FREE);
+                index
+            }
+        };
+
+        let meta = &mut self.metas[slot];
+        meta.location = Location::EMPTY;
+        meta.free = false;
+        Entity::new(slot, meta.generation, self.world)
+    }
 
+    /// Returns `true` when the entity is alive. The generation of a retired slot never matches a handle, and free slots
+    /// are rejected because handles with their next generation may exist, e.g. ones of the source world of an extraction.
+    #[inline]
+    #[must_use]
+    pub(crate) fn contains(&self, entity: Entity) -> bool {
+        self.metas.get(entity.index).is_some_and(|meta| {
+            meta.generation == entity.generation && meta.generation != RETIRED && !meta.free
+        }) && entity.belongs_to(self.world)
+    }
+
+    /// Frees the slot of a despawned entity, bumping its generation so its handles become invalid.
+    /// A slot which used up its generations is retired instead, so a stale handle can never alias a new entity.
+    /// Panics when the slot is free already, listing it twice would hand it to two entities.
+    pub(crate) fn release(&mut self, index: usize) {
+        let meta = &mut self.metas[index];
+        assert!(!meta.free, "Cannot free the slot {index} twice");
+        meta.generation += 1;
+        meta.location = Location::EMPTY;
+
+        if meta.generation == RETIRED {
+            self.retired += 1;
+        } else {
+            meta.free = true;
+            self.free.push(index);
+        }
+    }
+
+    /// Frees every slot, bumping the generations of the alive entities so their handles become invalid.
+    pub(crate) fn clear(&mut self) {
+        for meta in &mut self.metas {
+            if !meta.free && meta.generation != RETIRED {
+                meta.generation += 1;
+            }
             meta.location = Location::EMPTY;
+        }
 
-            return Entity {
-                index: slot,
-                generation: meta.generation,
-            };
+        // Slots are popped from the end, so the lowest indices are reused first
+        self.free = (0..self.metas.len())
+            .rev()
+            .filter(|index| self.metas[*index].generation != RETIRED)
+            .collect();
+        self.mark_free();
+        self.recount_retired();
+    }
+
+    /// Marks exactly the slots in the free list as free, after the list was replaced.
+    pub(crate) fn mark_free(&mut self) {
+        for meta in &mut self.metas {
+            meta.free = false;
         }
+        for index in &self.free {
+            self.metas[*index].free = true;
+        }
+    }
 
-        self.metas.push(EntityMeta {
-            generation: 0,
-            location: Location::EMPTY,
-        });
+    /// Recounts the retired slots after the generations were overwritten.
+    pub(crate) fn recount_retired(&mut self) {
+        self.retired = self
+            .metas
+            .iter()
+            .filter(|meta| meta.generation == RETIRED)
+            .count();
+    }
+
+    /// Returns the number of alive entities.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.metas.len() - self.free.len() - self.retired
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterates over every alive entity, including the ones without components.
+    pub(crate) fn alive(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.metas
+            .iter()
+            .enumerate()
+            .filter(|(_, meta)| !meta.free && meta.generation != RETIRED)
+            .map(|(index, meta)| Entity::new(index, meta.generation, self.world))
+    }
 
-        Entity {
-            index: self.metas.len() - 1,
-            generation: 0,
+    /// Makes the exact given entity alive, growing the metas when needed. Returns `false` when its slot is used by an alive entity or retired.
+    pub(crate) fn alloc_at(&mut self, entity: Entity) -> bool {
+        if entity.generation >= RETIRED {
+            return false;
+        }
+        if entity.index >= self.metas.len() {
+            // Skipped slots become free so they can still be allocated later
+            self.free.extend(self.metas.len()..entity.index);
+            self.metas.resize(entity.index + 1, EntityMeta::FREE);
+        } else {
+            let Some(position) = self.free.iter().position(|free| *free == entity.index) else {
+                return false;
+            };
+            self.free.swap_remove(position);
         }
+
+        self.metas[entity.index] = EntityMeta {
+            generation: entity.generation,
+            location: Location::EMPTY,
+            free: false,
+        };
+        true
     }
 }
 
+/// Highest generation of an entity. Once a slot was despawned at this generation it is retired for good instead of wrapping around,
+/// so handles always fit into [`Entity::to_bits`] and a stale handle never matches a new entity, even on 32-bit targets.
+pub const MAX_GENERATION: usize = u32::MAX as usize - 1;
+
+/// Generation of a retired slot, which no handle can have.
+pub(crate) const RETIRED: usize = MAX_GENERATION + 1;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct EntityMeta {
-    generation: usize,
-    location: Location,
+    pub(crate) generation: usize,
+    pub(crate) location: Location,
+    /// Whether the slot is in the free list of [`Entities`], its generation is the one of the next entity stored in it.
+    pub(crate) free: bool,
+}
+
+impl EntityMeta {
+    /// Slot which was never used, it is in the free list.
+    pub(crate) const FREE: EntityMeta = EntityMeta {
+        generation: 0,
+        location: Location::EMPTY,
+        free: true,
+    };
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -470,7 +1609,7 @@ pub struct Location {
 }
 
 impl Location {
-    const EMPTY: Location = Location {
+    pub(crate) const EMPTY: Location = Location {
         archetype: usize::MAX,
         row: usize::MAX,
     };
@@ -487,3 +1626,339 @@ fn index2<T>(x: &mut [T], i: usize, j: usize) -> (&mut T, &mut T) {
 }
 
 pub trait Component: 'static {}
+
+/// Identifies a component type, either a [`Component`] type or one registered at runtime with [`World::register_dynamic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ComponentId(ComponentKey);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum ComponentKey {
+    Type(TypeId),
+    Dynamic(usize),
+}
+
+impl ComponentId {
+    #[inline]
+    #[must_use]
+    pub fn of<T: 'static>() -> Self {
+        Self(ComponentKey::Type(TypeId::of::<T>()))
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn dynamic(index: usize) -> Self {
+        Self(ComponentKey::Dynamic(index))
+    }
+
+    /// Returns the type id of the Rust type, or `None` for a dynamic component.
+    #[inline]
+    #[must_use]
+    pub fn type_id(self) -> Option<TypeId> {
+        match self.0 {
+            ComponentKey::Type(id) => Some(id),
+            ComponentKey::Dynamic(_) => None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_dynamic(self) -> bool {
+        matches!(self.0, ComponentKey::Dynamic(_))
+    }
+}
+
+impl From<TypeId> for ComponentId {
+    fn from(id: TypeId) -> Self {
+        Self(ComponentKey::Type(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use super::{Component, Entity, World};
+    use crate::{
+        allocator::{EntityAllocator, RecyclingAllocator, Slot},
+        commands::CommandBuffer,
+        extract::ExtractionConfig,
+    };
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Value(u32);
+    #[derive(Debug, PartialEq)]
+    struct Label(String);
+    #[derive(Debug, Clone, PartialEq)]
+    struct Marker;
+
+    impl Component for Value {}
+    impl Component for Label {}
+    impl Component for Marker {}
+
+    #[test]
+    fn take_bundle_moves_the_values_out() {
+        let mut world = World::new();
+        let first = world.spawn((Value(1), Label("first".into()), Marker));
+        let last = world.spawn((Value(2), Label("last".into()), Marker));
+
+        let (label, value) = world.take_bundle::<(Label, Value)>(first).unwrap();
+        assert_eq!((label, value), (Label("first".into()), Value(1)));
+        assert!(world.has_component::<Marker>(first));
+        assert!(!world.has_component::<Value>(first));
+        assert!(!world.has_component::<Label>(first));
+
+        // The last row was moved into the taken one
+        assert_eq!(world.get_component::<Value>(last), Some(&Value(2)));
+        assert_eq!(
+            world.get_component::<Label>(last),
+            Some(&Label("last".into()))
+        );
+    }
+
+    #[test]
+    fn take_bundle_can_empty_the_entity() {
+        let mut world = World::new();
+        let entity = world.spawn((Label("only".into()), Marker));
+
+        let (label, marker) = world.take_bundle::<(Label, Marker)>(entity).unwrap();
+        assert_eq!((label, marker), (Label("only".into()), Marker));
+        assert!(world.is_alive(entity));
+        assert!(world.is_empty(entity));
+
+        world.insert_component(entity, Label("again".into()));
+        assert_eq!(
+            world.get_component::<Label>(entity),
+            Some(&Label("again".into()))
+        );
+    }
+
+    #[test]
+    fn take_bundle_leaves_entities_lacking_a_component_untouched() {
+        let mut world = World::new();
+        world.register_component::<Marker>();
+        let entity = world.spawn((Value(1), Label("kept".into())));
+
+        assert!(world.take_bundle::<(Value, Marker)>(entity).is_none());
+        assert_eq!(world.get_component::<Value>(entity), Some(&Value(1)));
+
+        world.despawn_entity(entity);
+        assert!(world.take_bundle::<Value>(entity).is_none());
+    }
+
+    #[test]
+    fn take_bundle_runs_remove_hooks_first() {
+        static SEEN: AtomicUsize = AtomicUsize::new(0);
+
+        let mut world = World::new();
+        world.on_remove::<Value>(|world, entity| {
+            let value = world.get_component::<Value>(entity).unwrap().0;
+            SEEN.store(value as usize, Ordering::Relaxed);
+        });
+        let entity = world.spawn((Value(7), Label("hooked".into())));
+
+        assert_eq!(world.take_bundle::<Value>(entity), Some(Value(7)));
+        assert_eq!(SEEN.load(Ordering::Relaxed), 7);
+    }
+
+    #[test]
+    fn take_bundle_returns_none_when_a_hook_removed_a_component() {
+        let mut world = World::new();
+        world.on_remove::<Value>(|world, entity| {
+            world.remove_component::<Label>(entity);
+        });
+        let entity = world.spawn((Value(1), Label("removed".into())));
+
+        assert!(world.take_bundle::<(Value, Label)>(entity).is_none());
+        assert_eq!(world.get_component::<Value>(entity), Some(&Value(1)));
+        assert!(!world.has_component::<Label>(entity));
+    }
+
+    #[test]
+    fn clear_keeps_hooks_and_observers() {
+        static ADDED: AtomicUsize = AtomicUsize::new(0);
+        static REMOVED: AtomicUsize = AtomicUsize::new(0);
+
+        let mut world = World::new();
+        world.on_add::<Value>(|_, _| {
+            ADDED.fetch_add(1, Ordering::Relaxed);
+        });
+        world.on_remove::<Value>(|_, _| {
+            REMOVED.fetch_add(1, Ordering::Relaxed);
+        });
+        let observed = Arc::new(AtomicUsize::new(0));
+        let counter = observed.clone();
+        world.observe_add::<Value, _>(move |_: Entity, _: &World, _: &mut CommandBuffer| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+
+        world.spawn(Value(1));
+        world.clear();
+        assert_eq!(REMOVED.load(Ordering::Relaxed), 1);
+        assert!(world.components().is_empty());
+
+        let entity = world.spawn(Value(2));
+        assert_eq!(ADDED.load(Ordering::Relaxed), 2);
+        assert_eq!(observed.load(Ordering::Relaxed), 2);
+        world.despawn_entity(entity);
+        assert_eq!(REMOVED.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn worlds_register_hundreds_of_components() {
+        unsafe fn drop_u32(_: *mut u8) {}
+
+        let mut world = World::new();
+        let ids: Vec<_> = (0..300)
+            .map(|_| world.register_dynamic(std::alloc::Layout::new::<u32>(), drop_u32))
+            .collect();
+        let entity = world.spawn(Value(7));
+        for (value, id) in [(1u32, ids[5]), (2, ids[150]), (3, ids[299])] {
+            let mut value = value;
+            unsafe {
+                world.insert_dynamic(entity, id, (&raw mut value).cast()); // SAFETY: The value is a u32 and copied into the column
+            }
+        }
+        let other = world.spawn(Label("last".into()));
+
+        let read = |world: &World, id| {
+            world
+                .get_dynamic(entity, id)
+                .map(|ptr| unsafe { ptr.cast::<u32>().read() }) // SAFETY: Every dynamic component stores a u32
+        };
+        assert_eq!(read(&world, ids[150]), Some(2));
+        assert_eq!(read(&world, ids[299]), Some(3));
+        assert_eq!(read(&world, ids[298]), None);
+        assert_eq!(world.get_component::<Value>(entity), Some(&Value(7)));
+        assert!(!world.has_component::<Label>(entity));
+
+        assert!(world.remove_dynamic(entity, ids[299]));
+        assert_eq!(read(&world, ids[299]), None);
+        assert_eq!(read(&world, ids[5]), Some(1));
+        assert_eq!(world.query::<&Value>().iter(&world).count(), 1);
+        assert_eq!(
+            world.get_component::<Label>(other),
+            Some(&Label("last".into()))
+        );
+    }
+
+    #[test]
+    fn archetype_handles_spawn_into_their_archetype() {
+        let mut world = World::new();
+        let handle = world.archetype_handle::<(Value, Marker)>();
+        let entity = world.spawn_in(handle, (Value(1), Marker));
+        assert_eq!(world.archetype_id_of(entity), Some(handle.id()));
+        assert_eq!(world.get_component::<Value>(entity), Some(&Value(1)));
+
+        // Clones copy the archetypes in order, so the handle stays valid in them
+        world.register_cloneable::<Value>();
+        world.register_cloneable::<Marker>();
+        let mut clone = world.clone_world().unwrap();
+        let cloned = clone.spawn_in(handle, (Value(2), Marker));
+        assert_eq!(clone.get_component::<Value>(cloned), Some(&Value(2)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Archetype handle does not belong to this world or is stale")]
+    fn archetype_handles_of_other_worlds_are_rejected() {
+        let mut world = World::new();
+        let mut other = World::new();
+        // Both handles have the same id and components, only the world differs
+        world.archetype_handle::<(Value, Marker)>();
+        let handle = other.archetype_handle::<(Value, Marker)>();
+        world.spawn_in(handle, (Value(1), Marker));
+    }
+
+    #[test]
+    #[should_panic(expected = "Archetype handle does not belong to this world or is stale")]
+    fn archetype_handles_are_stale_after_gc() {
+        let mut world = World::new();
+        world.spawn(Label("empty soon".into()));
+        let handle = world.archetype_handle::<(Value, Marker)>();
+        world.spawn_in(handle, (Value(1), Marker));
+        world.clear_entities();
+        world.spawn(Label("renumbered".into()));
+        assert!(world.gc_archetypes() > 0);
+        world.spawn_in(handle, (Value(2), Marker));
+    }
+
+    #[test]
+    #[should_panic(expected = "Archetype handle does not belong to this world or is stale")]
+    fn archetype_handles_are_stale_after_clear() {
+        let mut world = World::new();
+        let handle = world.archetype_handle::<(Value, Marker)>();
+        world.clear();
+        world.spawn((Label("first".into()), Marker));
+        world.spawn_in(handle, (Value(1), Marker));
+    }
+
+    #[test]
+    fn slots_skipped_by_the_allocator_are_not_alive() {
+        let mut world = World::new();
+        let mut skipped = false;
+        world.set_entity_allocator(move |free: &[usize], len: usize| {
+            if skipped {
+                RecyclingAllocator.allocate(free, len)
+            } else {
+                skipped = true;
+                Slot::New(len + 2)
+            }
+        });
+        let entity = world.spawn(Marker);
+        assert_eq!(entity.index, 2);
+
+        // The skipped slots have generation 0, like handles of their first entities
+        let skipped = Entity::new(0, 0, world.id());
+        assert!(!world.is_alive(skipped));
+        assert!(!world.despawn_entity(skipped));
+
+        // Freeing a skipped slot again would hand it to both spawns
+        let first = world.spawn(Value(1));
+        let second = world.spawn(Value(2));
+        assert_ne!(first, second);
+        assert_eq!(world.get_component::<Value>(first), Some(&Value(1)));
+        assert_eq!(world.get_component::<Value>(second), Some(&Value(2)));
+        assert_eq!(world.iter_entities().count(), 3);
+        assert_eq!(world.entity_count(), 3);
+    }
+
+    #[test]
+    fn slots_skipped_by_an_extraction_are_not_alive() {
+        let mut source = World::new();
+        let unextracted = source.spawn(Marker);
+        let extracted = source.spawn(Value(1));
+        let mut target = World::new();
+        let config = ExtractionConfig::new().component::<Value>();
+        source.extract_into(&mut target, &config);
+
+        assert!(target.is_alive(extracted));
+        assert!(!target.is_alive(unextracted));
+        assert!(!target.despawn_entity(unextracted));
+        let first = target.spawn(Marker);
+        let second = target.spawn(Marker);
+        assert_ne!(first, second);
+        assert_eq!(target.iter_entities().count(), 3);
+    }
+
+    #[test]
+    fn freed_slots_do_not_match_the_next_handle() {
+        let mut source = World::new();
+        let despawned = source.spawn(Value(1));
+        let mut target = World::new();
+        let config = ExtractionConfig::new().component::<Value>();
+        source.extract_into(&mut target, &config);
+
+        source.despawn_entity(despawned);
+        let reused = source.spawn(Marker);
+        assert_eq!(reused.index, despawned.index);
+        source.extract_into(&mut target, &config);
+
+        // The pruned slot has the generation of the reused handle but stays free
+        assert!(!target.is_alive(despawned));
+        assert!(!target.is_alive(reused));
+        assert!(!target.despawn_entity(reused));
+        assert_eq!(target.iter_entities().count(), 0);
+    }
+}
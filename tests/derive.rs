@@ -0,0 +1,112 @@
+use becs::prelude::*;
+
+struct Position(u32);
+struct Velocity(u32);
+
+impl Component for Position {}
+impl Component for Velocity {}
+
+struct Settings {
+    speed: u32,
+}
+
+#[derive(Default)]
+struct Moves(u32);
+
+#[derive(SystemParam)]
+struct Movement<'w, 's> {
+    bodies: Query<'w, 's, (&'static mut Position, &'static Velocity)>,
+    settings: Res<'w, Settings>,
+    moves: ResMut<'w, Moves>,
+    commands: Commands<'s>,
+    runs: Local<'s, u32>,
+}
+
+#[derive(SystemParam)]
+struct Clock<'w>(Res<'w, Time>);
+
+#[derive(SystemParam)]
+struct Nothing;
+
+fn access_of<M>(system: impl IntoSystem<M>) -> SystemAccess {
+    let mut world = World::new();
+    world.register_component::<Position>();
+    world.register_component::<Velocity>();
+    let mut system = system.into_system();
+    system.initialize(&mut world);
+    system.access().clone()
+}
+
+fn step(mut movement: Movement) {
+    *movement.runs += 1;
+    let speed = movement.settings.speed;
+    for (position, velocity) in movement.bodies.iter() {
+        position.0 += velocity.0 * speed;
+        movement.moves.0 += 1;
+    }
+    if *movement.runs == 2 {
+        movement.commands.spawn((Position(0), Velocity(0)));
+    }
+}
+
+fn tick(clock: Clock, _nothing: Nothing) {
+    assert_eq!(clock.0.tick(), 0);
+}
+
+#[test]
+fn derived_params_collect_the_access_of_their_fields() {
+    let access = access_of(step);
+    assert_eq!(
+        access.components().writes().collect::<Vec<_>>(),
+        [ComponentId::of::<Position>()]
+    );
+    assert_eq!(
+        access.components().reads().collect::<Vec<_>>(),
+        [ComponentId::of::<Velocity>()]
+    );
+    assert_eq!(
+        access.resources().reads().collect::<Vec<_>>(),
+        [ComponentId::of::<Settings>()]
+    );
+    assert_eq!(
+        access.resources().writes().collect::<Vec<_>>(),
+        [ComponentId::of::<Moves>()]
+    );
+    assert!(!access.is_exclusive());
+}
+
+#[test]
+fn tuple_and_unit_params_are_derived() {
+    let access = access_of(tick);
+    assert_eq!(
+        access.resources().reads().collect::<Vec<_>>(),
+        [ComponentId::of::<Time>()]
+    );
+    assert_eq!(access.components().reads().count(), 0);
+    assert_eq!(access.components().writes().count(), 0);
+
+    let mut world = World::new();
+    let mut schedule = Schedule::new();
+    schedule.add_system(tick);
+    schedule.run(&mut world);
+}
+
+#[test]
+fn derived_params_fetch_their_fields_and_apply_them() {
+    let mut world = World::new();
+    world.insert_resource(Settings { speed: 2 });
+    world.insert_resource(Moves::default());
+    let entity = world.spawn((Position(1), Velocity(3)));
+    let mut schedule = Schedule::new();
+    schedule.add_system(step);
+
+    schedule.run(&mut world);
+    assert_eq!(world.get_component::<Position>(entity).unwrap().0, 7);
+    assert_eq!(world.entity_count(), 1);
+
+    // The local counter is kept in the state, and the spawn of the second run is applied after it
+    schedule.run(&mut world);
+    assert_eq!(world.get_component::<Position>(entity).unwrap().0, 13);
+    assert_eq!(world.entity_count(), 2);
+    assert_eq!(world.get_resource::<Moves>().unwrap().0, 2);
+}